@@ -0,0 +1,136 @@
+//! Compares loading 10000 collections by their IDs with paging through them by cursor.
+//!
+//! Needs the `DATABASE_URL` of a migrated database and a `REDIS_URL`. A user and their
+//! collections are created with IDs above any generated ID for the run, and deleted afterwards.
+
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use labrinth::database::models::{Collection, CollectionId};
+use labrinth::database::redis::RedisPool;
+use sqlx::PgPool;
+
+const COLLECTION_COUNT: i64 = 10000;
+const PAGE_SIZE: i64 = 1000;
+// Generated IDs are at most 8 base62 digits, which is below 2^48
+const FIRST_ID: i64 = 1 << 50;
+const USER_ID: i64 = 1 << 50;
+
+async fn create_collections(pool: &PgPool) -> Vec<CollectionId> {
+    delete_collections(pool).await;
+
+    sqlx::query("INSERT INTO users (id, username) VALUES ($1, 'collections-bench')")
+        .bind(USER_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let ids = (FIRST_ID..FIRST_ID + COLLECTION_COUNT).collect::<Vec<_>>();
+    sqlx::query(
+        "
+        INSERT INTO collections (id, name, user_id)
+        SELECT id, 'Collection ' || id, $2 FROM UNNEST($1::bigint[]) id
+        ",
+    )
+    .bind(&ids)
+    .bind(USER_ID)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    ids.into_iter().map(CollectionId).collect()
+}
+
+async fn delete_collections(pool: &PgPool) {
+    sqlx::query("DELETE FROM collections WHERE user_id = $1")
+        .bind(USER_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(USER_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+// Collections are cached once loaded, so the cache is cleared before each timed load
+async fn clear_cache(ids: &[CollectionId], redis: &RedisPool) {
+    for id in ids {
+        Collection::clear_cache(*id, redis).await.unwrap();
+    }
+}
+
+async fn load_by_ids(ids: &[CollectionId], pool: &PgPool, redis: &RedisPool) -> usize {
+    Collection::get_by_ids(ids, pool, redis)
+        .await
+        .unwrap()
+        .len()
+}
+
+async fn load_by_cursor(pool: &PgPool, redis: &RedisPool) -> usize {
+    let mut count = 0;
+    let mut cursor = Some(CollectionId(FIRST_ID - 1));
+    loop {
+        let page = Collection::get_many(cursor, PAGE_SIZE, pool, redis)
+            .await
+            .unwrap();
+        let Some(last) = page.last() else {
+            break;
+        };
+        cursor = Some(last.id);
+        count += page.len();
+    }
+    count
+}
+
+fn collections(c: &mut Criterion) {
+    dotenvy::dotenv().ok();
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let pool = runtime.block_on(labrinth::database::connect()).unwrap();
+    let redis = RedisPool::new(Some("collections_bench".to_string()));
+    let ids = runtime.block_on(create_collections(&pool));
+
+    let mut group = c.benchmark_group("collections");
+    group.sample_size(10);
+    group.bench_function("get_by_ids", |b| {
+        b.to_async(&runtime).iter_custom(|iters| {
+            let (ids, pool, redis) = (&ids, &pool, &redis);
+            async move {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    clear_cache(ids, redis).await;
+                    let start = Instant::now();
+                    black_box(load_by_ids(ids, pool, redis).await);
+                    elapsed += start.elapsed();
+                }
+                elapsed
+            }
+        })
+    });
+    group.bench_function("get_many", |b| {
+        b.to_async(&runtime).iter_custom(|iters| {
+            let (ids, pool, redis) = (&ids, &pool, &redis);
+            async move {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    clear_cache(ids, redis).await;
+                    let start = Instant::now();
+                    black_box(load_by_cursor(pool, redis).await);
+                    elapsed += start.elapsed();
+                }
+                elapsed
+            }
+        })
+    });
+    group.finish();
+
+    runtime.block_on(delete_collections(&pool));
+}
+
+criterion_group!(benches, collections);
+criterion_main!(benches);
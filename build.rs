@@ -1,3 +1,24 @@
+use std::process::Command;
+
 fn main() {
     println!("cargo:rerun-if-changed=migrations");
+
+    // Builds without a git checkout (ex: from a source archive) can pass the commit instead
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT_HASH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let hash = std::env::var("GIT_COMMIT_HASH").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|x| x.status.success())
+            .and_then(|x| String::from_utf8(x.stdout).ok())
+            .map(|x| x.trim().to_string())
+    });
+    println!(
+        "cargo:rustc-env=GIT_COMMIT_HASH={}",
+        hash.unwrap_or_else(|| "unknown".to_string())
+    );
 }
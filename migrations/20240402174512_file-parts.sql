@@ -0,0 +1,4 @@
+ALTER TABLE files ADD COLUMN file_part_group_id bigint NULL REFERENCES files ON DELETE CASCADE;
+ALTER TABLE files ADD COLUMN part_index integer NULL;
+
+CREATE UNIQUE INDEX files_part_group_index ON files (file_part_group_id, part_index) WHERE file_part_group_id IS NOT NULL;
@@ -0,0 +1,11 @@
+CREATE TABLE project_audit_log (
+    id bigserial PRIMARY KEY,
+    project_id bigint REFERENCES mods ON DELETE CASCADE NOT NULL,
+    user_id bigint REFERENCES users ON DELETE SET NULL NULL,
+    changed_field varchar(64) NOT NULL,
+    old_value text NULL,
+    new_value text NULL,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX project_audit_log_project_id ON project_audit_log (project_id, created_at DESC);
@@ -0,0 +1,7 @@
+CREATE TABLE project_custom_licenses (
+    project_id bigint PRIMARY KEY REFERENCES mods ON DELETE CASCADE,
+    license_text text NOT NULL,
+    -- The license the project had before the custom license was uploaded, restored when it is removed
+    previous_license varchar(2048) NOT NULL,
+    updated timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,11 @@
+CREATE TABLE moderation_log (
+    id bigserial PRIMARY KEY,
+    moderator_id bigint REFERENCES users ON DELETE SET NULL NULL,
+    action varchar(64) NOT NULL,
+    target_user_id bigint REFERENCES users ON DELETE CASCADE NULL,
+    old_value text NULL,
+    new_value text NULL,
+    created timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX moderation_log_target_user_id ON moderation_log (target_user_id);
@@ -0,0 +1,2 @@
+-- Incremented on every edit of a project, for optimistic locking
+ALTER TABLE mods ADD COLUMN version integer NOT NULL DEFAULT 1;
@@ -0,0 +1,2 @@
+-- The search a collection was created from, if any
+ALTER TABLE collections ADD COLUMN source_search_query jsonb NULL;
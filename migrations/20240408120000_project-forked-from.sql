@@ -0,0 +1,2 @@
+-- The project a project was duplicated from, for attribution
+ALTER TABLE mods ADD COLUMN forked_from bigint NULL REFERENCES mods ON DELETE SET NULL;
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN trust_score real NOT NULL DEFAULT 0 CHECK (trust_score BETWEEN 0 AND 1);
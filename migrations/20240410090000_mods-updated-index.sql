@@ -0,0 +1 @@
+CREATE INDEX mods_updated_id ON mods (updated, id);
@@ -0,0 +1,12 @@
+CREATE TABLE user_push_tokens (
+    token text PRIMARY KEY,
+    user_id bigint REFERENCES users NOT NULL,
+    platform varchar(32) NOT NULL CHECK (platform IN ('apns', 'fcm')),
+    created timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE INDEX user_push_tokens_user_id ON user_push_tokens (user_id);
+
+-- Notifications created before push support existed are never delivered
+ALTER TABLE notifications ADD COLUMN pushed boolean NOT NULL DEFAULT FALSE;
+UPDATE notifications SET pushed = TRUE;
+CREATE INDEX notifications_unpushed ON notifications (created) WHERE NOT pushed;
@@ -0,0 +1,11 @@
+CREATE TABLE meilisearch_retry_queue (
+    id bigserial PRIMARY KEY,
+    document_type varchar(32) NOT NULL,
+    document_id bigint NOT NULL,
+    payload jsonb NOT NULL,
+    attempts int NOT NULL DEFAULT 0,
+    last_attempt timestamptz NULL,
+    next_attempt_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    created timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE INDEX meilisearch_retry_queue_next_attempt ON meilisearch_retry_queue (next_attempt_at);
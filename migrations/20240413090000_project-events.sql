@@ -0,0 +1,98 @@
+CREATE TABLE project_events (
+    id bigserial PRIMARY KEY,
+    project_id bigint REFERENCES mods ON DELETE CASCADE NOT NULL,
+    event_type varchar(64) NOT NULL CHECK (event_type IN (
+        'project_created', 'status_changed',
+        'version_published', 'version_deleted',
+        'member_added', 'member_removed', 'member_role_changed'
+    )),
+    payload jsonb DEFAULT '{}'::jsonb NOT NULL,
+    user_id bigint REFERENCES users ON DELETE SET NULL NULL,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX project_events_project_id ON project_events (project_id, id);
+
+-- Projects
+
+CREATE FUNCTION project_events_mods() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        VALUES (
+            NEW.id, 'project_created',
+            jsonb_build_object('name', NEW.name, 'status', NEW.status),
+            (SELECT user_id FROM team_members WHERE team_id = NEW.team_id AND is_owner LIMIT 1)
+        );
+    ELSIF NEW.status IS DISTINCT FROM OLD.status THEN
+        INSERT INTO project_events (project_id, event_type, payload)
+        VALUES (NEW.id, 'status_changed', jsonb_build_object('old_status', OLD.status, 'new_status', NEW.status));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER project_events_mods
+AFTER INSERT OR UPDATE OF status ON mods
+FOR EACH ROW EXECUTE FUNCTION project_events_mods();
+
+-- Versions. Deletions that are part of a project deletion are skipped, as the project is gone.
+
+CREATE FUNCTION project_events_versions() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        VALUES (
+            NEW.mod_id, 'version_published',
+            jsonb_build_object('version_id', NEW.id, 'version_number', NEW.version_number, 'name', NEW.name),
+            NEW.author_id
+        );
+    ELSE
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        SELECT
+            OLD.mod_id, 'version_deleted',
+            jsonb_build_object('version_id', OLD.id, 'version_number', OLD.version_number, 'name', OLD.name),
+            OLD.author_id
+        WHERE EXISTS (SELECT 1 FROM mods WHERE id = OLD.mod_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER project_events_versions
+AFTER INSERT OR DELETE ON versions
+FOR EACH ROW EXECUTE FUNCTION project_events_versions();
+
+-- Team members. Only members of a project's own team are tracked, and a member is added once they
+-- accept their invite.
+
+CREATE FUNCTION project_events_team_members() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.accepted THEN
+            INSERT INTO project_events (project_id, event_type, payload, user_id)
+            SELECT id, 'member_added', jsonb_build_object('role', NEW.role), NEW.user_id
+            FROM mods WHERE team_id = NEW.team_id;
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        IF OLD.accepted THEN
+            INSERT INTO project_events (project_id, event_type, payload, user_id)
+            SELECT id, 'member_removed', jsonb_build_object('role', OLD.role), OLD.user_id
+            FROM mods WHERE team_id = OLD.team_id;
+        END IF;
+    ELSIF NEW.accepted AND NOT OLD.accepted THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        SELECT id, 'member_added', jsonb_build_object('role', NEW.role), NEW.user_id
+        FROM mods WHERE team_id = NEW.team_id;
+    ELSIF NEW.accepted AND NEW.role IS DISTINCT FROM OLD.role THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        SELECT id, 'member_role_changed', jsonb_build_object('old_role', OLD.role, 'new_role', NEW.role), NEW.user_id
+        FROM mods WHERE team_id = NEW.team_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER project_events_team_members
+AFTER INSERT OR UPDATE OR DELETE ON team_members
+FOR EACH ROW EXECUTE FUNCTION project_events_team_members();
@@ -0,0 +1,10 @@
+CREATE TABLE project_status_messages (
+    id bigserial PRIMARY KEY,
+    project_id bigint REFERENCES mods ON DELETE CASCADE NOT NULL,
+    moderator_id bigint REFERENCES users ON DELETE SET NULL NULL,
+    status varchar(128) NOT NULL,
+    message text NULL,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX project_status_messages_project_id ON project_status_messages (project_id, created_at DESC);
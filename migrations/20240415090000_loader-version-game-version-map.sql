@@ -0,0 +1,11 @@
+-- Maps loader versions to the game version they were made for (ex: forge 41.* is for 1.18.2),
+-- used to infer the game versions of uploaded versions that do not specify them
+CREATE TABLE loader_version_game_version_map (
+    id serial PRIMARY KEY,
+    loader_id int REFERENCES loaders ON DELETE CASCADE NOT NULL,
+    -- An exact loader version, or a prefix followed by `*` (ex: `41.*`)
+    loader_version_pattern varchar(255) NOT NULL,
+    game_version varchar(255) NOT NULL,
+    created timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (loader_id, loader_version_pattern, game_version)
+);
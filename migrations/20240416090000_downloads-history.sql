@@ -0,0 +1,13 @@
+CREATE TABLE project_downloads_history (
+    project_id bigint REFERENCES mods ON DELETE CASCADE NOT NULL,
+    date date NOT NULL,
+    downloads integer DEFAULT 0 NOT NULL,
+    PRIMARY KEY (project_id, date)
+);
+
+CREATE TABLE version_downloads_history (
+    version_id bigint REFERENCES versions ON DELETE CASCADE NOT NULL,
+    date date NOT NULL,
+    downloads integer DEFAULT 0 NOT NULL,
+    PRIMARY KEY (version_id, date)
+);
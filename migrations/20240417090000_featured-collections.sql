@@ -0,0 +1,5 @@
+CREATE TABLE featured_collections (
+    collection_id bigint PRIMARY KEY REFERENCES collections ON DELETE CASCADE,
+    ordering integer NOT NULL,
+    created timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE backfill_jobs (
+    id bigserial PRIMARY KEY,
+    algorithm varchar(255) NOT NULL,
+    processed integer DEFAULT 0 NOT NULL,
+    total integer NOT NULL,
+    errors text[] DEFAULT '{}' NOT NULL,
+    started_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    completed_at timestamptz NULL
+);
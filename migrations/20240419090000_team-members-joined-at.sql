@@ -0,0 +1,3 @@
+-- When a member accepted their invite, or was added already accepted. Unknown for members who
+-- joined before this was recorded.
+ALTER TABLE team_members ADD COLUMN joined_at timestamptz NULL;
@@ -0,0 +1,3 @@
+-- Deleted versions are kept for a while so they can be restored, and purged afterwards
+ALTER TABLE versions ADD COLUMN deleted_at timestamptz NULL;
+CREATE INDEX versions_deleted_at ON versions (deleted_at) WHERE deleted_at IS NOT NULL;
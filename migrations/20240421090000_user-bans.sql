@@ -0,0 +1,6 @@
+-- Suspended users cannot authenticate, and their projects are hidden from search.
+-- A ban without an end date is permanent.
+ALTER TABLE users ADD COLUMN banned boolean NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN ban_reason text NULL;
+ALTER TABLE users ADD COLUMN banned_until timestamptz NULL;
+CREATE INDEX users_banned_until ON users (banned_until) WHERE banned;
@@ -0,0 +1,3 @@
+-- Deprecated loaders can still be used, but uploads with them are warned about.
+ALTER TABLE loaders ADD COLUMN deprecated boolean NOT NULL DEFAULT FALSE;
+ALTER TABLE loaders ADD COLUMN superseded_by varchar(255) NULL;
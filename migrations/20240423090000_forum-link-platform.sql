@@ -0,0 +1,2 @@
+-- Forums can be linked alongside the other external links of a project
+INSERT INTO link_platforms (name, donation) VALUES ('forum', false);
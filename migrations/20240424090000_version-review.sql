@@ -0,0 +1,4 @@
+-- Moderation actions can target versions as well as users
+ALTER TABLE moderation_log ADD COLUMN target_type varchar(64) NOT NULL DEFAULT 'user';
+ALTER TABLE moderation_log ADD COLUMN target_version_id bigint REFERENCES versions ON DELETE CASCADE NULL;
+CREATE INDEX moderation_log_target_version_id ON moderation_log (target_version_id);
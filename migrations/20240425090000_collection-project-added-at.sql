@@ -0,0 +1 @@
+ALTER TABLE collections_mods ADD COLUMN added_at timestamptz NOT NULL DEFAULT now();
@@ -0,0 +1,9 @@
+ALTER TABLE users ADD COLUMN last_username_change timestamptz NULL;
+
+CREATE TABLE username_history (
+    id bigserial PRIMARY KEY,
+    user_id bigint REFERENCES users ON DELETE CASCADE NOT NULL,
+    username varchar(255) NOT NULL,
+    changed_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX username_history_user_id ON username_history (user_id);
@@ -0,0 +1,8 @@
+-- Deleted projects are hidden right away and permanently deleted once their deletion is due
+ALTER TABLE mods ADD COLUMN deleted_at timestamptz NULL;
+
+CREATE TABLE scheduled_deletions (
+    mod_id bigint PRIMARY KEY REFERENCES mods ON DELETE CASCADE,
+    scheduled_for timestamptz NOT NULL
+);
+CREATE INDEX scheduled_deletions_scheduled_for ON scheduled_deletions (scheduled_for);
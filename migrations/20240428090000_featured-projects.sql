@@ -0,0 +1,9 @@
+-- Projects pinned to the home page by moderators, shown in ascending position
+CREATE TABLE featured_projects (
+    project_id bigint PRIMARY KEY REFERENCES mods ON DELETE CASCADE,
+    position integer NOT NULL,
+    featured_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    featured_by bigint NULL REFERENCES users ON DELETE SET NULL,
+    expires_at timestamptz NULL
+);
+CREATE INDEX featured_projects_expires_at ON featured_projects (expires_at) WHERE expires_at IS NOT NULL;
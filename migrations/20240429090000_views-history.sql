@@ -0,0 +1,6 @@
+CREATE TABLE project_views_history (
+    project_id bigint REFERENCES mods ON DELETE CASCADE NOT NULL,
+    date date NOT NULL,
+    views integer DEFAULT 0 NOT NULL,
+    PRIMARY KEY (project_id, date)
+);
@@ -0,0 +1,8 @@
+ALTER TABLE backfill_jobs ADD COLUMN failed boolean DEFAULT FALSE NOT NULL;
+ALTER TABLE backfill_jobs ADD COLUMN updated_at timestamptz DEFAULT CURRENT_TIMESTAMP NOT NULL;
+
+-- Jobs left running by an earlier version of the backfill may have stopped without completing
+UPDATE backfill_jobs SET completed_at = NOW(), failed = TRUE WHERE completed_at IS NULL;
+
+-- Only one job per algorithm can be running at once
+CREATE UNIQUE INDEX backfill_jobs_running_algorithm ON backfill_jobs (algorithm) WHERE completed_at IS NULL;
@@ -0,0 +1,28 @@
+-- Soft deleting a project is recorded on its timeline as well
+CREATE OR REPLACE FUNCTION project_events_mods() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        VALUES (
+            NEW.id, 'project_created',
+            jsonb_build_object('name', NEW.name, 'status', NEW.status),
+            (SELECT user_id FROM team_members WHERE team_id = NEW.team_id AND is_owner LIMIT 1)
+        );
+    ELSE
+        IF NEW.status IS DISTINCT FROM OLD.status THEN
+            INSERT INTO project_events (project_id, event_type, payload)
+            VALUES (NEW.id, 'status_changed', jsonb_build_object('old_status', OLD.status, 'new_status', NEW.status));
+        END IF;
+        IF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+            INSERT INTO project_events (project_id, event_type, payload)
+            VALUES (NEW.id, 'project_deleted', jsonb_build_object('deleted_at', NEW.deleted_at));
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER project_events_mods ON mods;
+CREATE TRIGGER project_events_mods
+AFTER INSERT OR UPDATE OF status, deleted_at ON mods
+FOR EACH ROW EXECUTE FUNCTION project_events_mods();
@@ -0,0 +1,43 @@
+-- Soft deleting and restoring a version is recorded on its project's timeline. Versions purged
+-- after being soft deleted were already recorded as deleted.
+CREATE OR REPLACE FUNCTION project_events_versions() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        VALUES (
+            NEW.mod_id, 'version_published',
+            jsonb_build_object('version_id', NEW.id, 'version_number', NEW.version_number, 'name', NEW.name),
+            NEW.author_id
+        );
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+            INSERT INTO project_events (project_id, event_type, payload, user_id)
+            VALUES (
+                NEW.mod_id, 'version_deleted',
+                jsonb_build_object('version_id', NEW.id, 'version_number', NEW.version_number, 'name', NEW.name),
+                NEW.author_id
+            );
+        ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+            INSERT INTO project_events (project_id, event_type, payload, user_id)
+            VALUES (
+                NEW.mod_id, 'version_restored',
+                jsonb_build_object('version_id', NEW.id, 'version_number', NEW.version_number, 'name', NEW.name),
+                NEW.author_id
+            );
+        END IF;
+    ELSIF OLD.deleted_at IS NULL THEN
+        INSERT INTO project_events (project_id, event_type, payload, user_id)
+        SELECT
+            OLD.mod_id, 'version_deleted',
+            jsonb_build_object('version_id', OLD.id, 'version_number', OLD.version_number, 'name', OLD.name),
+            OLD.author_id
+        WHERE EXISTS (SELECT 1 FROM mods WHERE id = OLD.mod_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER project_events_versions ON versions;
+CREATE TRIGGER project_events_versions
+AFTER INSERT OR DELETE OR UPDATE OF deleted_at ON versions
+FOR EACH ROW EXECUTE FUNCTION project_events_versions();
@@ -0,0 +1,7 @@
+-- Project deletions and version restores are recorded on the timeline since soft deletes were added
+ALTER TABLE project_events DROP CONSTRAINT project_events_event_type_check;
+ALTER TABLE project_events ADD CONSTRAINT project_events_event_type_check CHECK (event_type IN (
+    'project_created', 'status_changed', 'project_deleted',
+    'version_published', 'version_deleted', 'version_restored',
+    'member_added', 'member_removed', 'member_role_changed'
+));
@@ -70,6 +70,7 @@ pub async fn filter_visible_projects(
 // - it's not hidden
 // - the user is enlisted on the project's team (filter_enlisted_projects)
 // - the user is a mod
+// Deleted projects are never visible.
 // This is essentially whether you can know of the project's existence
 pub async fn filter_visible_project_ids(
     projects: Vec<&Project>,
@@ -82,6 +83,10 @@ pub async fn filter_visible_project_ids(
 
     // Return projects that are not hidden or we are a mod of
     for project in projects {
+        if project.deleted_at.is_some() {
+            continue;
+        }
+
         if (if hide_unlisted {
             project.status.is_searchable()
         } else {
@@ -229,17 +234,20 @@ pub async fn filter_visible_version_ids(
     let project_ids = versions.iter().map(|x| x.project_id).collect::<Vec<_>>();
 
     // Get visible projects- ones we are allowed to see public versions for.
+    let projects = Project::get_many_ids(&project_ids, pool, redis).await?;
     let visible_project_ids = filter_visible_project_ids(
-        Project::get_many_ids(&project_ids, pool, redis)
-            .await?
-            .iter()
-            .map(|x| &x.inner)
-            .collect(),
+        projects.iter().map(|x| &x.inner).collect(),
         user_option,
         pool,
         false,
     )
     .await?;
+    // Versions of deleted projects are hidden from everyone, like their projects
+    let deleted_project_ids = projects
+        .iter()
+        .filter(|x| x.inner.deleted_at.is_some())
+        .map(|x| x.inner.id)
+        .collect::<Vec<_>>();
 
     // Then, get enlisted versions (Versions that are a part of a project we are a member of)
     let enlisted_version_ids =
@@ -247,6 +255,10 @@ pub async fn filter_visible_version_ids(
 
     // Return versions that are not hidden, we are a mod of, or we are enlisted on the team of
     for version in versions {
+        if deleted_project_ids.contains(&version.project_id) {
+            continue;
+        }
+
         // We can see the version if:
         // - it's not hidden and we can see the project
         // - we are a mod
@@ -4,6 +4,8 @@ use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Address, Message, SmtpTransport, Transport};
 use thiserror::Error;
 
+use crate::util::env::parse_var;
+
 #[derive(Error, Debug)]
 pub enum MailError {
     #[error("Environment Error")]
@@ -32,7 +34,16 @@ pub fn send_email_raw(to: String, subject: String, body: String) -> Result<(), M
     let host = dotenvy::var("SMTP_HOST")?;
     let creds = Credentials::new(username, password);
 
-    let mailer = SmtpTransport::relay(&host)?.credentials(creds).build();
+    // `SMTP_TLS` is one of `tls` (the default), `starttls` or `none`
+    let mut mailer = match dotenvy::var("SMTP_TLS").as_deref() {
+        Ok("starttls") => SmtpTransport::starttls_relay(&host)?,
+        Ok("none") => SmtpTransport::builder_dangerous(&host),
+        _ => SmtpTransport::relay(&host)?,
+    };
+    if let Some(port) = parse_var::<u16>("SMTP_PORT") {
+        mailer = mailer.port(port);
+    }
+    let mailer = mailer.credentials(creds).build();
 
     mailer.send(&email)?;
 
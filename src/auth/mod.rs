@@ -1,6 +1,7 @@
 pub mod checks;
 pub mod email;
 pub mod oauth;
+pub mod password;
 pub mod templates;
 pub mod validate;
 pub use checks::{
@@ -15,6 +16,7 @@ use crate::file_hosting::FileHostingError;
 use crate::models::error::ApiError;
 use actix_web::http::StatusCode;
 use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -47,6 +49,11 @@ pub enum AuthenticationError {
     SocketError,
     #[error("Invalid callback URL specified")]
     Url,
+    #[error("This account is suspended")]
+    Suspended {
+        reason: Option<String>,
+        until: Option<DateTime<Utc>>,
+    },
 }
 
 impl actix_web::ResponseError for AuthenticationError {
@@ -66,10 +73,20 @@ impl actix_web::ResponseError for AuthenticationError {
             AuthenticationError::FileHosting(..) => StatusCode::INTERNAL_SERVER_ERROR,
             AuthenticationError::DuplicateUser => StatusCode::BAD_REQUEST,
             AuthenticationError::SocketError => StatusCode::BAD_REQUEST,
+            AuthenticationError::Suspended { .. } => StatusCode::FORBIDDEN,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
+        if let AuthenticationError::Suspended { reason, until } = self {
+            return HttpResponse::build(self.status_code()).json(serde_json::json!({
+                "error": self.error_name(),
+                "description": self.to_string(),
+                "reason": reason,
+                "until": until,
+            }));
+        }
+
         HttpResponse::build(self.status_code()).json(ApiError {
             error: self.error_name(),
             description: self.to_string(),
@@ -94,6 +111,7 @@ impl AuthenticationError {
             AuthenticationError::FileHosting(..) => "file_hosting",
             AuthenticationError::DuplicateUser => "duplicate_user",
             AuthenticationError::SocketError => "socket",
+            AuthenticationError::Suspended { .. } => "suspended",
         }
     }
 }
@@ -0,0 +1,111 @@
+//! Password hashing. Hashes made with older Argon2 parameters are upgraded when their owner
+//! signs in successfully.
+
+use crate::database::models::{DatabaseError, User, UserId};
+use crate::database::redis::RedisPool;
+use argon2::password_hash::SaltString;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sqlx::PgPool;
+use std::convert::TryFrom;
+
+/// Hashes a password with the current algorithm and parameters
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut ChaCha20Rng::from_entropy());
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// Checks a password against a stored hash, whatever parameters it was made with
+pub fn verify_password(hash: &str, password: &str) -> Result<(), argon2::password_hash::Error> {
+    Argon2::default().verify_password(password.as_bytes(), &PasswordHash::new(hash)?)
+}
+
+/// Whether a stored hash was made with another algorithm or version, or weaker parameters than
+/// the current ones
+pub fn needs_upgrade(hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(hash) else {
+        return true;
+    };
+    if hash.algorithm != Algorithm::default().ident()
+        || hash.version != Some(Version::default().into())
+    {
+        return true;
+    }
+
+    let current = Params::default();
+    match Params::try_from(&hash) {
+        Ok(params) => {
+            params.m_cost() < current.m_cost()
+                || params.t_cost() < current.t_cost()
+                || params.p_cost() < current.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
+/// Rehashes the password of a user who just signed in with it, if their hash is outdated
+pub async fn upgrade_password_hash(
+    user_id: UserId,
+    hash: &str,
+    password: &str,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), DatabaseError> {
+    if !needs_upgrade(hash) {
+        return Ok(());
+    }
+
+    let Ok(new_hash) = hash_password(password) else {
+        return Ok(());
+    };
+    sqlx::query!(
+        "
+        UPDATE users
+        SET password = $1
+        WHERE id = $2
+        ",
+        new_hash,
+        user_id as UserId,
+    )
+    .execute(pool)
+    .await?;
+
+    User::clear_caches(&[(user_id, None)], redis).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outdated_hashes_are_upgraded() {
+        let salt = SaltString::generate(&mut ChaCha20Rng::from_entropy());
+        let outdated = Argon2::new(
+            Algorithm::Argon2i,
+            Version::V0x13,
+            Params::new(4096, 1, 1, None).unwrap(),
+        )
+        .hash_password(b"hunter22", &salt)
+        .unwrap()
+        .to_string();
+
+        assert!(verify_password(&outdated, "hunter22").is_ok());
+        assert!(verify_password(&outdated, "wrong").is_err());
+        assert!(needs_upgrade(&outdated));
+
+        // Hashes made with the current algorithm and parameters but an older version, too
+        let outdated_version = Argon2::new(Algorithm::default(), Version::V0x10, Params::default())
+            .hash_password(b"hunter22", &salt)
+            .unwrap()
+            .to_string();
+        assert!(verify_password(&outdated_version, "hunter22").is_ok());
+        assert!(needs_upgrade(&outdated_version));
+
+        let current = hash_password("hunter22").unwrap();
+        assert!(verify_password(&current, "hunter22").is_ok());
+        assert!(!needs_upgrade(&current));
+    }
+}
@@ -175,6 +175,17 @@ where
         }
         _ => return Err(AuthenticationError::InvalidAuthMethod),
     };
+
+    // Bans which have ended but are not lifted yet no longer apply
+    if let Some((_, user)) = &possible_user {
+        if user.banned && user.banned_until.map_or(true, |x| x > Utc::now()) {
+            return Err(AuthenticationError::Suspended {
+                reason: user.ban_reason.clone(),
+                until: user.banned_until,
+            });
+        }
+    }
+
     Ok(possible_user)
 }
 
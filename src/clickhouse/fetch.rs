@@ -162,3 +162,38 @@ pub async fn fetch_countries_views(
 
     Ok(query.fetch_all().await?)
 }
+
+#[derive(clickhouse::Row, Serialize, Deserialize, Clone, Debug)]
+pub struct ReturnSearchQuery {
+    pub query: String,
+    pub total: u64,
+}
+
+// Fetches the most common queries that had no results, most common first
+pub async fn fetch_zero_result_searches(
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    limit: u64,
+    client: Arc<clickhouse::Client>,
+) -> Result<Vec<ReturnSearchQuery>, ApiError> {
+    let query = client
+        .query(
+            "
+            SELECT
+                query_text AS query,
+                COUNT(*) AS total
+            FROM search_logs
+            WHERE recorded BETWEEN ? AND ?
+            AND result_count = 0
+            AND query_text != ''
+            GROUP BY query_text
+            ORDER BY total DESC
+            LIMIT ?
+            ",
+        )
+        .bind(start_date.timestamp())
+        .bind(end_date.timestamp())
+        .bind(limit);
+
+    Ok(query.fetch_all().await?)
+}
@@ -104,5 +104,25 @@ pub async fn init_client_with_database(
         .execute()
         .await?;
 
+    client
+        .query(&format!(
+            "
+            CREATE TABLE IF NOT EXISTS {database}.search_logs
+            (
+                recorded DateTime64(4),
+                query_hash String,
+                query_text String,
+                facets_hash String,
+
+                result_count UInt64,
+                response_time_ms UInt64
+            )
+            ENGINE = MergeTree()
+            PRIMARY KEY (result_count, recorded)
+            "
+        ))
+        .execute()
+        .await?;
+
     Ok(client.with_database(database))
 }
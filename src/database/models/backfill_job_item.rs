@@ -0,0 +1,156 @@
+use crate::database::models::DatabaseError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The most errors kept on a job, so a broken origin does not grow the row without bounds
+const MAX_JOB_ERRORS: i32 = 100;
+/// How long a job can go without progress before it is considered stopped (ex: by a restart)
+const STALE_JOB_MINUTES: i32 = 60;
+
+/// A background job computing a hash algorithm for files that do not have it yet
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BackfillJob {
+    pub id: i64,
+    pub algorithm: String,
+    pub processed: i32,
+    pub total: i32,
+    pub errors: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub failed: bool,
+}
+
+impl BackfillJob {
+    /// Starts a job for the algorithm, returning `None` if one is already running. Jobs that
+    /// stopped making progress are marked as failed first, so they do not block new ones.
+    pub async fn claim(
+        algorithm: &str,
+        total: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<i64>, DatabaseError> {
+        sqlx::query!(
+            "
+            UPDATE backfill_jobs
+            SET completed_at = NOW(), failed = TRUE
+            WHERE algorithm = $1 AND completed_at IS NULL
+            AND updated_at < NOW() - make_interval(mins => $2)
+            ",
+            algorithm,
+            STALE_JOB_MINUTES,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        let id = sqlx::query!(
+            "
+            INSERT INTO backfill_jobs (algorithm, total)
+            VALUES ($1, $2)
+            ON CONFLICT (algorithm) WHERE completed_at IS NULL DO NOTHING
+            RETURNING id
+            ",
+            algorithm,
+            total,
+        )
+        .fetch_optional(&mut **transaction)
+        .await?
+        .map(|x| x.id);
+
+        Ok(id)
+    }
+
+    pub async fn get<'a, E>(id: i64, exec: E) -> Result<Option<BackfillJob>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let job = sqlx::query!(
+            "
+            SELECT id, algorithm, processed, total, errors, started_at, completed_at, failed
+            FROM backfill_jobs
+            WHERE id = $1
+            ",
+            id,
+        )
+        .fetch_optional(exec)
+        .await?
+        .map(|x| BackfillJob {
+            id: x.id,
+            algorithm: x.algorithm,
+            processed: x.processed,
+            total: x.total,
+            errors: x.errors,
+            started_at: x.started_at,
+            completed_at: x.completed_at,
+            failed: x.failed,
+        });
+
+        Ok(job)
+    }
+
+    /// Counts one more file as processed, recording the error it failed with if any
+    pub async fn record_progress<'a, E>(
+        id: i64,
+        error: Option<String>,
+        exec: E,
+    ) -> Result<(), DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        sqlx::query!(
+            "
+            UPDATE backfill_jobs
+            SET processed = processed + 1,
+                updated_at = NOW(),
+                errors = CASE
+                    WHEN $2::text IS NULL OR cardinality(errors) >= $3 THEN errors
+                    ELSE array_append(errors, $2)
+                END
+            WHERE id = $1
+            ",
+            id,
+            error,
+            MAX_JOB_ERRORS,
+        )
+        .execute(exec)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete<'a, E>(id: i64, exec: E) -> Result<(), DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        sqlx::query!(
+            "
+            UPDATE backfill_jobs
+            SET completed_at = NOW()
+            WHERE id = $1
+            ",
+            id,
+        )
+        .execute(exec)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stops a job that could not go on, recording why
+    pub async fn fail<'a, E>(id: i64, error: String, exec: E) -> Result<(), DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        sqlx::query!(
+            "
+            UPDATE backfill_jobs
+            SET completed_at = NOW(), failed = TRUE, errors = array_append(errors, $2)
+            WHERE id = $1
+            ",
+            id,
+            error,
+        )
+        .execute(exec)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -8,6 +8,11 @@ use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
 
 const TAGS_NAMESPACE: &str = "tags";
+/// How long the statistics of a category are cached, in seconds. They are also refreshed by a
+/// background task at this interval.
+pub const CATEGORY_STATS_EXPIRY: i64 = 30 * 60;
+/// How many of the most downloaded projects of a category are kept in its statistics
+const CATEGORY_MOST_POPULAR_COUNT: i64 = 5;
 
 pub struct ProjectType {
     pub id: ProjectTypeId,
@@ -129,6 +134,128 @@ impl Category {
     }
 }
 
+/// A project in the statistics of a category
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CategoryProject {
+    pub id: ProjectId,
+    pub slug: Option<String>,
+    pub name: String,
+    pub icon_url: Option<String>,
+    pub downloads: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CategoryStats {
+    /// The number of searchable projects in the category
+    pub project_count: i64,
+    /// The most downloaded searchable projects of the category
+    pub most_popular: Vec<CategoryProject>,
+}
+
+impl CategoryStats {
+    pub async fn get(
+        category: &str,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<CategoryStats, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+        if let Some(stats) = redis_connection
+            .get_deserialized_from_json(TAGS_NAMESPACE, &format!("category:{category}"))
+            .await?
+        {
+            return Ok(stats);
+        }
+
+        Self::refresh(category, pool, redis).await
+    }
+
+    /// Computes the statistics of a category and caches them
+    pub async fn refresh(
+        category: &str,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<CategoryStats, DatabaseError> {
+        let statuses = crate::models::projects::ProjectStatus::iterator()
+            .filter(|x| x.is_searchable())
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>();
+
+        let project_count = sqlx::query!(
+            "
+            SELECT COUNT(DISTINCT m.id) FROM mods m
+            INNER JOIN mods_categories mc ON mc.joining_mod_id = m.id
+            INNER JOIN categories c ON c.id = mc.joining_category_id
+            WHERE c.category = $1 AND m.status = ANY($2) AND m.deleted_at IS NULL
+            ",
+            category,
+            &statuses,
+        )
+        .fetch_one(pool)
+        .await?
+        .count
+        .unwrap_or(0);
+
+        let most_popular = sqlx::query!(
+            "
+            SELECT m.id, m.slug, m.name, m.icon_url, m.downloads FROM mods m
+            WHERE m.status = ANY($2) AND m.deleted_at IS NULL AND EXISTS(
+                SELECT 1 FROM mods_categories mc
+                INNER JOIN categories c ON c.id = mc.joining_category_id
+                WHERE mc.joining_mod_id = m.id AND c.category = $1
+            )
+            ORDER BY m.downloads DESC, m.id
+            LIMIT $3
+            ",
+            category,
+            &statuses,
+            CATEGORY_MOST_POPULAR_COUNT,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| CategoryProject {
+            id: ProjectId(x.id),
+            slug: x.slug,
+            name: x.name,
+            icon_url: x.icon_url,
+            downloads: x.downloads,
+        })
+        .collect();
+
+        let stats = CategoryStats {
+            project_count,
+            most_popular,
+        };
+
+        let mut redis_connection = redis.connect().await?;
+        redis_connection
+            .set_serialized_to_json(
+                TAGS_NAMESPACE,
+                format!("category:{category}"),
+                &stats,
+                Some(CATEGORY_STATS_EXPIRY),
+            )
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Refreshes the statistics of every category
+    pub async fn refresh_all(pool: &sqlx::PgPool, redis: &RedisPool) -> Result<(), DatabaseError> {
+        let names = Category::list(pool, redis)
+            .await?
+            .into_iter()
+            .map(|x| x.category)
+            .collect::<std::collections::HashSet<_>>();
+
+        for name in names {
+            Self::refresh(&name, pool, redis).await?;
+        }
+
+        Ok(())
+    }
+}
+
 impl LinkPlatform {
     pub async fn get_id<'a, E>(id: &str, exec: E) -> Result<Option<LinkPlatformId>, DatabaseError>
     where
@@ -2,13 +2,17 @@ use super::ids::*;
 use crate::database::models;
 use crate::database::models::DatabaseError;
 use crate::database::redis::RedisPool;
-use crate::models::collections::CollectionStatus;
+use crate::models::collections::{CollectionSort, CollectionStatus};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 const COLLECTIONS_NAMESPACE: &str = "collections";
+/// The key of the featured collections list, in the collections namespace
+const FEATURED_COLLECTIONS_KEY: &str = "featured";
+const FEATURED_COLLECTIONS_EXPIRY: i64 = 60 * 10;
 
 #[derive(Clone)]
 pub struct CollectionBuilder {
@@ -18,6 +22,7 @@ pub struct CollectionBuilder {
     pub description: Option<String>,
     pub status: CollectionStatus,
     pub projects: Vec<ProjectId>,
+    pub source_search_query: Option<serde_json::Value>,
 }
 
 impl CollectionBuilder {
@@ -36,6 +41,8 @@ impl CollectionBuilder {
             color: None,
             status: self.status,
             projects: self.projects,
+            project_added_dates: HashMap::new(),
+            source_search_query: self.source_search_query,
         };
         collection_struct.insert(transaction).await?;
 
@@ -54,6 +61,29 @@ pub struct Collection {
     pub color: Option<u32>,
     pub status: CollectionStatus,
     pub projects: Vec<ProjectId>,
+    /// When each of the projects was added to the collection
+    #[serde(default)]
+    pub project_added_dates: HashMap<ProjectId, DateTime<Utc>>,
+    pub source_search_query: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionSummary {
+    pub id: CollectionId,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    pub status: CollectionStatus,
+    pub project_count: i64,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+/// The sort value of the collection a page of summaries starts after
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollectionSortValue {
+    Date(DateTime<Utc>),
+    Name(String),
 }
 
 impl Collection {
@@ -65,11 +95,11 @@ impl Collection {
             "
             INSERT INTO collections (
                 id, user_id, name, description, 
-                created, icon_url, status
+                created, icon_url, status, source_search_query
             )
             VALUES (
                 $1, $2, $3, $4, 
-                $5, $6, $7
+                $5, $6, $7, $8
             )
             ",
             self.id as CollectionId,
@@ -79,6 +109,7 @@ impl Collection {
             self.created,
             self.icon_url.as_ref(),
             self.status.to_string(),
+            self.source_search_query.as_ref(),
         )
         .execute(&mut **transaction)
         .await?;
@@ -144,12 +175,45 @@ impl Collection {
     where
         E: sqlx::Executor<'a, Database = sqlx::Postgres>,
     {
-        Collection::get_many(&[id], executor, redis)
+        Collection::get_by_ids(&[id], executor, redis)
             .await
             .map(|x| x.into_iter().next())
     }
 
+    /// Gets up to `limit` collections with an ID greater than `cursor`, ordered by ID. Pass the
+    /// ID of the last collection of a page as the cursor of the next one.
     pub async fn get_many<'a, E>(
+        cursor: Option<CollectionId>,
+        limit: i64,
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<Vec<Collection>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres> + Copy,
+    {
+        let ids = sqlx::query!(
+            "
+            SELECT id FROM collections
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2
+            ",
+            cursor.map(|x| x.0).unwrap_or(i64::MIN),
+            limit,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| CollectionId(x.id))
+        .collect::<Vec<_>>();
+
+        let mut collections = Collection::get_by_ids(&ids, exec, redis).await?;
+        collections.sort_by_key(|x| x.id.0);
+
+        Ok(collections)
+    }
+
+    pub async fn get_by_ids<'a, E>(
         collection_ids: &[CollectionId],
         exec: E,
         redis: &RedisPool,
@@ -166,8 +230,9 @@ impl Collection {
                         "
                     SELECT c.id id, c.name name, c.description description,
                     c.icon_url icon_url, c.color color, c.created created, c.user_id user_id,
-                    c.updated updated, c.status status,
-                    ARRAY_AGG(DISTINCT cm.mod_id) filter (where cm.mod_id is not null) mods
+                    c.updated updated, c.status status, c.source_search_query source_search_query,
+                    ARRAY_AGG(cm.mod_id ORDER BY cm.mod_id) filter (where cm.mod_id is not null) mods,
+                    ARRAY_AGG(cm.added_at ORDER BY cm.mod_id) filter (where cm.mod_id is not null) mods_added_at
                     FROM collections c
                     LEFT JOIN collections_mods cm ON cm.collection_id = c.id
                     WHERE c.id = ANY($1)
@@ -177,6 +242,12 @@ impl Collection {
                     )
                     .fetch(exec)
                     .try_fold(DashMap::new(), |acc, m| {
+                        let projects = m
+                            .mods
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(ProjectId)
+                            .collect::<Vec<_>>();
                         let collection = Collection {
                             id: CollectionId(m.id),
                             user_id: UserId(m.user_id),
@@ -187,12 +258,13 @@ impl Collection {
                             created: m.created,
                             updated: m.updated,
                             status: CollectionStatus::from_string(&m.status),
-                            projects: m
-                                .mods
-                                .unwrap_or_default()
-                                .into_iter()
-                                .map(ProjectId)
+                            project_added_dates: projects
+                                .iter()
+                                .copied()
+                                .zip(m.mods_added_at.unwrap_or_default())
                                 .collect(),
+                            projects,
+                            source_search_query: m.source_search_query,
                         };
 
                         acc.insert(m.id, collection);
@@ -214,4 +286,171 @@ impl Collection {
         redis.delete(COLLECTIONS_NAMESPACE, id.0).await?;
         Ok(())
     }
+
+    /// Gets a page of a user's collection summaries, sorted by `sort` and then by ID so that
+    /// collections with the same sort value keep their order. Pages are keyset paginated: `after`
+    /// is the sort value and ID of the last collection of the previous page, so collections being
+    /// created while paginating do not shift the following pages. Projects are counted instead of
+    /// loaded.
+    pub async fn get_summaries_for_user<'a, E>(
+        user_id: UserId,
+        statuses: &[String],
+        sort: CollectionSort,
+        descending: bool,
+        after: Option<(CollectionSortValue, CollectionId)>,
+        limit: i64,
+        exec: E,
+    ) -> Result<Vec<CollectionSummary>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let (after_date, after_name, after_id) = match after {
+            Some((CollectionSortValue::Date(date), id)) => (Some(date), None, Some(id.0)),
+            Some((CollectionSortValue::Name(name), id)) => (None, Some(name), Some(id.0)),
+            None => (None, None, None),
+        };
+
+        let summaries = sqlx::query!(
+            r#"
+            SELECT c.id, c.name, c.description, c.icon_url, c.status, c.created, c.updated,
+            COUNT(cm.mod_id) AS "project_count!"
+            FROM collections c
+            LEFT JOIN collections_mods cm ON cm.collection_id = c.id
+            WHERE c.user_id = $1 AND c.status = ANY($2) AND (
+                $5::bigint IS NULL
+                OR ($3 = 'created' AND NOT $4 AND (c.created, c.id) > ($6, $5))
+                OR ($3 = 'created' AND $4 AND (c.created, c.id) < ($6, $5))
+                OR ($3 = 'updated' AND NOT $4 AND (c.updated, c.id) > ($6, $5))
+                OR ($3 = 'updated' AND $4 AND (c.updated, c.id) < ($6, $5))
+                OR ($3 = 'title' AND NOT $4 AND (c.name, c.id) > ($7, $5))
+                OR ($3 = 'title' AND $4 AND (c.name, c.id) < ($7, $5))
+            )
+            GROUP BY c.id
+            ORDER BY
+                CASE WHEN $3 = 'created' AND NOT $4 THEN c.created END ASC,
+                CASE WHEN $3 = 'created' AND $4 THEN c.created END DESC,
+                CASE WHEN $3 = 'updated' AND NOT $4 THEN c.updated END ASC,
+                CASE WHEN $3 = 'updated' AND $4 THEN c.updated END DESC,
+                CASE WHEN $3 = 'title' AND NOT $4 THEN c.name END ASC,
+                CASE WHEN $3 = 'title' AND $4 THEN c.name END DESC,
+                CASE WHEN $4 THEN c.id END DESC,
+                c.id ASC
+            LIMIT $8
+            "#,
+            user_id as UserId,
+            statuses,
+            sort.as_str(),
+            descending,
+            after_id,
+            after_date,
+            after_name,
+            limit,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| CollectionSummary {
+            id: CollectionId(x.id),
+            name: x.name,
+            description: x.description,
+            icon_url: x.icon_url,
+            status: CollectionStatus::from_string(&x.status),
+            project_count: x.project_count,
+            created: x.created,
+            updated: x.updated,
+        })
+        .collect();
+
+        Ok(summaries)
+    }
+
+    /// Gets the IDs of the featured collections, in the order they should be shown
+    pub async fn get_featured_ids<'a, E>(
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<Vec<CollectionId>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let mut redis = redis.connect().await?;
+        if let Some(ids) = redis
+            .get_deserialized_from_json::<Vec<CollectionId>>(
+                COLLECTIONS_NAMESPACE,
+                FEATURED_COLLECTIONS_KEY,
+            )
+            .await?
+        {
+            return Ok(ids);
+        }
+
+        let ids = sqlx::query!(
+            "
+            SELECT collection_id FROM featured_collections
+            ORDER BY ordering, created
+            "
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| CollectionId(x.collection_id))
+        .collect::<Vec<_>>();
+
+        redis
+            .set_serialized_to_json(
+                COLLECTIONS_NAMESPACE,
+                FEATURED_COLLECTIONS_KEY,
+                &ids,
+                Some(FEATURED_COLLECTIONS_EXPIRY),
+            )
+            .await?;
+
+        Ok(ids)
+    }
+
+    /// Features a collection, or changes its position if it is already featured
+    pub async fn set_featured(
+        id: CollectionId,
+        ordering: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO featured_collections (collection_id, ordering)
+            VALUES ($1, $2)
+            ON CONFLICT (collection_id) DO UPDATE SET ordering = EXCLUDED.ordering
+            ",
+            id as CollectionId,
+            ordering,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_featured(
+        id: CollectionId,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let result = sqlx::query!(
+            "
+            DELETE FROM featured_collections
+            WHERE collection_id = $1
+            ",
+            id as CollectionId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(()))
+    }
+
+    pub async fn clear_featured_cache(redis: &RedisPool) -> Result<(), DatabaseError> {
+        let mut redis = redis.connect().await?;
+
+        redis
+            .delete(COLLECTIONS_NAMESPACE, FEATURED_COLLECTIONS_KEY)
+            .await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,214 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The downloads of a project or version in one period of its history
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DownloadHistoryEntry {
+    pub date: NaiveDate,
+    pub downloads: i64,
+}
+
+/// The downloads and views of a project in one period of its history. A metric is absent if the
+/// project had no activity of that kind in the period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProjectTimelineEntry {
+    pub date: NaiveDate,
+    pub downloads: Option<i64>,
+    pub views: Option<i64>,
+}
+
+pub struct DownloadHistory;
+
+impl DownloadHistory {
+    /// Adds downloads to the daily history of their projects and versions. Downloads of projects or
+    /// versions that no longer exist are ignored.
+    pub async fn record(
+        downloads: &[(ProjectId, VersionId, NaiveDate)],
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        let mut project_downloads: HashMap<(ProjectId, NaiveDate), i32> = HashMap::new();
+        let mut version_downloads: HashMap<(VersionId, NaiveDate), i32> = HashMap::new();
+        for (project_id, version_id, date) in downloads {
+            *project_downloads.entry((*project_id, *date)).or_default() += 1;
+            *version_downloads.entry((*version_id, *date)).or_default() += 1;
+        }
+
+        let (project_keys, project_amounts): (Vec<_>, Vec<_>) =
+            project_downloads.into_iter().unzip();
+        let (project_ids, project_dates): (Vec<_>, Vec<_>) = project_keys
+            .into_iter()
+            .map(|(id, date)| (id.0, date))
+            .unzip();
+        sqlx::query!(
+            "
+            INSERT INTO project_downloads_history (project_id, date, downloads)
+            SELECT x.id, x.date, x.amount
+            FROM unnest($1::bigint[], $2::date[], $3::int[]) AS x(id, date, amount)
+            INNER JOIN mods m ON m.id = x.id
+            ON CONFLICT (project_id, date)
+            DO UPDATE SET downloads = project_downloads_history.downloads + EXCLUDED.downloads
+            ",
+            &project_ids,
+            &project_dates,
+            &project_amounts,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        let (version_keys, version_amounts): (Vec<_>, Vec<_>) =
+            version_downloads.into_iter().unzip();
+        let (version_ids, version_dates): (Vec<_>, Vec<_>) = version_keys
+            .into_iter()
+            .map(|(id, date)| (id.0, date))
+            .unzip();
+        sqlx::query!(
+            "
+            INSERT INTO version_downloads_history (version_id, date, downloads)
+            SELECT x.id, x.date, x.amount
+            FROM unnest($1::bigint[], $2::date[], $3::int[]) AS x(id, date, amount)
+            INNER JOIN versions v ON v.id = x.id
+            ON CONFLICT (version_id, date)
+            DO UPDATE SET downloads = version_downloads_history.downloads + EXCLUDED.downloads
+            ",
+            &version_ids,
+            &version_dates,
+            &version_amounts,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the downloads of a version between two dates (inclusive), summed per `resolution`
+    /// (`day`, `week` or `month`), oldest first. Periods without downloads are omitted.
+    pub async fn get_for_version<'a, E>(
+        version_id: VersionId,
+        resolution: &str,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+        exec: E,
+    ) -> Result<Vec<DownloadHistoryEntry>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let entries = sqlx::query!(
+            r#"
+            SELECT date_trunc($2, date::timestamp)::date AS "date!", SUM(downloads) AS "downloads!"
+            FROM version_downloads_history
+            WHERE version_id = $1
+                AND ($3::date IS NULL OR date >= $3)
+                AND ($4::date IS NULL OR date <= $4)
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            version_id as VersionId,
+            resolution,
+            start,
+            end,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| DownloadHistoryEntry {
+            date: x.date,
+            downloads: x.downloads,
+        })
+        .collect();
+
+        Ok(entries)
+    }
+
+    /// Gets the downloads and views of a project between two dates (inclusive), summed per
+    /// `resolution` (`day`, `week` or `month`), oldest first. Periods without any activity are
+    /// omitted.
+    pub async fn get_project_timeline<'a, E>(
+        project_id: ProjectId,
+        resolution: &str,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+        exec: E,
+    ) -> Result<Vec<ProjectTimelineEntry>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let entries = sqlx::query!(
+            r#"
+            WITH downloads AS (
+                SELECT date_trunc($2, date::timestamp)::date AS bucket, SUM(downloads) AS amount
+                FROM project_downloads_history
+                WHERE project_id = $1
+                    AND ($3::date IS NULL OR date >= $3)
+                    AND ($4::date IS NULL OR date <= $4)
+                GROUP BY 1
+            ), views AS (
+                SELECT date_trunc($2, date::timestamp)::date AS bucket, SUM(views) AS amount
+                FROM project_views_history
+                WHERE project_id = $1
+                    AND ($3::date IS NULL OR date >= $3)
+                    AND ($4::date IS NULL OR date <= $4)
+                GROUP BY 1
+            )
+            SELECT COALESCE(d.bucket, v.bucket) AS "date!", d.amount AS "downloads?", v.amount AS "views?"
+            FROM downloads d
+            FULL OUTER JOIN views v ON v.bucket = d.bucket
+            ORDER BY 1
+            "#,
+            project_id as ProjectId,
+            resolution,
+            start,
+            end,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| ProjectTimelineEntry {
+            date: x.date,
+            downloads: x.downloads,
+            views: x.views,
+        })
+        .collect();
+
+        Ok(entries)
+    }
+}
+
+pub struct ViewHistory;
+
+impl ViewHistory {
+    /// Adds page views to the daily history of their projects. Views of projects that no longer
+    /// exist are ignored.
+    pub async fn record(
+        views: &[(ProjectId, NaiveDate)],
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        let mut project_views: HashMap<(ProjectId, NaiveDate), i32> = HashMap::new();
+        for (project_id, date) in views {
+            *project_views.entry((*project_id, *date)).or_default() += 1;
+        }
+
+        let (keys, amounts): (Vec<_>, Vec<_>) = project_views.into_iter().unzip();
+        let (project_ids, dates): (Vec<_>, Vec<_>) =
+            keys.into_iter().map(|(id, date)| (id.0, date)).unzip();
+        sqlx::query!(
+            "
+            INSERT INTO project_views_history (project_id, date, views)
+            SELECT x.id, x.date, x.amount
+            FROM unnest($1::bigint[], $2::date[], $3::int[]) AS x(id, date, amount)
+            INNER JOIN mods m ON m.id = x.id
+            ON CONFLICT (project_id, date)
+            DO UPDATE SET views = project_views_history.views + EXCLUDED.views
+            ",
+            &project_ids,
+            &dates,
+            &amounts,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -35,6 +35,11 @@ pub enum Flow {
         user_id: UserId,
         confirm_email: String,
     },
+    /// A requested change of email, which is applied once the new address is verified
+    ChangeEmail {
+        user_id: UserId,
+        new_email: String,
+    },
     MinecraftAuth,
     InitOAuthAppApproval {
         user_id: UserId,
@@ -403,6 +403,16 @@ impl From<VersionId> for ids::VersionId {
         ids::VersionId(id.0 as u64)
     }
 }
+impl From<ids::FileId> for FileId {
+    fn from(id: ids::FileId) -> Self {
+        FileId(id.0 as i64)
+    }
+}
+impl From<FileId> for ids::FileId {
+    fn from(id: FileId) -> Self {
+        ids::FileId(id.0 as u64)
+    }
+}
 impl From<ids::CollectionId> for CollectionId {
     fn from(id: ids::CollectionId) -> Self {
         CollectionId(id.0 as i64)
@@ -24,6 +24,17 @@ pub struct MinecraftGameVersion {
     pub type_: String,
     pub created: DateTime<Utc>,
     pub major: bool,
+    /// The numeric components of the version, used to sort versions without comparing strings.
+    /// Only set on versions added by moderators.
+    pub semver: Option<GameVersionSemver>,
+}
+
+/// The `major.minor.patch` components of a game version, ordered numerically
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameVersionSemver {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
 }
 
 impl MinecraftGameVersion {
@@ -72,6 +83,70 @@ impl MinecraftGameVersion {
         Ok(game_versions)
     }
 
+    /// Expands game version range notation into the game versions it matches. This is either a
+    /// pattern where `x` or `*` matches any component (ex: `1.20.x`, which includes `1.20`), or a
+    /// comparison of release dates against another version (ex: `>= 1.19`).
+    ///
+    /// Other values, and ranges matching no versions, are returned as is.
+    pub fn expand_range(value: &str, game_versions: &[MinecraftGameVersion]) -> Vec<String> {
+        let value = value.trim();
+        let is_wildcard = |x: &str| x == "x" || x == "*";
+
+        let matching = if let Some((operator, version)) = ["<=", ">=", "<", ">"]
+            .iter()
+            .find_map(|op| value.strip_prefix(*op).map(|x| (*op, x.trim())))
+        {
+            let Some(date) = game_versions
+                .iter()
+                .find(|x| x.version == version)
+                .map(|x| x.created)
+            else {
+                return vec![value.to_string()];
+            };
+
+            game_versions
+                .iter()
+                .filter(|x| match operator {
+                    "<=" => x.created <= date,
+                    ">=" => x.created >= date,
+                    "<" => x.created < date,
+                    _ => x.created > date,
+                })
+                .collect_vec()
+        } else if value.split('.').any(is_wildcard) {
+            let pattern = value.split('.').collect_vec();
+            // Trailing wildcards may be omitted, so `1.20.x` matches `1.20`
+            let required = pattern.iter().rev().skip_while(|x| is_wildcard(x)).count();
+
+            game_versions
+                .iter()
+                .filter(|x| {
+                    let components = x.version.split('.').collect_vec();
+                    components.len() >= required
+                        && components.len() <= pattern.len()
+                        && pattern
+                            .iter()
+                            .zip(&components)
+                            .all(|(p, c)| is_wildcard(p) || p == c)
+                })
+                .collect_vec()
+        } else {
+            Vec::new()
+        };
+
+        if matching.is_empty() {
+            vec![value.to_string()]
+        } else {
+            matching.into_iter().map(|x| x.version.clone()).collect()
+        }
+    }
+
+    /// Whether a game version filter uses range notation, see [`Self::expand_range`]
+    pub fn is_range(value: &str) -> bool {
+        let value = value.trim();
+        value.starts_with(['<', '>']) || value.split('.').any(|x| x == "x" || x == "*")
+    }
+
     // Tries to create a MinecraftGameVersion from a VersionField
     // Clones on success
     pub fn try_from_version_field(
@@ -121,6 +196,10 @@ impl MinecraftGameVersion {
                 .get("major")
                 .and_then(|x| x.as_bool())
                 .unwrap_or_default(),
+            semver: loader_field_enum_value
+                .metadata
+                .get("semver")
+                .and_then(|x| serde_json::from_value(x.clone()).ok()),
         }
     }
 }
@@ -130,6 +209,7 @@ pub struct MinecraftGameVersionBuilder<'a> {
     pub version: Option<&'a str>,
     pub version_type: Option<&'a str>,
     pub date: Option<&'a DateTime<Utc>>,
+    pub semver: Option<GameVersionSemver>,
 }
 
 impl<'a> MinecraftGameVersionBuilder<'a> {
@@ -164,6 +244,13 @@ impl<'a> MinecraftGameVersionBuilder<'a> {
         }
     }
 
+    pub fn semver(self, semver: GameVersionSemver) -> MinecraftGameVersionBuilder<'a> {
+        Self {
+            semver: Some(semver),
+            ..self
+        }
+    }
+
     pub async fn insert<'b, E>(
         self,
         exec: E,
@@ -179,10 +266,13 @@ impl<'a> MinecraftGameVersionBuilder<'a> {
             ))?;
 
         // Get enum id for game versions
-        let metadata = json!({
+        let mut metadata = json!({
             "type": self.version_type,
             "major": false
         });
+        if let Some(semver) = self.semver {
+            metadata["semver"] = json!(semver);
+        }
 
         // This looks like a mess, but it *should* work
         // This allows game versions to be partially updated without
@@ -193,9 +283,13 @@ impl<'a> MinecraftGameVersionBuilder<'a> {
                 VALUES ($1, $2, COALESCE($3, timezone('utc', now())), $4)
                 ON CONFLICT (enum_id, value) DO UPDATE
                     SET metadata = jsonb_set(
-                        COALESCE(loader_field_enum_values.metadata, $4),
-                        '{type}', 
-                        COALESCE($4->'type', loader_field_enum_values.metadata->'type')
+                        jsonb_set(
+                            COALESCE(loader_field_enum_values.metadata, $4),
+                            '{type}', 
+                            COALESCE($4->'type', loader_field_enum_values.metadata->'type')
+                        ),
+                        '{semver}',
+                        COALESCE($4->'semver', loader_field_enum_values.metadata->'semver', 'null')
                     ),
                     created = COALESCE($3, loader_field_enum_values.created)
                 RETURNING id
@@ -218,3 +312,51 @@ impl<'a> MinecraftGameVersionBuilder<'a> {
         Ok(LoaderFieldEnumValueId(result.id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_version(version: &str, day: u32) -> MinecraftGameVersion {
+        MinecraftGameVersion {
+            id: LoaderFieldEnumValueId(0),
+            version: version.to_string(),
+            type_: "release".to_string(),
+            created: chrono::TimeZone::with_ymd_and_hms(&Utc, 2023, 1, day, 0, 0, 0).unwrap(),
+            major: false,
+            semver: None,
+        }
+    }
+
+    #[test]
+    fn expand_range_matches_patterns_and_comparisons() {
+        let game_versions = vec![
+            game_version("1.19.4", 1),
+            game_version("1.20", 2),
+            game_version("1.20.1", 3),
+            game_version("23w31a", 4),
+        ];
+
+        assert_eq!(
+            MinecraftGameVersion::expand_range("1.20.x", &game_versions),
+            vec!["1.20", "1.20.1"]
+        );
+        assert_eq!(
+            MinecraftGameVersion::expand_range(">= 1.20", &game_versions),
+            vec!["1.20", "1.20.1", "23w31a"]
+        );
+        assert_eq!(
+            MinecraftGameVersion::expand_range("<1.20", &game_versions),
+            vec!["1.19.4"]
+        );
+        // Values that aren't ranges, or match nothing, are kept as is
+        assert_eq!(
+            MinecraftGameVersion::expand_range("1.18.x", &game_versions),
+            vec!["1.18.x"]
+        );
+        assert_eq!(
+            MinecraftGameVersion::expand_range("1.19.4", &game_versions),
+            vec!["1.19.4"]
+        );
+    }
+}
@@ -89,6 +89,8 @@ pub struct Loader {
     pub supported_project_types: Vec<String>,
     pub supported_games: Vec<String>, // slugs
     pub metadata: serde_json::Value,
+    pub deprecated: bool,
+    pub superseded_by: Option<String>,
 }
 
 impl Loader {
@@ -141,6 +143,7 @@ impl Loader {
         let result = sqlx::query!(
             "
             SELECT l.id id, l.loader loader, l.icon icon, l.metadata metadata,
+            l.deprecated deprecated, l.superseded_by superseded_by,
             ARRAY_AGG(DISTINCT pt.name) filter (where pt.name is not null) project_types,
             ARRAY_AGG(DISTINCT g.slug) filter (where g.slug is not null) games
             FROM loaders l            
@@ -166,8 +169,9 @@ impl Loader {
                 supported_games: x
                     .games
                     .unwrap_or_default(),
-                metadata: x.metadata
-
+                metadata: x.metadata,
+                deprecated: x.deprecated,
+                superseded_by: x.superseded_by,
             }))
         })
         .try_collect::<Vec<_>>()
@@ -179,6 +183,38 @@ impl Loader {
 
         Ok(result)
     }
+
+    /// Sets whether the loader is deprecated and which loader replaces it.
+    /// Returns whether the loader exists.
+    pub async fn set_deprecation<'a, E>(
+        name: &str,
+        deprecated: bool,
+        superseded_by: Option<&str>,
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<bool, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let updated = sqlx::query!(
+            "
+            UPDATE loaders
+            SET deprecated = $2, superseded_by = $3
+            WHERE loader = $1
+            ",
+            name,
+            deprecated,
+            superseded_by,
+        )
+        .execute(exec)
+        .await?
+        .rows_affected();
+
+        let mut redis = redis.connect().await?;
+        redis.delete(LOADERS_LIST_NAMESPACE, "all").await?;
+
+        Ok(updated > 0)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -0,0 +1,116 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+use serde::{Deserialize, Serialize};
+
+/// Maps versions of a loader to the game version they were made for
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LoaderVersionMapping {
+    pub id: i32,
+    pub loader_id: LoaderId,
+    pub loader_version_pattern: String,
+    pub game_version: String,
+}
+
+impl LoaderVersionMapping {
+    pub async fn insert(
+        loader_id: LoaderId,
+        loader_version_pattern: &str,
+        game_version: &str,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<i32, DatabaseError> {
+        let id = sqlx::query!(
+            "
+            INSERT INTO loader_version_game_version_map (loader_id, loader_version_pattern, game_version)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (loader_id, loader_version_pattern, game_version)
+            DO UPDATE SET game_version = EXCLUDED.game_version
+            RETURNING id
+            ",
+            loader_id as LoaderId,
+            loader_version_pattern,
+            game_version,
+        )
+        .fetch_one(&mut **transaction)
+        .await?
+        .id;
+
+        Ok(id)
+    }
+
+    pub async fn remove(
+        id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let result = sqlx::query!(
+            "
+            DELETE FROM loader_version_game_version_map
+            WHERE id = $1
+            ",
+            id,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(()))
+    }
+
+    pub async fn list_for_loaders<'a, E>(
+        loader_ids: &[LoaderId],
+        exec: E,
+    ) -> Result<Vec<LoaderVersionMapping>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let mappings = sqlx::query!(
+            "
+            SELECT id, loader_id, loader_version_pattern, game_version
+            FROM loader_version_game_version_map
+            WHERE loader_id = ANY($1)
+            ORDER BY loader_version_pattern, game_version
+            ",
+            &loader_ids.iter().map(|x| x.0).collect::<Vec<_>>(),
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| LoaderVersionMapping {
+            id: x.id,
+            loader_id: LoaderId(x.loader_id),
+            loader_version_pattern: x.loader_version_pattern,
+            game_version: x.game_version,
+        })
+        .collect();
+
+        Ok(mappings)
+    }
+
+    /// Whether a loader version matches this mapping's pattern, which is either an exact version
+    /// or a prefix followed by `*`
+    pub fn matches(&self, loader_version: &str) -> bool {
+        match self.loader_version_pattern.strip_suffix('*') {
+            Some(prefix) => loader_version.starts_with(prefix),
+            None => self.loader_version_pattern == loader_version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_patterns_match_prefixes() {
+        let mapping = |pattern: &str| LoaderVersionMapping {
+            id: 0,
+            loader_id: LoaderId(0),
+            loader_version_pattern: pattern.to_string(),
+            game_version: "1.18.2".to_string(),
+        };
+
+        assert!(mapping("41.*").matches("41.1.0"));
+        assert!(!mapping("41.*").matches("40.2.0"));
+        assert!(!mapping("4.*").matches("41.1.0"));
+        assert!(mapping("41.1.0").matches("41.1.0"));
+        assert!(!mapping("41.1").matches("41.1.0"));
+    }
+}
@@ -1,12 +1,16 @@
 use thiserror::Error;
 
+pub mod backfill_job_item;
 pub mod categories;
 pub mod collection_item;
+pub mod download_history_item;
 pub mod flow_item;
 pub mod ids;
 pub mod image_item;
 pub mod legacy_loader_fields;
 pub mod loader_fields;
+pub mod loader_version_map_item;
+pub mod moderation_log_item;
 pub mod notification_item;
 pub mod oauth_client_authorization_item;
 pub mod oauth_client_item;
@@ -14,7 +18,11 @@ pub mod oauth_token_item;
 pub mod organization_item;
 pub mod pat_item;
 pub mod payout_item;
+pub mod project_audit_log_item;
+pub mod project_event_item;
 pub mod project_item;
+pub mod project_status_message_item;
+pub mod push_token_item;
 pub mod report_item;
 pub mod session_item;
 pub mod team_item;
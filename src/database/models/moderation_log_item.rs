@@ -0,0 +1,44 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+
+/// An action taken by a moderator or admin, kept for accountability
+pub struct ModerationLogBuilder {
+    pub moderator_id: UserId,
+    pub action: &'static str,
+    /// The kind of item the action was taken on, `user` or `version`
+    pub target_type: &'static str,
+    pub target_user_id: Option<UserId>,
+    pub target_version_id: Option<VersionId>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl ModerationLogBuilder {
+    pub async fn insert(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO moderation_log (
+                moderator_id, action, target_type, target_user_id, target_version_id,
+                old_value, new_value
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7
+            )
+            ",
+            self.moderator_id as UserId,
+            self.action,
+            self.target_type,
+            self.target_user_id.map(|x| x.0),
+            self.target_version_id.map(|x| x.0),
+            self.old_value,
+            self.new_value,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
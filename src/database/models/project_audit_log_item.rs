@@ -0,0 +1,89 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub struct ProjectAuditLogBuilder {
+    pub project_id: ProjectId,
+    pub user_id: UserId,
+    pub changed_field: &'static str,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProjectAuditLogEntry {
+    pub id: i64,
+    pub project_id: ProjectId,
+    pub user_id: Option<UserId>,
+    pub changed_field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectAuditLogBuilder {
+    pub async fn insert(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO project_audit_log (
+                project_id, user_id, changed_field, old_value, new_value
+            )
+            VALUES (
+                $1, $2, $3, $4, $5
+            )
+            ",
+            self.project_id as ProjectId,
+            self.user_id as UserId,
+            self.changed_field,
+            self.old_value,
+            self.new_value,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
+
+impl ProjectAuditLogEntry {
+    /// Gets the most recent audit log entries of a project, newest first
+    pub async fn get_for_project<'a, E>(
+        project_id: ProjectId,
+        limit: i64,
+        exec: E,
+    ) -> Result<Vec<ProjectAuditLogEntry>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let entries = sqlx::query!(
+            "
+            SELECT id, project_id, user_id, changed_field, old_value, new_value, created_at
+            FROM project_audit_log
+            WHERE project_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            ",
+            project_id as ProjectId,
+            limit,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| ProjectAuditLogEntry {
+            id: x.id,
+            project_id: ProjectId(x.project_id),
+            user_id: x.user_id.map(UserId),
+            changed_field: x.changed_field,
+            old_value: x.old_value,
+            new_value: x.new_value,
+            created_at: x.created_at,
+        })
+        .collect();
+
+        Ok(entries)
+    }
+}
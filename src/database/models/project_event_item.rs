@@ -0,0 +1,68 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An entry of a project's timeline. Events are recorded by database triggers, so there is no
+/// builder.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProjectEvent {
+    pub id: i64,
+    pub project_id: ProjectId,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub user_id: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectEvent {
+    /// Gets the events of a project after the event with the given ID, oldest first.
+    ///
+    /// If `version_statuses` is set, only version publish events of versions that still exist with
+    /// one of these statuses are returned.
+    pub async fn get_for_project<'a, E>(
+        project_id: ProjectId,
+        after: Option<i64>,
+        limit: i64,
+        version_statuses: Option<&[String]>,
+        exec: E,
+    ) -> Result<Vec<ProjectEvent>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let events = sqlx::query!(
+            "
+            SELECT e.id, e.project_id, e.event_type, e.payload, e.user_id, e.created_at
+            FROM project_events e
+            WHERE e.project_id = $1 AND e.id > $2 AND (
+                $4::varchar[] IS NULL OR (
+                    e.event_type = 'version_published' AND EXISTS (
+                        SELECT 1 FROM versions v
+                        WHERE v.id = (e.payload->>'version_id')::bigint AND v.status = ANY($4) AND v.deleted_at IS NULL
+                    )
+                )
+            )
+            ORDER BY e.id
+            LIMIT $3
+            ",
+            project_id as ProjectId,
+            after.unwrap_or(0),
+            limit,
+            version_statuses,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| ProjectEvent {
+            id: x.id,
+            project_id: ProjectId(x.project_id),
+            event_type: x.event_type,
+            payload: x.payload,
+            user_id: x.user_id.map(UserId),
+            created_at: x.created_at,
+        })
+        .collect();
+
+        Ok(events)
+    }
+}
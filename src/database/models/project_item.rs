@@ -6,18 +6,63 @@ use crate::database::models;
 use crate::database::models::DatabaseError;
 use crate::database::redis::RedisPool;
 use crate::models::ids::base62_impl::parse_base62;
-use crate::models::projects::{MonetizationStatus, ProjectStatus};
+use crate::models::projects::{MonetizationStatus, ProjectStatus, VersionStatus};
 use chrono::{DateTime, Utc};
 use dashmap::{DashMap, DashSet};
 use futures::TryStreamExt;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
 pub const PROJECTS_NAMESPACE: &str = "projects";
 pub const PROJECTS_SLUGS_NAMESPACE: &str = "projects_slugs";
-const PROJECTS_DEPENDENCIES_NAMESPACE: &str = "projects_dependencies";
+pub const PROJECTS_DEPENDENCIES_NAMESPACE: &str = "projects_dependencies";
+pub const PROJECTS_EMBEDS_NAMESPACE: &str = "projects_embeds";
+pub const PROJECTS_VERSION_COUNTS_NAMESPACE: &str = "projects_version_counts";
+pub const PROJECTS_LATEST_FILES_NAMESPACE: &str = "projects_latest_files";
+pub const PROJECTS_VERSION_MATRICES_NAMESPACE: &str = "projects_version_matrices";
+pub const PROJECTS_GAME_VERSION_VERSIONS_NAMESPACE: &str = "projects_game_version_versions";
+pub const PROJECTS_RELATED_NAMESPACE: &str = "projects_related";
+pub const PROJECTS_SEEDED_RANDOM_NAMESPACE: &str = "projects_seeded_random";
+pub const PROJECTS_BADGES_NAMESPACE: &str = "projects_badges";
+pub const PROJECTS_LATEST_PER_LOADER_NAMESPACE: &str = "projects_latest_per_loader";
+pub const PROJECTS_SUPPORTED_GAME_VERSIONS_NAMESPACE: &str = "projects_supported_game_versions";
+
+/// How long a deleted project is kept, and shown as gone, before it is permanently deleted
+pub const PROJECT_DELETION_DELAY_DAYS: i32 = 30;
+
+/// How long version counts are cached, in seconds
+const VERSION_COUNTS_EXPIRY: i64 = 2 * 60;
+/// How long the latest versions per loader are cached, in seconds
+const LATEST_PER_LOADER_EXPIRY: i64 = 3 * 60;
+/// How long version matrices are cached, in seconds
+const VERSION_MATRIX_EXPIRY: i64 = 5 * 60;
+/// How long the game versions supported by a project are cached, in seconds
+const SUPPORTED_GAME_VERSIONS_EXPIRY: i64 = 5 * 60;
+/// How long related projects are cached, in seconds
+const RELATED_PROJECTS_EXPIRY: i64 = 15 * 60;
+/// How long the projects picked by a seed are cached, in seconds
+const SEEDED_RANDOM_PROJECTS_EXPIRY: i64 = 60 * 60;
+/// The key of the featured projects list, in the projects namespace
+const FEATURED_PROJECTS_KEY: &str = "featured";
+const FEATURED_PROJECTS_EXPIRY: i64 = 60 * 10;
+/// The most related projects kept for a project
+pub const MAX_RELATED_PROJECTS: i64 = 100;
+/// The most projects picked by a seed, the largest count of the random projects route
+pub const MAX_SEEDED_RANDOM_PROJECTS: i64 = 100;
+
+/// The latest listed version of a project for each loader and game version it supports.
+/// `matrix[i][j]` is the latest version supporting `loaders[i]` and `game_versions[j]`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionMatrix {
+    /// Sorted alphabetically
+    pub loaders: Vec<String>,
+    /// Sorted by their `major.minor.patch` components, oldest first
+    pub game_versions: Vec<String>,
+    pub matrix: Vec<Vec<Option<VersionId>>>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LinkUrl {
@@ -165,6 +210,7 @@ pub struct ProjectBuilder {
     pub gallery_items: Vec<GalleryItem>,
     pub color: Option<u32>,
     pub monetization_status: MonetizationStatus,
+    pub forked_from: Option<ProjectId>,
 }
 
 impl ProjectBuilder {
@@ -201,6 +247,10 @@ impl ProjectBuilder {
             color: self.color,
             monetization_status: self.monetization_status,
             loaders: vec![],
+            version: 1,
+            forked_from: self.forked_from,
+            deleted_at: None,
+            featured: false,
         };
         project_struct.insert(&mut *transaction).await?;
 
@@ -262,6 +312,11 @@ pub struct Project {
     pub color: Option<u32>,
     pub monetization_status: MonetizationStatus,
     pub loaders: Vec<String>,
+    pub version: i32,
+    pub forked_from: Option<ProjectId>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub featured: bool,
 }
 
 impl Project {
@@ -275,13 +330,15 @@ impl Project {
                 id, team_id, name, summary, description,
                 published, downloads, icon_url, status, requested_status,
                 license_url, license,
-                slug, color, monetization_status, organization_id
+                slug, color, monetization_status, organization_id,
+                forked_from
             )
             VALUES (
                 $1, $2, $3, $4, $5, $6, 
                 $7, $8, $9, $10, 
                 $11, $12, 
-                LOWER($13), $14, $15, $16
+                LOWER($13), $14, $15, $16,
+                $17
             )
             ",
             self.id as ProjectId,
@@ -300,6 +357,7 @@ impl Project {
             self.color.map(|x| x as i32),
             self.monetization_status.as_str(),
             self.organization_id.map(|x| x.0 as i64),
+            self.forked_from.map(|x| x.0),
         )
         .execute(&mut **transaction)
         .await?;
@@ -307,6 +365,97 @@ impl Project {
         Ok(())
     }
 
+    /// Hides a project and schedules it to be permanently deleted. Returns when it will be, or
+    /// `None` if the project is already deleted. The slug stays taken until then, so links to
+    /// the project keep resolving to it and answer that it is gone.
+    pub async fn soft_delete(
+        id: ProjectId,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        redis: &RedisPool,
+    ) -> Result<Option<DateTime<Utc>>, DatabaseError> {
+        let Some(slug) = sqlx::query!(
+            "
+            UPDATE mods
+            SET deleted_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING slug
+            ",
+            id as ProjectId,
+        )
+        .fetch_optional(&mut **transaction)
+        .await?
+        .map(|x| x.slug) else {
+            return Ok(None);
+        };
+
+        let scheduled_for = sqlx::query!(
+            "
+            INSERT INTO scheduled_deletions (mod_id, scheduled_for)
+            VALUES ($1, NOW() + make_interval(days => $2))
+            ON CONFLICT (mod_id) DO UPDATE SET scheduled_for = EXCLUDED.scheduled_for
+            RETURNING scheduled_for
+            ",
+            id as ProjectId,
+            PROJECT_DELETION_DELAY_DAYS,
+        )
+        .fetch_one(&mut **transaction)
+        .await?
+        .scheduled_for;
+
+        clear_project_caches(id, slug, redis).await?;
+
+        Ok(Some(scheduled_for))
+    }
+
+    /// Gets the deleted projects whose permanent deletion is due
+    pub async fn get_due_deletions<'a, E>(exec: E) -> Result<Vec<ProjectId>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let ids = sqlx::query!(
+            "
+            SELECT mod_id FROM scheduled_deletions
+            WHERE scheduled_for <= NOW()
+            ",
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| ProjectId(x.mod_id))
+        .collect();
+
+        Ok(ids)
+    }
+
+    /// Whether a version of another project requires this project
+    pub async fn has_required_dependents<'a, E>(
+        id: ProjectId,
+        exec: E,
+    ) -> Result<bool, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let exists = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM dependencies d
+                INNER JOIN versions v ON v.id = d.dependent_id AND v.deleted_at IS NULL
+                INNER JOIN mods m ON m.id = v.mod_id AND m.deleted_at IS NULL
+                LEFT JOIN versions dv ON dv.id = d.dependency_id
+                WHERE d.dependency_type = $2 AND v.mod_id <> $1
+                AND (d.mod_dependency_id = $1 OR dv.mod_id = $1)
+            ) AS "exists!"
+            "#,
+            id as ProjectId,
+            crate::models::projects::DependencyType::Required.as_str(),
+        )
+        .fetch_one(exec)
+        .await?
+        .exists;
+
+        Ok(exists)
+    }
+
     pub async fn remove(
         id: ProjectId,
         transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -315,7 +464,7 @@ impl Project {
         let project = Self::get_id(id, &mut **transaction, redis).await?;
 
         if let Some(project) = project {
-            Project::clear_cache(id, project.inner.slug, Some(true), redis).await?;
+            clear_project_caches(id, project.inner.slug, redis).await?;
 
             sqlx::query!(
                 "
@@ -399,8 +548,17 @@ impl Project {
             .execute(&mut **transaction)
             .await?;
 
-            for version in project.versions {
-                super::Version::remove_full(version, redis, transaction).await?;
+            // Deleted versions are not part of the project anymore, but still have to be removed
+            let version_ids = sqlx::query!(
+                "
+                SELECT id FROM versions WHERE mod_id = $1
+                ",
+                id as ProjectId,
+            )
+            .fetch_all(&mut **transaction)
+            .await?;
+            for version in version_ids {
+                super::Version::remove_full(VersionId(version.id), redis, transaction).await?;
             }
 
             sqlx::query!(
@@ -537,7 +695,7 @@ impl Project {
                     "
                     SELECT DISTINCT mod_id, v.id as id, date_published
                     FROM mods m
-                    INNER JOIN versions v ON m.id = v.mod_id AND v.status = ANY($3)
+                    INNER JOIN versions v ON m.id = v.mod_id AND v.status = ANY($3) AND v.deleted_at IS NULL
                     WHERE m.id = ANY($1) OR m.slug = ANY($2)
                     ",
                     &project_ids_parsed,
@@ -747,7 +905,11 @@ impl Project {
                     m.license_url license_url,
                     m.team_id team_id, m.organization_id organization_id, m.license license, m.slug slug, m.moderation_message moderation_message, m.moderation_message_body moderation_message_body,
                     m.webhook_sent, m.color,
-                    t.id thread_id, m.monetization_status monetization_status,
+                    t.id thread_id, m.monetization_status monetization_status, m.version version, m.forked_from, m.deleted_at,
+                    EXISTS(
+                        SELECT 1 FROM featured_projects fp
+                        WHERE fp.project_id = m.id AND (fp.expires_at IS NULL OR fp.expires_at > NOW())
+                    ) \"featured!\",
                     ARRAY_AGG(DISTINCT c.category) filter (where c.category is not null and mc.is_additional is false) categories,
                     ARRAY_AGG(DISTINCT c.category) filter (where c.category is not null and mc.is_additional is true) additional_categories
                     FROM mods m
@@ -811,6 +973,10 @@ impl Project {
                                     &m.monetization_status,
                                 ),
                                 loaders,
+                                version: m.version,
+                                forked_from: m.forked_from.map(ProjectId),
+                                deleted_at: m.deleted_at,
+                                featured: m.featured,
                             },
                             categories: m.categories.unwrap_or_default(),
                             additional_categories: m.additional_categories.unwrap_or_default(),
@@ -870,7 +1036,7 @@ impl Project {
             FROM versions v
             INNER JOIN dependencies d ON d.dependent_id = v.id
             LEFT JOIN versions vd ON d.dependency_id = vd.id
-            WHERE v.mod_id = $1
+            WHERE v.mod_id = $1 AND v.deleted_at IS NULL
             ",
             id as ProjectId
         )
@@ -897,30 +1063,554 @@ impl Project {
         Ok(dependencies)
     }
 
-    pub async fn clear_cache(
+    /// Counts the listed versions of a project by loader and game version
+    pub async fn get_version_counts<'a, E>(
+        id: ProjectId,
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        type VersionCounts = HashMap<String, HashMap<String, i64>>;
+
+        let mut redis = redis.connect().await?;
+
+        let counts = redis
+            .get_deserialized_from_json::<VersionCounts>(
+                PROJECTS_VERSION_COUNTS_NAMESPACE,
+                &id.0.to_string(),
+            )
+            .await?;
+        if let Some(counts) = counts {
+            return Ok(counts);
+        }
+
+        // Drafts and archived (yanked) versions are not counted
+        let statuses = [VersionStatus::Listed.as_str().to_string()];
+        let counts = sqlx::query!(
+            "
+            SELECT l.loader, lfev.value game_version, COUNT(DISTINCT v.id) count
+            FROM versions v
+            INNER JOIN loaders_versions lv ON lv.version_id = v.id
+            INNER JOIN loaders l ON l.id = lv.loader_id
+            INNER JOIN version_fields vf ON vf.version_id = v.id
+            INNER JOIN loader_fields lf ON lf.id = vf.field_id AND lf.field = 'game_versions'
+            INNER JOIN loader_field_enum_values lfev ON lfev.id = vf.enum_value
+            WHERE v.mod_id = $1 AND v.status = ANY($2) AND v.deleted_at IS NULL
+            GROUP BY l.loader, lfev.value
+            ",
+            id as ProjectId,
+            &statuses,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .fold(VersionCounts::new(), |mut acc, x| {
+            acc.entry(x.loader)
+                .or_default()
+                .insert(x.game_version, x.count.unwrap_or(0));
+            acc
+        });
+
+        redis
+            .set_serialized_to_json(
+                PROJECTS_VERSION_COUNTS_NAMESPACE,
+                id.0,
+                &counts,
+                Some(VERSION_COUNTS_EXPIRY),
+            )
+            .await?;
+        Ok(counts)
+    }
+
+    /// Gets the latest listed version of a project for each loader it supports. Releases are
+    /// preferred, then betas, then alphas.
+    pub async fn get_latest_versions_per_loader(
         id: ProjectId,
-        slug: Option<String>,
-        clear_dependencies: Option<bool>,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<HashMap<String, VersionId>, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+
+        let latest = redis_connection
+            .get_deserialized_from_json::<HashMap<String, VersionId>>(
+                PROJECTS_LATEST_PER_LOADER_NAMESPACE,
+                &id.0.to_string(),
+            )
+            .await?;
+        if let Some(latest) = latest {
+            return Ok(latest);
+        }
+
+        // Drafts and archived (yanked) versions are left out
+        let statuses = [VersionStatus::Listed.as_str().to_string()];
+        let latest = sqlx::query!(
+            r#"
+            SELECT loader AS "loader!", version_id AS "version_id!"
+            FROM (
+                SELECT l.loader, v.id version_id, ROW_NUMBER() OVER (
+                    PARTITION BY l.loader
+                    ORDER BY
+                        CASE v.version_type WHEN 'release' THEN 0 WHEN 'beta' THEN 1 ELSE 2 END,
+                        v.date_published DESC,
+                        v.id DESC
+                ) row_number
+                FROM versions v
+                INNER JOIN loaders_versions lv ON lv.version_id = v.id
+                INNER JOIN loaders l ON l.id = lv.loader_id
+                WHERE v.mod_id = $1 AND v.status = ANY($2) AND v.deleted_at IS NULL
+            ) ranked
+            WHERE row_number = 1
+            "#,
+            id as ProjectId,
+            &statuses,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| (x.loader, VersionId(x.version_id)))
+        .collect::<HashMap<_, _>>();
+
+        redis_connection
+            .set_serialized_to_json(
+                PROJECTS_LATEST_PER_LOADER_NAMESPACE,
+                id.0,
+                &latest,
+                Some(LATEST_PER_LOADER_EXPIRY),
+            )
+            .await?;
+        Ok(latest)
+    }
+
+    /// Gets the searchable projects sharing at least two categories and a project type with a
+    /// project, most downloaded first
+    pub async fn get_related(
+        id: ProjectId,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<Vec<ProjectId>, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+
+        let related = redis_connection
+            .get_deserialized_from_json::<Vec<ProjectId>>(
+                PROJECTS_RELATED_NAMESPACE,
+                &id.0.to_string(),
+            )
+            .await?;
+        if let Some(related) = related {
+            return Ok(related);
+        }
+
+        let related = sqlx::query!(
+            "
+            WITH project_categories AS (
+                SELECT joining_category_id FROM mods_categories
+                WHERE joining_mod_id = $1
+            ),
+            source_types AS (
+                SELECT DISTINCT lpt.joining_project_type_id FROM versions v
+                INNER JOIN loaders_versions lv ON lv.version_id = v.id
+                INNER JOIN loaders_project_types lpt ON lpt.joining_loader_id = lv.loader_id
+                WHERE v.mod_id = $1 AND v.deleted_at IS NULL
+            ),
+            category_overlaps AS (
+                SELECT mc.joining_mod_id mod_id, COUNT(DISTINCT mc.joining_category_id) overlap
+                FROM mods_categories mc
+                INNER JOIN project_categories pc ON pc.joining_category_id = mc.joining_category_id
+                WHERE mc.joining_mod_id <> $1
+                GROUP BY mc.joining_mod_id
+            )
+            SELECT m.id FROM category_overlaps o
+            INNER JOIN mods m ON m.id = o.mod_id
+            WHERE o.overlap >= 2 AND m.status = ANY($2) AND m.deleted_at IS NULL
+            AND EXISTS (
+                SELECT 1 FROM versions v
+                INNER JOIN loaders_versions lv ON lv.version_id = v.id
+                INNER JOIN loaders_project_types lpt ON lpt.joining_loader_id = lv.loader_id
+                INNER JOIN source_types st ON st.joining_project_type_id = lpt.joining_project_type_id
+                WHERE v.mod_id = m.id AND v.deleted_at IS NULL
+            )
+            ORDER BY m.downloads DESC, m.id
+            LIMIT $3
+            ",
+            id as ProjectId,
+            &*ProjectStatus::iterator()
+                .filter(|x| x.is_searchable())
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>(),
+            MAX_RELATED_PROJECTS,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| ProjectId(x.id))
+        .collect::<Vec<_>>();
+
+        redis_connection
+            .set_serialized_to_json(
+                PROJECTS_RELATED_NAMESPACE,
+                id.0,
+                &related,
+                Some(RELATED_PROJECTS_EXPIRY),
+            )
+            .await?;
+        Ok(related)
+    }
+
+    /// Gets searchable projects in an order given by a seed, so the same seed picks the same
+    /// projects. Every project is ordered to pick them, so the first
+    /// [`MAX_SEEDED_RANDOM_PROJECTS`] are cached per seed and smaller counts take a prefix.
+    pub async fn get_seeded_random(
+        seed: &str,
+        count: usize,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<Vec<ProjectId>, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+
+        let cached = redis_connection
+            .get_deserialized_from_json::<Vec<ProjectId>>(PROJECTS_SEEDED_RANDOM_NAMESPACE, seed)
+            .await?;
+        if let Some(cached) = cached {
+            return Ok(cached.into_iter().take(count).collect());
+        }
+
+        let picks = sqlx::query!(
+            "
+            SELECT id FROM mods
+            WHERE status = ANY($1) AND deleted_at IS NULL
+            ORDER BY hashtext(id::text || $2), id
+            LIMIT $3
+            ",
+            &*ProjectStatus::iterator()
+                .filter(|x| x.is_searchable())
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>(),
+            seed,
+            MAX_SEEDED_RANDOM_PROJECTS,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| ProjectId(x.id))
+        .collect::<Vec<_>>();
+
+        redis_connection
+            .set_serialized_to_json(
+                PROJECTS_SEEDED_RANDOM_NAMESPACE,
+                seed,
+                &picks,
+                Some(SEEDED_RANDOM_PROJECTS_EXPIRY),
+            )
+            .await?;
+        Ok(picks.into_iter().take(count).collect())
+    }
+
+    /// Gets the IDs of the projects featured on the home page that have not expired, in the
+    /// order they should be shown
+    pub async fn get_featured_ids<'a, E>(
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<Vec<ProjectId>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let mut redis = redis.connect().await?;
+        if let Some(ids) = redis
+            .get_deserialized_from_json::<Vec<ProjectId>>(PROJECTS_NAMESPACE, FEATURED_PROJECTS_KEY)
+            .await?
+        {
+            return Ok(ids);
+        }
+
+        let ids = sqlx::query!(
+            "
+            SELECT project_id FROM featured_projects
+            WHERE expires_at IS NULL OR expires_at > NOW()
+            ORDER BY position, featured_at
+            "
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| ProjectId(x.project_id))
+        .collect::<Vec<_>>();
+
+        redis
+            .set_serialized_to_json(
+                PROJECTS_NAMESPACE,
+                FEATURED_PROJECTS_KEY,
+                &ids,
+                Some(FEATURED_PROJECTS_EXPIRY),
+            )
+            .await?;
+
+        Ok(ids)
+    }
+
+    /// Features a project, or changes its position and expiry if it is already featured
+    pub async fn set_featured(
+        id: ProjectId,
+        position: i32,
+        featured_by: UserId,
+        expires_at: Option<DateTime<Utc>>,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO featured_projects (project_id, position, featured_by, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (project_id) DO UPDATE
+            SET position = EXCLUDED.position, featured_by = EXCLUDED.featured_by,
+                featured_at = NOW(), expires_at = EXCLUDED.expires_at
+            ",
+            id as ProjectId,
+            position,
+            featured_by as UserId,
+            expires_at,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_featured(
+        id: ProjectId,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let result = sqlx::query!(
+            "
+            DELETE FROM featured_projects
+            WHERE project_id = $1
+            ",
+            id as ProjectId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(()))
+    }
+
+    /// Removes the featured projects past their expiry, clearing the caches that show them
+    pub async fn remove_expired_featured(
+        pool: &sqlx::PgPool,
         redis: &RedisPool,
     ) -> Result<(), DatabaseError> {
+        let expired = sqlx::query!(
+            "
+            DELETE FROM featured_projects
+            WHERE expires_at <= NOW()
+            RETURNING project_id
+            "
+        )
+        .fetch_all(pool)
+        .await?;
+
+        if !expired.is_empty() {
+            for project in expired {
+                clear_project_caches(ProjectId(project.project_id), None, redis).await?;
+            }
+            Self::clear_featured_cache(redis).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn clear_featured_cache(redis: &RedisPool) -> Result<(), DatabaseError> {
         let mut redis = redis.connect().await?;
 
         redis
-            .delete_many([
-                (PROJECTS_NAMESPACE, Some(id.0.to_string())),
-                (PROJECTS_SLUGS_NAMESPACE, slug.map(|x| x.to_lowercase())),
-                (
-                    PROJECTS_DEPENDENCIES_NAMESPACE,
-                    if clear_dependencies.unwrap_or(false) {
-                        Some(id.0.to_string())
-                    } else {
-                        None
-                    },
-                ),
-            ])
+            .delete(PROJECTS_NAMESPACE, FEATURED_PROJECTS_KEY)
             .await?;
         Ok(())
     }
+
+    pub async fn get_version_matrix(
+        id: ProjectId,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<VersionMatrix, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+
+        let matrix = redis_connection
+            .get_deserialized_from_json::<VersionMatrix>(
+                PROJECTS_VERSION_MATRICES_NAMESPACE,
+                &id.0.to_string(),
+            )
+            .await?;
+        if let Some(matrix) = matrix {
+            return Ok(matrix);
+        }
+
+        // The latest version is the greatest by `QueryVersion`'s ordering: versions without an
+        // explicit ordering come last, then they are sorted by publication date
+        let statuses = [VersionStatus::Listed.as_str().to_string()];
+        let latest = sqlx::query!(
+            "
+            SELECT DISTINCT ON (l.loader, lfev.value) l.loader, lfev.value game_version, v.id version_id
+            FROM versions v
+            INNER JOIN loaders_versions lv ON lv.version_id = v.id
+            INNER JOIN loaders l ON l.id = lv.loader_id
+            INNER JOIN version_fields vf ON vf.version_id = v.id
+            INNER JOIN loader_fields lf ON lf.id = vf.field_id AND lf.field = 'game_versions'
+            INNER JOIN loader_field_enum_values lfev ON lfev.id = vf.enum_value
+            WHERE v.mod_id = $1 AND v.status = ANY($2) AND v.deleted_at IS NULL
+            ORDER BY l.loader, lfev.value, v.ordering DESC NULLS FIRST, v.date_published DESC, v.id DESC
+            ",
+            id as ProjectId,
+            &statuses,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| ((x.loader, x.game_version), VersionId(x.version_id)))
+        .collect::<HashMap<_, _>>();
+
+        let loaders = latest
+            .keys()
+            .map(|(loader, _)| loader.clone())
+            .unique()
+            .sorted()
+            .collect_vec();
+
+        // Game versions are sorted oldest first, with ones missing from the tag table last
+        let all_game_versions =
+            models::legacy_loader_fields::MinecraftGameVersion::list(None, None, pool, redis)
+                .await?;
+        let game_versions = latest
+            .keys()
+            .map(|(_, game_version)| game_version.clone())
+            .unique()
+            .sorted_by_cached_key(|game_version| {
+                let key = game_version_release_key(&all_game_versions, game_version);
+                (key.is_none(), key, game_version.clone())
+            })
+            .collect_vec();
+
+        let matrix = loaders
+            .iter()
+            .map(|loader| {
+                game_versions
+                    .iter()
+                    .map(|game_version| {
+                        latest.get(&(loader.clone(), game_version.clone())).copied()
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let matrix = VersionMatrix {
+            loaders,
+            game_versions,
+            matrix,
+        };
+
+        redis_connection
+            .set_serialized_to_json(
+                PROJECTS_VERSION_MATRICES_NAMESPACE,
+                id.0,
+                &matrix,
+                Some(VERSION_MATRIX_EXPIRY),
+            )
+            .await?;
+        Ok(matrix)
+    }
+
+    /// Gets the game versions supported by any listed version of a project, newest first
+    pub async fn get_supported_game_versions(
+        id: ProjectId,
+        pool: &sqlx::PgPool,
+        redis: &RedisPool,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+
+        let game_versions = redis_connection
+            .get_deserialized_from_json::<Vec<String>>(
+                PROJECTS_SUPPORTED_GAME_VERSIONS_NAMESPACE,
+                &id.0.to_string(),
+            )
+            .await?;
+        if let Some(game_versions) = game_versions {
+            return Ok(game_versions);
+        }
+
+        // Drafts and archived (yanked) versions are left out
+        let statuses = [VersionStatus::Listed.as_str().to_string()];
+        let game_versions = sqlx::query!(
+            "
+            SELECT DISTINCT lfev.value game_version
+            FROM versions v
+            INNER JOIN version_fields vf ON vf.version_id = v.id
+            INNER JOIN loader_fields lf ON lf.id = vf.field_id AND lf.field = 'game_versions'
+            INNER JOIN loader_field_enum_values lfev ON lfev.id = vf.enum_value
+            WHERE v.mod_id = $1 AND v.status = ANY($2) AND v.deleted_at IS NULL
+            ",
+            id as ProjectId,
+            &statuses,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|x| x.game_version)
+        .collect_vec();
+
+        // Game versions missing from the tag table are sorted last here too
+        let all_game_versions =
+            models::legacy_loader_fields::MinecraftGameVersion::list(None, None, pool, redis)
+                .await?;
+        let game_versions = game_versions
+            .into_iter()
+            .sorted_by_cached_key(|game_version| {
+                let key = game_version_release_key(&all_game_versions, game_version);
+                (key.is_none(), std::cmp::Reverse(key), game_version.clone())
+            })
+            .collect_vec();
+
+        redis_connection
+            .set_serialized_to_json(
+                PROJECTS_SUPPORTED_GAME_VERSIONS_NAMESPACE,
+                id.0,
+                &game_versions,
+                Some(SUPPORTED_GAME_VERSIONS_EXPIRY),
+            )
+            .await?;
+        Ok(game_versions)
+    }
+
+    /// Clears the cached entries of many projects with a single command
+    pub async fn clear_cache_many(
+        projects: &[(ProjectId, Option<String>)],
+        redis: &RedisPool,
+    ) -> Result<(), DatabaseError> {
+        let mut redis = redis.connect().await?;
+
+        redis
+            .delete_many(
+                projects
+                    .iter()
+                    .flat_map(|(id, slug)| project_cache_keys(*id, slug.as_deref())),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// The key game versions are sorted by, oldest first: their release date from the tag table, with
+/// the numeric version only breaking ties. Game versions missing from the tag table, which should
+/// not happen, have none.
+fn game_version_release_key(
+    all_game_versions: &[models::legacy_loader_fields::MinecraftGameVersion],
+    game_version: &str,
+) -> Option<(
+    DateTime<Utc>,
+    Option<models::legacy_loader_fields::GameVersionSemver>,
+)> {
+    all_game_versions
+        .iter()
+        .find(|x| x.version == game_version)
+        .map(|x| (x.created, x.semver))
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -936,3 +1626,38 @@ pub struct QueryProject {
     pub thread_id: ThreadId,
     pub aggregate_version_fields: Vec<VersionField>,
 }
+
+/// Clears every cache holding data of a project with a single command. Cached search results are
+/// left alone, as they only change once the project is re-indexed.
+pub async fn clear_project_caches(
+    id: ProjectId,
+    slug: Option<String>,
+    redis: &RedisPool,
+) -> Result<(), DatabaseError> {
+    let mut redis = redis.connect().await?;
+
+    redis
+        .delete_many(project_cache_keys(id, slug.as_deref()))
+        .await
+}
+
+/// The cache keys holding data of a project, cleared together whenever it changes
+fn project_cache_keys(id: ProjectId, slug: Option<&str>) -> [(&'static str, Option<String>); 9] {
+    [
+        (PROJECTS_NAMESPACE, Some(id.0.to_string())),
+        (PROJECTS_SLUGS_NAMESPACE, slug.map(|x| x.to_lowercase())),
+        (PROJECTS_EMBEDS_NAMESPACE, Some(id.0.to_string())),
+        (PROJECTS_VERSION_COUNTS_NAMESPACE, Some(id.0.to_string())),
+        (PROJECTS_VERSION_MATRICES_NAMESPACE, Some(id.0.to_string())),
+        (PROJECTS_LATEST_PER_LOADER_NAMESPACE, Some(id.0.to_string())),
+        (
+            PROJECTS_SUPPORTED_GAME_VERSIONS_NAMESPACE,
+            Some(id.0.to_string()),
+        ),
+        (PROJECTS_DEPENDENCIES_NAMESPACE, Some(id.0.to_string())),
+        (
+            PROJECTS_GAME_VERSION_VERSIONS_NAMESPACE,
+            Some(id.0.to_string()),
+        ),
+    ]
+}
@@ -0,0 +1,37 @@
+use super::ids::*;
+use crate::database::models::DatabaseError;
+use crate::models::projects::ProjectStatus;
+
+/// A status set on a project by a moderator, with the message explaining it
+pub struct ProjectStatusMessageBuilder {
+    pub project_id: ProjectId,
+    pub moderator_id: UserId,
+    pub status: ProjectStatus,
+    pub message: Option<String>,
+}
+
+impl ProjectStatusMessageBuilder {
+    pub async fn insert(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO project_status_messages (
+                project_id, moderator_id, status, message
+            )
+            VALUES (
+                $1, $2, $3, $4
+            )
+            ",
+            self.project_id as ProjectId,
+            self.moderator_id as UserId,
+            self.status.as_str(),
+            self.message,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
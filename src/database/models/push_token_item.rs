@@ -0,0 +1,108 @@
+use super::ids::*;
+use super::DatabaseError;
+use crate::models::notifications::PushPlatform;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+
+/// A device registered to receive push notifications for a user
+pub struct PushToken {
+    pub token: String,
+    pub user_id: UserId,
+    pub platform: PushPlatform,
+    pub created: DateTime<Utc>,
+}
+
+impl PushToken {
+    /// Registers the token, moving it over if a different user had registered it before
+    pub async fn insert(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "
+            INSERT INTO user_push_tokens (token, user_id, platform, created)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (token) DO UPDATE
+            SET user_id = EXCLUDED.user_id, platform = EXCLUDED.platform, created = EXCLUDED.created
+            ",
+            self.token,
+            self.user_id as UserId,
+            self.platform.as_str(),
+            self.created,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_many_users<'a, E>(
+        user_ids: &[UserId],
+        exec: E,
+    ) -> Result<Vec<PushToken>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let tokens = sqlx::query!(
+            "
+            SELECT token, user_id, platform, created
+            FROM user_push_tokens
+            WHERE user_id = ANY($1)
+            ",
+            &user_ids.iter().map(|x| x.0).collect::<Vec<_>>(),
+        )
+        .fetch_many(exec)
+        .try_filter_map(|e| async {
+            Ok(e.right().and_then(|x| {
+                Some(PushToken {
+                    token: x.token,
+                    user_id: UserId(x.user_id),
+                    platform: PushPlatform::from_string(&x.platform)?,
+                    created: x.created,
+                })
+            }))
+        })
+        .try_collect::<Vec<PushToken>>()
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Removes a token of a user, returning `None` if the user had not registered it
+    pub async fn remove(
+        token: &str,
+        user_id: UserId,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let result = sqlx::query!(
+            "
+            DELETE FROM user_push_tokens
+            WHERE token = $1 AND user_id = $2
+            ",
+            token,
+            user_id as UserId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(()))
+    }
+
+    /// Removes tokens that the push services reported as expired or invalid
+    pub async fn remove_many<'a, E>(tokens: &[String], exec: E) -> Result<(), DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        sqlx::query!(
+            "
+            DELETE FROM user_push_tokens
+            WHERE token = ANY($1)
+            ",
+            tokens,
+        )
+        .execute(exec)
+        .await?;
+
+        Ok(())
+    }
+}
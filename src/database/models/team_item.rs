@@ -3,11 +3,13 @@ use crate::{
     database::redis::RedisPool,
     models::teams::{OrganizationPermissions, ProjectPermissions},
 };
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use futures::TryStreamExt;
 use itertools::Itertools;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 const TEAMS_NAMESPACE: &str = "teams";
 
@@ -87,8 +89,10 @@ impl TeamBuilder {
             .multiunzip();
         sqlx::query!(
             "
-            INSERT INTO team_members (id, team_id, user_id, role, is_owner, permissions, organization_permissions, accepted, payouts_split, ordering)
-            SELECT * FROM UNNEST ($1::int8[], $2::int8[], $3::int8[], $4::varchar[], $5::bool[], $6::int8[], $7::int8[], $8::bool[], $9::numeric[], $10::int8[])
+            INSERT INTO team_members (id, team_id, user_id, role, is_owner, permissions, organization_permissions, accepted, payouts_split, ordering, joined_at)
+            SELECT x.*, CASE WHEN x.accepted THEN NOW() END
+            FROM UNNEST ($1::int8[], $2::int8[], $3::int8[], $4::varchar[], $5::bool[], $6::int8[], $7::int8[], $8::bool[], $9::numeric[], $10::int8[])
+                AS x(id, team_id, user_id, role, is_owner, permissions, organization_permissions, accepted, payouts_split, ordering)
             ",
             &team_member_ids[..],
             &team_ids[..],
@@ -197,6 +201,32 @@ impl TeamMember {
         Self::get_from_team_full_many(&[id], executor, redis).await
     }
 
+    /// Gets when the members of a team joined it. Members who are still invited, or who joined
+    /// before this was recorded, are missing from the map.
+    pub async fn get_joined_at<'a, E>(
+        id: TeamId,
+        executor: E,
+    ) -> Result<HashMap<UserId, DateTime<Utc>>, super::DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let joined_at = sqlx::query!(
+            r#"
+            SELECT user_id, joined_at AS "joined_at!"
+            FROM team_members
+            WHERE team_id = $1 AND joined_at IS NOT NULL
+            "#,
+            id as TeamId,
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .map(|x| (UserId(x.user_id), x.joined_at))
+        .collect();
+
+        Ok(joined_at)
+    }
+
     pub async fn get_from_team_full_many<'a, E>(
         team_ids: &[TeamId],
         exec: E,
@@ -385,10 +415,10 @@ impl TeamMember {
         sqlx::query!(
             "
             INSERT INTO team_members (
-                id, team_id, user_id, role, permissions, organization_permissions, is_owner, accepted, payouts_split
+                id, team_id, user_id, role, permissions, organization_permissions, is_owner, accepted, payouts_split, joined_at
             )
             VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, CASE WHEN $8 THEN NOW() END
             )
             ",
             self.id as TeamMemberId,
@@ -489,7 +519,7 @@ impl TeamMember {
                 sqlx::query!(
                     "
                     UPDATE team_members
-                    SET accepted = TRUE
+                    SET accepted = TRUE, joined_at = COALESCE(joined_at, NOW())
                     WHERE (team_id = $1 AND user_id = $2)
                     ",
                     id as TeamId,
@@ -661,7 +691,7 @@ impl TeamMember {
             FROM versions v
             INNER JOIN mods m ON m.id = v.mod_id
             INNER JOIN team_members tm ON tm.team_id = m.team_id AND tm.user_id = $2 AND tm.accepted = TRUE
-            WHERE v.id = $1
+            WHERE v.id = $1 AND v.deleted_at IS NULL
             ",
             id as VersionId,
             user_id as UserId
@@ -1,4 +1,4 @@
-use super::ids::{ProjectId, UserId};
+use super::ids::{ProjectId, UserId, VersionId};
 use super::{CollectionId, ThreadId};
 use crate::database::models;
 use crate::database::models::{DatabaseError, OrganizationId};
@@ -15,6 +15,8 @@ use std::hash::Hash;
 const USERS_NAMESPACE: &str = "users";
 const USER_USERNAMES_NAMESPACE: &str = "users_usernames";
 const USERS_PROJECTS_NAMESPACE: &str = "users_projects";
+const USERS_DOWNLOAD_STATS_NAMESPACE: &str = "users_download_stats";
+const DOWNLOAD_STATS_EXPIRY: i64 = 10 * 60;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct User {
@@ -46,6 +48,20 @@ pub struct User {
     pub badges: Badges,
 
     pub balance: Decimal,
+
+    pub banned: bool,
+    pub ban_reason: Option<String>,
+    /// When the ban ends, or `None` if it is permanent
+    pub banned_until: Option<DateTime<Utc>>,
+}
+
+/// The downloads of one of a user's projects
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct UserProjectDownloads {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub status: String,
+    pub downloads: i64,
 }
 
 impl User {
@@ -170,7 +186,7 @@ impl User {
                         balance,
                         github_id, discord_id, gitlab_id, google_id, steam_id, microsoft_id,
                         email_verified, password, totp_secret, paypal_id, paypal_country, paypal_email,
-                        venmo_handle
+                        venmo_handle, banned, ban_reason, banned_until
                     FROM users
                     WHERE id = ANY($1) OR LOWER(username) = ANY($2)
                     ",
@@ -203,6 +219,9 @@ impl User {
                             paypal_email: u.paypal_email,
                             venmo_handle: u.venmo_handle,
                             totp_secret: u.totp_secret,
+                            banned: u.banned,
+                            ban_reason: u.ban_reason,
+                            banned_until: u.banned_until,
                         };
 
                         acc.insert(u.id, (Some(u.username), user));
@@ -276,53 +295,290 @@ impl User {
         Ok(db_projects)
     }
 
-    pub async fn get_organizations<'a, E>(
+    /// Sums the downloads of the versions of each project the user is a member of, most
+    /// downloaded first
+    pub async fn get_download_stats<'a, E>(
         user_id: UserId,
         exec: E,
-    ) -> Result<Vec<OrganizationId>, sqlx::Error>
+        redis: &RedisPool,
+    ) -> Result<Vec<UserProjectDownloads>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        use futures::stream::TryStreamExt;
+
+        let mut redis = redis.connect().await?;
+
+        if let Some(stats) = redis
+            .get_deserialized_from_json(USERS_DOWNLOAD_STATS_NAMESPACE, &user_id.0.to_string())
+            .await?
+        {
+            return Ok(stats);
+        }
+
+        let stats = sqlx::query!(
+            r#"
+            SELECT m.id, m.name, m.status, COALESCE(SUM(v.downloads), 0) AS "downloads!"
+            FROM mods m
+            INNER JOIN team_members tm ON tm.team_id = m.team_id AND tm.accepted = TRUE
+            LEFT JOIN versions v ON v.mod_id = m.id AND v.deleted_at IS NULL
+            WHERE tm.user_id = $1 AND m.deleted_at IS NULL
+            GROUP BY m.id
+            ORDER BY 4 DESC, m.id
+            "#,
+            user_id as UserId,
+        )
+        .fetch(exec)
+        .map_ok(|m| UserProjectDownloads {
+            project_id: ProjectId(m.id),
+            name: m.name,
+            status: m.status,
+            downloads: m.downloads,
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+        redis
+            .set_serialized_to_json(
+                USERS_DOWNLOAD_STATS_NAMESPACE,
+                user_id.0,
+                &stats,
+                Some(DOWNLOAD_STATS_EXPIRY),
+            )
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Clears the download stats of the members of the projects, after their downloads change
+    pub async fn clear_download_stats_caches<'a, E>(
+        project_ids: &[ProjectId],
+        exec: E,
+        redis: &RedisPool,
+    ) -> Result<(), DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        use futures::stream::TryStreamExt;
+
+        let user_ids = sqlx::query!(
+            "
+            SELECT DISTINCT tm.user_id
+            FROM team_members tm
+            INNER JOIN mods m ON m.team_id = tm.team_id
+            WHERE m.id = ANY($1)
+            ",
+            &project_ids.iter().map(|x| x.0).collect::<Vec<_>>(),
+        )
+        .fetch(exec)
+        .map_ok(|x| x.user_id)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+        let mut redis = redis.connect().await?;
+        redis
+            .delete_many(
+                user_ids
+                    .into_iter()
+                    .map(|x| (USERS_DOWNLOAD_STATS_NAMESPACE, Some(x.to_string()))),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Gets the projects of a user with the given status. Not cached, as the status varies per request
+    pub async fn get_projects_with_status<'a, E>(
+        user_id: UserId,
+        status: &str,
+        exec: E,
+    ) -> Result<Vec<ProjectId>, sqlx::Error>
     where
         E: sqlx::Executor<'a, Database = sqlx::Postgres> + Copy,
     {
         use futures::stream::TryStreamExt;
 
-        let orgs = sqlx::query!(
+        let projects = sqlx::query!(
             "
-            SELECT o.id FROM organizations o
-            INNER JOIN team_members tm ON tm.team_id = o.team_id AND tm.accepted = TRUE
-            WHERE tm.user_id = $1
+            SELECT m.id FROM mods m
+            INNER JOIN team_members tm ON tm.team_id = m.team_id AND tm.accepted = TRUE
+            WHERE tm.user_id = $1 AND m.status = $2
+            ORDER BY m.downloads DESC
             ",
             user_id as UserId,
+            status,
         )
         .fetch_many(exec)
-        .try_filter_map(|e| async { Ok(e.right().map(|m| OrganizationId(m.id))) })
-        .try_collect::<Vec<OrganizationId>>()
+        .try_filter_map(|e| async { Ok(e.right().map(|m| ProjectId(m.id))) })
+        .try_collect::<Vec<ProjectId>>()
         .await?;
 
-        Ok(orgs)
+        Ok(projects)
     }
 
-    pub async fn get_collections<'a, E>(
+    /// Gets the projects owned by a user, either directly or through an organization they own
+    pub async fn get_owned_projects<'a, E>(
         user_id: UserId,
         exec: E,
-    ) -> Result<Vec<CollectionId>, sqlx::Error>
+    ) -> Result<Vec<ProjectId>, sqlx::Error>
     where
         E: sqlx::Executor<'a, Database = sqlx::Postgres> + Copy,
     {
         use futures::stream::TryStreamExt;
 
         let projects = sqlx::query!(
+            r#"
+            SELECT m.id AS "id!" FROM mods m
+            INNER JOIN team_members tm ON tm.team_id = m.team_id AND tm.is_owner = TRUE
+            WHERE tm.user_id = $1
+            UNION
+            SELECT m.id AS "id!" FROM mods m
+            INNER JOIN organizations o ON o.id = m.organization_id
+            INNER JOIN team_members tm ON tm.team_id = o.team_id AND tm.is_owner = TRUE
+            WHERE tm.user_id = $1
+            "#,
+            user_id as UserId,
+        )
+        .fetch_many(exec)
+        .try_filter_map(|e| async { Ok(e.right().map(|m| ProjectId(m.id))) })
+        .try_collect::<Vec<ProjectId>>()
+        .await?;
+
+        Ok(projects)
+    }
+
+    /// Lifts the bans which have ended, returning the users which were unbanned
+    pub async fn unban_expired<'a, E>(exec: E) -> Result<Vec<(UserId, String)>, sqlx::Error>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let users = sqlx::query!(
+            "
+            UPDATE users
+            SET banned = FALSE, ban_reason = NULL, banned_until = NULL
+            WHERE banned AND banned_until <= NOW()
+            RETURNING id, username
+            ",
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| (UserId(x.id), x.username))
+        .collect();
+
+        Ok(users)
+    }
+
+    /// Gets up to `limit` of the versions uploaded by a user, newest first.
+    ///
+    /// If `after` is set, only versions published before that version are returned.
+    pub async fn get_version_contributions<'a, E>(
+        user_id: UserId,
+        project_id: Option<ProjectId>,
+        after: Option<VersionId>,
+        limit: i64,
+        exec: E,
+    ) -> Result<Vec<VersionId>, sqlx::Error>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres> + Copy,
+    {
+        use futures::stream::TryStreamExt;
+
+        let versions = sqlx::query!(
             "
-            SELECT c.id FROM collections c
-            WHERE c.user_id = $1
+            SELECT v.id FROM versions v
+            WHERE v.author_id = $1 AND v.deleted_at IS NULL
+            AND ($2::bigint IS NULL OR v.mod_id = $2)
+            AND (
+                $3::bigint IS NULL OR
+                (v.date_published, v.id) < (SELECT date_published, id FROM versions WHERE id = $3)
+            )
+            ORDER BY v.date_published DESC, v.id DESC
+            LIMIT $4
             ",
             user_id as UserId,
+            project_id.map(|x| x.0),
+            after.map(|x| x.0),
+            limit,
         )
         .fetch_many(exec)
-        .try_filter_map(|e| async { Ok(e.right().map(|m| CollectionId(m.id))) })
-        .try_collect::<Vec<CollectionId>>()
+        .try_filter_map(|e| async { Ok(e.right().map(|m| VersionId(m.id))) })
+        .try_collect::<Vec<VersionId>>()
         .await?;
 
-        Ok(projects)
+        Ok(versions)
+    }
+
+    /// Recalculates the trust scores of users, or of every user if `user_ids` is `None`.
+    ///
+    /// The score ranges from 0 to 1, and is made up of the account age (up to a year), the number
+    /// of approved projects the user owns (up to 5), the follower count of their projects (up to
+    /// 100), and whether they have linked an OAuth provider.
+    pub async fn update_trust_scores<'a, E>(
+        user_ids: Option<&[UserId]>,
+        exec: E,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let approved_statuses = crate::models::projects::ProjectStatus::iterator()
+            .filter(|x| x.is_approved())
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+
+        sqlx::query!(
+            "
+            UPDATE users u
+            SET trust_score = (
+                0.25 * LEAST(EXTRACT(EPOCH FROM NOW() - u.created) / 31536000, 1)
+                + 0.35 * LEAST((
+                    SELECT COUNT(*) FROM team_members tm
+                    INNER JOIN mods m ON m.team_id = tm.team_id
+                    WHERE tm.user_id = u.id AND tm.is_owner AND m.status = ANY($2)
+                ) / 5.0, 1)
+                + 0.2 * LEAST((
+                    SELECT COALESCE(SUM(m.follows), 0) FROM team_members tm
+                    INNER JOIN mods m ON m.team_id = tm.team_id
+                    WHERE tm.user_id = u.id AND tm.accepted
+                ) / 100.0, 1)
+                + CASE WHEN COALESCE(
+                    u.github_id::text, u.discord_id::text, u.gitlab_id::text,
+                    u.google_id, u.steam_id::text, u.microsoft_id
+                ) IS NULL THEN 0 ELSE 0.2 END
+            )::real
+            WHERE $1::bigint[] IS NULL OR u.id = ANY($1)
+            ",
+            user_ids.map(|x| x.iter().map(|y| y.0).collect::<Vec<_>>()) as Option<Vec<i64>>,
+            &approved_statuses,
+        )
+        .execute(exec)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_organizations<'a, E>(
+        user_id: UserId,
+        exec: E,
+    ) -> Result<Vec<OrganizationId>, sqlx::Error>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres> + Copy,
+    {
+        use futures::stream::TryStreamExt;
+
+        let orgs = sqlx::query!(
+            "
+            SELECT o.id FROM organizations o
+            INNER JOIN team_members tm ON tm.team_id = o.team_id AND tm.accepted = TRUE
+            WHERE tm.user_id = $1
+            ",
+            user_id as UserId,
+        )
+        .fetch_many(exec)
+        .try_filter_map(|e| async { Ok(e.right().map(|m| OrganizationId(m.id))) })
+        .try_collect::<Vec<OrganizationId>>()
+        .await?;
+
+        Ok(orgs)
     }
 
     pub async fn get_backup_codes<'a, E>(
@@ -455,6 +711,16 @@ impl User {
             .execute(&mut **transaction)
             .await?;
 
+            sqlx::query!(
+                "
+                DELETE FROM user_push_tokens
+                WHERE user_id = $1
+                ",
+                id as UserId,
+            )
+            .execute(&mut **transaction)
+            .await?;
+
             let user_collections = sqlx::query!(
                 "
                 SELECT id
@@ -469,7 +735,7 @@ impl User {
             .await?;
 
             for collection_id in user_collections {
-                models::Collection::remove(collection_id, transaction, &redis).await?;
+                models::Collection::remove(collection_id, transaction, redis).await?;
             }
 
             let report_threads = sqlx::query!(
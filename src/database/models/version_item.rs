@@ -17,6 +17,8 @@ use std::iter;
 
 pub const VERSIONS_NAMESPACE: &str = "versions";
 const VERSION_FILES_NAMESPACE: &str = "versions_files";
+/// How long a deleted version can be restored for before it is purged
+pub const VERSION_RESTORE_WINDOW_DAYS: i32 = 30;
 
 #[derive(Clone)]
 pub struct VersionBuilder {
@@ -118,12 +120,16 @@ impl DependencyBuilder {
 
 #[derive(Clone, Debug)]
 pub struct VersionFileBuilder {
+    /// Generated with the builder so the ID can be returned before the file is inserted
+    pub id: FileId,
     pub url: String,
     pub filename: String,
     pub hashes: Vec<HashBuilder>,
     pub primary: bool,
     pub size: u32,
     pub file_type: Option<FileType>,
+    pub part_group_id: Option<FileId>,
+    pub part_index: Option<i32>,
 }
 
 impl VersionFileBuilder {
@@ -132,12 +138,12 @@ impl VersionFileBuilder {
         version_id: VersionId,
         transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<FileId, DatabaseError> {
-        let file_id = generate_file_id(&mut *transaction).await?;
+        let file_id = self.id;
 
         sqlx::query!(
             "
-            INSERT INTO files (id, version_id, url, filename, is_primary, size, file_type)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO files (id, version_id, url, filename, is_primary, size, file_type, file_part_group_id, part_index)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ",
             file_id as FileId,
             version_id as VersionId,
@@ -146,6 +152,8 @@ impl VersionFileBuilder {
             self.primary,
             self.size as i32,
             self.file_type.map(|x| x.as_str()),
+            self.part_group_id.map(|x| x.0),
+            self.part_index,
         )
         .execute(&mut **transaction)
         .await?;
@@ -318,20 +326,216 @@ impl Version {
         Ok(())
     }
 
-    pub async fn remove_full(
+    /// Marks a version as deleted, hiding it everywhere until it is restored or purged. Returns
+    /// `None` if the version does not exist or is already deleted.
+    pub async fn soft_delete(
         id: VersionId,
         redis: &RedisPool,
         transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<Option<()>, DatabaseError> {
-        let result = Self::get(id, &mut **transaction, redis).await?;
-
-        let result = if let Some(result) = result {
-            result
-        } else {
+        let Some(result) = Self::get(id, &mut **transaction, redis).await? else {
             return Ok(None);
         };
 
+        sqlx::query!(
+            "
+            UPDATE versions
+            SET deleted_at = NOW()
+            WHERE id = $1
+            ",
+            id as VersionId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
         Version::clear_cache(&result, redis).await?;
+        crate::database::models::project_item::clear_project_caches(
+            result.inner.project_id,
+            None,
+            redis,
+        )
+        .await?;
+
+        Ok(Some(()))
+    }
+
+    /// Gets the project of a version deleted recently enough to be restored
+    pub async fn get_restorable_project<'a, E>(
+        id: VersionId,
+        exec: E,
+    ) -> Result<Option<ProjectId>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let project_id = sqlx::query!(
+            "
+            SELECT mod_id FROM versions
+            WHERE id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            ",
+            id as VersionId,
+            VERSION_RESTORE_WINDOW_DAYS,
+        )
+        .fetch_optional(exec)
+        .await?
+        .map(|x| ProjectId(x.mod_id));
+
+        Ok(project_id)
+    }
+
+    /// Restores a version deleted recently enough. Returns `None` if there is no such version.
+    pub async fn restore(
+        id: VersionId,
+        redis: &RedisPool,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let Some(project_id) = sqlx::query!(
+            "
+            UPDATE versions
+            SET deleted_at = NULL
+            WHERE id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            RETURNING mod_id
+            ",
+            id as VersionId,
+            VERSION_RESTORE_WINDOW_DAYS,
+        )
+        .fetch_optional(&mut **transaction)
+        .await?
+        .map(|x| ProjectId(x.mod_id)) else {
+            return Ok(None);
+        };
+
+        let mut redis_connection = redis.connect().await?;
+        redis_connection.delete(VERSIONS_NAMESPACE, id.0).await?;
+        crate::database::models::project_item::clear_project_caches(project_id, None, redis)
+            .await?;
+
+        Ok(Some(()))
+    }
+
+    /// Features a version and unfeatures the other versions of its project sharing a loader and
+    /// game version with it. The project is marked as updated. Returns the unfeatured versions,
+    /// whose caches should be cleared with [`Version::clear_promoted_cache`] once committed.
+    pub async fn promote(
+        id: VersionId,
+        project_id: ProjectId,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Vec<VersionId>, DatabaseError> {
+        sqlx::query!(
+            "
+            UPDATE versions
+            SET featured = TRUE
+            WHERE id = $1
+            ",
+            id as VersionId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        let unfeatured = sqlx::query!(
+            "
+            UPDATE versions v
+            SET featured = FALSE
+            WHERE v.mod_id = $2 AND v.id != $1 AND v.featured AND EXISTS (
+                SELECT 1
+                FROM loaders_versions lv
+                INNER JOIN loaders_versions target_lv
+                    ON target_lv.loader_id = lv.loader_id AND target_lv.version_id = $1
+                INNER JOIN version_fields vf ON vf.version_id = v.id
+                INNER JOIN loader_fields lf ON lf.id = vf.field_id AND lf.field = 'game_versions'
+                INNER JOIN version_fields target_vf
+                    ON target_vf.version_id = $1
+                    AND target_vf.field_id = vf.field_id
+                    AND target_vf.enum_value = vf.enum_value
+                WHERE lv.version_id = v.id
+            )
+            RETURNING v.id
+            ",
+            id as VersionId,
+            project_id as ProjectId,
+        )
+        .fetch_all(&mut **transaction)
+        .await?
+        .into_iter()
+        .map(|x| VersionId(x.id))
+        .collect::<Vec<_>>();
+
+        sqlx::query!(
+            "
+            UPDATE mods
+            SET updated = NOW()
+            WHERE id = $1
+            ",
+            project_id as ProjectId,
+        )
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(unfeatured)
+    }
+
+    /// Clears the caches of a promoted version, the versions it unfeatured and their project
+    pub async fn clear_promoted_cache(
+        id: VersionId,
+        unfeatured: &[VersionId],
+        project_id: ProjectId,
+        redis: &RedisPool,
+    ) -> Result<(), DatabaseError> {
+        let mut redis_connection = redis.connect().await?;
+        redis_connection
+            .delete_many(
+                std::iter::once(id)
+                    .chain(unfeatured.iter().copied())
+                    .map(|x| (VERSIONS_NAMESPACE, Some(x.0.to_string()))),
+            )
+            .await?;
+        crate::database::models::project_item::clear_project_caches(project_id, None, redis)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gets the versions deleted too long ago to be restored, which should be purged
+    pub async fn get_expired_deleted<'a, E>(exec: E) -> Result<Vec<VersionId>, DatabaseError>
+    where
+        E: sqlx::Executor<'a, Database = sqlx::Postgres>,
+    {
+        let ids = sqlx::query!(
+            "
+            SELECT id FROM versions
+            WHERE deleted_at <= NOW() - make_interval(days => $1)
+            ",
+            VERSION_RESTORE_WINDOW_DAYS,
+        )
+        .fetch_all(exec)
+        .await?
+        .into_iter()
+        .map(|x| VersionId(x.id))
+        .collect();
+
+        Ok(ids)
+    }
+
+    pub async fn remove_full(
+        id: VersionId,
+        redis: &RedisPool,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let Some(project_id) = sqlx::query!(
+            "
+            SELECT mod_id FROM versions WHERE id = $1
+            ",
+            id as VersionId,
+        )
+        .fetch_optional(&mut **transaction)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        // Deleted versions were already cleared from the cache when they were deleted
+        if let Some(result) = Self::get(id, &mut **transaction, redis).await? {
+            Version::clear_cache(&result, redis).await?;
+        }
 
         sqlx::query!(
             "
@@ -389,15 +593,6 @@ impl Version {
 
         // Sync dependencies
 
-        let project_id = sqlx::query!(
-            "
-            SELECT mod_id FROM versions WHERE id = $1
-            ",
-            id as VersionId,
-        )
-        .fetch_one(&mut **transaction)
-        .await?;
-
         sqlx::query!(
             "
             UPDATE dependencies
@@ -438,10 +633,9 @@ impl Version {
         .execute(&mut **transaction)
         .await?;
 
-        crate::database::models::Project::clear_cache(
+        crate::database::models::project_item::clear_project_caches(
             ProjectId(project_id.mod_id),
             None,
-            None,
             redis,
         )
         .await?;
@@ -618,13 +812,15 @@ impl Version {
                     pub primary: bool,
                     pub size: u32,
                     pub file_type: Option<FileType>,
+                    pub part_group_id: Option<FileId>,
+                    pub part_index: Option<i32>,
                 }
 
                 let file_ids = DashSet::new();
                 let reverse_file_map = DashMap::new();
                 let files : DashMap<VersionId, Vec<File>> = sqlx::query!(
                     "
-                    SELECT DISTINCT version_id, f.id, f.url, f.filename, f.is_primary, f.size, f.file_type
+                    SELECT DISTINCT version_id, f.id, f.url, f.filename, f.is_primary, f.size, f.file_type, f.file_part_group_id, f.part_index
                     FROM files f
                     WHERE f.version_id = ANY($1)
                     ",
@@ -638,6 +834,8 @@ impl Version {
                             primary: m.is_primary,
                             size: m.size as u32,
                             file_type: m.file_type.map(|x| FileType::from_string(&x)),
+                            part_group_id: m.file_part_group_id.map(FileId),
+                            part_index: m.part_index,
                         };
 
                         file_ids.insert(FileId(m.id));
@@ -704,7 +902,7 @@ impl Version {
                     v.changelog changelog, v.date_published date_published, v.downloads downloads,
                     v.version_type version_type, v.featured featured, v.status status, v.requested_status requested_status, v.ordering ordering
                     FROM versions v
-                    WHERE v.id = ANY($1);
+                    WHERE v.id = ANY($1) AND v.deleted_at IS NULL;
                     ",
                     &version_ids
                 )
@@ -764,6 +962,8 @@ impl Version {
                                         primary: x.primary,
                                         size: x.size,
                                         file_type: x.file_type,
+                                        part_group_id: x.part_group_id,
+                                        part_index: x.part_index,
                                     }
                                 }).collect::<Vec<_>>();
 
@@ -772,6 +972,8 @@ impl Version {
                                         Ordering::Less
                                     } else if b.primary {
                                         Ordering::Greater
+                                    } else if a.part_group_id.is_some() && a.part_group_id == b.part_group_id {
+                                        a.part_index.cmp(&b.part_index)
                                     } else {
                                         a.filename.cmp(&b.filename)
                                     }
@@ -838,7 +1040,7 @@ impl Version {
                     FROM files f
                     INNER JOIN versions v on v.id = f.version_id
                     INNER JOIN hashes h on h.file_id = f.id
-                    WHERE h.algorithm = $1 AND h.hash = ANY($2)
+                    WHERE h.algorithm = $1 AND h.hash = ANY($2) AND v.deleted_at IS NULL
                     GROUP BY f.id, v.mod_id, v.date_published
                     ORDER BY v.date_published
                     ",
@@ -939,6 +1141,9 @@ pub struct QueryFile {
     pub primary: bool,
     pub size: u32,
     pub file_type: Option<FileType>,
+    /// The file this file is a part of, if it is one part of a split archive
+    pub part_group_id: Option<FileId>,
+    pub part_index: Option<i32>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
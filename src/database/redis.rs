@@ -1,8 +1,9 @@
 use super::models::DatabaseError;
 use crate::models::ids::base62_impl::{parse_base62, to_base62};
-use chrono::{TimeZone, Utc};
+use chrono::Utc;
 use dashmap::DashMap;
 use deadpool_redis::{Config, Runtime};
+use futures::{Stream, StreamExt};
 use redis::{cmd, Cmd, ExistenceCheck, SetExpiry, SetOptions};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -11,15 +12,20 @@ use std::fmt::{Debug, Display};
 use std::future::Future;
 use std::hash::Hash;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
 
 const DEFAULT_EXPIRY: i64 = 60 * 60 * 12; // 12 hours
 const ACTUAL_EXPIRY: i64 = 60 * 30; // 30 minutes
+const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
 
 #[derive(Clone)]
 pub struct RedisPool {
     pub pool: deadpool_redis::Pool,
     meta_namespace: String,
+    // each subscription holds its own connection for as long as it is open
+    subscriptions: Arc<Semaphore>,
 }
 
 pub struct RedisConnection {
@@ -48,6 +54,12 @@ impl RedisPool {
         RedisPool {
             pool: redis_pool,
             meta_namespace: meta_namespace.unwrap_or("".to_string()),
+            subscriptions: Arc::new(Semaphore::new(
+                dotenvy::var("REDIS_MAX_SUBSCRIPTIONS")
+                    .ok()
+                    .and_then(|x| x.parse().ok())
+                    .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS),
+            )),
         }
     }
 
@@ -58,6 +70,30 @@ impl RedisPool {
         })
     }
 
+    /// Subscribes to a pub/sub channel, returning the payloads of the messages published to it.
+    /// A subscribed connection cannot run other commands, so it is taken out of the pool. At most
+    /// `REDIS_MAX_SUBSCRIPTIONS` subscriptions are open at once, and `None` is returned past that.
+    pub async fn subscribe(
+        &self,
+        channel: &str,
+    ) -> Result<Option<impl Stream<Item = String>>, DatabaseError> {
+        let Ok(permit) = self.subscriptions.clone().try_acquire_owned() else {
+            return Ok(None);
+        };
+
+        let mut pubsub = deadpool_redis::Connection::take(self.pool.get().await?).into_pubsub();
+        pubsub
+            .subscribe(format!("{}_{}", self.meta_namespace, channel))
+            .await?;
+
+        Ok(Some(pubsub.into_on_message().filter_map(move |msg| {
+            // the permit is released when the stream is dropped
+            let _permit = &permit;
+            let payload = msg.get_payload::<String>().ok();
+            async move { payload }
+        })))
+    }
+
     pub async fn get_cached_keys<F, Fut, T, K>(
         &self,
         namespace: &str,
@@ -217,7 +253,7 @@ impl RedisPool {
         let mut cached_values = cached_values_raw
             .into_iter()
             .filter_map(|(key, val)| {
-                if Utc.timestamp(val.iat + ACTUAL_EXPIRY, 0) < current_time {
+                if val.iat + ACTUAL_EXPIRY < current_time.timestamp() {
                     expired_values.insert(val.key.to_string(), val);
 
                     None
@@ -501,6 +537,20 @@ impl RedisConnection {
         Ok(())
     }
 
+    pub async fn publish(&mut self, channel: &str, message: &str) -> Result<(), DatabaseError> {
+        let mut cmd = cmd("PUBLISH");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}", self.meta_namespace, channel),
+                message.to_string(),
+            ]
+            .as_slice(),
+        );
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
     pub async fn delete_many(
         &mut self,
         iter: impl IntoIterator<Item = (&str, Option<String>)>,
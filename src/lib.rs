@@ -24,6 +24,7 @@ use crate::{
     search::indexing::index_projects,
     util::env::{parse_strings_from_var, parse_var},
 };
+use ipnetwork::IpNetwork;
 
 pub mod auth;
 pub mod clickhouse;
@@ -58,6 +59,7 @@ pub struct LabrinthConfig {
     pub active_sockets: web::Data<RwLock<ActiveSockets>>,
     pub automated_moderation_queue: web::Data<AutomatedModerationQueue>,
     pub rate_limiter: KeyedRateLimiter,
+    pub trusted_proxies: Arc<Vec<IpNetwork>>,
 }
 
 pub fn app_setup(
@@ -127,6 +129,82 @@ pub fn app_setup(
         }
     });
 
+    // Retries search index updates that failed while Meilisearch was unavailable
+    let pool_ref = pool.clone();
+    let search_config_ref = search_config.clone();
+    let redis_pool_ref = redis_pool.clone();
+    scheduler.run(std::time::Duration::from_secs(30), move || {
+        let pool_ref = pool_ref.clone();
+        let redis_pool_ref = redis_pool_ref.clone();
+        let search_config_ref = search_config_ref.clone();
+        async move {
+            let result = search::indexing::retry::process_retry_queue(
+                &pool_ref,
+                &redis_pool_ref,
+                &search_config_ref,
+            )
+            .await;
+            if let Err(e) = result {
+                warn!("Retrying search index operations failed: {:?}", e);
+            }
+        }
+    });
+
+    // Lifts bans once they end
+    let pool_ref = pool.clone();
+    let search_config_ref = search_config.clone();
+    let redis_pool_ref = redis_pool.clone();
+    scheduler.run(std::time::Duration::from_secs(60 * 5), move || {
+        let pool_ref = pool_ref.clone();
+        let redis_pool_ref = redis_pool_ref.clone();
+        let search_config_ref = search_config_ref.clone();
+        async move {
+            let result =
+                queue::bans::unban_expired_users(&pool_ref, &redis_pool_ref, &search_config_ref)
+                    .await;
+            if let Err(e) = result {
+                warn!("Lifting ended bans failed: {:?}", e);
+            }
+        }
+    });
+
+    // Stops featuring projects once their featuring expires
+    let pool_ref = pool.clone();
+    let redis_pool_ref = redis_pool.clone();
+    scheduler.run(std::time::Duration::from_secs(60), move || {
+        let pool_ref = pool_ref.clone();
+        let redis_pool_ref = redis_pool_ref.clone();
+        async move {
+            let result =
+                database::models::Project::remove_expired_featured(&pool_ref, &redis_pool_ref)
+                    .await;
+            if let Err(e) = result {
+                warn!("Expiring featured projects failed: {:?}", e);
+            }
+        }
+    });
+
+    // Refreshes the cached project counts and most popular projects of each category
+    let pool_ref = pool.clone();
+    let redis_pool_ref = redis_pool.clone();
+    scheduler.run(
+        Duration::from_secs(database::models::categories::CATEGORY_STATS_EXPIRY as u64),
+        move || {
+            let pool_ref = pool_ref.clone();
+            let redis_pool_ref = redis_pool_ref.clone();
+            async move {
+                let result = database::models::categories::CategoryStats::refresh_all(
+                    &pool_ref,
+                    &redis_pool_ref,
+                )
+                .await;
+                if let Err(e) = result {
+                    warn!("Refreshing category statistics failed: {:?}", e);
+                }
+            }
+        },
+    );
+
     // Changes statuses of scheduled projects/versions
     let pool_ref = pool.clone();
     // TODO: Clear cache when these are run
@@ -171,6 +249,67 @@ pub fn app_setup(
 
     scheduler::schedule_versions(&mut scheduler, pool.clone(), redis_pool.clone());
 
+    // Recalculates user trust scores, which are used to prioritize the moderation queue
+    let pool_ref = pool.clone();
+    scheduler.run(std::time::Duration::from_secs(60 * 60 * 24), move || {
+        let pool_ref = pool_ref.clone();
+
+        async move {
+            info!("Updating user trust scores");
+            let result = database::models::User::update_trust_scores(None, &pool_ref).await;
+            if let Err(e) = result {
+                warn!("Updating user trust scores failed: {:?}", e);
+            }
+            info!("Done updating user trust scores");
+        }
+    });
+
+    // Permanently deletes versions deleted too long ago to be restored. This is not run by the
+    // scheduler, as removing versions goes through the cache, whose futures are not `Send`.
+    let pool_ref = pool.clone();
+    let redis_pool_ref = redis_pool.clone();
+    let file_host_ref = file_host.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(60 * 60 * 24));
+        loop {
+            interval.tick().await;
+            info!("Purging deleted versions");
+            let result = queue::deleted_versions::purge_deleted_versions(
+                &pool_ref,
+                &redis_pool_ref,
+                &file_host_ref,
+            )
+            .await;
+            if let Err(e) = result {
+                warn!("Purging deleted versions failed: {:?}", e);
+            }
+            info!("Done purging deleted versions");
+        }
+    });
+
+    // Permanently deletes deleted projects once their deletion is due. Like purging versions,
+    // this is not run by the scheduler.
+    let pool_ref = pool.clone();
+    let redis_pool_ref = redis_pool.clone();
+    let file_host_ref = file_host.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            info!("Purging deleted projects");
+            let result = queue::deleted_projects::purge_deleted_projects(
+                &pool_ref,
+                &redis_pool_ref,
+                &file_host_ref,
+            )
+            .await;
+            if let Err(e) = result {
+                warn!("Purging deleted projects failed: {:?}", e);
+            }
+            info!("Done purging deleted projects");
+        }
+    });
+
     let session_queue = web::Data::new(AuthQueue::new());
 
     let pool_ref = pool.clone();
@@ -257,6 +396,26 @@ pub fn app_setup(
         });
     }
 
+    match queue::push::PushQueue::new(&queue::push::PushConfig::from_env()) {
+        Ok(Some(push_queue)) => {
+            let push_queue = Arc::new(push_queue);
+            let pool_ref = pool.clone();
+            scheduler.run(std::time::Duration::from_secs(15), move || {
+                let push_queue = push_queue.clone();
+                let pool_ref = pool_ref.clone();
+
+                async move {
+                    let result = push_queue.push(&pool_ref).await;
+                    if let Err(e) = result {
+                        warn!("Pushing notifications failed: {:?}", e);
+                    }
+                }
+            });
+        }
+        Ok(None) => info!("No push notification services configured"),
+        Err(e) => warn!("Setting up push notifications failed: {:?}", e),
+    }
+
     let ip_salt = Pepper {
         pepper: models::ids::Base62Id(models::ids::random_base62(11)).to_string(),
     };
@@ -279,6 +438,7 @@ pub fn app_setup(
         active_sockets,
         automated_moderation_queue,
         rate_limiter: limiter,
+        trusted_proxies: Arc::new(util::ip::trusted_proxies_from_env()),
     }
 }
 
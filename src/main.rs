@@ -4,6 +4,8 @@ use env_logger::Env;
 use labrinth::database::redis::RedisPool;
 use labrinth::file_hosting::S3Host;
 use labrinth::search;
+use labrinth::util::headers::{build_info_headers, security_headers};
+use labrinth::util::ip::ClientIpResolver;
 use labrinth::util::ratelimit::RateLimit;
 use labrinth::{check_env_vars, clickhouse, database, file_hosting, queue};
 use log::{error, info};
@@ -94,6 +96,11 @@ async fn main() -> std::io::Result<()> {
 
     let search_config = search::SearchConfig::new(None);
 
+    info!("Syncing search index settings");
+    if let Err(err) = search::indexing::sync_index_settings(&search_config).await {
+        error!("Failed to sync search index settings: {err}");
+    }
+
     let labrinth_config = labrinth::app_setup(
         pool.clone(),
         redis_pool.clone(),
@@ -112,6 +119,11 @@ async fn main() -> std::io::Result<()> {
             .wrap(RateLimit(Arc::clone(&labrinth_config.rate_limiter)))
             .wrap(actix_web::middleware::Compress::default())
             .wrap(sentry_actix::Sentry::new())
+            .wrap(security_headers())
+            .wrap(build_info_headers())
+            .wrap(ClientIpResolver(Arc::clone(
+                &labrinth_config.trusted_proxies,
+            )))
             .configure(|cfg| labrinth::app_config(cfg, labrinth_config.clone()))
     })
     .bind(dotenvy::var("BIND_ADDR").unwrap())?
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// An error returned by the API
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ApiError<'a> {
     pub error: &'a str,
     pub description: String,
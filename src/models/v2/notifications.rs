@@ -7,7 +7,7 @@ use crate::models::{
         UserId, VersionId,
     },
     notifications::{Notification, NotificationAction, NotificationBody},
-    projects::ProjectStatus,
+    projects::{ProjectStatus, VersionStatus},
 };
 
 #[derive(Serialize, Deserialize)]
@@ -65,6 +65,18 @@ pub enum LegacyNotificationBody {
         project_id: Option<ProjectId>,
         report_id: Option<ReportId>,
     },
+    VersionReview {
+        project_id: ProjectId,
+        version_id: VersionId,
+        status: VersionStatus,
+        message: Option<String>,
+    },
+    ProjectDeleted {
+        project_id: ProjectId,
+        project_title: String,
+        deleted_by: UserId,
+        scheduled_for: DateTime<Utc>,
+    },
     LegacyMarkdown {
         notification_type: Option<String>,
         title: String,
@@ -83,6 +95,8 @@ impl LegacyNotification {
             NotificationBody::OrganizationInvite { .. } => Some("organization_invite".to_string()),
             NotificationBody::StatusChange { .. } => Some("status_change".to_string()),
             NotificationBody::ModeratorMessage { .. } => Some("moderator_message".to_string()),
+            NotificationBody::VersionReview { .. } => Some("version_review".to_string()),
+            NotificationBody::ProjectDeleted { .. } => Some("project_deleted".to_string()),
             NotificationBody::LegacyMarkdown {
                 notification_type, ..
             } => notification_type.clone(),
@@ -139,6 +153,28 @@ impl LegacyNotification {
                 project_id,
                 report_id,
             },
+            NotificationBody::VersionReview {
+                project_id,
+                version_id,
+                status,
+                message,
+            } => LegacyNotificationBody::VersionReview {
+                project_id,
+                version_id,
+                status,
+                message,
+            },
+            NotificationBody::ProjectDeleted {
+                project_id,
+                project_title,
+                deleted_by,
+                scheduled_for,
+            } => LegacyNotificationBody::ProjectDeleted {
+                project_id,
+                project_title,
+                deleted_by,
+                scheduled_for,
+            },
             NotificationBody::LegacyMarkdown {
                 notification_type,
                 name,
@@ -162,16 +162,14 @@ impl LegacyResultSearchProject {
 
 impl LegacySearchResults {
     pub fn from(search_results: crate::search::SearchResults) -> Self {
-        let limit = search_results.hits_per_page;
-        let offset = (search_results.page - 1) * limit;
         Self {
             hits: search_results
                 .hits
                 .into_iter()
                 .map(LegacyResultSearchProject::from)
                 .collect(),
-            offset,
-            limit,
+            offset: search_results.offset,
+            limit: search_results.limit,
             total_hits: search_results.total_hits,
         }
     }
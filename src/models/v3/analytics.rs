@@ -1,3 +1,5 @@
+use crate::models::ids::VersionId;
+use chrono::{DateTime, Utc};
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
@@ -24,6 +26,14 @@ pub struct Download {
     pub headers: Vec<(String, String)>,
 }
 
+/// A download as it happens, sent to the project's owners on its traffic stream
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrafficEvent {
+    pub version_id: VersionId,
+    pub timestamp: DateTime<Utc>,
+    pub country_code: String,
+}
+
 #[derive(Row, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct PageView {
     pub recorded: i64,
@@ -62,3 +72,16 @@ pub struct Playtime {
     /// Parent modpack this playtime was recorded in
     pub parent: u64,
 }
+
+#[derive(Row, Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SearchLog {
+    pub recorded: i64,
+    // Hash of the trimmed, lowercased query
+    pub query_hash: String,
+    // Only stored for queries without results, or if verbose search logging is enabled
+    pub query_text: String,
+    pub facets_hash: String,
+
+    pub result_count: u64,
+    pub response_time_ms: u64,
+}
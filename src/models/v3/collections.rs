@@ -40,6 +40,9 @@ pub struct Collection {
 
     /// A list of ProjectIds that are in this collection.
     pub projects: Vec<ProjectId>,
+
+    /// The search query and facets this collection was created from, if any.
+    pub source_search_query: Option<serde_json::Value>,
 }
 
 impl From<database::models::Collection> for Collection {
@@ -55,6 +58,56 @@ impl From<database::models::Collection> for Collection {
             icon_url: c.icon_url,
             color: c.color,
             status: c.status,
+            source_search_query: c.source_search_query,
+        }
+    }
+}
+
+/// A collection listed on a user's profile, without its projects
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CollectionSummary {
+    pub id: CollectionId,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    pub status: CollectionStatus,
+    /// The number of projects in the collection, including ones the viewer cannot see
+    pub project_count: u32,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl From<database::models::collection_item::CollectionSummary> for CollectionSummary {
+    fn from(c: database::models::collection_item::CollectionSummary) -> Self {
+        Self {
+            id: c.id.into(),
+            name: c.name,
+            description: c.description,
+            icon_url: c.icon_url,
+            status: c.status,
+            project_count: c.project_count as u32,
+            created: c.created,
+            updated: c.updated,
+        }
+    }
+}
+
+/// The order a user's collections are listed in
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionSort {
+    #[default]
+    Created,
+    Updated,
+    Title,
+}
+
+impl CollectionSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollectionSort::Created => "created",
+            CollectionSort::Updated => "updated",
+            CollectionSort::Title => "title",
         }
     }
 }
@@ -8,7 +8,7 @@ pub use super::oauth_clients::{OAuthClientId, OAuthRedirectUriId};
 pub use super::organizations::OrganizationId;
 pub use super::pats::PatId;
 pub use super::payouts::PayoutId;
-pub use super::projects::{ProjectId, VersionId};
+pub use super::projects::{FileId, ProjectId, VersionId};
 pub use super::reports::ReportId;
 pub use super::sessions::SessionId;
 pub use super::teams::TeamId;
@@ -110,15 +110,32 @@ macro_rules! impl_base62_display {
 }
 impl_base62_display!(Base62Id);
 
+// IDs are always serialized as their base62 string
+macro_rules! impl_base62_schema {
+    ($struct:ty) => {
+        impl utoipa::PartialSchema for $struct {
+            fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+                utoipa::openapi::ObjectBuilder::new()
+                    .schema_type(utoipa::openapi::schema::Type::String)
+                    .description(Some("An ID, encoded as a base62 string"))
+                    .into()
+            }
+        }
+        impl utoipa::ToSchema for $struct {}
+    };
+}
+
 macro_rules! base62_id_impl {
     ($struct:ty, $cons:expr) => {
         from_base62id!($struct, $cons;);
         impl_base62_display!($struct);
+        impl_base62_schema!($struct);
     }
 }
 base62_id_impl!(ProjectId, ProjectId);
 base62_id_impl!(UserId, UserId);
 base62_id_impl!(VersionId, VersionId);
+base62_id_impl!(FileId, FileId);
 base62_id_impl!(CollectionId, CollectionId);
 base62_id_impl!(TeamId, TeamId);
 base62_id_impl!(OrganizationId, OrganizationId);
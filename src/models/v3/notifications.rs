@@ -4,7 +4,7 @@ use super::users::UserId;
 use crate::database::models::notification_item::Notification as DBNotification;
 use crate::database::models::notification_item::NotificationAction as DBNotificationAction;
 use crate::models::ids::{ProjectId, ReportId, TeamId, ThreadId, ThreadMessageId, VersionId};
-use crate::models::projects::ProjectStatus;
+use crate::models::projects::{ProjectStatus, VersionStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -58,6 +58,18 @@ pub enum NotificationBody {
         project_id: Option<ProjectId>,
         report_id: Option<ReportId>,
     },
+    VersionReview {
+        project_id: ProjectId,
+        version_id: VersionId,
+        status: VersionStatus,
+        message: Option<String>,
+    },
+    ProjectDeleted {
+        project_id: ProjectId,
+        project_title: String,
+        deleted_by: UserId,
+        scheduled_for: DateTime<Utc>,
+    },
     LegacyMarkdown {
         notification_type: Option<String>,
         name: String,
@@ -84,6 +96,20 @@ impl From<DBNotification> for Notification {
                     format!("/project/{}/version/{}", project_id, version_id),
                     vec![],
                 ),
+                NotificationBody::ProjectDeleted {
+                    project_title,
+                    scheduled_for,
+                    ..
+                } => (
+                    format!("{} has been deleted", project_title),
+                    format!(
+                        "The project {} has been deleted and will be permanently removed on {}.",
+                        project_title,
+                        scheduled_for.format("%Y-%m-%d")
+                    ),
+                    "#".to_string(),
+                    vec![],
+                ),
                 NotificationBody::TeamInvite {
                     project_id,
                     role,
@@ -166,6 +192,26 @@ impl From<DBNotification> for Notification {
                     },
                     vec![],
                 ),
+                NotificationBody::VersionReview {
+                    project_id,
+                    version_id,
+                    status,
+                    message,
+                } => (
+                    "A version of your project has been reviewed".to_string(),
+                    match message {
+                        Some(message) => format!(
+                            "A moderator set the status of version {} to {}: {}",
+                            version_id, status, message
+                        ),
+                        None => format!(
+                            "A moderator set the status of version {} to {}",
+                            version_id, status
+                        ),
+                    },
+                    format!("/project/{}/version/{}", project_id, version_id),
+                    vec![],
+                ),
                 NotificationBody::LegacyMarkdown {
                     name,
                     text,
@@ -214,3 +260,28 @@ impl From<DBNotificationAction> for NotificationAction {
         }
     }
 }
+
+/// The service a device's push token is registered with
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PushPlatform {
+    Apns,
+    Fcm,
+}
+
+impl PushPlatform {
+    pub fn from_string(string: &str) -> Option<PushPlatform> {
+        match string {
+            "apns" => Some(PushPlatform::Apns),
+            "fcm" => Some(PushPlatform::Fcm),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushPlatform::Apns => "apns",
+            PushPlatform::Fcm => "fcm",
+        }
+    }
+}
@@ -9,6 +9,7 @@ use crate::database::models::version_item::QueryVersion;
 use crate::models::threads::ThreadId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 /// The ID of a specific project, encoded as base62 for usage in the API
@@ -23,8 +24,14 @@ pub struct ProjectId(pub u64);
 #[serde(into = "Base62Id")]
 pub struct VersionId(pub u64);
 
+/// The ID of a file of a version
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Debug)]
+#[serde(from = "Base62Id")]
+#[serde(into = "Base62Id")]
+pub struct FileId(pub u64);
+
 /// A project returned from the API
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct Project {
     /// The ID of the project, encoded as a base62 string.
     pub id: ProjectId,
@@ -102,6 +109,16 @@ pub struct Project {
     /// The monetization status of this project
     pub monetization_status: MonetizationStatus,
 
+    /// The edit counter of this project, incremented on every edit.
+    /// Pass it as `expected_version` when editing to detect concurrent modifications.
+    pub version: i32,
+
+    /// The project this project was duplicated from, if any
+    pub forked_from: Option<ProjectId>,
+
+    /// Whether the project is currently featured on the home page
+    pub featured: bool,
+
     /// Aggregated loader-fields across its myriad of versions
     #[serde(flatten)]
     pub fields: HashMap<String, Vec<serde_json::Value>>,
@@ -225,6 +242,9 @@ impl From<QueryProject> for Project {
             color: m.color,
             thread_id: data.thread_id.into(),
             monetization_status: m.monetization_status,
+            version: m.version,
+            forked_from: m.forked_from.map(|x| x.into()),
+            featured: m.featured,
             fields,
         }
     }
@@ -384,7 +404,7 @@ impl Project {
     //     })
     // }
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct GalleryItem {
     pub url: String,
     pub featured: bool,
@@ -394,7 +414,7 @@ pub struct GalleryItem {
     pub ordering: i64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct ModeratorMessage {
     pub message: String,
     pub body: Option<String>,
@@ -402,14 +422,14 @@ pub struct ModeratorMessage {
 
 pub const DEFAULT_LICENSE_ID: &str = "LicenseRef-All-Rights-Reserved";
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct License {
     pub id: String,
     pub name: String,
     pub url: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Validate, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Validate, Clone, Eq, PartialEq, ToSchema)]
 pub struct Link {
     pub platform: String,
     pub donation: bool,
@@ -438,7 +458,7 @@ impl From<LinkUrl> for Link {
 /// Processing - Project is not displayed on search, and not accessible by URL (Temporary state, project under review)
 /// Scheduled - Project is scheduled to be released in the future
 /// Private - Project is approved, but is not viewable to the public
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectStatus {
     Approved,
@@ -570,7 +590,7 @@ impl ProjectStatus {
     }
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, ToSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum MonetizationStatus {
     ForceDemonetized,
@@ -604,7 +624,7 @@ impl MonetizationStatus {
 }
 
 /// A specific version of a project
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct Version {
     /// The ID of the version, encoded as a base62 string.
     pub id: VersionId,
@@ -687,18 +707,37 @@ impl From<QueryVersion> for Version {
 
             status: v.status,
             requested_status: v.requested_status,
-            files: data
-                .files
-                .into_iter()
-                .map(|f| VersionFile {
-                    url: f.url,
-                    filename: f.filename,
-                    hashes: f.hashes,
-                    primary: f.primary,
-                    size: f.size,
-                    file_type: f.file_type,
-                })
-                .collect(),
+            files: {
+                // Parts of split archives are listed under the file they belong to
+                let (parts, files): (Vec<_>, Vec<_>) = data
+                    .files
+                    .into_iter()
+                    .partition(|f| f.part_group_id.is_some());
+
+                files
+                    .into_iter()
+                    .map(|f| VersionFile {
+                        parts: parts
+                            .iter()
+                            .filter(|p| p.part_group_id == Some(f.id))
+                            .map(|p| FilePart {
+                                hashes: p.hashes.clone(),
+                                url: p.url.clone(),
+                                filename: p.filename.clone(),
+                                size: p.size,
+                                part_index: p.part_index.unwrap_or_default() as u32,
+                            })
+                            .collect(),
+                        id: f.id.into(),
+                        url: f.url,
+                        filename: f.filename,
+                        hashes: f.hashes,
+                        primary: f.primary,
+                        size: f.size,
+                        file_type: f.file_type,
+                    })
+                    .collect()
+            },
             dependencies: data
                 .dependencies
                 .into_iter()
@@ -727,7 +766,7 @@ impl From<QueryVersion> for Version {
 /// Draft - Version is not displayed on project, and not accessible by URL
 /// Unlisted - Version is not displayed on project, and accessible by URL
 /// Scheduled - Version is scheduled to be released in the future
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum VersionStatus {
     Listed,
@@ -735,6 +774,8 @@ pub enum VersionStatus {
     Draft,
     Unlisted,
     Scheduled,
+    /// Rejected by a moderator, such as for containing malware
+    Rejected,
     Unknown,
 }
 
@@ -751,6 +792,7 @@ impl VersionStatus {
             "draft" => VersionStatus::Draft,
             "unlisted" => VersionStatus::Unlisted,
             "scheduled" => VersionStatus::Scheduled,
+            "rejected" => VersionStatus::Rejected,
             _ => VersionStatus::Unknown,
         }
     }
@@ -762,6 +804,7 @@ impl VersionStatus {
             VersionStatus::Unlisted => "unlisted",
             VersionStatus::Unknown => "unknown",
             VersionStatus::Scheduled => "scheduled",
+            VersionStatus::Rejected => "rejected",
         }
     }
 
@@ -772,6 +815,7 @@ impl VersionStatus {
             VersionStatus::Draft,
             VersionStatus::Unlisted,
             VersionStatus::Scheduled,
+            VersionStatus::Rejected,
             VersionStatus::Unknown,
         ]
         .iter()
@@ -787,6 +831,7 @@ impl VersionStatus {
 
             VersionStatus::Draft => true,
             VersionStatus::Scheduled => true,
+            VersionStatus::Rejected => true,
             VersionStatus::Unknown => true,
         }
     }
@@ -804,6 +849,7 @@ impl VersionStatus {
             VersionStatus::Draft => true,
             VersionStatus::Unlisted => true,
             VersionStatus::Scheduled => false,
+            VersionStatus::Rejected => false,
 
             VersionStatus::Unknown => false,
         }
@@ -811,8 +857,10 @@ impl VersionStatus {
 }
 
 /// A single project file, with a url for the file and the file's hash
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct VersionFile {
+    /// The ID of the file, encoded as a base62 string.
+    pub id: FileId,
     /// A map of hashes of the file.  The key is the hashing algorithm
     /// and the value is the string version of the hash.
     pub hashes: std::collections::HashMap<String, String>,
@@ -826,11 +874,30 @@ pub struct VersionFile {
     pub size: u32,
     /// The type of the file
     pub file_type: Option<FileType>,
+    /// The remaining parts of this file, if it is the first part of a split archive
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<FilePart>,
+}
+
+/// One part of a split archive (eg. `mod.z01`), belonging to a version file
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub struct FilePart {
+    /// A map of hashes of the file.  The key is the hashing algorithm
+    /// and the value is the string version of the hash.
+    pub hashes: std::collections::HashMap<String, String>,
+    /// A direct link to the file for downloading it.
+    pub url: String,
+    /// The filename of the file.
+    pub filename: String,
+    /// The size in bytes of the file
+    pub size: u32,
+    /// The position of this part in the archive, starting at 1
+    pub part_index: u32,
 }
 
 /// A dendency which describes what versions are required, break support, or are optional to the
 /// version's functionality
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
 pub struct Dependency {
     /// The specific version id that the dependency uses
     pub version_id: Option<VersionId>,
@@ -842,7 +909,7 @@ pub struct Dependency {
     pub dependency_type: DependencyType,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum VersionType {
     Release,
@@ -867,7 +934,7 @@ impl VersionType {
     }
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DependencyType {
     Required,
@@ -904,7 +971,7 @@ impl DependencyType {
     }
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum FileType {
     RequiredResourcePack,
@@ -939,17 +1006,20 @@ impl FileType {
 }
 
 /// A project loader
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
 #[serde(transparent)]
 pub struct Loader(pub String);
 
 // These fields must always succeed parsing; deserialize errors aren't
 // processed correctly (don't return JSON errors)
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchRequest {
     pub query: Option<String>,
+    /// The amount of results to skip, at most 10000
     pub offset: Option<String>,
     pub index: Option<String>,
+    /// The amount of results to return, at most 100
     pub limit: Option<String>,
 
     pub new_filters: Option<String>,
@@ -959,3 +1029,112 @@ pub struct SearchRequest {
     pub filters: Option<String>,
     pub version: Option<String>,
 }
+
+/// A change to one of the audited fields of a project
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectAuditLogEntry {
+    pub project_id: ProjectId,
+    /// The user who made the change, if they still exist
+    pub user_id: Option<UserId>,
+    pub changed_field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::database::models::project_audit_log_item::ProjectAuditLogEntry>
+    for ProjectAuditLogEntry
+{
+    fn from(data: crate::database::models::project_audit_log_item::ProjectAuditLogEntry) -> Self {
+        Self {
+            project_id: data.project_id.into(),
+            user_id: data.user_id.map(|x| x.into()),
+            changed_field: data.changed_field,
+            old_value: data.old_value,
+            new_value: data.new_value,
+            created_at: data.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectEventType {
+    ProjectCreated,
+    StatusChanged,
+    ProjectDeleted,
+    VersionPublished,
+    VersionDeleted,
+    VersionRestored,
+    MemberAdded,
+    MemberRemoved,
+    MemberRoleChanged,
+    Unknown,
+}
+
+impl ProjectEventType {
+    pub fn from_string(string: &str) -> ProjectEventType {
+        match string {
+            "project_created" => ProjectEventType::ProjectCreated,
+            "status_changed" => ProjectEventType::StatusChanged,
+            "project_deleted" => ProjectEventType::ProjectDeleted,
+            "version_published" => ProjectEventType::VersionPublished,
+            "version_deleted" => ProjectEventType::VersionDeleted,
+            "version_restored" => ProjectEventType::VersionRestored,
+            "member_added" => ProjectEventType::MemberAdded,
+            "member_removed" => ProjectEventType::MemberRemoved,
+            "member_role_changed" => ProjectEventType::MemberRoleChanged,
+            _ => ProjectEventType::Unknown,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectEventType::ProjectCreated => "project_created",
+            ProjectEventType::StatusChanged => "status_changed",
+            ProjectEventType::ProjectDeleted => "project_deleted",
+            ProjectEventType::VersionPublished => "version_published",
+            ProjectEventType::VersionDeleted => "version_deleted",
+            ProjectEventType::VersionRestored => "version_restored",
+            ProjectEventType::MemberAdded => "member_added",
+            ProjectEventType::MemberRemoved => "member_removed",
+            ProjectEventType::MemberRoleChanged => "member_role_changed",
+            ProjectEventType::Unknown => "unknown",
+        }
+    }
+}
+
+/// An entry of a project's timeline
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectEvent {
+    /// The ID of the event, used as the `after` cursor when paginating
+    pub id: i64,
+    pub project_id: ProjectId,
+    pub event_type: ProjectEventType,
+    /// Details of the event, depending on its type
+    pub payload: serde_json::Value,
+    /// The user the event is about (the version author or team member), if any
+    pub user_id: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::database::models::project_event_item::ProjectEvent> for ProjectEvent {
+    fn from(data: crate::database::models::project_event_item::ProjectEvent) -> Self {
+        let mut payload = data.payload;
+        // Version IDs are stored as numbers by the database triggers
+        if let Some(version_id) = payload.get_mut("version_id") {
+            if let Some(id) = version_id.as_i64() {
+                *version_id = serde_json::json!(VersionId(id as u64));
+            }
+        }
+
+        Self {
+            id: data.id,
+            project_id: data.project_id.into(),
+            event_type: ProjectEventType::from_string(&data.event_type),
+            payload,
+            user_id: data.user_id.map(|x| x.into()),
+            created_at: data.created_at,
+        }
+    }
+}
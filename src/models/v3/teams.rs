@@ -1,6 +1,7 @@
 use super::ids::Base62Id;
 use crate::bitflags_serde_impl;
-use crate::models::users::User;
+use crate::models::users::{User, UserId};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -200,3 +201,18 @@ impl TeamMember {
         }
     }
 }
+
+/// A member of a project's team, as listed by `GET /project/{id}/teams`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProjectTeamMember {
+    pub user_id: UserId,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: String,
+    /// Only shown to the project's members and moderators
+    pub permissions: Option<ProjectPermissions>,
+    /// Whether the user has joined the team or is just invited to it
+    pub accepted: bool,
+    /// When the user joined the team. Unknown for invites and for members who joined a long time ago
+    pub joined_at: Option<DateTime<Utc>>,
+}
@@ -1,7 +1,9 @@
-use crate::database::models::DatabaseError;
+use crate::database::models::download_history_item::{DownloadHistory, ViewHistory};
+use crate::database::models::{DatabaseError, ProjectId, User, VersionId};
 use crate::database::redis::RedisPool;
-use crate::models::analytics::{Download, PageView, Playtime};
+use crate::models::analytics::{Download, PageView, Playtime, SearchLog, TrafficEvent};
 use crate::routes::ApiError;
+use chrono::{DateTime, Utc};
 use dashmap::{DashMap, DashSet};
 use redis::cmd;
 use sqlx::PgPool;
@@ -15,6 +17,7 @@ pub struct AnalyticsQueue {
     views_queue: DashMap<(u64, u64), Vec<PageView>>,
     downloads_queue: DashMap<(u64, u64), Download>,
     playtime_queue: DashSet<Playtime>,
+    search_logs_queue: DashSet<SearchLog>,
 }
 
 impl Default for AnalyticsQueue {
@@ -30,6 +33,7 @@ impl AnalyticsQueue {
             views_queue: DashMap::with_capacity(1000),
             downloads_queue: DashMap::with_capacity(1000),
             playtime_queue: DashSet::with_capacity(1000),
+            search_logs_queue: DashSet::with_capacity(1000),
         }
     }
 
@@ -64,6 +68,10 @@ impl AnalyticsQueue {
         self.playtime_queue.insert(playtime);
     }
 
+    pub fn add_search_log(&self, search_log: SearchLog) {
+        self.search_logs_queue.insert(search_log);
+    }
+
     pub async fn index(
         &self,
         client: clickhouse::Client,
@@ -79,6 +87,9 @@ impl AnalyticsQueue {
         let playtime_queue = self.playtime_queue.clone();
         self.playtime_queue.clear();
 
+        let search_logs_queue = self.search_logs_queue.clone();
+        self.search_logs_queue.clear();
+
         if !playtime_queue.is_empty() {
             let mut playtimes = client.insert("playtime")?;
 
@@ -89,6 +100,16 @@ impl AnalyticsQueue {
             playtimes.end().await?;
         }
 
+        if !search_logs_queue.is_empty() {
+            let mut search_logs = client.insert("search_logs")?;
+
+            for search_log in search_logs_queue {
+                search_logs.write(&search_log).await?;
+            }
+
+            search_logs.end().await?;
+        }
+
         if !views_queue.is_empty() {
             let mut views_keys = Vec::new();
             let mut raw_views = Vec::new();
@@ -145,6 +166,7 @@ impl AnalyticsQueue {
                 .map_err(DatabaseError::CacheError)?;
 
             let mut views = client.insert("views")?;
+            let mut history = Vec::new();
 
             for (all_views, monetized) in raw_views {
                 for (idx, mut view) in all_views.into_iter().enumerate() {
@@ -152,11 +174,27 @@ impl AnalyticsQueue {
                         view.monetized = false;
                     }
 
+                    if view.project_id != 0 {
+                        history.push((
+                            ProjectId(view.project_id as i64),
+                            // `recorded` is in tenths of a millisecond
+                            DateTime::from_timestamp(view.recorded / 10_000, 0)
+                                .unwrap_or_else(Utc::now)
+                                .date_naive(),
+                        ));
+                    }
+
                     views.write(&view).await?;
                 }
             }
 
             views.end().await?;
+
+            // The history is only used for timelines, so failing to record it does not stop the
+            // downloads from being flushed
+            if let Err(err) = record_view_history(&history, pool).await {
+                log::warn!("Failed to record view history: {err}");
+            }
         }
 
         if !downloads_queue.is_empty() {
@@ -168,7 +206,7 @@ impl AnalyticsQueue {
                 raw_downloads.insert(index, download);
             }
 
-            let mut redis = redis.pool.get().await.map_err(DatabaseError::RedisPool)?;
+            let mut redis_conn = redis.pool.get().await.map_err(DatabaseError::RedisPool)?;
 
             let results = cmd("MGET")
                 .arg(
@@ -177,7 +215,7 @@ impl AnalyticsQueue {
                         .map(|x| format!("{}:{}-{}", DOWNLOADS_NAMESPACE, x.0, x.1))
                         .collect::<Vec<_>>(),
                 )
-                .query_async::<_, Vec<Option<u32>>>(&mut redis)
+                .query_async::<_, Vec<Option<u32>>>(&mut redis_conn)
                 .await
                 .map_err(DatabaseError::CacheError)?;
 
@@ -202,7 +240,7 @@ impl AnalyticsQueue {
                     6 * 60 * 60,
                 );
             }
-            pipe.query_async(&mut *redis)
+            pipe.query_async(&mut *redis_conn)
                 .await
                 .map_err(DatabaseError::CacheError)?;
 
@@ -211,6 +249,7 @@ impl AnalyticsQueue {
 
             let mut version_downloads: HashMap<i64, i32> = HashMap::new();
             let mut project_downloads: HashMap<i64, i32> = HashMap::new();
+            let mut history = Vec::new();
 
             for (_, download) in raw_downloads {
                 *version_downloads
@@ -219,6 +258,14 @@ impl AnalyticsQueue {
                 *project_downloads
                     .entry(download.project_id as i64)
                     .or_default() += 1;
+                history.push((
+                    ProjectId(download.project_id as i64),
+                    VersionId(download.version_id as i64),
+                    // `recorded` is in tenths of a millisecond
+                    DateTime::from_timestamp(download.recorded / 10_000, 0)
+                        .unwrap_or_else(Utc::now)
+                        .date_naive(),
+                ));
 
                 downloads.write(&download).await?;
             }
@@ -249,10 +296,52 @@ impl AnalyticsQueue {
             .execute(&mut *transaction)
             .await?;
 
+            DownloadHistory::record(&history, &mut transaction).await?;
+
             transaction.commit().await?;
             downloads.end().await?;
+
+            // The downloads are stored at this point, so a stale cache is only logged
+            if let Err(err) = User::clear_download_stats_caches(
+                &project_downloads
+                    .keys()
+                    .map(|x| ProjectId(*x))
+                    .collect::<Vec<_>>(),
+                pool,
+                redis,
+            )
+            .await
+            {
+                log::warn!("Failed to clear download stats caches: {err}");
+            }
         }
 
         Ok(())
     }
 }
+
+async fn record_view_history(
+    history: &[(ProjectId, chrono::NaiveDate)],
+    pool: &PgPool,
+) -> Result<(), DatabaseError> {
+    let mut transaction = pool.begin().await?;
+    ViewHistory::record(history, &mut transaction).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// The pub/sub channel the downloads of a project are published to as they are counted
+pub fn traffic_channel(project_id: crate::models::ids::ProjectId) -> String {
+    format!("project:{project_id}:traffic")
+}
+
+pub async fn publish_traffic_event(
+    project_id: crate::models::ids::ProjectId,
+    event: &TrafficEvent,
+    redis: &RedisPool,
+) -> Result<(), DatabaseError> {
+    let mut redis = redis.connect().await?;
+    redis
+        .publish(&traffic_channel(project_id), &serde_json::to_string(event)?)
+        .await
+}
@@ -0,0 +1,45 @@
+use crate::database::models::{User, UserId};
+use crate::database::redis::RedisPool;
+use crate::search::indexing::retry::index_projects_by_id_or_retry;
+use crate::search::indexing::IndexingError;
+use crate::search::SearchConfig;
+use sqlx::PgPool;
+
+/// Re-indexes the projects a user owns, so their search documents reflect the user's current
+/// username and whether they are banned
+pub async fn reindex_user_projects(
+    user_id: UserId,
+    pool: &PgPool,
+    redis: &RedisPool,
+    config: &SearchConfig,
+) -> Result<(), IndexingError> {
+    let project_ids = User::get_owned_projects(user_id, pool).await?;
+    index_projects_by_id_or_retry(pool, redis, config, &project_ids).await
+}
+
+/// Lifts the bans which have ended
+pub async fn unban_expired_users(
+    pool: &PgPool,
+    redis: &RedisPool,
+    config: &SearchConfig,
+) -> Result<(), IndexingError> {
+    let users = User::unban_expired(pool).await?;
+    if users.is_empty() {
+        return Ok(());
+    }
+
+    User::clear_caches(
+        &users
+            .iter()
+            .map(|(id, username)| (*id, Some(username.clone())))
+            .collect::<Vec<_>>(),
+        redis,
+    )
+    .await?;
+
+    for (user_id, _) in users {
+        reindex_user_projects(user_id, pool, redis, config).await?;
+    }
+
+    Ok(())
+}
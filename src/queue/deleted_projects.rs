@@ -0,0 +1,80 @@
+use crate::database::models::image_item::Image;
+use crate::database::models::{Project, ProjectId};
+use crate::database::redis::RedisPool;
+use crate::file_hosting::FileHost;
+use crate::models::images::ImageContext;
+use crate::routes::ApiError;
+use log::warn;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Permanently deletes the deleted projects whose deletion is due, along with the files of all
+/// their versions on the CDN. A project that fails to be deleted is retried on the next run.
+pub async fn purge_deleted_projects(
+    pool: &PgPool,
+    redis: &RedisPool,
+    file_host: &Arc<dyn FileHost + Send + Sync>,
+) -> Result<(), ApiError> {
+    let cdn_url = dotenvy::var("CDN_URL")?;
+
+    for project_id in Project::get_due_deletions(pool).await? {
+        if let Err(err) = purge_project(project_id, &cdn_url, pool, redis, file_host).await {
+            warn!(
+                "Failed to purge deleted project {}: {err}",
+                crate::models::ids::ProjectId::from(project_id)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn purge_project(
+    project_id: ProjectId,
+    cdn_url: &str,
+    pool: &PgPool,
+    redis: &RedisPool,
+    file_host: &Arc<dyn FileHost + Send + Sync>,
+) -> Result<(), ApiError> {
+    let file_urls = sqlx::query!(
+        "
+        SELECT f.url FROM files f
+        INNER JOIN versions v ON v.id = f.version_id
+        WHERE v.mod_id = $1
+        ",
+        project_id as ProjectId,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut transaction = pool.begin().await?;
+    let context = ImageContext::Project {
+        project_id: Some(project_id.into()),
+    };
+    for image in Image::get_many_contexted(context, &mut transaction).await? {
+        Image::remove(image.id, &mut transaction, redis).await?;
+    }
+
+    sqlx::query!(
+        "
+        DELETE FROM collections_mods
+        WHERE mod_id = $1
+        ",
+        project_id as ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    Project::remove(project_id, &mut transaction, redis).await?;
+    transaction.commit().await?;
+
+    for file in file_urls {
+        if let Some(path) = file.url.split(&format!("{cdn_url}/")).nth(1) {
+            if let Err(err) = file_host.delete_file_version("", path).await {
+                warn!("Failed to delete purged project file {}: {err}", file.url);
+            }
+        }
+    }
+
+    Ok(())
+}
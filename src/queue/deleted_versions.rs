@@ -0,0 +1,50 @@
+use crate::database::models::image_item::Image;
+use crate::database::models::{Version, VersionId};
+use crate::database::redis::RedisPool;
+use crate::file_hosting::FileHost;
+use crate::models::images::ImageContext;
+use crate::routes::ApiError;
+use log::warn;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Permanently deletes the versions deleted too long ago to be restored, along with their files
+/// on the CDN
+pub async fn purge_deleted_versions(
+    pool: &PgPool,
+    redis: &RedisPool,
+    file_host: &Arc<dyn FileHost + Send + Sync>,
+) -> Result<(), ApiError> {
+    let cdn_url = dotenvy::var("CDN_URL")?;
+
+    for version_id in Version::get_expired_deleted(pool).await? {
+        let file_urls = sqlx::query!(
+            "
+            SELECT url FROM files WHERE version_id = $1
+            ",
+            version_id as VersionId,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut transaction = pool.begin().await?;
+        let context = ImageContext::Version {
+            version_id: Some(version_id.into()),
+        };
+        for image in Image::get_many_contexted(context, &mut transaction).await? {
+            Image::remove(image.id, &mut transaction, redis).await?;
+        }
+        Version::remove_full(version_id, redis, &mut transaction).await?;
+        transaction.commit().await?;
+
+        for file in file_urls {
+            if let Some(path) = file.url.split(&format!("{cdn_url}/")).nth(1) {
+                if let Err(err) = file_host.delete_file_version("", path).await {
+                    warn!("Failed to delete purged version file {}: {err}", file.url);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use crate::database::models::backfill_job_item::BackfillJob;
+use crate::database::models::version_item::VERSIONS_NAMESPACE;
+use crate::database::models::{DatabaseError, FileId, VersionId};
+use crate::database::redis::RedisPool;
+use crate::util::env::parse_var;
+use log::warn;
+use sha2::Digest;
+use sqlx::PgPool;
+use std::time::Duration;
+
+/// The default delay between two file downloads of a backfill, so the CDN origin is not overloaded
+const DEFAULT_FETCH_INTERVAL_MS: u64 = 250;
+/// How long connecting to the CDN and downloading a single file may take, so one stalled download
+/// cannot keep the job running forever
+const FETCH_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A hash algorithm files can be backfilled with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn from_string(string: &str) -> Option<HashAlgorithm> {
+        match string {
+            "sha1" => Some(HashAlgorithm::Sha1),
+            "sha256" => Some(HashAlgorithm::Sha256),
+            "sha512" => Some(HashAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+}
+
+enum Hasher {
+    Sha1(sha1::Sha1),
+    Sha256(sha2::Sha256),
+    Sha512(sha2::Sha512),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha1 => Hasher::Sha1(sha1::Sha1::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha512 => Hasher::Sha512(sha2::Sha512::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha1(hasher) => hasher.update(data),
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+        }
+    }
+
+    /// The hex digest, which is how hashes are stored
+    fn finish(self) -> String {
+        match self {
+            Hasher::Sha1(hasher) => hasher.digest().to_string(),
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha512(hasher) => format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+/// A file that does not have a hash of the job's algorithm yet
+pub struct BackfillFile {
+    pub id: FileId,
+    pub version_id: VersionId,
+    pub url: String,
+}
+
+/// Downloads each file one at a time, computes its hash while streaming it and stores it.
+/// Failed or timed out downloads are recorded on the job and do not stop it, while database
+/// errors stop it and mark it as failed.
+pub async fn run_hash_backfill(
+    job_id: i64,
+    algorithm: HashAlgorithm,
+    files: Vec<BackfillFile>,
+    pool: PgPool,
+    redis: RedisPool,
+) -> Result<(), DatabaseError> {
+    let client = match reqwest::Client::builder()
+        .connect_timeout(FETCH_CONNECT_TIMEOUT)
+        .timeout(FETCH_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => return BackfillJob::fail(job_id, err.to_string(), &pool).await,
+    };
+
+    match backfill_files(job_id, algorithm, files, &client, &pool, &redis).await {
+        Ok(()) => BackfillJob::complete(job_id, &pool).await,
+        Err(err) => {
+            BackfillJob::fail(job_id, err.to_string(), &pool).await?;
+            Err(err)
+        }
+    }
+}
+
+async fn backfill_files(
+    job_id: i64,
+    algorithm: HashAlgorithm,
+    files: Vec<BackfillFile>,
+    client: &reqwest::Client,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), DatabaseError> {
+    let interval = Duration::from_millis(
+        parse_var("HASH_BACKFILL_INTERVAL_MS").unwrap_or(DEFAULT_FETCH_INTERVAL_MS),
+    );
+
+    for (index, file) in files.into_iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(interval).await;
+        }
+
+        let error = match hash_file(client, &file.url, algorithm).await {
+            Ok(hash) => {
+                sqlx::query!(
+                    "
+                    INSERT INTO hashes (file_id, algorithm, hash)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT DO NOTHING
+                    ",
+                    file.id as FileId,
+                    algorithm.as_str(),
+                    hash.as_bytes(),
+                )
+                .execute(pool)
+                .await?;
+
+                let mut redis = redis.connect().await?;
+                redis.delete(VERSIONS_NAMESPACE, file.version_id.0).await?;
+                None
+            }
+            Err(err) => {
+                warn!("Failed to backfill the hash of {}: {err}", file.url);
+                Some(format!("{}: {err}", file.url))
+            }
+        };
+
+        BackfillJob::record_progress(job_id, error, pool).await?;
+    }
+
+    Ok(())
+}
+
+async fn hash_file(
+    client: &reqwest::Client,
+    url: &str,
+    algorithm: HashAlgorithm,
+) -> Result<String, reqwest::Error> {
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let mut hasher = Hasher::new(algorithm);
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+    }
+
+    Ok(hasher.finish())
+}
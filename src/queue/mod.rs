@@ -1,6 +1,11 @@
 pub mod analytics;
+pub mod bans;
+pub mod deleted_projects;
+pub mod deleted_versions;
+pub mod hash_backfill;
 pub mod maxmind;
 pub mod moderation;
 pub mod payouts;
+pub mod push;
 pub mod session;
 pub mod socket;
@@ -690,12 +690,7 @@ impl AutomatedModerationQueue {
                                         .execute(&pool)
                                         .await?;
 
-                                    database::models::Project::clear_cache(
-                                        project.inner.id,
-                                        project.inner.slug.clone(),
-                                        None,
-                                        &redis,
-                                    )
+                                    database::models::project_item::clear_project_caches(project.inner.id, project.inner.slug.clone(), &redis)
                                         .await?;
                                 } else {
                                     NotificationBuilder {
@@ -0,0 +1,262 @@
+use crate::database::models::notification_item::Notification as DBNotification;
+use crate::database::models::push_token_item::PushToken;
+use crate::database::models::{DatabaseError, NotificationId};
+use crate::models::notifications::{Notification, PushPlatform};
+use crate::util::env::parse_var;
+use a2::{DefaultNotificationBuilder, ErrorReason, NotificationBuilder, NotificationOptions};
+use chrono::{Duration, Utc};
+use itertools::Itertools;
+use log::warn;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::fs::File;
+
+const FCM_SEND_URL: &str = "https://fcm.googleapis.com/fcm/send";
+
+/// The most notifications that are pushed in one run of the queue
+const PUSH_BATCH_SIZE: i64 = 500;
+
+/// Notifications older than this when they are picked up are marked as pushed without being sent,
+/// so enabling push (or recovering from an outage) does not flood devices with old notifications
+const MAX_PUSH_AGE_MINUTES: i64 = 60;
+
+/// Credentials of the push services. Each service is disabled unless all of its fields are set.
+#[derive(Clone, Default)]
+pub struct PushConfig {
+    /// Path to the `.p8` token signing key of the APNs app
+    pub apns_key_path: Option<String>,
+    pub apns_key_id: Option<String>,
+    pub apns_team_id: Option<String>,
+    /// The bundle ID of the iOS app
+    pub apns_topic: Option<String>,
+    /// Whether to send to the APNs development environment instead of production
+    pub apns_sandbox: bool,
+    pub fcm_server_key: Option<String>,
+}
+
+impl PushConfig {
+    pub fn from_env() -> Self {
+        Self {
+            apns_key_path: dotenvy::var("APNS_KEY_PATH").ok(),
+            apns_key_id: dotenvy::var("APNS_KEY_ID").ok(),
+            apns_team_id: dotenvy::var("APNS_TEAM_ID").ok(),
+            apns_topic: dotenvy::var("APNS_TOPIC").ok(),
+            apns_sandbox: parse_var("APNS_SANDBOX").unwrap_or(false),
+            fcm_server_key: dotenvy::var("FCM_SERVER_KEY").ok(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PushError {
+    #[error("Database Error: {0}")]
+    Database(#[from] DatabaseError),
+    #[error("Database Error: {0}")]
+    SqlxDatabase(#[from] sqlx::Error),
+    #[error("APNs Error: {0}")]
+    Apns(#[from] a2::Error),
+    #[error("FCM Error: {0}")]
+    Fcm(#[from] reqwest::Error),
+}
+
+struct ApnsSender {
+    client: a2::Client,
+    topic: String,
+}
+
+#[derive(Deserialize)]
+struct FcmResponse {
+    #[serde(default)]
+    results: Vec<FcmResult>,
+}
+
+#[derive(Deserialize)]
+struct FcmResult {
+    error: Option<String>,
+}
+
+/// Delivers new notifications to the mobile devices of their users
+pub struct PushQueue {
+    apns: Option<ApnsSender>,
+    fcm_server_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl PushQueue {
+    /// Creates the queue, returning `None` if no push service is configured
+    pub fn new(config: &PushConfig) -> Result<Option<Self>, PushError> {
+        let apns = match (
+            &config.apns_key_path,
+            &config.apns_key_id,
+            &config.apns_team_id,
+            &config.apns_topic,
+        ) {
+            (Some(key_path), Some(key_id), Some(team_id), Some(topic)) => {
+                let mut key = File::open(key_path).map_err(a2::Error::from)?;
+                let endpoint = if config.apns_sandbox {
+                    a2::Endpoint::Sandbox
+                } else {
+                    a2::Endpoint::Production
+                };
+
+                Some(ApnsSender {
+                    client: a2::Client::token(&mut key, key_id, team_id, endpoint)?,
+                    topic: topic.clone(),
+                })
+            }
+            _ => None,
+        };
+
+        if apns.is_none() && config.fcm_server_key.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            apns,
+            fcm_server_key: config.fcm_server_key.clone(),
+            client: reqwest::Client::new(),
+        }))
+    }
+
+    /// Pushes notifications that have not been pushed yet, and removes tokens the push services
+    /// report as expired or invalid. Failed deliveries are logged and not retried.
+    ///
+    /// Notifications are marked as pushed as they are picked up, so instances running the queue
+    /// at the same time never pick up the same ones.
+    pub async fn push(&self, pool: &PgPool) -> Result<(), PushError> {
+        let pending = sqlx::query!(
+            "
+            UPDATE notifications
+            SET pushed = TRUE
+            WHERE id IN (
+                SELECT id FROM notifications
+                WHERE NOT pushed
+                ORDER BY created
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, created
+            ",
+            PUSH_BATCH_SIZE,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let max_age = Utc::now() - Duration::minutes(MAX_PUSH_AGE_MINUTES);
+        let fresh_ids = pending
+            .iter()
+            .filter(|x| x.created > max_age)
+            .map(|x| NotificationId(x.id))
+            .collect::<Vec<_>>();
+
+        let notifications = DBNotification::get_many(&fresh_ids, pool).await?;
+        let user_ids = notifications
+            .iter()
+            .map(|x| x.user_id)
+            .unique()
+            .collect::<Vec<_>>();
+        let tokens = PushToken::get_many_users(&user_ids, pool).await?;
+
+        let mut stale_tokens = Vec::new();
+        for notification in notifications {
+            let user_id = notification.user_id;
+            let notification: Notification = notification.into();
+
+            for token in tokens.iter().filter(|x| x.user_id == user_id) {
+                let result = match token.platform {
+                    PushPlatform::Apns => self.send_apns(&token.token, &notification).await,
+                    PushPlatform::Fcm => self.send_fcm(&token.token, &notification).await,
+                };
+
+                match result {
+                    Ok(true) => stale_tokens.push(token.token.clone()),
+                    Ok(false) => {}
+                    Err(err) => warn!("Failed to push notification {}: {err}", notification.id.0),
+                }
+            }
+        }
+
+        if !stale_tokens.is_empty() {
+            PushToken::remove_many(&stale_tokens, pool).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a notification through APNs, returning whether the token is no longer valid
+    async fn send_apns(&self, token: &str, notification: &Notification) -> Result<bool, PushError> {
+        let Some(apns) = &self.apns else {
+            return Ok(false);
+        };
+
+        let mut payload = DefaultNotificationBuilder::new()
+            .set_title(&notification.name)
+            .set_body(&notification.text)
+            .set_sound("default")
+            .build(
+                token,
+                NotificationOptions {
+                    apns_topic: Some(&apns.topic),
+                    ..Default::default()
+                },
+            );
+        payload.add_custom_data("link", &notification.link)?;
+
+        match apns.client.send(payload).await {
+            Ok(_) => Ok(false),
+            Err(a2::Error::ResponseError(response))
+                if response.code == 410
+                    || response.error.as_ref().is_some_and(|x| {
+                        matches!(
+                            x.reason,
+                            ErrorReason::BadDeviceToken
+                                | ErrorReason::Unregistered
+                                | ErrorReason::DeviceTokenNotForTopic
+                        )
+                    }) =>
+            {
+                Ok(true)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Sends a notification through FCM, returning whether the token is no longer valid
+    async fn send_fcm(&self, token: &str, notification: &Notification) -> Result<bool, PushError> {
+        let Some(server_key) = &self.fcm_server_key else {
+            return Ok(false);
+        };
+
+        let response: FcmResponse = self
+            .client
+            .post(FCM_SEND_URL)
+            .header("Authorization", format!("key={server_key}"))
+            .json(&json!({
+                "to": token,
+                "notification": {
+                    "title": notification.name,
+                    "body": notification.text,
+                },
+                "data": {
+                    "link": notification.link,
+                },
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.results.iter().any(|x| {
+            matches!(
+                x.error.as_deref(),
+                Some("NotRegistered") | Some("InvalidRegistration")
+            )
+        }))
+    }
+}
@@ -8,6 +8,7 @@ use crate::queue::session::AuthQueue;
 use crate::routes::ApiError;
 use crate::util::date::get_current_tenths_of_ms;
 use crate::util::env::parse_strings_from_var;
+use crate::util::ip::ClientIp;
 use actix_web::{post, web};
 use actix_web::{HttpRequest, HttpResponse};
 use serde::Deserialize;
@@ -68,7 +69,7 @@ pub async fn page_view_ingest(
     let user = get_user_from_headers(&req, &**pool, &redis, &session_queue, None)
         .await
         .ok();
-    let conn_info = req.connection_info().peer_addr().map(|x| x.to_string());
+    let client_ip = ClientIp::get(&req);
 
     let url = Url::parse(&url_input.url)
         .map_err(|_| ApiError::InvalidInput("invalid page view URL specified!".to_string()))?;
@@ -98,12 +99,11 @@ pub async fn page_view_ingest(
         })
         .collect::<HashMap<String, String>>();
 
-    let ip = convert_to_ip_v6(if let Some(header) = headers.get("cf-connecting-ip") {
-        header
-    } else {
-        conn_info.as_deref().unwrap_or_default()
-    })
-    .unwrap_or_else(|_| Ipv4Addr::new(127, 0, 0, 1).to_ipv6_mapped());
+    let ip = match client_ip {
+        Some(IpAddr::V4(x)) => x.to_ipv6_mapped(),
+        Some(IpAddr::V6(x)) => x,
+        None => Ipv4Addr::new(127, 0, 0, 1).to_ipv6_mapped(),
+    };
 
     let mut view = PageView {
         recorded: get_current_tenths_of_ms(),
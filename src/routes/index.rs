@@ -1,5 +1,10 @@
-use actix_web::{get, HttpResponse};
+use std::time::Duration;
+
+use crate::database::redis::RedisPool;
+use crate::search::SearchConfig;
+use actix_web::{get, web, HttpResponse};
 use serde_json::json;
+use sqlx::PgPool;
 
 #[get("/")]
 pub async fn index_get() -> HttpResponse {
@@ -12,3 +17,61 @@ pub async fn index_get() -> HttpResponse {
 
     HttpResponse::Ok().json(data)
 }
+
+/// How long each dependency has to respond before it is considered degraded
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Reports the connectivity of the services the API depends on, for readiness probes.
+///
+/// The API is `down` (503) without Postgres, and only `degraded` without Redis or search.
+#[get("/health")]
+pub async fn health_get(
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    config: web::Data<SearchConfig>,
+) -> HttpResponse {
+    let postgres = async { sqlx::query("SELECT 1").execute(&**pool).await.is_ok() };
+    let redis = async {
+        let Ok(mut redis) = redis.connect().await else {
+            return false;
+        };
+
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut redis.connection)
+            .await
+            .is_ok()
+    };
+    let meilisearch = async {
+        let client = config.make_client();
+        client.health().await.is_ok()
+    };
+
+    let (postgres, redis, meilisearch) = futures::join!(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, postgres),
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, redis),
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, meilisearch),
+    );
+    let [postgres, redis, meilisearch] = [postgres, redis, meilisearch].map(|x| x.unwrap_or(false));
+
+    let label = |ok: bool| if ok { "ok" } else { "degraded" };
+    let status = if !postgres {
+        "down"
+    } else if !redis || !meilisearch {
+        "degraded"
+    } else {
+        "ok"
+    };
+
+    let data = json!({
+        "postgres": label(postgres),
+        "redis": label(redis),
+        "meilisearch": label(meilisearch),
+        "status": status,
+    });
+
+    if postgres {
+        HttpResponse::Ok().json(data)
+    } else {
+        HttpResponse::ServiceUnavailable().json(data)
+    }
+}
@@ -1,17 +1,33 @@
+use crate::auth::check_is_moderator_from_headers;
 use crate::auth::validate::get_user_record_from_bearer_token;
+use crate::database::models::backfill_job_item::BackfillJob;
+use crate::database::models::categories::Category;
+use crate::database::models::legacy_loader_fields::{GameVersionSemver, MinecraftGameVersion};
+use crate::database::models::loader_fields::Loader;
+use crate::database::models::loader_version_map_item::LoaderVersionMapping;
+use crate::database::models::moderation_log_item::ModerationLogBuilder;
+use crate::database::models::project_item;
+use crate::database::models::session_item::Session;
+use crate::database::models::{Collection, User};
 use crate::database::redis::RedisPool;
-use crate::models::analytics::Download;
-use crate::models::ids::ProjectId;
+use crate::models::analytics::{Download, TrafficEvent};
+use crate::models::ids::{CollectionId, ProjectId, UserId};
 use crate::models::pats::Scopes;
-use crate::queue::analytics::AnalyticsQueue;
+use crate::models::users::Role;
+use crate::queue::analytics::{publish_traffic_event, AnalyticsQueue};
+use crate::queue::bans::reindex_user_projects;
+use crate::queue::hash_backfill::{run_hash_backfill, BackfillFile, HashAlgorithm};
 use crate::queue::maxmind::MaxMindIndexer;
 use crate::queue::session::AuthQueue;
 use crate::routes::ApiError;
+use crate::search::indexing::reindex::{run_reindex_task, ReindexTask};
 use crate::search::SearchConfig;
 use crate::util::date::get_current_tenths_of_ms;
 use crate::util::guards::admin_key_guard;
-use actix_web::{patch, post, web, HttpRequest, HttpResponse};
-use serde::Deserialize;
+use crate::util::validate::validate_string_length;
+use actix_web::{delete, get, patch, post, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::net::Ipv4Addr;
@@ -21,7 +37,25 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("admin")
             .service(count_download)
-            .service(force_reindex),
+            .service(force_reindex)
+            .service(reindex_create)
+            .service(reindex_get)
+            .service(search_stats_get)
+            .service(bulk_recategorize)
+            .service(users_list)
+            .service(zero_result_searches)
+            .service(loader_version_map_add)
+            .service(loader_version_map_delete)
+            .service(loader_deprecation_edit)
+            .service(game_version_create)
+            .service(featured_collection_add)
+            .service(featured_collection_delete)
+            .service(featured_project_add)
+            .service(featured_project_delete)
+            .service(hash_backfill_create)
+            .service(hash_backfill_get)
+            .service(user_ban)
+            .service(user_unban),
     );
 }
 
@@ -68,7 +102,7 @@ pub async fn count_download(
         "
             SELECT v.id id, v.mod_id mod_id FROM files f
             INNER JOIN versions v ON v.id = f.version_id
-            WHERE f.url = $1
+            WHERE f.url = $1 AND v.deleted_at IS NULL
             ",
         download_body.url,
     )
@@ -79,7 +113,7 @@ pub async fn count_download(
     } else if let Some(version) = sqlx::query!(
         "
         SELECT id, mod_id FROM versions
-        WHERE ((version_number = $1 OR id = $3) AND mod_id = $2)
+        WHERE ((version_number = $1 OR id = $3) AND mod_id = $2 AND deleted_at IS NULL)
         ",
         download_body.version_name,
         project_id as crate::database::models::ids::ProjectId,
@@ -101,6 +135,19 @@ pub async fn count_download(
     let ip = crate::routes::analytics::convert_to_ip_v6(&download_body.ip)
         .unwrap_or_else(|_| Ipv4Addr::new(127, 0, 0, 1).to_ipv6_mapped());
 
+    let country = maxmind.query(ip).await.unwrap_or_default();
+
+    let traffic_event = TrafficEvent {
+        version_id: crate::models::ids::VersionId(version_id as u64),
+        timestamp: Utc::now(),
+        country_code: country.clone(),
+    };
+    if let Err(err) =
+        publish_traffic_event(ProjectId(project_id as u64), &traffic_event, &redis).await
+    {
+        log::warn!("Failed to publish the traffic of project {project_id}: {err}");
+    }
+
     analytics_queue.add_download(Download {
         recorded: get_current_tenths_of_ms(),
         domain: url.host_str().unwrap_or_default().to_string(),
@@ -117,7 +164,7 @@ pub async fn count_download(
         project_id: project_id as u64,
         version_id: version_id as u64,
         ip,
-        country: maxmind.query(ip).await.unwrap_or_default(),
+        country,
         user_agent: download_body
             .headers
             .get("user-agent")
@@ -145,3 +192,1197 @@ pub async fn force_reindex(
     index_projects(pool.as_ref().clone(), redis.clone(), &config).await?;
     Ok(HttpResponse::NoContent().finish())
 }
+
+#[derive(Deserialize)]
+pub struct ReindexBody {
+    /// IDs or slugs of the projects to re-index. If set, the other filters are ignored.
+    pub project_ids: Option<Vec<String>>,
+    pub project_type: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Starts re-indexing a selection of projects in the background
+#[post("/reindex")]
+pub async fn reindex_create(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    config: web::Data<SearchConfig>,
+    body: web::Json<ReindexBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let body = body.into_inner();
+    let mut errors = Vec::new();
+    let project_ids = if let Some(ids) = body.project_ids {
+        let projects = project_item::Project::get_many(&ids, &**pool, &redis).await?;
+        for id in &ids {
+            if !projects.iter().any(|x| {
+                ProjectId::from(x.inner.id).to_string() == *id
+                    || x.inner.slug.as_deref() == Some(id.as_str())
+            }) {
+                errors.push(format!("Unknown project `{id}`"));
+            }
+        }
+
+        projects.into_iter().map(|x| x.inner.id).collect::<Vec<_>>()
+    } else if body.project_type.is_some() || body.category.is_some() {
+        sqlx::query!(
+            "
+            SELECT m.id FROM mods m
+            WHERE m.status = ANY($1)
+            AND ($2::text IS NULL OR EXISTS (
+                SELECT 1 FROM versions v
+                INNER JOIN loaders_versions lv ON lv.version_id = v.id
+                INNER JOIN loaders_project_types lpt ON lpt.joining_loader_id = lv.loader_id
+                INNER JOIN project_types pt ON pt.id = lpt.joining_project_type_id
+                WHERE v.mod_id = m.id AND pt.name = $2 AND v.deleted_at IS NULL
+            ))
+            AND ($3::text IS NULL OR EXISTS (
+                SELECT 1 FROM mods_categories mc
+                INNER JOIN categories c ON c.id = mc.joining_category_id
+                WHERE mc.joining_mod_id = m.id AND c.category = $3
+            ))
+            ORDER BY m.id
+            ",
+            &*crate::models::projects::ProjectStatus::iterator()
+                .filter(|x| x.is_searchable())
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>(),
+            body.project_type,
+            body.category,
+        )
+        .fetch_all(&**pool)
+        .await?
+        .into_iter()
+        .map(|x| crate::database::models::ProjectId(x.id))
+        .collect()
+    } else {
+        return Err(ApiError::InvalidInput(
+            "One of `project_ids`, `project_type` or `category` must be given!".to_string(),
+        ));
+    };
+
+    let task_id = ReindexTask::create(project_ids.len(), errors, &redis).await?;
+
+    let pool = pool.get_ref().clone();
+    let redis = redis.get_ref().clone();
+    let config = config.get_ref().clone();
+    let id = task_id.clone();
+    actix_rt::spawn(async move {
+        if let Err(err) = run_reindex_task(id, project_ids, pool, redis, config).await {
+            log::warn!("Failed to record the state of a re-index task: {err}");
+        }
+    });
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({ "task_id": task_id })))
+}
+
+const SEARCH_STATS_NAMESPACE: &str = "search_stats";
+/// How long search index statistics are cached, in seconds
+const SEARCH_STATS_EXPIRY: i64 = 60;
+/// The amount of recent Meilisearch tasks shown when the statistics are refreshed
+const SEARCH_STATS_TASKS: u32 = 5;
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchIndexStats {
+    pub number_of_documents: usize,
+    pub is_indexing: bool,
+    pub field_distribution: HashMap<String, usize>,
+    /// How many tasks of the index are still enqueued or processing in Meilisearch
+    pub index_lag: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchTaskStatus {
+    pub uid: u32,
+    pub status: String,
+    pub index_uid: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchStats {
+    pub total_projects_in_db: i64,
+    pub indexes: HashMap<String, SearchIndexStats>,
+    /// Only fetched when the statistics are refreshed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_tasks: Option<Vec<SearchTaskStatus>>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchStatsQuery {
+    #[serde(default)]
+    pub force_refresh: bool,
+}
+
+/// The statistics of the search indexes, compared with the database. `force_refresh` skips the
+/// cache and also returns the latest Meilisearch tasks.
+#[get("/stats/search")]
+pub async fn search_stats_get(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    config: web::Data<SearchConfig>,
+    web::Query(query): web::Query<SearchStatsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await?;
+    if !user.role.is_admin() {
+        return Err(ApiError::CustomAuthentication(
+            "You must be an admin to view search statistics!".to_string(),
+        ));
+    }
+
+    let mut redis = redis.connect().await?;
+    if !query.force_refresh {
+        if let Some(stats) = redis
+            .get_deserialized_from_json::<SearchStats>(SEARCH_STATS_NAMESPACE, "stats")
+            .await?
+        {
+            return Ok(HttpResponse::Ok().json(stats));
+        }
+    }
+
+    let total_projects_in_db = sqlx::query!(
+        "
+        SELECT COUNT(id)
+        FROM mods
+        WHERE status = ANY($1) AND deleted_at IS NULL
+        ",
+        &*crate::models::projects::ProjectStatus::iterator()
+            .filter(|x| x.is_searchable())
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>(),
+    )
+    .fetch_one(&**pool)
+    .await?
+    .count
+    .unwrap_or(0);
+
+    let client = config.make_client();
+    let mut indexes = HashMap::new();
+    for name in ["projects", "projects_filtered"] {
+        use meilisearch_sdk::tasks::TasksSearchQuery;
+
+        let index_name = config.get_index_name(name, false);
+        let stats = client.index(&index_name).get_stats().await?;
+        let pending_tasks = client
+            .get_tasks_with(
+                TasksSearchQuery::new(&client)
+                    .with_index_uids([index_name.as_str()])
+                    .with_statuses(["enqueued", "processing"])
+                    .with_limit(1),
+            )
+            .await?;
+        indexes.insert(
+            index_name,
+            SearchIndexStats {
+                number_of_documents: stats.number_of_documents,
+                is_indexing: stats.is_indexing,
+                field_distribution: stats.field_distribution,
+                index_lag: pending_tasks.total,
+            },
+        );
+    }
+
+    let mut stats = SearchStats {
+        total_projects_in_db,
+        indexes,
+        recent_tasks: None,
+    };
+    redis
+        .set_serialized_to_json(
+            SEARCH_STATS_NAMESPACE,
+            "stats",
+            &stats,
+            Some(SEARCH_STATS_EXPIRY),
+        )
+        .await?;
+
+    if query.force_refresh {
+        use meilisearch_sdk::tasks::{Task, TasksSearchQuery};
+
+        let tasks = client
+            .get_tasks_with(TasksSearchQuery::new(&client).with_limit(SEARCH_STATS_TASKS))
+            .await?;
+        stats.recent_tasks = Some(
+            tasks
+                .results
+                .into_iter()
+                .map(|task| match task {
+                    Task::Enqueued { content } => SearchTaskStatus {
+                        uid: content.uid,
+                        status: "enqueued".to_string(),
+                        index_uid: content.index_uid,
+                        error: None,
+                    },
+                    Task::Processing { content } => SearchTaskStatus {
+                        uid: content.uid,
+                        status: "processing".to_string(),
+                        index_uid: content.index_uid,
+                        error: None,
+                    },
+                    Task::Failed { content } => SearchTaskStatus {
+                        uid: content.task.uid,
+                        status: "failed".to_string(),
+                        index_uid: content.task.index_uid,
+                        error: Some(content.error.error_message),
+                    },
+                    Task::Succeeded { content } => SearchTaskStatus {
+                        uid: content.uid,
+                        status: "succeeded".to_string(),
+                        index_uid: content.index_uid,
+                        error: None,
+                    },
+                })
+                .collect(),
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+#[get("/reindex/{task_id}")]
+pub async fn reindex_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await?;
+
+    let task = ReindexTask::get(&info.into_inner().0, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(task))
+}
+
+#[derive(Deserialize)]
+pub struct BulkCategoryBody {
+    pub from_category: String,
+    pub to_category: String,
+    pub project_type: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BulkCategoryResult {
+    pub affected_count: usize,
+}
+
+#[patch("/projects/bulk-category")]
+pub async fn bulk_recategorize(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    config: web::Data<SearchConfig>,
+    body: web::Json<BulkCategoryBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let body = body.into_inner();
+    if body.from_category == body.to_category {
+        return Err(ApiError::InvalidInput(
+            "The source and target categories must differ!".to_string(),
+        ));
+    }
+
+    // Checked up front, so that a misspelled category is not mistaken for one without projects
+    let categories = Category::list(&**pool, &redis).await?;
+    for category in [&body.from_category, &body.to_category] {
+        if !categories.iter().any(|x| {
+            &x.category == category
+                && body
+                    .project_type
+                    .as_ref()
+                    .map_or(true, |project_type| &x.project_type == project_type)
+        }) {
+            return Err(ApiError::InvalidInput(format!(
+                "Category {category} does not exist."
+            )));
+        }
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    // Categories are unique per project type, so each row is moved to the target category
+    // of its own project type
+    let affected = sqlx::query!(
+        "
+        SELECT DISTINCT m.id, m.slug
+        FROM mods_categories mc
+        INNER JOIN categories f ON f.id = mc.joining_category_id AND f.category = $1
+        INNER JOIN categories t ON t.project_type = f.project_type AND t.category = $2
+        INNER JOIN project_types pt ON pt.id = f.project_type
+        INNER JOIN mods m ON m.id = mc.joining_mod_id
+        WHERE $3::text IS NULL OR pt.name = $3
+        ",
+        body.from_category,
+        body.to_category,
+        body.project_type,
+    )
+    .fetch_all(&mut *transaction)
+    .await?
+    .into_iter()
+    .map(|x| (crate::database::models::ProjectId(x.id), x.slug))
+    .collect::<Vec<_>>();
+
+    // Projects that already have the target category only lose the source category
+    sqlx::query!(
+        "
+        DELETE FROM mods_categories mc
+        USING categories f, categories t, project_types pt
+        WHERE mc.joining_category_id = f.id AND f.category = $1
+        AND t.project_type = f.project_type AND t.category = $2
+        AND pt.id = f.project_type AND ($3::text IS NULL OR pt.name = $3)
+        AND EXISTS (
+            SELECT 1 FROM mods_categories e
+            WHERE e.joining_mod_id = mc.joining_mod_id AND e.joining_category_id = t.id
+        )
+        ",
+        body.from_category,
+        body.to_category,
+        body.project_type,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE mods_categories mc
+        SET joining_category_id = t.id
+        FROM categories f, categories t, project_types pt
+        WHERE mc.joining_category_id = f.id AND f.category = $1
+        AND t.project_type = f.project_type AND t.category = $2
+        AND pt.id = f.project_type AND ($3::text IS NULL OR pt.name = $3)
+        ",
+        body.from_category,
+        body.to_category,
+        body.project_type,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    project_item::Project::clear_cache_many(&affected, &redis).await?;
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &config,
+        &affected.iter().map(|x| x.0).collect::<Vec<_>>(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(BulkCategoryResult {
+        affected_count: affected.len(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct AdminUsersQuery {
+    pub role: Option<Role>,
+    pub min_projects: Option<i64>,
+    pub max_projects: Option<i64>,
+    pub joined_after: Option<DateTime<Utc>>,
+    pub joined_before: Option<DateTime<Utc>>,
+    /// Matched against usernames, and against emails for admins
+    pub search: Option<String>,
+    pub limit: Option<i64>,
+    /// The ID of the last user of the previous page
+    pub after: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminUserEntry {
+    pub user_id: UserId,
+    pub username: String,
+    /// Only returned to admins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub role: Role,
+    pub joined_at: DateTime<Utc>,
+    pub project_count: i64,
+}
+
+/// The most users returned in one page
+const MAX_ADMIN_USERS_LIMIT: i64 = 100;
+
+#[get("/users")]
+pub async fn users_list(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    web::Query(query): web::Query<AdminUsersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_READ]),
+    )
+    .await?;
+
+    // Moderators may list users, but their emails are only shown to and searched by admins
+    let show_email = user.role.is_admin();
+    let search = query.search.map(|x| {
+        format!(
+            "%{}%",
+            x.replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        )
+    });
+
+    let users = sqlx::query!(
+        "
+        SELECT u.id, u.username, u.email, u.role, u.created, COUNT(DISTINCT m.id) project_count
+        FROM users u
+        LEFT JOIN team_members tm ON tm.user_id = u.id AND tm.accepted = TRUE
+        LEFT JOIN mods m ON m.team_id = tm.team_id
+        WHERE ($1::text IS NULL OR u.role = $1)
+        AND ($2::timestamptz IS NULL OR u.created >= $2)
+        AND ($3::timestamptz IS NULL OR u.created <= $3)
+        AND ($4::text IS NULL OR u.username ILIKE $4 OR ($9 AND u.email ILIKE $4))
+        AND ($5::bigint IS NULL OR u.id > $5)
+        GROUP BY u.id
+        HAVING ($6::bigint IS NULL OR COUNT(DISTINCT m.id) >= $6)
+        AND ($7::bigint IS NULL OR COUNT(DISTINCT m.id) <= $7)
+        ORDER BY u.id
+        LIMIT $8
+        ",
+        query.role.as_ref().map(|x| x.as_str()),
+        query.joined_after,
+        query.joined_before,
+        search,
+        query
+            .after
+            .map(|x| crate::database::models::UserId::from(x).0),
+        query.min_projects,
+        query.max_projects,
+        query
+            .limit
+            .unwrap_or(MAX_ADMIN_USERS_LIMIT)
+            .clamp(1, MAX_ADMIN_USERS_LIMIT),
+        show_email,
+    )
+    .fetch_all(&**pool)
+    .await?;
+
+    let users = users
+        .into_iter()
+        .map(|x| AdminUserEntry {
+            user_id: crate::database::models::UserId(x.id).into(),
+            username: x.username,
+            email: x.email.filter(|_| show_email),
+            role: Role::from_string(&x.role),
+            joined_at: x.created,
+            project_count: x.project_count.unwrap_or(0),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+#[derive(Deserialize)]
+pub struct SearchAnalyticsQuery {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// The most distinct queries returned by the search analytics routes
+const MAX_SEARCH_ANALYTICS_QUERIES: u64 = 100;
+
+/// The most common search queries without any results, by default over the last 30 days
+#[get("/analytics/searches/zero-result")]
+pub async fn zero_result_searches(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    clickhouse: web::Data<clickhouse::Client>,
+    web::Query(query): web::Query<SearchAnalyticsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::ANALYTICS]),
+    )
+    .await?;
+
+    let end = query.end.unwrap_or_else(Utc::now);
+    let start = query
+        .start
+        .unwrap_or_else(|| end - chrono::Duration::days(30));
+
+    let searches = crate::clickhouse::fetch_zero_result_searches(
+        start,
+        end,
+        MAX_SEARCH_ANALYTICS_QUERIES,
+        clickhouse.into_inner(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(searches))
+}
+
+#[derive(Deserialize)]
+pub struct LoaderVersionMapBody {
+    pub loader: String,
+    /// An exact loader version, or a prefix followed by `*` (ex: `41.*`)
+    pub loader_version_pattern: String,
+    pub game_version: String,
+}
+
+/// Maps versions of a loader to a game version, used to infer the game versions of uploads
+#[post("/loader-version-map")]
+pub async fn loader_version_map_add(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    body: web::Json<LoaderVersionMapBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let pattern = body.loader_version_pattern.trim();
+    if pattern.is_empty() || pattern.len() > 255 || pattern[..pattern.len() - 1].contains('*') {
+        return Err(ApiError::InvalidInput(
+            "The loader version pattern must be a version, optionally ending with `*`".to_string(),
+        ));
+    }
+
+    let loader_id = Loader::get_id(&body.loader, &**pool, &redis)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput(format!("Unknown loader `{}`", body.loader)))?;
+
+    if !MinecraftGameVersion::list(None, None, &**pool, &redis)
+        .await?
+        .iter()
+        .any(|x| x.version == body.game_version)
+    {
+        return Err(ApiError::InvalidInput(format!(
+            "Unknown game version `{}`",
+            body.game_version
+        )));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let id = LoaderVersionMapping::insert(loader_id, pattern, &body.game_version, &mut transaction)
+        .await?;
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "id": id })))
+}
+
+#[delete("/loader-version-map/{id}")]
+pub async fn loader_version_map_delete(
+    req: HttpRequest,
+    info: web::Path<(i32,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let mut transaction = pool.begin().await?;
+    let result = LoaderVersionMapping::remove(info.into_inner().0, &mut transaction).await?;
+    transaction.commit().await?;
+
+    if result.is_some() {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LoaderDeprecationBody {
+    pub deprecated: bool,
+    /// The loader that should be used instead
+    pub superseded_by: Option<String>,
+}
+
+/// Marks a loader as deprecated, or not. Versions can still be uploaded with deprecated loaders,
+/// but the response warns about them.
+#[patch("/tags/loader/{name}")]
+pub async fn loader_deprecation_edit(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    body: web::Json<LoaderDeprecationBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let name = info.into_inner().0;
+    if let Some(superseded_by) = &body.superseded_by {
+        if *superseded_by == name {
+            return Err(ApiError::InvalidInput(
+                "A loader cannot be superseded by itself".to_string(),
+            ));
+        }
+        if Loader::get_id(superseded_by, &**pool, &redis)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::InvalidInput(format!(
+                "Unknown loader `{}`",
+                superseded_by
+            )));
+        }
+    }
+
+    let updated = Loader::set_deprecation(
+        &name,
+        body.deprecated,
+        body.superseded_by.as_deref(),
+        &**pool,
+        &redis,
+    )
+    .await?;
+
+    if updated {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum GameVersionCreateType {
+    Release,
+    Snapshot,
+}
+
+#[derive(Deserialize)]
+pub struct GameVersionCreateBody {
+    pub version: String,
+    #[serde(rename = "type")]
+    pub type_: GameVersionCreateType,
+    pub date: NaiveDate,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+/// Adds a game version, or updates it if it already exists. Its numeric components are used to
+/// sort game versions.
+#[post("/tags/game_version")]
+pub async fn game_version_create(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    body: web::Json<GameVersionCreateBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let version = body.version.trim();
+    if version.is_empty() || version.len() > 255 || version.contains(char::is_whitespace) {
+        return Err(ApiError::InvalidInput(
+            "The game version must be between 1 and 255 characters, without spaces".to_string(),
+        ));
+    }
+
+    let version_type = match body.type_ {
+        GameVersionCreateType::Release => "release",
+        GameVersionCreateType::Snapshot => "snapshot",
+    };
+    let created = body.date.and_time(NaiveTime::MIN).and_utc();
+
+    // Inserting clears the cached game versions, so they can be used right away
+    MinecraftGameVersion::builder()
+        .version(version)?
+        .version_type(version_type)?
+        .created(&created)
+        .semver(GameVersionSemver {
+            major: body.major,
+            minor: body.minor,
+            patch: body.patch,
+        })
+        .insert(&**pool, &redis)
+        .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[derive(Deserialize)]
+pub struct FeaturedCollectionBody {
+    pub collection_id: CollectionId,
+    /// Featured collections are shown in ascending order
+    pub order: i32,
+}
+
+/// Features a collection, or moves it if it is already featured
+#[post("/collections/featured")]
+pub async fn featured_collection_add(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    body: web::Json<FeaturedCollectionBody>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::COLLECTION_WRITE]),
+    )
+    .await?;
+
+    let collection = Collection::get(body.collection_id.into(), &**pool, &redis)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("The collection does not exist!".to_string()))?;
+
+    let mut transaction = pool.begin().await?;
+    Collection::set_featured(collection.id, body.order, &mut transaction).await?;
+    transaction.commit().await?;
+    Collection::clear_featured_cache(&redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[delete("/collections/featured/{collection_id}")]
+pub async fn featured_collection_delete(
+    req: HttpRequest,
+    info: web::Path<(CollectionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::COLLECTION_WRITE]),
+    )
+    .await?;
+
+    let mut transaction = pool.begin().await?;
+    let result = Collection::remove_featured(info.into_inner().0.into(), &mut transaction).await?;
+    transaction.commit().await?;
+    Collection::clear_featured_cache(&redis).await?;
+
+    if result.is_some() {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FeaturedProjectBody {
+    pub project_id: ProjectId,
+    /// Featured projects are shown in ascending position
+    pub position: i32,
+    /// When the project stops being featured, if ever
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Features a project on the home page, or moves it if it is already featured
+#[post("/projects/featured")]
+pub async fn featured_project_add(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    body: web::Json<FeaturedProjectBody>,
+) -> Result<HttpResponse, ApiError> {
+    let moderator = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let project = project_item::Project::get_id(body.project_id.into(), &**pool, &redis)
+        .await?
+        .filter(|x| x.inner.deleted_at.is_none())
+        .ok_or_else(|| ApiError::InvalidInput("The project does not exist!".to_string()))?;
+
+    if body.expires_at.map(|x| x <= Utc::now()).unwrap_or(false) {
+        return Err(ApiError::InvalidInput(
+            "The expiry must be in the future!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    project_item::Project::set_featured(
+        project.inner.id,
+        body.position,
+        moderator.id.into(),
+        body.expires_at,
+        &mut transaction,
+    )
+    .await?;
+    transaction.commit().await?;
+    project_item::clear_project_caches(project.inner.id, project.inner.slug, &redis).await?;
+    project_item::Project::clear_featured_cache(&redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[delete("/projects/featured/{project_id}")]
+pub async fn featured_project_delete(
+    req: HttpRequest,
+    info: web::Path<(ProjectId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let project_id = info.into_inner().0.into();
+    let mut transaction = pool.begin().await?;
+    let result = project_item::Project::remove_featured(project_id, &mut transaction).await?;
+    transaction.commit().await?;
+    project_item::clear_project_caches(project_id, None, &redis).await?;
+    project_item::Project::clear_featured_cache(&redis).await?;
+
+    if result.is_some() {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HashBackfillQuery {
+    pub algorithm: String,
+}
+
+/// Starts computing a hash algorithm for all files that do not have it yet. Admin only.
+#[post("/backfill/hashes")]
+pub async fn hash_backfill_create(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    web::Query(query): web::Query<HashBackfillQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_WRITE]),
+    )
+    .await?;
+    if !user.role.is_admin() {
+        return Err(ApiError::CustomAuthentication(
+            "You must be an admin to backfill hashes!".to_string(),
+        ));
+    }
+
+    let algorithm = HashAlgorithm::from_string(&query.algorithm).ok_or_else(|| {
+        ApiError::InvalidInput(format!("Unsupported hash algorithm `{}`", query.algorithm))
+    })?;
+
+    let mut transaction = pool.begin().await?;
+    let files = sqlx::query!(
+        "
+        SELECT f.id, f.version_id, f.url FROM files f
+        WHERE NOT EXISTS (
+            SELECT 1 FROM hashes h
+            WHERE h.file_id = f.id AND h.algorithm = $1
+        )
+        ORDER BY f.id
+        ",
+        algorithm.as_str(),
+    )
+    .fetch_all(&mut *transaction)
+    .await?
+    .into_iter()
+    .map(|x| BackfillFile {
+        id: crate::database::models::FileId(x.id),
+        version_id: crate::database::models::VersionId(x.version_id),
+        url: x.url,
+    })
+    .collect::<Vec<_>>();
+
+    let job_id = BackfillJob::claim(algorithm.as_str(), files.len() as i32, &mut transaction)
+        .await?
+        .ok_or_else(|| {
+            ApiError::InvalidInput(format!(
+                "A backfill of {} hashes is already running",
+                algorithm.as_str()
+            ))
+        })?;
+    transaction.commit().await?;
+
+    let pool = pool.get_ref().clone();
+    let redis = redis.get_ref().clone();
+    actix_rt::spawn(async move {
+        if let Err(err) = run_hash_backfill(job_id, algorithm, files, pool, redis).await {
+            log::warn!("Hash backfill job {job_id} stopped: {err}");
+        }
+    });
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({ "job_id": job_id })))
+}
+
+#[get("/backfill/hashes/{job_id}")]
+pub async fn hash_backfill_get(
+    req: HttpRequest,
+    info: web::Path<(i64,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_READ]),
+    )
+    .await?;
+
+    let job = BackfillJob::get(info.into_inner().0, &**pool)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
+
+#[derive(Deserialize)]
+pub struct UserBanBody {
+    pub reason: String,
+    /// How long the ban lasts, or `None` for a permanent ban
+    pub duration_days: Option<u32>,
+}
+
+/// Suspends a user: they are logged out, cannot authenticate until the ban ends and their
+/// projects are hidden from search
+#[patch("/user/{id}/ban")]
+pub async fn user_ban(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+    body: web::Json<UserBanBody>,
+) -> Result<HttpResponse, ApiError> {
+    let moderator = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_WRITE]),
+    )
+    .await?;
+
+    let target = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if target.id == moderator.id.into() {
+        return Err(ApiError::InvalidInput(
+            "You cannot ban yourself!".to_string(),
+        ));
+    }
+    if Role::from_string(&target.role).is_mod() && !moderator.role.is_admin() {
+        return Err(ApiError::CustomAuthentication(
+            "Only admins can ban staff members!".to_string(),
+        ));
+    }
+
+    let reason = body.reason.trim();
+    validate_string_length("reason", reason, 1, 2000)?;
+    let banned_until = body
+        .duration_days
+        .map(|x| Utc::now() + chrono::Duration::days(x as i64));
+
+    let session_ids = Session::get_user_sessions(target.id, &**pool, &redis).await?;
+    let sessions = Session::get_many_ids(&session_ids, &**pool, &redis).await?;
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET banned = TRUE, ban_reason = $1, banned_until = $2
+        WHERE id = $3
+        ",
+        reason,
+        banned_until,
+        target.id as crate::database::models::UserId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    ModerationLogBuilder {
+        moderator_id: moderator.id.into(),
+        action: "user_ban",
+        target_type: "user",
+        target_user_id: Some(target.id),
+        target_version_id: None,
+        old_value: None,
+        new_value: Some(match banned_until {
+            Some(until) => format!("{reason} (until {})", until.to_rfc3339()),
+            None => reason.to_string(),
+        }),
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    for session in &sessions {
+        Session::remove(session.id, &mut transaction).await?;
+    }
+
+    transaction.commit().await?;
+
+    Session::clear_cache(
+        sessions
+            .into_iter()
+            .map(|x| (Some(x.id), Some(x.session), Some(x.user_id)))
+            .collect(),
+        &redis,
+    )
+    .await?;
+    User::clear_caches(&[(target.id, Some(target.username))], &redis).await?;
+    reindex_user_projects(target.id, &pool, &redis, &search_config).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// Lifts the ban of a user before it ends
+#[delete("/user/{id}/ban")]
+pub async fn user_unban(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let moderator = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_WRITE]),
+    )
+    .await?;
+
+    let target = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if !target.banned {
+        return Err(ApiError::InvalidInput(
+            "The user is not banned!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET banned = FALSE, ban_reason = NULL, banned_until = NULL
+        WHERE id = $1
+        ",
+        target.id as crate::database::models::UserId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    ModerationLogBuilder {
+        moderator_id: moderator.id.into(),
+        action: "user_unban",
+        target_type: "user",
+        target_user_id: Some(target.id),
+        target_version_id: None,
+        old_value: target.ban_reason,
+        new_value: None,
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    User::clear_caches(&[(target.id, Some(target.username))], &redis).await?;
+    reindex_user_projects(target.id, &pool, &redis, &search_config).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
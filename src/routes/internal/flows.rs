@@ -1,4 +1,5 @@
 use crate::auth::email::send_email;
+use crate::auth::password::{hash_password, upgrade_password_hash, verify_password};
 use crate::auth::validate::get_user_record_from_bearer_token;
 use crate::auth::{get_user_from_headers, AuthProvider, AuthenticationError};
 use crate::database::models::flow_item::Flow;
@@ -19,8 +20,6 @@ use crate::util::validate::{validation_errors_to_string, RE_URL_SAFE};
 use actix_web::web::{scope, Data, Payload, Query, ServiceConfig};
 use actix_web::{delete, get, patch, post, web, HttpRequest, HttpResponse};
 use actix_ws::Closed;
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use base64::Engine;
 use chrono::{Duration, Utc};
 use rand_chacha::rand_core::SeedableRng;
@@ -228,6 +227,9 @@ impl TempUser {
                 role: Role::Developer.to_string(),
                 badges: Badges::default(),
                 balance: Decimal::ZERO,
+                banned: false,
+                ban_reason: None,
+                banned_until: None,
             }
             .insert(transaction)
             .await?;
@@ -1482,11 +1484,7 @@ pub async fn create_account_with_password(
         ));
     }
 
-    let hasher = Argon2::default();
-    let salt = SaltString::generate(&mut ChaCha20Rng::from_entropy());
-    let password_hash = hasher
-        .hash_password(new_account.password.as_bytes(), &salt)?
-        .to_string();
+    let password_hash = hash_password(&new_account.password)?;
 
     if crate::database::models::User::get_email(&new_account.email, &**pool)
         .await?
@@ -1534,6 +1532,9 @@ pub async fn create_account_with_password(
         role: Role::Developer.to_string(),
         badges: Badges::default(),
         balance: Decimal::ZERO,
+        banned: false,
+        ban_reason: None,
+        banned_until: None,
     }
     .insert(&mut transaction)
     .await?;
@@ -1582,17 +1583,20 @@ pub async fn login_password(
             .ok_or_else(|| AuthenticationError::InvalidCredentials)?
     };
 
-    let hasher = Argon2::default();
-    hasher
-        .verify_password(
-            login.password.as_bytes(),
-            &PasswordHash::new(
-                &user
-                    .password
-                    .ok_or_else(|| AuthenticationError::InvalidCredentials)?,
-            )?,
-        )
+    let password_hash = user
+        .password
+        .as_deref()
+        .ok_or_else(|| AuthenticationError::InvalidCredentials)?;
+    verify_password(password_hash, &login.password)
         .map_err(|_| AuthenticationError::InvalidCredentials)?;
+    if let Err(err) =
+        upgrade_password_hash(user.id, password_hash, &login.password, &pool, &redis).await
+    {
+        log::warn!(
+            "Failed to upgrade the password hash of user {}: {err}",
+            user.id.0
+        );
+    }
 
     if user.totp_secret.is_some() {
         let flow = Flow::Login2FA { user_id: user.id }
@@ -2054,8 +2058,7 @@ pub async fn change_password(
                 )
             })?;
 
-            let hasher = Argon2::default();
-            hasher.verify_password(old_password.as_bytes(), &PasswordHash::new(pass)?)?;
+            verify_password(pass, old_password)?;
         }
 
         user
@@ -2083,13 +2086,7 @@ pub async fn change_password(
             ));
         }
 
-        let hasher = Argon2::default();
-        let salt = SaltString::generate(&mut ChaCha20Rng::from_entropy());
-        let password_hash = hasher
-            .hash_password(new_password.as_bytes(), &salt)?
-            .to_string();
-
-        Some(password_hash)
+        Some(hash_password(new_password)?)
     } else {
         if !(user.github_id.is_some()
             || user.gitlab_id.is_some()
@@ -2150,6 +2147,8 @@ pub struct SetEmail {
     pub email: String,
 }
 
+/// Changes the email of the signed in account, applying the same rules as `PATCH /user/{id}/email`.
+/// No password is asked for, so accounts signed in through a provider can use it as well.
 #[patch("email")]
 pub async fn set_email(
     req: HttpRequest,
@@ -2173,46 +2172,15 @@ pub async fn set_email(
     .await?
     .1;
 
-    let mut transaction = pool.begin().await?;
-
-    sqlx::query!(
-        "
-        UPDATE users
-        SET email = $1, email_verified = FALSE
-        WHERE (id = $2)
-        ",
-        email.email,
-        user.id.0 as i64,
+    crate::routes::v3::users::request_email_change(
+        user.id.into(),
+        user.email,
+        email.email.clone(),
+        &pool,
+        &redis,
     )
-    .execute(&mut *transaction)
     .await?;
 
-    if let Some(user_email) = user.email {
-        send_email(
-            user_email,
-            "Email changed",
-            &format!("Your email has been updated to {} on your account.", email.email),
-            "If you did not make this change, please contact us immediately through our support channels on Discord or via email (support@modrinth.com).",
-            None,
-        )?;
-    }
-
-    let flow = Flow::ConfirmEmail {
-        user_id: user.id.into(),
-        confirm_email: email.email.clone(),
-    }
-    .insert(Duration::hours(24), &redis)
-    .await?;
-
-    send_email_verify(
-        email.email.clone(),
-        flow,
-        "We need to verify your email address.",
-    )?;
-
-    transaction.commit().await?;
-    crate::database::models::User::clear_caches(&[(user.id.into(), None)], &redis).await?;
-
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -2303,6 +2271,17 @@ pub async fn verify_email(
         transaction.commit().await?;
         crate::database::models::User::clear_caches(&[(user.id, None)], &redis).await?;
 
+        Ok(HttpResponse::NoContent().finish())
+    } else if let Some(Flow::ChangeEmail { user_id, new_email }) = flow {
+        crate::routes::v3::users::complete_email_change(
+            &email.flow,
+            user_id,
+            new_email,
+            &pool,
+            &redis,
+        )
+        .await?;
+
         Ok(HttpResponse::NoContent().finish())
     } else {
         Err(ApiError::InvalidInput(
@@ -2339,7 +2318,7 @@ pub async fn subscribe_newsletter(
     }
 }
 
-fn send_email_verify(
+pub fn send_email_verify(
     email: String,
     flow: String,
     opener: &str,
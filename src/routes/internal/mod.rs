@@ -3,6 +3,7 @@ pub mod flows;
 pub mod moderation;
 pub mod pats;
 pub mod session;
+pub mod version_checker;
 
 use super::v3::oauth_clients;
 pub use super::ApiError;
@@ -17,6 +18,7 @@ pub fn config(cfg: &mut actix_web::web::ServiceConfig) {
             .configure(session::config)
             .configure(flows::config)
             .configure(pats::config)
-            .configure(moderation::config),
+            .configure(moderation::config)
+            .configure(version_checker::config),
     );
 }
@@ -7,7 +7,7 @@ use crate::queue::moderation::{ApprovalType, IdentifiedFile, MissingMetadata};
 use crate::queue::session::AuthQueue;
 use crate::{auth::check_is_moderator_from_headers, models::pats::Scopes};
 use actix_web::{web, HttpRequest, HttpResponse};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::HashMap;
 
@@ -21,6 +21,26 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 pub struct ResultCount {
     #[serde(default = "default_count")]
     pub count: i16,
+    #[serde(default)]
+    pub sort: ModerationQueueSort,
+}
+
+#[derive(Deserialize, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationQueueSort {
+    /// Oldest submissions first
+    #[default]
+    Queued,
+    /// Submissions from the least trusted authors first
+    TrustScoreAsc,
+}
+
+#[derive(Serialize)]
+pub struct QueuedProject {
+    #[serde(flatten)]
+    pub project: crate::models::projects::Project,
+    /// The trust score of the project's owner, if it has one
+    pub author_trust_score: Option<f32>,
 }
 
 fn default_count() -> i16 {
@@ -45,26 +65,44 @@ pub async fn get_projects(
 
     use futures::stream::TryStreamExt;
 
-    let project_ids = sqlx::query!(
+    let queue = sqlx::query!(
         "
-        SELECT id FROM mods
-        WHERE status = $1
-        ORDER BY queued ASC
+        SELECT m.id, u.trust_score AS \"trust_score?\" FROM mods m
+        LEFT JOIN team_members tm ON tm.team_id = m.team_id AND tm.is_owner
+        LEFT JOIN users u ON u.id = tm.user_id
+        WHERE m.status = $1 AND m.deleted_at IS NULL
+        ORDER BY CASE WHEN $3 THEN u.trust_score END ASC NULLS LAST, m.queued ASC
         LIMIT $2;
         ",
         ProjectStatus::Processing.as_str(),
-        count.count as i64
+        count.count as i64,
+        count.sort == ModerationQueueSort::TrustScoreAsc,
     )
     .fetch_many(&**pool)
-    .try_filter_map(|e| async { Ok(e.right().map(|m| database::models::ProjectId(m.id))) })
-    .try_collect::<Vec<database::models::ProjectId>>()
+    .try_filter_map(|e| async {
+        Ok(e.right()
+            .map(|m| (database::models::ProjectId(m.id), m.trust_score)))
+    })
+    .try_collect::<Vec<(database::models::ProjectId, Option<f32>)>>()
     .await?;
 
-    let projects: Vec<_> = database::Project::get_many_ids(&project_ids, &**pool, &redis)
+    let project_ids = queue.iter().map(|x| x.0).collect::<Vec<_>>();
+    let mut projects = database::Project::get_many_ids(&project_ids, &**pool, &redis)
         .await?
         .into_iter()
-        .map(crate::models::projects::Project::from)
-        .collect();
+        .map(|x| (x.inner.id, x))
+        .collect::<HashMap<_, _>>();
+
+    // Keeps the queue order, which is lost when getting the projects
+    let projects = queue
+        .into_iter()
+        .filter_map(|(id, author_trust_score)| {
+            projects.remove(&id).map(|project| QueuedProject {
+                project: project.into(),
+                author_trust_score,
+            })
+        })
+        .collect::<Vec<_>>();
 
     Ok(HttpResponse::Ok().json(projects))
 }
@@ -95,7 +133,7 @@ pub async fn get_project_meta(
             f.metadata, v.id version_id
             FROM versions v
             INNER JOIN files f ON f.version_id = v.id
-            WHERE v.mod_id = $1
+            WHERE v.mod_id = $1 AND v.deleted_at IS NULL
             ",
             project.inner.id.0
         )
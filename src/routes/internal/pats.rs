@@ -5,8 +5,9 @@ use crate::auth::get_user_from_headers;
 use crate::routes::ApiError;
 
 use crate::database::redis::RedisPool;
+use actix_web::dev::Payload;
 use actix_web::web::{self, Data};
-use actix_web::{delete, get, patch, post, HttpRequest, HttpResponse};
+use actix_web::{delete, get, patch, post, FromRequest, HttpMessage, HttpRequest, HttpResponse};
 use chrono::{DateTime, Utc};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
@@ -16,6 +17,7 @@ use rand_chacha::ChaCha20Rng;
 use crate::models::pats::{PersonalAccessToken, Scopes};
 use crate::queue::session::AuthQueue;
 use crate::util::validate::validation_errors_to_string;
+use futures::future::LocalBoxFuture;
 use serde::Deserialize;
 use sqlx::postgres::PgPool;
 use validator::Validate;
@@ -69,16 +71,33 @@ pub struct NewPersonalAccessToken {
     pub expires: DateTime<Utc>,
 }
 
+/// The body of a PAT creation, either as JSON or form-encoded (as sent by `curl -d`)
+pub struct CreatePatRequest(pub NewPersonalAccessToken);
+
+impl FromRequest for CreatePatRequest {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if req.content_type() == "application/x-www-form-urlencoded" {
+            let form = web::Form::<NewPersonalAccessToken>::from_request(req, payload);
+            Box::pin(async move { Ok(CreatePatRequest(form.await?.into_inner())) })
+        } else {
+            let json = web::Json::<NewPersonalAccessToken>::from_request(req, payload);
+            Box::pin(async move { Ok(CreatePatRequest(json.await?.into_inner())) })
+        }
+    }
+}
+
 #[post("pat")]
 pub async fn create_pat(
     req: HttpRequest,
-    info: web::Json<NewPersonalAccessToken>,
+    CreatePatRequest(info): CreatePatRequest,
     pool: Data<PgPool>,
     redis: Data<RedisPool>,
     session_queue: Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
-    info.0
-        .validate()
+    info.validate()
         .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
 
     if info.scopes.is_restricted() {
@@ -7,7 +7,7 @@ use crate::models::pats::Scopes;
 use crate::models::sessions::Session;
 use crate::queue::session::AuthQueue;
 use crate::routes::ApiError;
-use crate::util::env::parse_var;
+use crate::util::ip::ClientIp;
 use actix_web::http::header::AUTHORIZATION;
 use actix_web::web::{scope, Data, ServiceConfig};
 use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
@@ -40,16 +40,7 @@ pub struct SessionMetadata {
 pub async fn get_session_metadata(
     req: &HttpRequest,
 ) -> Result<SessionMetadata, AuthenticationError> {
-    let conn_info = req.connection_info().clone();
-    let ip_addr = if parse_var("CLOUDFLARE_INTEGRATION").unwrap_or(false) {
-        if let Some(header) = req.headers().get("CF-Connecting-IP") {
-            header.to_str().ok()
-        } else {
-            conn_info.peer_addr()
-        }
-    } else {
-        conn_info.peer_addr()
-    };
+    let ip_addr = ClientIp::get(req);
 
     let country = req
         .headers()
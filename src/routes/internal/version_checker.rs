@@ -0,0 +1,183 @@
+use super::ApiError;
+use crate::auth::checks::filter_visible_version_ids;
+use crate::auth::get_user_from_headers;
+use crate::database;
+use crate::database::redis::RedisPool;
+use crate::models::ids::base62_impl::parse_base62;
+use crate::models::ids::{ProjectId, VersionId};
+use crate::models::pats::Scopes;
+use crate::models::projects::DependencyType;
+use crate::queue::session::AuthQueue;
+use actix_web::{web, HttpRequest, HttpResponse};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("version-checker", web::post().to(check_versions));
+}
+
+#[derive(Deserialize)]
+pub struct VersionCheckRequest {
+    pub versions: Vec<String>,
+    pub loader: String,
+    pub game_version: String,
+}
+
+#[derive(Serialize)]
+pub struct VersionCheckResult {
+    pub compatible: bool,
+    pub missing_deps: Vec<MissingDependency>,
+    pub conflicts: Vec<VersionConflict>,
+}
+
+#[derive(Serialize)]
+pub struct MissingDependency {
+    pub project_id: ProjectId,
+    pub required_by: VersionId,
+}
+
+#[derive(Serialize)]
+pub struct VersionConflict {
+    pub version_a: VersionId,
+    /// Absent if the version conflicts with the requested loader or game version
+    pub version_b: Option<VersionId>,
+    pub reason: String,
+}
+
+/// Checks whether a set of versions can be launched together with a loader and game version,
+/// based on their dependencies. Nothing is installed or recorded.
+pub async fn check_versions(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    web::Json(check): web::Json<VersionCheckRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let version_ids = check
+        .versions
+        .iter()
+        .map(|x| parse_base62(x).map(|x| database::models::VersionId(x as i64)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let versions = database::models::Version::get_many(&version_ids, &**pool, &redis).await?;
+    let visible_version_ids = filter_visible_version_ids(
+        versions.iter().map(|x| &x.inner).collect_vec(),
+        &user_option,
+        &pool,
+        &redis,
+    )
+    .await?;
+    let versions = versions
+        .into_iter()
+        .filter(|x| visible_version_ids.contains(&x.inner.id))
+        .collect_vec();
+
+    if let Some(missing) = version_ids
+        .iter()
+        .find(|id| !versions.iter().any(|x| x.inner.id == **id))
+    {
+        return Err(ApiError::InvalidInput(format!(
+            "Version {} does not exist!",
+            VersionId::from(*missing)
+        )));
+    }
+
+    // Dependencies on a specific version only know the version, so their projects are looked up
+    let dependency_projects: HashMap<_, _> = database::models::Version::get_many(
+        &versions
+            .iter()
+            .flat_map(|x| x.dependencies.iter())
+            .filter(|x| x.project_id.is_none())
+            .filter_map(|x| x.version_id)
+            .unique()
+            .collect_vec(),
+        &**pool,
+        &redis,
+    )
+    .await?
+    .into_iter()
+    .map(|x| (x.inner.id, x.inner.project_id))
+    .collect();
+
+    let game_version = serde_json::Value::String(check.game_version.clone());
+    let mut missing_deps = Vec::new();
+    let mut conflicts = Vec::new();
+    for version in &versions {
+        if !version.loaders.contains(&check.loader) {
+            conflicts.push(VersionConflict {
+                version_a: version.inner.id.into(),
+                version_b: None,
+                reason: format!("The version does not support the {} loader", check.loader),
+            });
+        }
+        if !version
+            .version_fields
+            .iter()
+            .any(|f| f.field_name == "game_versions" && f.value.contains_json_value(&game_version))
+        {
+            conflicts.push(VersionConflict {
+                version_a: version.inner.id.into(),
+                version_b: None,
+                reason: format!("The version does not support {}", check.game_version),
+            });
+        }
+
+        for dependency in &version.dependencies {
+            let project_id = dependency.project_id.or_else(|| {
+                dependency
+                    .version_id
+                    .and_then(|x| dependency_projects.get(&x).copied())
+            });
+
+            match DependencyType::from_string(&dependency.dependency_type) {
+                DependencyType::Required => {
+                    if let Some(project_id) = project_id {
+                        if !versions.iter().any(|x| x.inner.project_id == project_id) {
+                            missing_deps.push(MissingDependency {
+                                project_id: project_id.into(),
+                                required_by: version.inner.id.into(),
+                            });
+                        }
+                    }
+                }
+                DependencyType::Incompatible => {
+                    for other in versions.iter().filter(|x| x.inner.id != version.inner.id) {
+                        let conflicting = match dependency.version_id {
+                            Some(version_id) => other.inner.id == version_id,
+                            None => Some(other.inner.project_id) == project_id,
+                        };
+
+                        if conflicting {
+                            conflicts.push(VersionConflict {
+                                version_a: version.inner.id.into(),
+                                version_b: Some(other.inner.id.into()),
+                                reason: "The version is marked as incompatible with the other"
+                                    .to_string(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(VersionCheckResult {
+        compatible: missing_deps.is_empty() && conflicts.is_empty(),
+        missing_deps,
+        conflicts,
+    }))
+}
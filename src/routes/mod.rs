@@ -73,6 +73,7 @@ pub fn root_config(cfg: &mut web::ServiceConfig) {
         web::scope("")
             .wrap(default_cors())
             .service(index::index_get)
+            .service(index::health_get)
             .service(Files::new("/", "assets/")),
     );
 }
@@ -101,6 +102,8 @@ pub enum ApiError {
     InvalidInput(String),
     #[error("Error while validating input: {0}")]
     Validation(String),
+    #[error("Error while validating input: {0}")]
+    StringLength(#[from] crate::util::validate::StringLengthError),
     #[error("Search Error: {0}")]
     Search(#[from] meilisearch_sdk::errors::Error),
     #[error("Indexing Error: {0}")]
@@ -149,6 +152,7 @@ impl ApiError {
                 ApiError::FileHosting(..) => "file_hosting_error",
                 ApiError::InvalidInput(..) => "invalid_input",
                 ApiError::Validation(..) => "invalid_input",
+                ApiError::StringLength(..) => "invalid_input",
                 ApiError::Payments(..) => "payments_error",
                 ApiError::Discord(..) => "discord_error",
                 ApiError::Turnstile => "turnstile_error",
@@ -176,6 +180,9 @@ impl actix_web::ResponseError for ApiError {
             ApiError::Database(..) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::SqlxDatabase(..) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::Clickhouse(..) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Authentication(crate::auth::AuthenticationError::Suspended { .. }) => {
+                StatusCode::FORBIDDEN
+            }
             ApiError::Authentication(..) => StatusCode::UNAUTHORIZED,
             ApiError::CustomAuthentication(..) => StatusCode::UNAUTHORIZED,
             ApiError::Xml(..) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -185,6 +192,7 @@ impl actix_web::ResponseError for ApiError {
             ApiError::FileHosting(..) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::InvalidInput(..) => StatusCode::BAD_REQUEST,
             ApiError::Validation(..) => StatusCode::BAD_REQUEST,
+            ApiError::StringLength(..) => StatusCode::BAD_REQUEST,
             ApiError::Payments(..) => StatusCode::FAILED_DEPENDENCY,
             ApiError::Discord(..) => StatusCode::FAILED_DEPENDENCY,
             ApiError::Turnstile => StatusCode::BAD_REQUEST,
@@ -202,6 +210,24 @@ impl actix_web::ResponseError for ApiError {
     }
 
     fn error_response(&self) -> HttpResponse {
+        if let ApiError::Authentication(err @ crate::auth::AuthenticationError::Suspended { .. }) =
+            self
+        {
+            return err.error_response();
+        }
+
+        if let ApiError::StringLength(err) = self {
+            let error = self.as_api_error();
+            return HttpResponse::build(self.status_code()).json(serde_json::json!({
+                "error": error.error,
+                "description": error.description,
+                "field": err.field,
+                "min": err.min,
+                "max": err.max,
+                "actual": err.actual,
+            }));
+        }
+
         HttpResponse::build(self.status_code()).json(self.as_api_error())
     }
 }
@@ -14,11 +14,13 @@ mod versions;
 
 pub use super::ApiError;
 use crate::util::cors::default_cors;
+use crate::util::head::HeadSupport;
 
 pub fn config(cfg: &mut actix_web::web::ServiceConfig) {
     cfg.service(
         actix_web::web::scope("v2")
             .wrap(default_cors())
+            .wrap(HeadSupport)
             .configure(super::internal::admin::config)
             // Todo: separate these- they need to also follow v2-v3 conversion
             .configure(super::internal::session::config)
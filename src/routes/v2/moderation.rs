@@ -34,7 +34,10 @@ pub async fn get_projects(
         req,
         pool.clone(),
         redis.clone(),
-        web::Query(internal::moderation::ResultCount { count: count.count }),
+        web::Query(internal::moderation::ResultCount {
+            count: count.count,
+            sort: Default::default(),
+        }),
         session_queue,
     )
     .await
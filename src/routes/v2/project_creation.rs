@@ -10,6 +10,7 @@ use crate::routes::v3::project_creation::default_project_type;
 use crate::routes::v3::project_creation::{CreateError, NewGalleryItem};
 use crate::routes::{v2_reroute, v3};
 use actix_multipart::Multipart;
+use actix_web::http::header::WARNING;
 use actix_web::web::Data;
 use actix_web::{post, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
@@ -33,7 +34,7 @@ pub fn default_requested_status() -> ProjectStatus {
 #[derive(Serialize, Deserialize, Validate, Clone)]
 struct ProjectCreateData {
     #[validate(
-        length(min = 3, max = 64),
+        length(min = 2, max = 64),
         custom(function = "crate::util::validate::validate_name")
     )]
     #[serde(alias = "mod_name")]
@@ -50,11 +51,11 @@ struct ProjectCreateData {
     #[serde(alias = "mod_slug")]
     /// The slug of a project, used for vanity URLs
     pub slug: String,
-    #[validate(length(min = 3, max = 255))]
+    #[validate(length(min = 2, max = 256))]
     #[serde(alias = "mod_description")]
     /// A short description of the project.
     pub description: String,
-    #[validate(length(max = 65536))]
+    #[validate(length(max = 65535))]
     #[serde(alias = "mod_body")]
     /// A long description of the project, in markdown.
     pub body: String,
@@ -244,7 +245,8 @@ pub async fn project_create(
     )
     .await?;
 
-    // Convert response to V2 format
+    // Convert response to V2 format, keeping deprecated loader warnings
+    let warning = response.headers().get(WARNING).cloned();
     match v2_reroute::extract_ok_json::<Project>(response).await {
         Ok(project) => {
             let version_item = match project.versions.first() {
@@ -252,7 +254,11 @@ pub async fn project_create(
                 None => None,
             };
             let project = LegacyProject::from(project, version_item);
-            Ok(HttpResponse::Ok().json(project))
+            let mut response = HttpResponse::Ok();
+            if let Some(warning) = warning {
+                response.insert_header((WARNING, warning));
+            }
+            Ok(response.json(project))
         }
         Err(response) => Ok(response),
     }
@@ -7,11 +7,12 @@ use crate::models::projects::{
 };
 use crate::models::v2::projects::{DonationLink, LegacyProject, LegacySideType, LegacyVersion};
 use crate::models::v2::search::LegacySearchResults;
+use crate::queue::analytics::AnalyticsQueue;
 use crate::queue::moderation::AutomatedModerationQueue;
 use crate::queue::session::AuthQueue;
 use crate::routes::v3::projects::ProjectIds;
 use crate::routes::{v2_reroute, v3, ApiError};
-use crate::search::{search_for_project, SearchConfig, SearchError};
+use crate::search::{search_for_project_cached, search_log, SearchConfig, SearchError};
 use actix_web::{delete, get, patch, post, web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -52,12 +53,16 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 pub async fn project_search(
     web::Query(info): web::Query<SearchRequest>,
     config: web::Data<SearchConfig>,
-) -> Result<HttpResponse, SearchError> {
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    analytics_queue: web::Data<Arc<AnalyticsQueue>>,
+) -> Result<HttpResponse, actix_web::Error> {
     // Search now uses loader_fields instead of explicit 'client_side' and 'server_side' fields
     // While the backend for this has changed, it doesnt affect much
     // in the API calls except that 'versions:x' is now 'game_versions:x'
     let facets: Option<Vec<Vec<String>>> = if let Some(facets) = info.facets {
-        let facets = serde_json::from_str::<Vec<Vec<String>>>(&facets)?;
+        let facets =
+            serde_json::from_str::<Vec<Vec<String>>>(&facets).map_err(SearchError::from)?;
 
         // These loaders specifically used to be combined with 'mod' to be a plugin, but now
         // they are their own loader type. We will convert 'mod' to 'mod' OR 'plugin'
@@ -95,12 +100,17 @@ pub async fn project_search(
         None
     };
 
-    let info = SearchRequest {
+    let mut info = SearchRequest {
         facets: facets.and_then(|x| serde_json::to_string(&x).ok()),
         ..info
     };
+    v3::projects::expand_search_game_versions(&mut info, &pool, &redis).await?;
 
-    let results = search_for_project(&info, &config).await?;
+    let start = std::time::Instant::now();
+    let results = search_for_project_cached(&info, &config, &redis).await?;
+    if let Some(log) = search_log(&info, results.total_hits, start.elapsed(), config.logging) {
+        analytics_queue.add_search_log(log);
+    }
 
     let results = LegacySearchResults::from(results);
 
@@ -153,7 +163,10 @@ pub async fn random_projects_get(
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
 ) -> Result<HttpResponse, ApiError> {
-    let count = v3::projects::RandomProjects { count: count.count };
+    let count = v3::projects::RandomProjects {
+        count: count.count,
+        seed: None,
+    };
 
     let response =
         v3::projects::random_projects_get(web::Query(count), pool.clone(), redis.clone())
@@ -181,7 +194,10 @@ pub async fn projects_get(
     // Call V3 project creation
     let response = v3::projects::projects_get(
         req,
-        web::Query(ids),
+        web::Query(v3::projects::ProjectsGetQuery {
+            ids: Some(ids.ids),
+            ..Default::default()
+        }),
         pool.clone(),
         redis.clone(),
         session_queue,
@@ -284,13 +300,13 @@ pub async fn dependency_list(
 #[derive(Serialize, Deserialize, Validate)]
 pub struct EditProject {
     #[validate(
-        length(min = 3, max = 64),
+        length(min = 2, max = 64),
         custom(function = "crate::util::validate::validate_name")
     )]
     pub title: Option<String>,
-    #[validate(length(min = 3, max = 256))]
+    #[validate(length(min = 2, max = 256))]
     pub description: Option<String>,
-    #[validate(length(max = 65536))]
+    #[validate(length(max = 65535))]
     pub body: Option<String>,
     #[validate(length(max = 3))]
     pub categories: Option<Vec<String>>,
@@ -485,6 +501,7 @@ pub async fn project_edit(
         moderation_message: v2_new_project.moderation_message,
         moderation_message_body: v2_new_project.moderation_message_body,
         monetization_status: v2_new_project.monetization_status,
+        update_mask: None,
     };
 
     // This returns 204 or failure so we don't need to do anything with it
@@ -492,6 +509,7 @@ pub async fn project_edit(
     let mut response = v3::projects::project_edit(
         req.clone(),
         info,
+        web::Query(Default::default()),
         pool.clone(),
         search_config,
         web::Json(new_project),
@@ -714,6 +732,7 @@ pub async fn project_icon_edit(
     v3::projects::project_icon_edit(
         web::Query(v3::projects::Extension { ext: ext.ext }),
         req,
+        web::Query(Default::default()),
         info,
         pool,
         redis,
@@ -735,9 +754,17 @@ pub async fn delete_project_icon(
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
     // Returns NoContent, so no need to convert
-    v3::projects::delete_project_icon(req, info, pool, redis, file_host, session_queue)
-        .await
-        .or_else(v2_reroute::flatten_404_error)
+    v3::projects::delete_project_icon(
+        req,
+        web::Query(Default::default()),
+        info,
+        pool,
+        redis,
+        file_host,
+        session_queue,
+    )
+    .await
+    .or_else(v2_reroute::flatten_404_error)
 }
 
 #[derive(Serialize, Deserialize, Validate)]
@@ -767,6 +794,7 @@ pub async fn add_gallery_item(
     v3::projects::add_gallery_item(
         web::Query(v3::projects::Extension { ext: ext.ext }),
         req,
+        web::Query(Default::default()),
         web::Query(v3::projects::GalleryCreateQuery {
             featured: item.featured,
             name: item.title,
@@ -818,6 +846,7 @@ pub async fn edit_gallery_item(
     // Returns NoContent, so no need to convert
     v3::projects::edit_gallery_item(
         req,
+        web::Query(Default::default()),
         web::Query(v3::projects::GalleryEditQuery {
             url: item.url,
             featured: item.featured,
@@ -852,6 +881,7 @@ pub async fn delete_gallery_item(
     // Returns NoContent, so no need to convert
     v3::projects::delete_gallery_item(
         req,
+        web::Query(Default::default()),
         web::Query(v3::projects::GalleryDeleteQuery { url: item.url }),
         info,
         pool,
@@ -872,10 +902,19 @@ pub async fn project_delete(
     search_config: web::Data<SearchConfig>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
+    // V2 has no confirmation step, so deletions through it are always confirmed
     // Returns NoContent, so no need to convert
-    v3::projects::project_delete(req, info, pool, redis, search_config, session_queue)
-        .await
-        .or_else(v2_reroute::flatten_404_error)
+    v3::projects::project_delete(
+        req,
+        info,
+        pool,
+        redis,
+        search_config,
+        session_queue,
+        web::Json(v3::projects::ProjectDeletion { confirm: true }),
+    )
+    .await
+    .or_else(v2_reroute::flatten_404_error)
 }
 
 #[post("{id}/follow")]
@@ -8,6 +8,7 @@ use crate::models::v2::projects::LegacyProject;
 use crate::models::v2::user::LegacyUser;
 use crate::queue::session::AuthQueue;
 use crate::routes::{v2_reroute, v3, ApiError};
+use crate::search::SearchConfig;
 use actix_web::{delete, get, patch, web, HttpRequest, HttpResponse};
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -107,9 +108,16 @@ pub async fn projects_list(
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
-    let response = v3::users::projects_list(req, info, pool.clone(), redis.clone(), session_queue)
-        .await
-        .or_else(v2_reroute::flatten_404_error)?;
+    let response = v3::users::projects_list(
+        req,
+        info,
+        web::Query(Default::default()),
+        pool.clone(),
+        redis.clone(),
+        session_queue,
+    )
+    .await
+    .or_else(v2_reroute::flatten_404_error)?;
 
     // Convert to V2 projects
     match v2_reroute::extract_ok_json::<Vec<Project>>(response).await {
@@ -141,7 +149,7 @@ pub struct EditUser {
         skip_serializing_if = "Option::is_none",
         with = "::serde_with::rust::double_option"
     )]
-    #[validate(length(max = 160))]
+    #[validate(length(max = 4000))]
     pub bio: Option<Option<String>>,
     pub role: Option<Role>,
     pub badges: Option<Badges>,
@@ -155,6 +163,7 @@ pub async fn user_edit(
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
 ) -> Result<HttpResponse, ApiError> {
     let new_user = new_user.into_inner();
     // Returns NoContent, so we don't need to convert to V2
@@ -172,6 +181,7 @@ pub async fn user_edit(
         pool,
         redis,
         session_queue,
+        search_config,
     )
     .await
     .or_else(v2_reroute::flatten_404_error)
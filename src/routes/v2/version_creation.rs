@@ -12,7 +12,7 @@ use crate::routes::v3::project_creation::CreateError;
 use crate::routes::v3::version_creation;
 use crate::routes::{v2_reroute, v3};
 use actix_multipart::Multipart;
-use actix_web::http::header::ContentDisposition;
+use actix_web::http::header::{ContentDisposition, WARNING};
 use actix_web::web::Data;
 use actix_web::{post, web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
@@ -43,7 +43,7 @@ pub struct InitialVersionData {
     )]
     #[serde(alias = "name")]
     pub version_title: String,
-    #[validate(length(max = 65536))]
+    #[validate(length(max = 65535))]
     #[serde(alias = "changelog")]
     pub version_body: Option<String>,
     #[validate(
@@ -236,11 +236,16 @@ pub async fn version_create(
     )
     .await?;
 
-    // Convert response to V2 format
+    // Convert response to V2 format, keeping deprecated loader warnings
+    let warning = response.headers().get(WARNING).cloned();
     match v2_reroute::extract_ok_json::<Version>(response).await {
         Ok(version) => {
             let v2_version = LegacyVersion::from(version);
-            Ok(HttpResponse::Ok().json(v2_version))
+            let mut response = HttpResponse::Ok();
+            if let Some(warning) = warning {
+                response.insert_header((WARNING, warning));
+            }
+            Ok(response.json(v2_version))
         }
         Err(response) => Ok(response),
     }
@@ -287,6 +292,7 @@ pub async fn upload_file_to_version(
     let response = v3::version_creation::upload_file_to_version(
         req,
         url_data,
+        web::Query(Default::default()),
         payload,
         client.clone(),
         redis.clone(),
@@ -200,7 +200,7 @@ pub struct EditVersion {
         regex = "crate::util::validate::RE_URL_SAFE"
     )]
     pub version_number: Option<String>,
-    #[validate(length(max = 65536))]
+    #[validate(length(max = 65535))]
     pub changelog: Option<String>,
     pub version_type: Option<models::projects::VersionType>,
     #[validate(
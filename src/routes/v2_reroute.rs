@@ -71,6 +71,11 @@ where
         let field_filename = content_disposition.get_filename();
         let field_content_type = field.content_type();
         let field_content_type = field_content_type.map(|ct| ct.to_string());
+        let field_content_encoding = field
+            .headers()
+            .get("Content-Encoding")
+            .and_then(|x| x.to_str().ok())
+            .map(|x| x.to_string());
 
         let mut buffer = Vec::new();
         while let Some(chunk) = field.next().await {
@@ -87,6 +92,7 @@ where
             name: field_name.to_string(),
             filename: field_filename.map(|s| s.to_string()),
             content_type: field_content_type,
+            content_encoding: field_content_encoding,
             data: MultipartSegmentData::Binary(vec![]), // Initialize to empty, will be finished after
         });
     }
@@ -98,6 +104,11 @@ where
         let field_filename = content_disposition.get_filename();
         let field_content_type = field.content_type();
         let field_content_type = field_content_type.map(|ct| ct.to_string());
+        let field_content_encoding = field
+            .headers()
+            .get("Content-Encoding")
+            .and_then(|x| x.to_str().ok())
+            .map(|x| x.to_string());
 
         let mut buffer = Vec::new();
         while let Some(chunk) = field.next().await {
@@ -110,6 +121,7 @@ where
             name: field_name.to_string(),
             filename: field_filename.map(|s| s.to_string()),
             content_type: field_content_type,
+            content_encoding: field_content_encoding,
             data: MultipartSegmentData::Binary(buffer),
         })
     }
@@ -1,24 +1,36 @@
 use crate::auth::checks::is_visible_collection;
-use crate::auth::{filter_visible_collections, get_user_from_headers};
+use crate::auth::checks::{
+    filter_visible_project_ids, filter_visible_version_ids, filter_visible_versions,
+    is_visible_project,
+};
+use crate::auth::{filter_visible_collections, filter_visible_projects, get_user_from_headers};
 use crate::database::models::{collection_item, generate_collection_id, project_item};
 use crate::database::redis::RedisPool;
 use crate::file_hosting::FileHost;
 use crate::models::collections::{Collection, CollectionStatus};
 use crate::models::ids::base62_impl::parse_base62;
 use crate::models::ids::{CollectionId, ProjectId};
+use crate::models::pack::{PackDependency, PackFile, PackFileHash, PackFormat};
 use crate::models::pats::Scopes;
+use crate::models::projects::{Project, SearchRequest, Version};
 use crate::queue::session::AuthQueue;
 use crate::routes::v3::project_creation::CreateError;
 use crate::routes::ApiError;
+use crate::search::{search_for_project, SearchConfig, SearchError};
+use crate::util::env::parse_var;
 use crate::util::routes::read_from_payload;
-use crate::util::validate::validation_errors_to_string;
+use crate::util::validate::{validate_string_length, validation_errors_to_string};
 use crate::{database, models};
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
 use validator::Validate;
 
@@ -28,20 +40,32 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 
     cfg.service(
         web::scope("collection")
+            .route("from-search", web::post().to(collection_create_from_search))
+            .route("featured", web::get().to(collections_featured_get))
             .route("{id}", web::get().to(collection_get))
             .route("{id}", web::delete().to(collection_delete))
             .route("{id}", web::patch().to(collection_edit))
+            .route("{id}/projects", web::get().to(collection_projects_get))
+            .route(
+                "{id}/projects/{project_id}",
+                web::post().to(collection_project_add),
+            )
+            .route(
+                "{id}/projects/{project_id}",
+                web::delete().to(collection_project_remove),
+            )
             .route("{id}/icon", web::patch().to(collection_icon_edit))
-            .route("{id}/icon", web::delete().to(delete_collection_icon)),
+            .route("{id}/icon", web::delete().to(delete_collection_icon))
+            .route(
+                "{id}/export/mrpack",
+                web::get().to(collection_export_mrpack),
+            ),
     );
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone)]
 pub struct CollectionCreateData {
-    #[validate(
-        length(min = 3, max = 64),
-        custom(function = "crate::util::validate::validate_name")
-    )]
+    #[validate(custom(function = "crate::util::validate::validate_name"))]
     /// The title or name of the project.
     pub name: String,
     #[validate(length(min = 3, max = 255))]
@@ -73,6 +97,7 @@ pub async fn collection_create(
     .await?
     .1;
 
+    validate_string_length("name", &collection_create_data.name, 3, 64)?;
     collection_create_data
         .validate()
         .map_err(|err| CreateError::InvalidInput(validation_errors_to_string(err, None)))?;
@@ -102,6 +127,7 @@ pub async fn collection_create(
             .copied()
             .map(|x| x.into())
             .collect(),
+        source_search_query: None,
     };
     let collection_builder = collection_builder_actual.clone();
 
@@ -119,6 +145,7 @@ pub async fn collection_create(
         color: None,
         status: collection_builder.status,
         projects: initial_project_ids,
+        source_search_query: None,
     };
     transaction.commit().await?;
 
@@ -129,6 +156,98 @@ pub async fn collection_create(
 pub struct CollectionIds {
     pub ids: String,
 }
+
+/// The most projects a collection created from a search can start with
+const MAX_PROJECTS_FROM_SEARCH: usize = 100;
+
+#[derive(Serialize, Deserialize, Validate)]
+pub struct CollectionFromSearchData {
+    #[validate(custom(function = "crate::util::validate::validate_name"))]
+    pub title: String,
+    #[validate(length(min = 3, max = 255))]
+    pub description: Option<String>,
+    pub query: String,
+    #[serde(default)]
+    pub facets: serde_json::Value,
+    /// The amount of results to add to the collection, at most 100
+    pub max_projects: usize,
+}
+
+pub async fn collection_create_from_search(
+    req: HttpRequest,
+    data: web::Json<CollectionFromSearchData>,
+    pool: Data<PgPool>,
+    redis: Data<RedisPool>,
+    search_config: Data<SearchConfig>,
+    session_queue: Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let data = data.into_inner();
+
+    let current_user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::COLLECTION_CREATE]),
+    )
+    .await?
+    .1;
+
+    validate_string_length("title", &data.title, 3, 64)?;
+    data.validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let search_request = SearchRequest {
+        query: Some(data.query.clone()),
+        facets: (!data.facets.is_null()).then(|| data.facets.to_string()),
+        limit: Some(data.max_projects.min(MAX_PROJECTS_FROM_SEARCH).to_string()),
+        offset: None,
+        index: None,
+        new_filters: None,
+        filters: None,
+        version: None,
+    };
+    let results = search_for_project(&search_request, &search_config)
+        .await
+        .map_err(|err| match err {
+            SearchError::MeiliSearch(err) => ApiError::Search(err),
+            err => ApiError::InvalidInput(err.to_string()),
+        })?;
+
+    let project_ids = results
+        .hits
+        .iter()
+        .map(|x| parse_base62(&x.project_id).map(|x| database::models::ProjectId(x as i64)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut transaction = pool.begin().await?;
+
+    let collection_id = generate_collection_id(&mut transaction).await?;
+    let source_search_query = json!({
+        "query": data.query,
+        "facets": data.facets,
+    });
+    collection_item::CollectionBuilder {
+        collection_id,
+        user_id: current_user.id.into(),
+        name: data.title,
+        description: data.description,
+        status: CollectionStatus::Private,
+        projects: project_ids,
+        source_search_query: Some(source_search_query),
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    let collection = database::models::Collection::get(collection_id, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(Collection::from(collection)))
+}
+
 pub async fn collections_get(
     req: HttpRequest,
     web::Query(ids): web::Query<CollectionIds>,
@@ -142,7 +261,7 @@ pub async fn collections_get(
         .map(|x| parse_base62(x).map(|x| database::models::CollectionId(x as i64)))
         .collect::<Result<Vec<_>, _>>()?;
 
-    let collections_data = database::models::Collection::get_many(&ids, &**pool, &redis).await?;
+    let collections_data = database::models::Collection::get_by_ids(&ids, &**pool, &redis).await?;
 
     let user_option = get_user_from_headers(
         &req,
@@ -190,12 +309,248 @@ pub async fn collection_get(
     Err(ApiError::NotFound)
 }
 
+#[derive(Deserialize, Validate)]
+pub struct FeaturedCollectionsQuery {
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+}
+
+/// A project of a featured collection, with what is needed to show its thumbnail
+#[derive(Serialize, Deserialize)]
+pub struct FeaturedCollectionProject {
+    pub id: ProjectId,
+    pub slug: Option<String>,
+    pub name: String,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FeaturedCollection {
+    #[serde(flatten)]
+    pub collection: Collection,
+    /// The visible projects of the collection, in the same order as `projects`
+    pub project_thumbnails: Vec<FeaturedCollectionProject>,
+}
+
+/// Returns the public collections featured by moderators, in their featured order
+pub async fn collections_featured_get(
+    req: HttpRequest,
+    web::Query(query): web::Query<FeaturedCollectionsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::COLLECTION_READ, Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let ids = database::models::Collection::get_featured_ids(&**pool, &redis).await?;
+    let collections = database::models::Collection::get_by_ids(&ids, &**pool, &redis).await?;
+
+    // Hidden collections are not shown, even to their owners
+    let featured = ids
+        .iter()
+        .filter_map(|id| collections.iter().find(|x| x.id.0 == id.0))
+        .filter(|x| !x.status.is_hidden())
+        .take(query.limit.unwrap_or(10))
+        .cloned()
+        .collect_vec();
+
+    let projects = project_item::Project::get_many_ids(
+        &featured
+            .iter()
+            .flat_map(|x| x.projects.iter().copied())
+            .unique()
+            .collect_vec(),
+        &**pool,
+        &redis,
+    )
+    .await?;
+    let projects = filter_visible_projects(projects, &user_option, &pool, false).await?;
+
+    let response = featured
+        .into_iter()
+        .map(|collection| {
+            let project_thumbnails = collection
+                .projects
+                .iter()
+                .filter_map(|id| projects.iter().find(|x| x.id == ProjectId::from(*id)))
+                .map(|x| FeaturedCollectionProject {
+                    id: x.id,
+                    slug: x.slug.clone(),
+                    name: x.name.clone(),
+                    icon_url: x.icon_url.clone(),
+                })
+                .collect();
+
+            FeaturedCollection {
+                collection: collection.into(),
+                project_thumbnails,
+            }
+        })
+        .collect_vec();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionProjectsSort {
+    AddedAtAsc,
+    AddedAtDesc,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CollectionProjectsQuery {
+    #[serde(default)]
+    pub include_versions: bool,
+    /// Sorts the projects by when they were added, instead of the collection's order
+    pub sort: Option<CollectionProjectsSort>,
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionProject {
+    #[serde(flatten)]
+    pub project: Project,
+    pub added_to_collection_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionProjects {
+    pub projects: Vec<CollectionProject>,
+    /// The featured version of each project, if `include_versions` was set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub versions: Option<Vec<Version>>,
+    /// The number of visible projects in the collection
+    pub total: usize,
+}
+
+pub async fn collection_projects_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Query(query): web::Query<CollectionProjectsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let string = info.into_inner().0;
+
+    let id = database::models::CollectionId(parse_base62(&string)? as i64);
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[
+            Scopes::COLLECTION_READ,
+            Scopes::PROJECT_READ,
+            Scopes::VERSION_READ,
+        ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let collection = match database::models::Collection::get(id, &**pool, &redis).await? {
+        Some(collection) if is_visible_collection(&collection, &user_option).await? => collection,
+        _ => return Err(ApiError::NotFound),
+    };
+
+    let projects =
+        project_item::Project::get_many_ids(&collection.projects, &**pool, &redis).await?;
+    let mut projects = filter_visible_projects(projects, &user_option, &pool, false)
+        .await?
+        .into_iter()
+        .sorted_by_key(|x| {
+            collection
+                .projects
+                .iter()
+                .position(|id| ProjectId::from(*id) == x.id)
+        })
+        .map(|project| CollectionProject {
+            // Collections cached before the dates were recorded have none
+            added_to_collection_at: collection
+                .project_added_dates
+                .get(&project.id.into())
+                .copied()
+                .unwrap_or(collection.created),
+            project,
+        })
+        .collect_vec();
+    match query.sort {
+        Some(CollectionProjectsSort::AddedAtAsc) => {
+            projects.sort_by_key(|x| x.added_to_collection_at)
+        }
+        Some(CollectionProjectsSort::AddedAtDesc) => {
+            projects.sort_by_key(|x| std::cmp::Reverse(x.added_to_collection_at))
+        }
+        None => {}
+    }
+
+    let total = projects.len();
+    let projects = projects
+        .into_iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(100))
+        .collect_vec();
+
+    let versions = if query.include_versions {
+        let versions = database::models::Version::get_many(
+            &projects
+                .iter()
+                .flat_map(|x| x.project.versions.iter().map(|id| (*id).into()))
+                .collect_vec(),
+            &**pool,
+            &redis,
+        )
+        .await?;
+        let versions = filter_visible_versions(versions, &user_option, &pool, &redis).await?;
+
+        // Prefer the newest featured version, falling back to the newest version
+        Some(
+            projects
+                .iter()
+                .filter_map(|project| {
+                    versions
+                        .iter()
+                        .filter(|x| x.project_id == project.project.id)
+                        .max_by_key(|x| (x.featured, x.date_published))
+                        .cloned()
+                })
+                .collect_vec(),
+        )
+    } else {
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(CollectionProjects {
+        projects,
+        versions,
+        total,
+    }))
+}
+
 #[derive(Deserialize, Validate)]
 pub struct EditCollection {
-    #[validate(
-        length(min = 3, max = 64),
-        custom(function = "crate::util::validate::validate_name")
-    )]
+    #[validate(custom(function = "crate::util::validate::validate_name"))]
     pub name: Option<String>,
     #[validate(length(min = 3, max = 256))]
     #[serde(
@@ -227,6 +582,9 @@ pub async fn collection_edit(
     .await?
     .1;
 
+    if let Some(name) = &new_collection.name {
+        validate_string_length("name", name, 3, 64)?;
+    }
     new_collection
         .validate()
         .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
@@ -295,17 +653,6 @@ pub async fn collection_edit(
         }
 
         if let Some(new_project_ids) = &new_collection.new_projects {
-            // Delete all existing projects
-            sqlx::query!(
-                "
-                DELETE FROM collections_mods
-                WHERE collection_id = $1
-                ",
-                collection_item.id as database::models::ids::CollectionId,
-            )
-            .execute(&mut *transaction)
-            .await?;
-
             let collection_item_ids = new_project_ids
                 .iter()
                 .map(|_| collection_item.id.0)
@@ -321,6 +668,17 @@ pub async fn collection_edit(
                     })?;
                 validated_project_ids.push(project.inner.id.0);
             }
+            // Delete the projects that were removed, the others keep the date they were added
+            sqlx::query!(
+                "
+                DELETE FROM collections_mods
+                WHERE collection_id = $1 AND NOT (mod_id = ANY($2))
+                ",
+                collection_item.id as database::models::ids::CollectionId,
+                &validated_project_ids[..],
+            )
+            .execute(&mut *transaction)
+            .await?;
             // Insert- don't throw an error if it already exists
             sqlx::query!(
                 "
@@ -355,11 +713,178 @@ pub async fn collection_edit(
     }
 }
 
+/// The most projects a collection can hold when adding them one at a time, unless overridden by
+/// `COLLECTION_MAX_PROJECTS`
+const DEFAULT_MAX_COLLECTION_PROJECTS: usize = 256;
+
+/// Gets a collection the user can modify, and a project that is visible to the user. Projects
+/// already in the collection are returned even if they are no longer visible (ex: they were made
+/// private), so they can still be removed from it.
+async fn get_collection_and_project_for_edit(
+    req: &HttpRequest,
+    collection_id: &str,
+    project_id: &str,
+    pool: &PgPool,
+    redis: &RedisPool,
+    session_queue: &AuthQueue,
+) -> Result<(database::models::Collection, project_item::QueryProject), ApiError> {
+    let user = get_user_from_headers(
+        req,
+        pool,
+        redis,
+        session_queue,
+        Some(&[Scopes::COLLECTION_WRITE]),
+    )
+    .await?
+    .1;
+
+    let id = database::models::CollectionId(parse_base62(collection_id)? as i64);
+    let collection = database::models::Collection::get(id, pool, redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if !can_modify_collection(&collection, &user) {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have permission to edit this collection!".to_string(),
+        ));
+    }
+
+    let project = database::models::Project::get(project_id, pool, redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if !collection.projects.contains(&project.inner.id)
+        && !is_visible_project(&project.inner, &Some(user), pool, false).await?
+    {
+        return Err(ApiError::NotFound);
+    }
+
+    Ok((collection, project))
+}
+
+/// Adds a single project to a collection
+pub async fn collection_project_add(
+    req: HttpRequest,
+    info: web::Path<(String, String)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (collection_id, project_id) = info.into_inner();
+    let (collection, project) = get_collection_and_project_for_edit(
+        &req,
+        &collection_id,
+        &project_id,
+        &pool,
+        &redis,
+        &session_queue,
+    )
+    .await?;
+
+    if collection.projects.contains(&project.inner.id) {
+        return Ok(HttpResponse::NoContent().body(""));
+    }
+
+    let max_projects =
+        parse_var("COLLECTION_MAX_PROJECTS").unwrap_or(DEFAULT_MAX_COLLECTION_PROJECTS);
+    if collection.projects.len() >= max_projects {
+        return Err(ApiError::InvalidInput(format!(
+            "Collections can contain at most {max_projects} projects!"
+        )));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        INSERT INTO collections_mods (collection_id, mod_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        ",
+        collection.id as database::models::ids::CollectionId,
+        project.inner.id as database::models::ids::ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE collections
+        SET updated = NOW()
+        WHERE id = $1
+        ",
+        collection.id as database::models::ids::CollectionId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+    database::models::Collection::clear_cache(collection.id, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// Removes a single project from a collection
+pub async fn collection_project_remove(
+    req: HttpRequest,
+    info: web::Path<(String, String)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (collection_id, project_id) = info.into_inner();
+    let (collection, project) = get_collection_and_project_for_edit(
+        &req,
+        &collection_id,
+        &project_id,
+        &pool,
+        &redis,
+        &session_queue,
+    )
+    .await?;
+
+    let mut transaction = pool.begin().await?;
+
+    let result = sqlx::query!(
+        "
+        DELETE FROM collections_mods
+        WHERE collection_id = $1 AND mod_id = $2
+        ",
+        collection.id as database::models::ids::CollectionId,
+        project.inner.id as database::models::ids::ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound);
+    }
+
+    sqlx::query!(
+        "
+        UPDATE collections
+        SET updated = NOW()
+        WHERE id = $1
+        ",
+        collection.id as database::models::ids::CollectionId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+    database::models::Collection::clear_cache(collection.id, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Extension {
     pub ext: String,
 }
 
+/// The width and height collection icons are resized to
+const COLLECTION_ICON_SIZE: u32 = 256;
+
 #[allow(clippy::too_many_arguments)]
 pub async fn collection_icon_edit(
     web::Query(ext): web::Query<Extension>,
@@ -371,7 +896,7 @@ pub async fn collection_icon_edit(
     mut payload: web::Payload,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
-    if let Some(content_type) = crate::util::ext::get_image_content_type(&ext.ext) {
+    if crate::util::ext::get_image_content_type(&ext.ext).is_some() {
         let cdn_url = dotenvy::var("CDN_URL")?;
         let user = get_user_from_headers(
             &req,
@@ -407,14 +932,16 @@ pub async fn collection_icon_edit(
             read_from_payload(&mut payload, 262144, "Icons must be smaller than 256KiB").await?;
 
         let color = crate::util::img::get_color_from_img(&bytes)?;
+        // Icons are stored at the size they are shown at, whatever format they were uploaded in
+        let bytes = crate::util::img::resize_icon(&bytes, COLLECTION_ICON_SIZE)?;
 
         let hash = sha1::Sha1::from(&bytes).hexdigest();
         let collection_id: CollectionId = collection_item.id.into();
         let upload_data = file_host
             .upload_file(
-                content_type,
-                &format!("data/{}/{}.{}", collection_id, hash, ext.ext),
-                bytes.freeze(),
+                "image/png",
+                &format!("data/{}/{}.png", collection_id, hash),
+                bytes.into(),
             )
             .await?;
 
@@ -550,3 +1077,177 @@ fn can_modify_collection(
 ) -> bool {
     collection.user_id == user.id.into() || user.role.is_mod()
 }
+
+#[derive(Deserialize)]
+pub struct MrpackExportQuery {
+    pub game_version: String,
+    pub loader: String,
+    /// The version of the loader to pin in the index. Required for loaders the launcher installs
+    /// itself (Fabric, Quilt, Forge and NeoForge), as the pack cannot be launched without it.
+    pub loader_version: Option<String>,
+}
+
+pub async fn collection_export_mrpack(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Query(query): web::Query<MrpackExportQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let loader_dependency = match query.loader.as_str() {
+        "fabric" => Some(PackDependency::FabricLoader),
+        "quilt" => Some(PackDependency::QuiltLoader),
+        "forge" => Some(PackDependency::Forge),
+        "neoforge" => Some(PackDependency::Neoforge),
+        _ => None,
+    };
+    if loader_dependency.is_some() && query.loader_version.is_none() {
+        return Err(ApiError::InvalidInput(format!(
+            "A loader_version is required to export a {} pack!",
+            query.loader
+        )));
+    }
+
+    let string = info.into_inner().0;
+
+    let id = database::models::CollectionId(parse_base62(&string)? as i64);
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[
+            Scopes::COLLECTION_READ,
+            Scopes::PROJECT_READ,
+            Scopes::VERSION_READ,
+        ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let collection = match database::models::Collection::get(id, &**pool, &redis).await? {
+        Some(collection) if is_visible_collection(&collection, &user_option).await? => collection,
+        _ => return Err(ApiError::NotFound),
+    };
+
+    let projects =
+        project_item::Project::get_many_ids(&collection.projects, &**pool, &redis).await?;
+    let visible_project_ids = filter_visible_project_ids(
+        projects.iter().map(|x| &x.inner).collect_vec(),
+        &user_option,
+        &pool,
+        false,
+    )
+    .await?;
+    let projects = projects
+        .into_iter()
+        .filter(|x| visible_project_ids.contains(&x.inner.id))
+        .collect_vec();
+
+    let game_version = serde_json::Value::String(query.game_version.clone());
+    let versions = database::models::Version::get_many(
+        &projects
+            .iter()
+            .flat_map(|x| x.versions.iter().copied())
+            .collect_vec(),
+        &**pool,
+        &redis,
+    )
+    .await?
+    .into_iter()
+    .filter(|x| {
+        x.loaders.contains(&query.loader)
+            && x.version_fields.iter().any(|f| {
+                f.field_name == "game_versions" && f.value.contains_json_value(&game_version)
+            })
+    })
+    .collect_vec();
+    let visible_version_ids = filter_visible_version_ids(
+        versions.iter().map(|x| &x.inner).collect_vec(),
+        &user_option,
+        &pool,
+        &redis,
+    )
+    .await?;
+
+    let mut files = Vec::new();
+    let mut incompatible = Vec::new();
+    for project in &projects {
+        let latest = versions
+            .iter()
+            .filter(|x| {
+                x.inner.project_id == project.inner.id && visible_version_ids.contains(&x.inner.id)
+            })
+            .max();
+        let Some(file) = latest.and_then(|x| {
+            x.files
+                .iter()
+                .find(|f| f.primary && f.part_group_id.is_none())
+                .or_else(|| x.files.iter().find(|f| f.part_group_id.is_none()))
+        }) else {
+            incompatible.push(ProjectId::from(project.inner.id).to_string());
+            continue;
+        };
+
+        let folder = if project.project_types.iter().any(|x| x == "resourcepack") {
+            "resourcepacks"
+        } else if project.project_types.iter().any(|x| x == "shader") {
+            "shaderpacks"
+        } else {
+            "mods"
+        };
+
+        files.push(PackFile {
+            path: format!("{folder}/{}", file.filename),
+            hashes: file
+                .hashes
+                .iter()
+                .map(|(algorithm, hash)| (PackFileHash::from(algorithm.clone()), hash.clone()))
+                .collect(),
+            env: None,
+            downloads: vec![file.url.clone()],
+            file_size: file.size,
+        });
+    }
+
+    let mut dependencies = HashMap::from([(PackDependency::Minecraft, query.game_version)]);
+    if let (Some(loader), Some(loader_version)) = (loader_dependency, query.loader_version) {
+        dependencies.insert(loader, loader_version);
+    }
+
+    let pack = PackFormat {
+        game: "minecraft".to_string(),
+        format_version: 1,
+        version_id: collection.updated.format("%Y.%m.%d").to_string(),
+        name: collection.name.clone(),
+        summary: collection.description.clone(),
+        files,
+        dependencies,
+    };
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file(
+        "modrinth.index.json",
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+    )?;
+    zip.write_all(&serde_json::to_vec_pretty(&pack)?)?;
+    let bytes = zip.finish()?.into_inner();
+
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type("application/octet-stream")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "{}.mrpack",
+                collection.name
+            ))],
+        });
+    if !incompatible.is_empty() {
+        response.insert_header(("X-Incompatible-Projects", incompatible.join(",")));
+    }
+
+    Ok(response.body(bytes))
+}
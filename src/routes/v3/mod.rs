@@ -1,5 +1,6 @@
 pub use super::ApiError;
 use crate::util::cors::default_cors;
+use crate::util::head::HeadSupport;
 use actix_web::{web, HttpResponse};
 use serde_json::json;
 
@@ -7,6 +8,7 @@ pub mod analytics_get;
 pub mod collections;
 pub mod images;
 pub mod notifications;
+pub mod openapi;
 pub mod organizations;
 pub mod payouts;
 pub mod project_creation;
@@ -27,10 +29,12 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("v3")
             .wrap(default_cors())
+            .wrap(HeadSupport)
             .configure(analytics_get::config)
             .configure(collections::config)
             .configure(images::config)
             .configure(notifications::config)
+            .configure(openapi::config)
             .configure(organizations::config)
             .configure(project_creation::config)
             .configure(projects::config)
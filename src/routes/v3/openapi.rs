@@ -0,0 +1,54 @@
+use actix_web::{web, HttpResponse};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("openapi.json", web::get().to(openapi_spec));
+}
+
+/// The spec only covers the public read routes of projects, versions, version files and tags.
+/// Mutating, authentication, user, team, organization and collection routes aren't documented yet.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Labrinth", description = "The Modrinth API"),
+    modifiers(&PatSecurity),
+    paths(
+        super::projects::project_search,
+        super::projects::projects_get,
+        super::projects::project_get,
+        super::projects::project_license_get,
+        super::versions::versions_get,
+        super::versions::version_get,
+        super::versions::version_list,
+        super::version_file::get_version_from_hash,
+        super::version_file::download_version,
+        super::tags::category_list,
+        super::tags::loader_list,
+        super::tags::loader_get,
+        super::tags::license_list,
+        super::tags::license_text,
+    )
+)]
+pub struct ApiDoc;
+
+/// Personal access tokens (and session tokens) are sent as-is in the `Authorization` header
+struct PatSecurity;
+
+impl Modify for PatSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "pat",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "Authorization",
+                    "A personal access token, eg. `mrp_...`",
+                ))),
+            );
+    }
+}
+
+pub async fn openapi_spec() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
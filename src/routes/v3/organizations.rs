@@ -647,6 +647,7 @@ pub async fn organization_projects_add(
 
     let project_item = database::models::Project::get(&project_info.project_id, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
@@ -734,10 +735,9 @@ pub async fn organization_projects_add(
 
         database::models::User::clear_project_cache(&[current_user.id.into()], &redis).await?;
         database::models::TeamMember::clear_cache(project_item.inner.team_id, &redis).await?;
-        database::models::Project::clear_cache(
+        database::models::project_item::clear_project_caches(
             project_item.inner.id,
             project_item.inner.slug,
-            None,
             &redis,
         )
         .await?;
@@ -895,10 +895,9 @@ pub async fn organization_projects_remove(
         transaction.commit().await?;
         database::models::User::clear_project_cache(&[current_user.id.into()], &redis).await?;
         database::models::TeamMember::clear_cache(project_item.inner.team_id, &redis).await?;
-        database::models::Project::clear_cache(
+        database::models::project_item::clear_project_caches(
             project_item.inner.id,
             project_item.inner.slug,
-            None,
             &redis,
         )
         .await?;
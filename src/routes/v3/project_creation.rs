@@ -18,8 +18,12 @@ use crate::models::users::UserId;
 use crate::queue::session::AuthQueue;
 use crate::search::indexing::IndexingError;
 use crate::util::routes::read_from_field;
-use crate::util::validate::validation_errors_to_string;
+use crate::util::validate::{
+    validate_string_length, validation_errors_by_field, validation_errors_to_string,
+    StringLengthError,
+};
 use actix_multipart::{Field, Multipart};
+use actix_web::http::header::WARNING;
 use actix_web::http::StatusCode;
 use actix_web::web::{self, Data};
 use actix_web::{HttpRequest, HttpResponse};
@@ -37,6 +41,7 @@ use validator::Validate;
 
 pub fn config(cfg: &mut actix_web::web::ServiceConfig) {
     cfg.route("project", web::post().to(project_create));
+    cfg.route("project/validate", web::post().to(project_validate));
 }
 
 #[derive(Error, Debug)]
@@ -55,10 +60,14 @@ pub enum CreateError {
     SerDeError(#[from] serde_json::Error),
     #[error("Error while validating input: {0}")]
     ValidationError(String),
+    #[error("Error while validating input: {0}")]
+    StringLengthError(#[from] crate::util::validate::StringLengthError),
     #[error("Error while uploading file: {0}")]
     FileHostingError(#[from] FileHostingError),
     #[error("Error while validating uploaded file: {0}")]
     FileValidationError(#[from] crate::validate::ValidationError),
+    #[error("Error while validating pack manifest: {0}")]
+    PackManifestError(#[from] crate::validate::modpack::MrpackError),
     #[error("{}", .0)]
     MissingValueError(String),
     #[error("Invalid format for image: {0}")]
@@ -69,6 +78,8 @@ pub enum CreateError {
     InvalidGameVersion(String),
     #[error("Invalid loader: {0}")]
     InvalidLoader(String),
+    #[error("Incompatible loader: {0}")]
+    IncompatibleLoader(String),
     #[error("Invalid category: {0}")]
     InvalidCategory(String),
     #[error("Invalid file type for version file: {0}")]
@@ -100,19 +111,44 @@ impl actix_web::ResponseError for CreateError {
             CreateError::InvalidInput(..) => StatusCode::BAD_REQUEST,
             CreateError::InvalidGameVersion(..) => StatusCode::BAD_REQUEST,
             CreateError::InvalidLoader(..) => StatusCode::BAD_REQUEST,
+            CreateError::IncompatibleLoader(..) => StatusCode::UNPROCESSABLE_ENTITY,
             CreateError::InvalidCategory(..) => StatusCode::BAD_REQUEST,
             CreateError::InvalidFileType(..) => StatusCode::BAD_REQUEST,
+            CreateError::Unauthorized(AuthenticationError::Suspended { .. }) => {
+                StatusCode::FORBIDDEN
+            }
             CreateError::Unauthorized(..) => StatusCode::UNAUTHORIZED,
             CreateError::CustomAuthenticationError(..) => StatusCode::UNAUTHORIZED,
             CreateError::SlugCollision => StatusCode::BAD_REQUEST,
             CreateError::ValidationError(..) => StatusCode::BAD_REQUEST,
+            CreateError::StringLengthError(..) => StatusCode::BAD_REQUEST,
             CreateError::FileValidationError(..) => StatusCode::BAD_REQUEST,
+            CreateError::PackManifestError(..) => StatusCode::BAD_REQUEST,
             CreateError::ImageError(..) => StatusCode::BAD_REQUEST,
             CreateError::RerouteError(..) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
+        if let CreateError::Unauthorized(err @ AuthenticationError::Suspended { .. }) = self {
+            return err.error_response();
+        }
+
+        if let CreateError::StringLengthError(err) = self {
+            return crate::routes::ApiError::StringLength(err.clone()).error_response();
+        }
+
+        if let CreateError::PackManifestError(
+            crate::validate::modpack::MrpackError::InvalidManifest(fields),
+        ) = self
+        {
+            return HttpResponse::build(self.status_code()).json(serde_json::json!({
+                "error": "invalid_input",
+                "description": self.to_string(),
+                "fields": fields,
+            }));
+        }
+
         HttpResponse::build(self.status_code()).json(ApiError {
             error: match self {
                 CreateError::EnvError(..) => "environment_error",
@@ -127,13 +163,16 @@ impl actix_web::ResponseError for CreateError {
                 CreateError::InvalidInput(..) => "invalid_input",
                 CreateError::InvalidGameVersion(..) => "invalid_input",
                 CreateError::InvalidLoader(..) => "invalid_input",
+                CreateError::IncompatibleLoader(..) => "invalid_input",
                 CreateError::InvalidCategory(..) => "invalid_input",
                 CreateError::InvalidFileType(..) => "invalid_input",
                 CreateError::Unauthorized(..) => "unauthorized",
                 CreateError::CustomAuthenticationError(..) => "unauthorized",
                 CreateError::SlugCollision => "invalid_input",
                 CreateError::ValidationError(..) => "invalid_input",
+                CreateError::StringLengthError(..) => "invalid_input",
                 CreateError::FileValidationError(..) => "invalid_input",
+                CreateError::PackManifestError(..) => "invalid_input",
                 CreateError::ImageError(..) => "invalid_image",
                 CreateError::RerouteError(..) => "reroute_error",
             },
@@ -152,10 +191,7 @@ fn default_requested_status() -> ProjectStatus {
 
 #[derive(Serialize, Deserialize, Validate, Clone)]
 pub struct ProjectCreateData {
-    #[validate(
-        length(min = 3, max = 64),
-        custom(function = "crate::util::validate::validate_name")
-    )]
+    #[validate(custom(function = "crate::util::validate::validate_name"))]
     #[serde(alias = "mod_name")]
     /// The title or name of the project.
     pub name: String,
@@ -166,11 +202,9 @@ pub struct ProjectCreateData {
     #[serde(alias = "mod_slug")]
     /// The slug of a project, used for vanity URLs
     pub slug: String,
-    #[validate(length(min = 3, max = 255))]
     #[serde(alias = "mod_description")]
     /// A short description of the project.
     pub summary: String,
-    #[validate(length(max = 65536))]
     #[serde(alias = "mod_body")]
     /// A long description of the project, in markdown.
     pub description: String,
@@ -190,7 +224,7 @@ pub struct ProjectCreateData {
     /// An optional link to the project's license page
     pub license_url: Option<String>,
     /// An optional list of all donation links the project has
-    #[validate(custom(function = "crate::util::validate::validate_url_hashmap_values"))]
+    #[validate(custom(function = "crate::util::validate::validate_link_urls"))]
     #[serde(default)]
     pub link_urls: HashMap<String, String>,
 
@@ -217,6 +251,20 @@ pub struct ProjectCreateData {
     pub organization_id: Option<OrganizationId>,
 }
 
+impl ProjectCreateData {
+    /// Checks the lengths of the free-text fields, reporting the first one out of range
+    pub fn validate_lengths(&self) -> Result<(), StringLengthError> {
+        validate_string_length("name", &self.name, 2, 64)?;
+        validate_string_length("summary", &self.summary, 2, 256)?;
+        validate_string_length("description", &self.description, 0, 65535)?;
+        for version in &self.initial_versions {
+            version.validate_lengths()?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Validate, Clone)]
 pub struct NewGalleryItem {
     /// The name of the multipart item where the gallery media is located
@@ -249,6 +297,244 @@ pub async fn undo_uploads(
     Ok(())
 }
 
+/// A problem found while validating the data of a new project
+pub struct ProjectValidationError {
+    /// The field of the project data the problem is with
+    pub field: String,
+    pub error: CreateError,
+}
+
+impl ProjectValidationError {
+    fn new(field: impl Into<String>, error: impl Into<CreateError>) -> Self {
+        ProjectValidationError {
+            field: field.into(),
+            error: error.into(),
+        }
+    }
+}
+
+/// The parts of the data of a new project which were looked up while validating it
+pub struct ValidatedProject {
+    pub categories: Vec<models::CategoryId>,
+    pub additional_categories: Vec<models::CategoryId>,
+    pub license: String,
+    pub link_urls: Vec<models::project_item::LinkUrl>,
+}
+
+/// Runs every check on the data of a new project which does not need its files, collecting all
+/// the problems found rather than stopping at the first one. Nothing is written.
+pub async fn validate_project_creation(
+    data: &ProjectCreateData,
+    all_loaders: &[models::loader_fields::Loader],
+    conn: &mut sqlx::PgConnection,
+    redis: &RedisPool,
+) -> Result<ValidatedProject, Vec<ProjectValidationError>> {
+    let mut errors = Vec::new();
+
+    if let Err(err) = data.validate_lengths() {
+        errors.push(ProjectValidationError::new(err.field, err));
+    }
+    if let Err(err) = data.validate() {
+        errors.extend(
+            validation_errors_by_field(err)
+                .into_iter()
+                .map(|(field, message)| {
+                    ProjectValidationError::new(field, CreateError::InvalidInput(message))
+                }),
+        );
+    }
+
+    if let Err(err) = check_slug_available(&data.slug, &mut *conn).await {
+        errors.push(ProjectValidationError::new("slug", err));
+    }
+
+    let mut file_parts = std::collections::HashSet::new();
+    for (index, version) in data.initial_versions.iter().enumerate() {
+        let field = format!("initial_versions[{index}]");
+
+        if version.file_parts.iter().any(|x| !file_parts.insert(x)) {
+            errors.push(ProjectValidationError::new(
+                &field,
+                CreateError::InvalidInput(String::from("Duplicate multipart field name")),
+            ));
+        }
+        if let Err(err) = validate_initial_version(version, all_loaders, &mut *conn, redis).await {
+            errors.push(ProjectValidationError::new(&field, err));
+        }
+    }
+
+    if !data.is_draft.unwrap_or(false) && data.initial_versions.is_empty() {
+        errors.push(ProjectValidationError::new(
+            "initial_versions",
+            CreateError::InvalidInput(String::from(
+                "Project submitted for review with no initial versions",
+            )),
+        ));
+    }
+
+    if let Some(gallery_items) = &data.gallery_items {
+        if gallery_items.iter().filter(|a| a.featured).count() > 1 {
+            errors.push(ProjectValidationError::new(
+                "gallery_items",
+                CreateError::InvalidInput(String::from("Only one gallery image can be featured.")),
+            ));
+        }
+    }
+
+    let mut categories = Vec::with_capacity(data.categories.len());
+    let mut additional_categories = Vec::with_capacity(data.additional_categories.len());
+    for (field, names, ids) in [
+        ("categories", &data.categories, &mut categories),
+        (
+            "additional_categories",
+            &data.additional_categories,
+            &mut additional_categories,
+        ),
+    ] {
+        for category in names {
+            match models::categories::Category::get_ids(category, &mut *conn).await {
+                // TODO: We should filter out categories that don't match the project type of any of the versions
+                // ie: if mod and modpack both share a name this should only have modpack if it only has a modpack as a version
+                Ok(category_ids) if !category_ids.is_empty() => ids.extend(category_ids.values()),
+                Ok(_) => errors.push(ProjectValidationError::new(
+                    field,
+                    CreateError::InvalidCategory(category.clone()),
+                )),
+                Err(err) => errors.push(ProjectValidationError::new(field, err)),
+            }
+        }
+    }
+
+    let license = match spdx::Expression::parse(&data.license_id) {
+        Ok(license) => license.to_string(),
+        Err(err) => {
+            errors.push(ProjectValidationError::new(
+                "license_id",
+                CreateError::InvalidInput(format!("Invalid SPDX license identifier: {err}")),
+            ));
+            String::new()
+        }
+    };
+
+    let mut link_urls = vec![];
+    match models::categories::LinkPlatform::list(&mut *conn, redis).await {
+        Ok(link_platforms) => {
+            for (platform, url) in &data.link_urls {
+                if let Some(link_platform) = link_platforms.iter().find(|x| &x.name == platform) {
+                    link_urls.push(models::project_item::LinkUrl {
+                        platform_id: link_platform.id,
+                        platform_name: link_platform.name.clone(),
+                        url: url.clone(),
+                        donation: link_platform.donation,
+                    })
+                } else {
+                    errors.push(ProjectValidationError::new(
+                        "link_urls",
+                        CreateError::InvalidInput(format!(
+                            "Link platform {platform} does not exist."
+                        )),
+                    ));
+                }
+            }
+        }
+        Err(err) => errors.push(ProjectValidationError::new("link_urls", err)),
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(ValidatedProject {
+        categories,
+        additional_categories,
+        license,
+        link_urls,
+    })
+}
+
+async fn check_slug_available(
+    slug: &str,
+    conn: &mut sqlx::PgConnection,
+) -> Result<(), CreateError> {
+    let slug_project_id_option: Option<ProjectId> =
+        serde_json::from_str(&format!("\"{}\"", slug)).ok();
+
+    if let Some(slug_project_id) = slug_project_id_option {
+        let slug_project_id: models::ids::ProjectId = slug_project_id.into();
+        let results = sqlx::query!(
+            "
+            SELECT EXISTS(SELECT 1 FROM mods WHERE id=$1)
+            ",
+            slug_project_id as models::ids::ProjectId
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CreateError::DatabaseError(e.into()))?;
+
+        if results.exists.unwrap_or(false) {
+            return Err(CreateError::SlugCollision);
+        }
+    }
+
+    let results = sqlx::query!(
+        "
+        SELECT EXISTS(SELECT 1 FROM mods WHERE slug = LOWER($1))
+        ",
+        slug
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| CreateError::DatabaseError(e.into()))?;
+
+    if results.exists.unwrap_or(false) {
+        return Err(CreateError::SlugCollision);
+    }
+
+    Ok(())
+}
+
+// Runs the checks of `create_initial_version` that do not need a version ID
+async fn validate_initial_version(
+    version_data: &InitialVersionData,
+    all_loaders: &[models::loader_fields::Loader],
+    conn: &mut sqlx::PgConnection,
+    redis: &RedisPool,
+) -> Result<(), CreateError> {
+    if version_data.project_id.is_some() {
+        return Err(CreateError::InvalidInput(String::from(
+            "Found project id in initial version for new project",
+        )));
+    }
+
+    version_data
+        .validate()
+        .map_err(|err| CreateError::ValidationError(validation_errors_to_string(err, None)))?;
+
+    let loaders = version_data
+        .loaders
+        .iter()
+        .map(|x| {
+            all_loaders
+                .iter()
+                .find(|y| y.loader == x.0)
+                .ok_or_else(|| CreateError::InvalidLoader(x.0.clone()))
+                .map(|y| y.id)
+        })
+        .collect::<Result<Vec<models::LoaderId>, CreateError>>()?;
+
+    let loader_fields = LoaderField::get_fields(&loaders, &mut *conn, redis).await?;
+    let mut loader_field_enum_values =
+        LoaderFieldEnumValue::list_many_loader_fields(&loader_fields, &mut *conn, redis).await?;
+    try_create_version_fields(
+        VersionId(0),
+        &version_data.fields,
+        &loader_fields,
+        &mut loader_field_enum_values,
+    )?;
+
+    Ok(())
+}
+
 pub async fn project_create(
     req: HttpRequest,
     mut payload: Multipart,
@@ -286,6 +572,192 @@ pub async fn project_create(
 
     result
 }
+#[derive(Serialize)]
+pub struct ProjectValidationResult {
+    pub valid: bool,
+    pub errors: Vec<ProjectFieldError>,
+}
+
+#[derive(Serialize)]
+pub struct ProjectFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Checks a project creation request like `POST /project` does, without creating the project or
+/// uploading its files. Files are checked for their names, types and sizes, but their contents
+/// are not read.
+pub async fn project_validate(
+    req: HttpRequest,
+    mut payload: Multipart,
+    pool: Data<PgPool>,
+    redis: Data<RedisPool>,
+    session_queue: Data<AuthQueue>,
+) -> Result<HttpResponse, CreateError> {
+    get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_CREATE]),
+    )
+    .await?;
+
+    let invalid = |field: &str, error: CreateError| {
+        HttpResponse::Ok().json(ProjectValidationResult {
+            valid: false,
+            errors: vec![ProjectFieldError {
+                field: field.to_string(),
+                message: error.to_string(),
+            }],
+        })
+    };
+
+    let Some(field) = payload.next().await else {
+        return Ok(invalid(
+            "data",
+            CreateError::MissingValueError(String::from("No `data` field in multipart upload")),
+        ));
+    };
+    let mut field = field?;
+    if field.content_disposition().get_name() != Some("data") {
+        return Ok(invalid(
+            "data",
+            CreateError::InvalidInput(String::from("`data` field must come before file fields")),
+        ));
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field.next().await {
+        data.extend_from_slice(&chunk?);
+    }
+    let create_data: ProjectCreateData = match serde_json::from_slice(&data) {
+        Ok(create_data) => create_data,
+        Err(err) => return Ok(invalid("data", err.into())),
+    };
+
+    let all_loaders = models::loader_fields::Loader::list(&**pool, &redis).await?;
+    let mut conn = pool.acquire().await?;
+    let mut errors = validate_project_creation(&create_data, &all_loaders, &mut conn, &redis)
+        .await
+        .err()
+        .unwrap_or_default();
+
+    // Errors of our own are returned as such rather than blamed on the project
+    if let Some(index) = errors
+        .iter()
+        .position(|x| actix_web::ResponseError::status_code(&x.error).is_server_error())
+    {
+        return Err(errors.swap_remove(index).error);
+    }
+
+    let mut received_parts = std::collections::HashSet::new();
+    let mut has_icon = false;
+    while let Some(item) = payload.next().await {
+        let mut field = item?;
+        let content_disposition = field.content_disposition().clone();
+        let name = content_disposition
+            .get_name()
+            .unwrap_or_default()
+            .to_string();
+
+        let result = async {
+            let (file_name, file_extension) =
+                super::version_creation::get_name_ext(&content_disposition)?;
+
+            if name == "icon" {
+                if std::mem::replace(&mut has_icon, true) {
+                    return Err(CreateError::InvalidInput(String::from(
+                        "Projects can only have one icon",
+                    )));
+                }
+                crate::util::ext::get_image_content_type(file_extension)
+                    .ok_or_else(|| CreateError::InvalidIconFormat(file_extension.to_string()))?;
+                read_from_field(&mut field, 262144, "Icons must be smaller than 256KiB").await?;
+            } else if create_data
+                .gallery_items
+                .iter()
+                .flatten()
+                .any(|x| x.item == name)
+            {
+                crate::util::ext::get_image_content_type(file_extension)
+                    .ok_or_else(|| CreateError::InvalidIconFormat(file_extension.to_string()))?;
+                read_from_field(
+                    &mut field,
+                    5 * (1 << 20),
+                    "Gallery image exceeds the maximum of 5MiB.",
+                )
+                .await?;
+            } else if create_data
+                .initial_versions
+                .iter()
+                .any(|x| x.file_parts.contains(&name))
+            {
+                if file_name.contains('/') {
+                    return Err(CreateError::InvalidInput(
+                        "File names must not contain slashes!".to_string(),
+                    ));
+                }
+                crate::util::ext::project_file_type(file_extension)
+                    .ok_or_else(|| CreateError::InvalidFileType(file_extension.to_string()))?;
+
+                // The file may be large, so it is only counted rather than read into memory
+                let mut size = 0;
+                while let Some(chunk) = field.next().await {
+                    size += chunk?.len();
+                    if size > 500 * (1 << 20) {
+                        return Err(CreateError::InvalidInput(String::from(
+                            "Project file exceeds the maximum of 500MiB. Contact a moderator or admin to request permission to upload larger files.",
+                        )));
+                    }
+                }
+                received_parts.insert(name.clone());
+            } else {
+                return Err(CreateError::InvalidInput(format!(
+                    "File `{file_name}` (field {name}) isn't specified in the versions data"
+                )));
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(err) = result {
+            errors.push(ProjectValidationError::new(name, err));
+            // The rest of the field has to be skipped to get to the next one
+            while let Some(chunk) = field.next().await {
+                chunk?;
+            }
+        }
+    }
+
+    for (index, version) in create_data.initial_versions.iter().enumerate() {
+        if version
+            .file_parts
+            .iter()
+            .any(|x| !received_parts.contains(x))
+        {
+            errors.push(ProjectValidationError::new(
+                format!("initial_versions[{index}]"),
+                CreateError::InvalidInput(String::from(
+                    "Some files were specified in initial_versions but not uploaded",
+                )),
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(ProjectValidationResult {
+        valid: errors.is_empty(),
+        errors: errors
+            .into_iter()
+            .map(|x| ProjectFieldError {
+                field: x.field,
+                message: x.error.to_string(),
+            })
+            .collect(),
+    }))
+}
+
 /*
 
 Project Creation Steps:
@@ -345,6 +817,7 @@ async fn project_create_inner(
     let all_loaders = models::loader_fields::Loader::list(&mut **transaction, redis).await?;
 
     let project_create_data: ProjectCreateData;
+    let validated: ValidatedProject;
     let mut versions;
     let mut versions_map = std::collections::HashMap::new();
     let mut gallery_urls = Vec::new();
@@ -379,57 +852,16 @@ async fn project_create_inner(
         }
         let create_data: ProjectCreateData = serde_json::from_slice(&data)?;
 
-        create_data
-            .validate()
-            .map_err(|err| CreateError::InvalidInput(validation_errors_to_string(err, None)))?;
-
-        let slug_project_id_option: Option<ProjectId> =
-            serde_json::from_str(&format!("\"{}\"", create_data.slug)).ok();
-
-        if let Some(slug_project_id) = slug_project_id_option {
-            let slug_project_id: models::ids::ProjectId = slug_project_id.into();
-            let results = sqlx::query!(
-                "
-                SELECT EXISTS(SELECT 1 FROM mods WHERE id=$1)
-                ",
-                slug_project_id as models::ids::ProjectId
-            )
-            .fetch_one(&mut **transaction)
+        validated = validate_project_creation(&create_data, &all_loaders, transaction, redis)
             .await
-            .map_err(|e| CreateError::DatabaseError(e.into()))?;
-
-            if results.exists.unwrap_or(false) {
-                return Err(CreateError::SlugCollision);
-            }
-        }
-
-        {
-            let results = sqlx::query!(
-                "
-                SELECT EXISTS(SELECT 1 FROM mods WHERE slug = LOWER($1))
-                ",
-                create_data.slug
-            )
-            .fetch_one(&mut **transaction)
-            .await
-            .map_err(|e| CreateError::DatabaseError(e.into()))?;
-
-            if results.exists.unwrap_or(false) {
-                return Err(CreateError::SlugCollision);
-            }
-        }
+            .map_err(|mut errors| errors.remove(0).error)?;
 
         // Create VersionBuilders for the versions specified in `initial_versions`
         versions = Vec::with_capacity(create_data.initial_versions.len());
         for (i, data) in create_data.initial_versions.iter().enumerate() {
             // Create a map of multipart field names to version indices
             for name in &data.file_parts {
-                if versions_map.insert(name.to_owned(), i).is_some() {
-                    // If the name is already used
-                    return Err(CreateError::InvalidInput(String::from(
-                        "Duplicate multipart field name",
-                    )));
-                }
+                versions_map.insert(name.to_owned(), i);
             }
             versions.push(
                 create_initial_version(
@@ -488,11 +920,6 @@ async fn project_create_inner(
                 return Ok(());
             }
             if let Some(gallery_items) = &project_create_data.gallery_items {
-                if gallery_items.iter().filter(|a| a.featured).count() > 1 {
-                    return Err(CreateError::InvalidInput(String::from(
-                        "Only one gallery image can be featured.",
-                    )));
-                }
                 if let Some(item) = gallery_items.iter().find(|x| x.item == name) {
                     let data = read_from_field(
                         &mut field,
@@ -555,6 +982,8 @@ async fn project_create_inner(
                 version_data.primary_file.is_some(),
                 version_data.primary_file.as_deref() == Some(name),
                 None,
+                false,
+                None,
                 transaction,
                 redis,
             )
@@ -587,31 +1016,6 @@ async fn project_create_inner(
             }
         }
 
-        // Convert the list of category names to actual categories
-        let mut categories = Vec::with_capacity(project_create_data.categories.len());
-        for category in &project_create_data.categories {
-            let ids = models::categories::Category::get_ids(category, &mut **transaction).await?;
-            if ids.is_empty() {
-                return Err(CreateError::InvalidCategory(category.clone()));
-            }
-
-            // TODO: We should filter out categories that don't match the project type of any of the versions
-            // ie: if mod and modpack both share a name this should only have modpack if it only has a modpack as a version
-            categories.extend(ids.values());
-        }
-
-        let mut additional_categories =
-            Vec::with_capacity(project_create_data.additional_categories.len());
-        for category in &project_create_data.additional_categories {
-            let ids = models::categories::Category::get_ids(category, &mut **transaction).await?;
-            if ids.is_empty() {
-                return Err(CreateError::InvalidCategory(category.clone()));
-            }
-            // TODO: We should filter out categories that don't match the project type of any of the versions
-            // ie: if mod and modpack both share a name this should only have modpack if it only has a modpack as a version
-            additional_categories.extend(ids.values());
-        }
-
         let mut members = vec![];
 
         if let Some(organization_id) = project_create_data.organization_id {
@@ -653,53 +1057,11 @@ async fn project_create_inner(
 
         let team_id = team.insert(&mut *transaction).await?;
 
-        let status;
-        if project_create_data.is_draft.unwrap_or(false) {
-            status = ProjectStatus::Draft;
+        let status = if project_create_data.is_draft.unwrap_or(false) {
+            ProjectStatus::Draft
         } else {
-            status = ProjectStatus::Processing;
-            if project_create_data.initial_versions.is_empty() {
-                return Err(CreateError::InvalidInput(String::from(
-                    "Project submitted for review with no initial versions",
-                )));
-            }
-        }
-
-        let license_id =
-            spdx::Expression::parse(&project_create_data.license_id).map_err(|err| {
-                CreateError::InvalidInput(format!("Invalid SPDX license identifier: {err}"))
-            })?;
-
-        let mut link_urls = vec![];
-
-        let link_platforms =
-            models::categories::LinkPlatform::list(&mut **transaction, redis).await?;
-        for (platform, url) in &project_create_data.link_urls {
-            let platform_id =
-                models::categories::LinkPlatform::get_id(platform, &mut **transaction)
-                    .await?
-                    .ok_or_else(|| {
-                        CreateError::InvalidInput(format!(
-                            "Link platform {} does not exist.",
-                            platform.clone()
-                        ))
-                    })?;
-            let link_platform = link_platforms
-                .iter()
-                .find(|x| x.id == platform_id)
-                .ok_or_else(|| {
-                    CreateError::InvalidInput(format!(
-                        "Link platform {} does not exist.",
-                        platform.clone()
-                    ))
-                })?;
-            link_urls.push(models::project_item::LinkUrl {
-                platform_id,
-                platform_name: link_platform.name.clone(),
-                url: url.clone(),
-                donation: link_platform.donation,
-            })
-        }
+            ProjectStatus::Processing
+        };
 
         let project_builder_actual = models::project_item::ProjectBuilder {
             project_id: project_id.into(),
@@ -711,14 +1073,14 @@ async fn project_create_inner(
             icon_url: icon_data.clone().map(|x| x.0),
 
             license_url: project_create_data.license_url,
-            categories,
-            additional_categories,
+            categories: validated.categories,
+            additional_categories: validated.additional_categories,
             initial_versions: versions,
             status,
             requested_status: Some(project_create_data.requested_status),
-            license: license_id.to_string(),
+            license: validated.license,
             slug: Some(project_create_data.slug),
-            link_urls,
+            link_urls: validated.link_urls,
             gallery_items: gallery_urls
                 .iter()
                 .map(|x| models::project_item::GalleryItem {
@@ -732,6 +1094,7 @@ async fn project_create_inner(
                 .collect(),
             color: icon_data.and_then(|x| x.1),
             monetization_status: MonetizationStatus::Monetized,
+            forked_from: None,
         };
         let project_builder = project_builder_actual.clone();
 
@@ -790,19 +1153,20 @@ async fn project_create_inner(
             .flat_map(|v| v.loaders.clone())
             .unique()
             .collect::<Vec<_>>();
-        let (project_types, games) = Loader::list(&mut **transaction, redis)
-            .await?
-            .into_iter()
-            .fold(
-                (Vec::new(), Vec::new()),
-                |(mut project_types, mut games), loader| {
-                    if loaders.contains(&loader.id) {
-                        project_types.extend(loader.supported_project_types);
-                        games.extend(loader.supported_games);
-                    }
-                    (project_types, games)
-                },
-            );
+        let all_loaders = Loader::list(&mut **transaction, redis).await?;
+        let warning = super::version_creation::deprecated_loaders_warning(
+            all_loaders.iter().filter(|x| loaders.contains(&x.id)),
+        );
+        let (project_types, games) = all_loaders.into_iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut project_types, mut games), loader| {
+                if loaders.contains(&loader.id) {
+                    project_types.extend(loader.supported_project_types);
+                    games.extend(loader.supported_games);
+                }
+                (project_types, games)
+            },
+        );
 
         let response = crate::models::projects::Project {
             id: project_id,
@@ -847,10 +1211,17 @@ async fn project_create_inner(
             color: project_builder.color,
             thread_id: thread_id.into(),
             monetization_status: MonetizationStatus::Monetized,
+            version: 1,
+            forked_from: None,
+            featured: false,
             fields: HashMap::new(), // Fields instantiate to empty
         };
 
-        Ok(HttpResponse::Ok().json(response))
+        let mut http_response = HttpResponse::Ok();
+        if let Some(warning) = warning {
+            http_response.insert_header((WARNING, warning));
+        }
+        Ok(http_response.json(response))
     }
 }
 
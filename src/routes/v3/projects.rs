@@ -2,11 +2,20 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::auth::checks::{filter_visible_versions, is_visible_project};
-use crate::auth::{filter_visible_projects, get_user_from_headers};
+use crate::auth::{
+    check_is_moderator_from_headers, filter_visible_projects, get_user_from_headers,
+};
+use crate::database::models::download_history_item::DownloadHistory;
+use crate::database::models::legacy_loader_fields::MinecraftGameVersion;
 use crate::database::models::notification_item::NotificationBuilder;
+use crate::database::models::project_audit_log_item::{
+    ProjectAuditLogBuilder, ProjectAuditLogEntry,
+};
+use crate::database::models::project_event_item::ProjectEvent;
 use crate::database::models::project_item::{GalleryItem, ModCategory};
+use crate::database::models::project_status_message_item::ProjectStatusMessageBuilder;
 use crate::database::models::thread_item::ThreadMessageBuilder;
-use crate::database::models::{ids as db_ids, image_item, TeamMember};
+use crate::database::models::{ids as db_ids, TeamMember};
 use crate::database::redis::RedisPool;
 use crate::database::{self, models as db_models};
 use crate::file_hosting::FileHost;
@@ -16,25 +25,33 @@ use crate::models::images::ImageContext;
 use crate::models::notifications::NotificationBody;
 use crate::models::pats::Scopes;
 use crate::models::projects::{
-    MonetizationStatus, Project, ProjectId, ProjectStatus, SearchRequest,
+    MonetizationStatus, Project, ProjectId, ProjectStatus, SearchRequest, VersionStatus,
 };
 use crate::models::teams::ProjectPermissions;
 use crate::models::threads::MessageBody;
+use crate::queue::analytics::{traffic_channel, AnalyticsQueue};
 use crate::queue::moderation::AutomatedModerationQueue;
 use crate::queue::session::AuthQueue;
 use crate::routes::ApiError;
-use crate::search::indexing::remove_documents;
-use crate::search::{search_for_project, SearchConfig, SearchError};
+use crate::search::indexing::retry::remove_documents_or_retry;
+use crate::search::{
+    expand_game_version_facets, search_for_project_cached, search_for_project_fields, search_log,
+    search_request_for_project_type, SearchConfig, SearchError, MAX_SEARCH_LIMIT,
+    MAX_SEARCH_OFFSET,
+};
+use crate::util::badge::{self, BadgeStyle};
 use crate::util::img;
 use crate::util::routes::read_from_payload;
-use crate::util::validate::validation_errors_to_string;
+use crate::util::validate::{validate_string_length, validation_errors_to_string};
+use actix_web::http::header::{self, Header};
 use actix_web::{web, HttpRequest, HttpResponse};
 use chrono::Utc;
-use futures::TryStreamExt;
+use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -42,11 +59,67 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.route("projects", web::get().to(projects_get));
     cfg.route("projects", web::patch().to(projects_edit));
     cfg.route("projects_random", web::get().to(random_projects_get));
+    cfg.route(
+        "projects/updated-since",
+        web::get().to(projects_updated_since_get),
+    );
 
     cfg.service(
         web::scope("project")
+            .route("featured", web::get().to(projects_featured_get))
             .route("{id}", web::get().to(project_get))
             .route("{id}/check", web::get().to(project_get_check))
+            .route("{id}/embed", web::get().to(project_embed_get))
+            .route("{id}/badge", web::get().to(project_badge_get))
+            .route(
+                "{id}/files",
+                web::get().to(super::versions::project_latest_file_get),
+            )
+            .route(
+                "{id}/versions/count",
+                web::get().to(project_version_counts_get),
+            )
+            .route(
+                "{id}/versions/matrix",
+                web::get().to(project_version_matrix_get),
+            )
+            .route(
+                "{id}/versions/latest",
+                web::get().to(project_latest_versions_get),
+            )
+            .route(
+                "{id}/versions/game_versions_all",
+                web::get().to(project_supported_game_versions_get),
+            )
+            .route("{id}/related", web::get().to(project_related_get))
+            .route("{id}/license", web::get().to(project_license_get))
+            .route(
+                "{id}/license/custom",
+                web::post().to(project_custom_license_upload),
+            )
+            .route(
+                "{id}/license/custom",
+                web::delete().to(project_custom_license_delete),
+            )
+            .route("{id}/audit", web::get().to(project_audit_log_get))
+            .route("{id}/events", web::get().to(project_events_get))
+            .route("{id}/traffic", web::get().to(project_traffic_get))
+            .route(
+                "{id}/stats/timeline",
+                web::get().to(project_stats_timeline_get),
+            )
+            .route("{id}/body", web::patch().to(project_body_edit))
+            .route("{id}/visibility", web::patch().to(project_visibility_edit))
+            .route("{id}/status", web::patch().to(project_status_edit))
+            .route(
+                "{id}/additional_categories",
+                web::patch().to(project_additional_categories_edit),
+            )
+            .route(
+                "{id}/external_links",
+                web::patch().to(project_external_links_edit),
+            )
+            .route("{id}/duplicate", web::post().to(project_duplicate))
             .route("{id}", web::delete().to(project_delete))
             .route("{id}", web::patch().to(project_edit))
             .route("{id}/icon", web::patch().to(project_icon_edit))
@@ -63,7 +136,16 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                         "members",
                         web::get().to(super::teams::team_members_get_project),
                     )
+                    .route("teams", web::get().to(super::teams::project_teams_get))
                     .route("version", web::get().to(super::versions::version_list))
+                    .route(
+                        "versions/game_version/{game_version}",
+                        web::get().to(super::versions::version_list_game_version),
+                    )
+                    .route(
+                        "versions.rss",
+                        web::get().to(super::versions::version_list_rss),
+                    )
                     .route(
                         "version/{slug}",
                         web::get().to(super::versions::version_project_get),
@@ -77,6 +159,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 pub struct RandomProjects {
     #[validate(range(min = 1, max = 100))]
     pub count: u32,
+    /// Makes the pick deterministic, so the same seed (eg: a date) always returns the same
+    /// projects
+    #[validate(length(min = 1, max = 64))]
+    pub seed: Option<String>,
 }
 
 pub async fn random_projects_get(
@@ -88,43 +174,156 @@ pub async fn random_projects_get(
         .validate()
         .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
 
-    let project_ids = sqlx::query!(
-        "
-            SELECT id FROM mods TABLESAMPLE SYSTEM_ROWS($1) WHERE status = ANY($2)
+    let statuses = crate::models::projects::ProjectStatus::iterator()
+        .filter(|x| x.is_searchable())
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    let project_ids = if let Some(seed) = &count.seed {
+        db_models::Project::get_seeded_random(seed, count.count as usize, &pool, &redis).await?
+    } else {
+        sqlx::query!(
+            "
+            SELECT id FROM mods TABLESAMPLE SYSTEM_ROWS($1) WHERE status = ANY($2) AND deleted_at IS NULL
             ",
-        count.count as i32,
-        &*crate::models::projects::ProjectStatus::iterator()
-            .filter(|x| x.is_searchable())
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>(),
-    )
-    .fetch_many(&**pool)
-    .try_filter_map(|e| async { Ok(e.right().map(|m| db_ids::ProjectId(m.id))) })
-    .try_collect::<Vec<_>>()
-    .await?;
+            count.count as i32,
+            &*statuses,
+        )
+        .fetch_many(&**pool)
+        .try_filter_map(|e| async { Ok(e.right().map(|m| db_ids::ProjectId(m.id))) })
+        .try_collect::<Vec<_>>()
+        .await?
+    };
 
     let projects_data = db_models::Project::get_many_ids(&project_ids, &**pool, &redis)
         .await?
         .into_iter()
+        // Seeded picks are cached, so they can include projects hidden or deleted since
+        .filter(|x| x.inner.status.is_searchable() && x.inner.deleted_at.is_none())
+        .sorted_by_key(|x| project_ids.iter().position(|id| *id == x.inner.id))
         .map(Project::from)
         .collect::<Vec<_>>();
 
     Ok(HttpResponse::Ok().json(projects_data))
 }
 
+#[derive(Deserialize, Validate)]
+pub struct UpdatedSinceQuery {
+    /// Unix timestamp, in seconds
+    pub timestamp: i64,
+    #[validate(range(min = 1, max = 1000))]
+    #[serde(default = "default_updated_since_limit")]
+    pub limit: u32,
+    /// The last project of the previous page
+    pub after: Option<ProjectId>,
+}
+
+fn default_updated_since_limit() -> u32 {
+    100
+}
+
 #[derive(Serialize, Deserialize)]
+pub struct UpdatedProject {
+    pub id: ProjectId,
+    pub slug: Option<String>,
+    pub updated: chrono::DateTime<Utc>,
+}
+
+// Lists the public projects updated after a timestamp, for clients syncing a local copy.
+// Pages are ordered by ID rather than update time, so updates made while a client is paging
+// through are not skipped.
+pub async fn projects_updated_since_get(
+    web::Query(query): web::Query<UpdatedSinceQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let projects = sqlx::query!(
+        "
+        SELECT id, slug, updated FROM mods
+        WHERE updated > to_timestamp($1) AND id > $2 AND status = ANY($3) AND deleted_at IS NULL
+        ORDER BY id
+        LIMIT $4
+        ",
+        query.timestamp as f64,
+        query
+            .after
+            .map(|x| db_ids::ProjectId::from(x).0)
+            .unwrap_or(i64::MIN),
+        &*crate::models::projects::ProjectStatus::iterator()
+            .filter(|x| x.is_searchable())
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>(),
+        query.limit as i64,
+    )
+    .fetch_all(&**pool)
+    .await?
+    .into_iter()
+    .map(|x| UpdatedProject {
+        id: db_ids::ProjectId(x.id).into(),
+        slug: x.slug,
+        updated: x.updated,
+    })
+    .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ProjectIds {
+    /// A JSON array of project IDs or slugs
     pub ids: String,
 }
 
+#[derive(Serialize, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectsGetQuery {
+    /// A JSON array of project IDs or slugs
+    pub ids: Option<String>,
+    /// A JSON array of project slugs
+    pub slugs: Option<String>,
+    /// If true, the request fails with a 404 if any of the projects are missing
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// The maximum amount of projects which can be requested at once
+const MAX_PROJECTS_PER_REQUEST: usize = 100;
+
+#[utoipa::path(
+    get,
+    path = "/v3/projects",
+    tag = "projects",
+    params(ProjectsGetQuery),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The requested projects, in the order they were requested", body = Vec<Project>),
+        (status = 404, description = "One of the projects was not found, and `strict` was set", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn projects_get(
     req: HttpRequest,
-    web::Query(ids): web::Query<ProjectIds>,
+    web::Query(query): web::Query<ProjectsGetQuery>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
-    let ids = serde_json::from_str::<Vec<&str>>(&ids.ids)?;
+    let mut ids = Vec::new();
+    if let Some(query_ids) = &query.ids {
+        ids.extend(serde_json::from_str::<Vec<String>>(query_ids)?);
+    }
+    if let Some(slugs) = &query.slugs {
+        ids.extend(serde_json::from_str::<Vec<String>>(slugs)?);
+    }
+
+    if ids.len() > MAX_PROJECTS_PER_REQUEST {
+        return Err(ApiError::InvalidInput(format!(
+            "At most {MAX_PROJECTS_PER_REQUEST} projects can be requested at once!"
+        )));
+    }
+
     let projects_data = db_models::Project::get_many(&ids, &**pool, &redis).await?;
 
     let user_option = get_user_from_headers(
@@ -140,9 +339,91 @@ pub async fn projects_get(
 
     let projects = filter_visible_projects(projects_data, &user_option, &pool, false).await?;
 
+    let matches = |project: &Project, id: &str| {
+        parse_base62(id).ok() == Some(project.id.0)
+            || project
+                .slug
+                .as_ref()
+                .map(|slug| slug.eq_ignore_ascii_case(id))
+                .unwrap_or(false)
+    };
+
+    if query.strict && !ids.iter().all(|id| projects.iter().any(|x| matches(x, id))) {
+        return Err(ApiError::NotFound);
+    }
+
+    // Return the projects in the order they were requested in, by ID or slug
+    let projects = projects
+        .into_iter()
+        .sorted_by_key(|x| {
+            ids.iter()
+                .position(|id| matches(x, id))
+                .unwrap_or(usize::MAX)
+        })
+        .collect::<Vec<_>>();
+
     Ok(HttpResponse::Ok().json(projects))
 }
 
+#[derive(Deserialize, Validate)]
+pub struct FeaturedProjectsQuery {
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+}
+
+/// Returns the projects featured on the home page by moderators, in their featured order
+pub async fn projects_featured_get(
+    req: HttpRequest,
+    web::Query(query): web::Query<FeaturedProjectsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let ids = db_models::Project::get_featured_ids(&**pool, &redis).await?;
+    let projects = filter_visible_projects(
+        db_models::Project::get_many_ids(&ids, &**pool, &redis).await?,
+        &user_option,
+        &pool,
+        true,
+    )
+    .await?;
+
+    let featured = ids
+        .into_iter()
+        .filter_map(|id| projects.iter().find(|x| x.id == id.into()).cloned())
+        .take(query.limit.unwrap_or(8))
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(featured))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v3/project/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "The ID or slug of the project")),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The requested project", body = Project),
+        (status = 410, description = "The project has been deleted and will be permanently deleted soon"),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn project_get(
     req: HttpRequest,
     info: web::Path<(String,)>,
@@ -165,81 +446,1576 @@ pub async fn project_get(
     .ok();
 
     if let Some(data) = project_data {
+        if let Some(deleted_at) = data.inner.deleted_at {
+            // Deleted projects are shown as gone until they are permanently deleted, to those who
+            // could see them before
+            let mut undeleted = data.inner.clone();
+            undeleted.deleted_at = None;
+            if deleted_at
+                > Utc::now()
+                    - chrono::Duration::days(
+                        db_models::project_item::PROJECT_DELETION_DELAY_DAYS as i64,
+                    )
+                && is_visible_project(&undeleted, &user_option, &pool, false).await?
+            {
+                return Ok(HttpResponse::Gone().json(json!({
+                    "error": "gone",
+                    "description": "The specified project has been deleted.",
+                })));
+            }
+            return Err(ApiError::NotFound);
+        }
+
         if is_visible_project(&data.inner, &user_option, &pool, false).await? {
-            return Ok(HttpResponse::Ok().json(Project::from(data)));
+            let project = Project::from(data);
+
+            return Ok(if prefers_json_ld(&req) {
+                HttpResponse::Ok()
+                    .content_type(JSON_LD_CONTENT_TYPE)
+                    .insert_header((header::VARY, "Accept"))
+                    .json(project_json_ld(&project))
+            } else {
+                HttpResponse::Ok()
+                    .insert_header((header::VARY, "Accept"))
+                    .json(project)
+            });
         }
     }
     Err(ApiError::NotFound)
 }
 
-#[derive(Serialize, Deserialize, Validate)]
-pub struct EditProject {
-    #[validate(
-        length(min = 3, max = 64),
-        custom(function = "crate::util::validate::validate_name")
-    )]
-    pub name: Option<String>,
-    #[validate(length(min = 3, max = 256))]
-    pub summary: Option<String>,
-    #[validate(length(max = 65536))]
-    pub description: Option<String>,
-    #[validate(length(max = 3))]
-    pub categories: Option<Vec<String>>,
-    #[validate(length(max = 256))]
-    pub additional_categories: Option<Vec<String>>,
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "::serde_with::rust::double_option"
-    )]
-    #[validate(
-        custom(function = "crate::util::validate::validate_url"),
-        length(max = 2048)
-    )]
-    pub license_url: Option<Option<String>>,
-    #[validate(custom(function = "crate::util::validate::validate_url_hashmap_optional_values"))]
-    // <name, url> (leave url empty to delete)
-    pub link_urls: Option<HashMap<String, Option<String>>>,
-    pub license_id: Option<String>,
-    #[validate(
-        length(min = 3, max = 64),
-        regex = "crate::util::validate::RE_URL_SAFE"
-    )]
-    pub slug: Option<String>,
-    pub status: Option<ProjectStatus>,
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "::serde_with::rust::double_option"
-    )]
-    pub requested_status: Option<Option<ProjectStatus>>,
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "::serde_with::rust::double_option"
-    )]
-    #[validate(length(max = 2000))]
-    pub moderation_message: Option<Option<String>>,
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "::serde_with::rust::double_option"
-    )]
-    #[validate(length(max = 65536))]
-    pub moderation_message_body: Option<Option<String>>,
-    pub monetization_status: Option<MonetizationStatus>,
+const JSON_LD_CONTENT_TYPE: &str = "application/ld+json";
+
+/// Whether the `Accept` header ranks JSON-LD above plain JSON
+fn prefers_json_ld(req: &HttpRequest) -> bool {
+    let Ok(accept) = header::Accept::parse(req) else {
+        return false;
+    };
+
+    accept
+        .ranked()
+        .into_iter()
+        .find(|x| {
+            x.essence_str() == JSON_LD_CONTENT_TYPE
+                || x.essence_str() == "application/json"
+                || x.essence_str() == "application/*"
+                || x.essence_str() == "*/*"
+        })
+        .is_some_and(|x| x.essence_str() == JSON_LD_CONTENT_TYPE)
+}
+
+/// Maps a project to a schema.org `SoftwareApplication`, for crawlers
+fn project_json_ld(project: &Project) -> serde_json::Value {
+    let url = format!(
+        "{}/project/{}",
+        dotenvy::var("SITE_URL").unwrap_or_default(),
+        project
+            .slug
+            .clone()
+            .unwrap_or_else(|| project.id.to_string())
+    );
+
+    json!({
+        "@context": "https://schema.org",
+        "@type": "SoftwareApplication",
+        "@id": url,
+        "url": url,
+        "identifier": project.id,
+        "name": project.name,
+        "description": project.summary,
+        "image": project.icon_url,
+        "applicationCategory": "GameApplication",
+        "keywords": project
+            .categories
+            .iter()
+            .chain(project.additional_categories.iter())
+            .join(", "),
+        "license": project.license.url.clone().unwrap_or_else(|| project.license.id.clone()),
+        "datePublished": project.published,
+        "dateModified": project.updated,
+        "screenshot": project.gallery.iter().map(|x| &x.url).collect::<Vec<_>>(),
+        "sameAs": project.link_urls.values().map(|x| &x.url).collect::<Vec<_>>(),
+        "interactionStatistic": {
+            "@type": "InteractionCounter",
+            "interactionType": "https://schema.org/DownloadAction",
+            "userInteractionCount": project.downloads,
+        },
+    })
+}
+
+/// How long a rendered embed is cached, in seconds
+const EMBED_CACHE_EXPIRY: i64 = 10 * 60;
+
+/// Renders the OpenGraph tags used by link previews (ex: Discord, Twitter).
+///
+/// Embeds are rendered for anonymous viewers, so projects that are not publicly visible yet get a
+/// generic "pending review" embed instead of a 404.
+pub async fn project_embed_get(
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let id = project.inner.id;
+
+    let mut redis_conn = redis.connect().await?;
+    let cached = redis_conn
+        .get(
+            db_models::project_item::PROJECTS_EMBEDS_NAMESPACE,
+            &id.0.to_string(),
+        )
+        .await?;
+
+    let html = if let Some(html) = cached {
+        html
+    } else {
+        let html = if is_visible_project(&project.inner, &None, &pool, false).await? {
+            let url = format!(
+                "{}/project/{}",
+                dotenvy::var("SITE_URL").unwrap_or_default(),
+                project
+                    .inner
+                    .slug
+                    .clone()
+                    .unwrap_or_else(|| ProjectId::from(id).to_string())
+            );
+
+            render_embed(
+                &project.inner.name,
+                &format!(
+                    "{} - {} downloads",
+                    project.inner.summary, project.inner.downloads
+                ),
+                project.inner.icon_url.as_deref(),
+                Some(&url),
+            )
+        } else {
+            render_embed(
+                "Pending review",
+                "This project is awaiting review.",
+                None,
+                None,
+            )
+        };
+
+        redis_conn
+            .set(
+                db_models::project_item::PROJECTS_EMBEDS_NAMESPACE,
+                &id.0.to_string(),
+                &html,
+                Some(EMBED_CACHE_EXPIRY),
+            )
+            .await?;
+        html
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((
+            "Content-Security-Policy",
+            crate::util::headers::HTML_CONTENT_SECURITY_POLICY,
+        ))
+        .body(html))
+}
+
+fn render_embed(title: &str, description: &str, image: Option<&str>, url: Option<&str>) -> String {
+    let escape = |x: &str| {
+        x.replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+
+    let mut tags = vec![
+        format!(r#"<meta property="og:title" content="{}">"#, escape(title)),
+        format!(
+            r#"<meta property="og:description" content="{}">"#,
+            escape(description)
+        ),
+        r#"<meta property="og:type" content="website">"#.to_string(),
+        r#"<meta property="og:site_name" content="Modrinth">"#.to_string(),
+        r#"<meta name="twitter:card" content="summary">"#.to_string(),
+    ];
+    if let Some(image) = image {
+        tags.push(format!(
+            r#"<meta property="og:image" content="{}">"#,
+            escape(image)
+        ));
+    }
+    if let Some(url) = url {
+        tags.push(format!(
+            r#"<meta property="og:url" content="{}">"#,
+            escape(url)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n{}\n</head>\n<body></body>\n</html>\n",
+        escape(title),
+        tags.join("\n")
+    )
+}
+
+/// How long a rendered badge is cached, in seconds
+const BADGE_CACHE_EXPIRY: i64 = 30;
+
+#[derive(Deserialize, Validate)]
+pub struct BadgeQuery {
+    #[serde(default)]
+    pub style: BadgeStyle,
+    #[validate(length(min = 1, max = 32))]
+    pub label: Option<String>,
+    /// A named color (ex: `brightgreen`) or a hex color without the `#`
+    pub color: Option<String>,
+}
+
+/// Renders an SVG badge showing the download count of a publicly visible project
+pub async fn project_badge_get(
+    info: web::Path<(String,)>,
+    web::Query(query): web::Query<BadgeQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+    let string = info.into_inner().0;
+
+    let color = match &query.color {
+        Some(color) => badge::parse_color(color)
+            .ok_or_else(|| ApiError::InvalidInput(format!("Unknown badge color {color}!")))?,
+        None => "#4c1".to_string(),
+    };
+    let label = query.label.as_deref().unwrap_or("downloads");
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&project.inner, &None, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let key = format!(
+        "{}:{}:{}:{}",
+        project.inner.id.0,
+        query.style.as_str(),
+        color,
+        label
+    );
+    let mut redis_conn = redis.connect().await?;
+    let svg = if let Some(svg) = redis_conn
+        .get(db_models::project_item::PROJECTS_BADGES_NAMESPACE, &key)
+        .await?
+    {
+        svg
+    } else {
+        let svg = badge::render_badge(
+            query.style,
+            label,
+            &badge::format_count(project.inner.downloads.max(0) as u64),
+            &color,
+        );
+        redis_conn
+            .set(
+                db_models::project_item::PROJECTS_BADGES_NAMESPACE,
+                &key,
+                &svg,
+                Some(BADGE_CACHE_EXPIRY),
+            )
+            .await?;
+        svg
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .insert_header((header::CACHE_CONTROL, "max-age=30"))
+        .body(svg))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectVersionCounts {
+    pub loaders: HashMap<String, LoaderVersionCounts>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoaderVersionCounts {
+    /// The amount of listed versions for each game version
+    pub game_versions: HashMap<String, i64>,
+}
+
+pub async fn project_version_counts_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let counts = db_models::Project::get_version_counts(project.inner.id, &**pool, &redis).await?;
+
+    Ok(HttpResponse::Ok().json(ProjectVersionCounts {
+        loaders: counts
+            .into_iter()
+            .map(|(loader, game_versions)| (loader, LoaderVersionCounts { game_versions }))
+            .collect(),
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectVersionMatrix {
+    pub loaders: Vec<String>,
+    pub game_versions: Vec<String>,
+    /// The latest listed version supporting `loaders[i]` and `game_versions[j]` is at
+    /// `matrix[i][j]`, or null if there is none
+    pub matrix: Vec<Vec<Option<models::ids::VersionId>>>,
+}
+
+/// Gets the latest listed version of a project for each combination of loader and game version,
+/// as a grid launchers can display
+pub async fn project_version_matrix_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let matrix = db_models::Project::get_version_matrix(project.inner.id, &pool, &redis).await?;
+
+    Ok(HttpResponse::Ok().json(ProjectVersionMatrix {
+        loaders: matrix.loaders,
+        game_versions: matrix.game_versions,
+        matrix: matrix
+            .matrix
+            .into_iter()
+            .map(|row| row.into_iter().map(|x| x.map(Into::into)).collect())
+            .collect(),
+    }))
+}
+
+/// Gets the latest listed version of a project for each loader it supports, keyed by loader.
+/// Releases are preferred, then betas, then alphas.
+pub async fn project_latest_versions_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let latest =
+        db_models::Project::get_latest_versions_per_loader(project.inner.id, &pool, &redis).await?;
+    let versions = db_models::Version::get_many(
+        &latest.values().copied().unique().collect_vec(),
+        &**pool,
+        &redis,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(
+        latest
+            .into_iter()
+            .filter_map(|(loader, version_id)| {
+                versions
+                    .iter()
+                    .find(|x| x.inner.id == version_id)
+                    .map(|x| (loader, models::projects::Version::from(x.clone())))
+            })
+            .collect::<HashMap<_, _>>(),
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectSupportedGameVersions {
+    /// Newest first
+    pub game_versions: Vec<String>,
+}
+
+/// Gets the game versions supported by any listed version of a project
+pub async fn project_supported_game_versions_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let game_versions =
+        db_models::Project::get_supported_game_versions(project.inner.id, &pool, &redis).await?;
+
+    Ok(HttpResponse::Ok().json(ProjectSupportedGameVersions { game_versions }))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RelatedProjectsQuery {
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<u32>,
+}
+
+/// Gets the projects sharing at least two categories and a project type with a project, most
+/// downloaded first. Projects the user follows are left out.
+pub async fn project_related_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Query(query): web::Query<RelatedProjectsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+    let string = info.into_inner().0;
+
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let mut related = db_models::Project::get_related(project.inner.id, &pool, &redis).await?;
+
+    if let Some(user) = &user_option {
+        let user_id: db_ids::UserId = user.id.into();
+        let followed = sqlx::query!(
+            "
+            SELECT mod_id FROM mod_follows
+            WHERE follower_id = $1 AND mod_id = ANY($2)
+            ",
+            user_id as db_ids::UserId,
+            &related.iter().map(|x| x.0).collect::<Vec<_>>(),
+        )
+        .fetch_all(&**pool)
+        .await?
+        .into_iter()
+        .map(|x| db_ids::ProjectId(x.mod_id))
+        .collect::<Vec<_>>();
+        related.retain(|x| !followed.contains(x));
+    }
+
+    let projects = filter_visible_projects(
+        db_models::Project::get_many_ids(&related, &**pool, &redis).await?,
+        &user_option,
+        &pool,
+        true,
+    )
+    .await?;
+    let projects = related
+        .into_iter()
+        .filter_map(|id| projects.iter().find(|x| x.id == id.into()).cloned())
+        .take(query.limit.unwrap_or(10) as usize)
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ProjectLicense {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v3/project/{id}/license",
+    tag = "projects",
+    params(("id" = String, Path, description = "The ID or slug of the project")),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The license of the project", body = ProjectLicense),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
+pub async fn project_license_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let project_data = db_models::Project::get(&string, &**pool, &redis).await?;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let Some(data) = project_data else {
+        return Err(ApiError::NotFound);
+    };
+    if !is_visible_project(&data.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let license_id = data.inner.license;
+    let url = data.inner.license_url;
+
+    // Custom licenses only have a text if the project uploaded one
+    if license_id.starts_with("LicenseRef-") {
+        let text = sqlx::query!(
+            "
+            SELECT license_text FROM project_custom_licenses
+            WHERE project_id = $1
+            ",
+            data.inner.id as db_ids::ProjectId,
+        )
+        .fetch_optional(&**pool)
+        .await?
+        .map(|x| x.license_text);
+
+        return Ok(HttpResponse::Ok().json(ProjectLicense {
+            id: license_id,
+            name: None,
+            url,
+            text,
+        }));
+    }
+
+    // The SPDX license texts are bundled into the binary, so no lookup is needed here
+    if let Some(license) = spdx::license_id(&license_id) {
+        return Ok(HttpResponse::Ok().json(ProjectLicense {
+            name: Some(license.full_name.to_string()),
+            text: Some(license.text().to_string()),
+            id: license_id,
+            url,
+        }));
+    }
+
+    Err(ApiError::NotFound)
+}
+
+/// The license ID used for projects with an uploaded license text
+pub const CUSTOM_LICENSE_ID: &str = "LicenseRef-Custom";
+const MAX_CUSTOM_LICENSE_LENGTH: usize = 20000;
+
+/// Gets a project for editing, if the user has the given permission for it
+async fn get_project_for_edit(
+    req: &HttpRequest,
+    string: &str,
+    pool: &PgPool,
+    redis: &RedisPool,
+    session_queue: &AuthQueue,
+    permission: ProjectPermissions,
+    permission_error: &str,
+) -> Result<(models::users::User, db_models::project_item::QueryProject), ApiError> {
+    let user = get_user_from_headers(
+        req,
+        pool,
+        redis,
+        session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?
+    .1;
+
+    let project_item = db_models::Project::get(string, pool, redis)
+        .await?
+        // Deleted projects can no longer be changed
+        .filter(|x| x.inner.deleted_at.is_none())
+        .ok_or_else(|| {
+            ApiError::InvalidInput("The specified project does not exist!".to_string())
+        })?;
+
+    if !user.role.is_mod() {
+        let (team_member, organization_team_member) =
+            db_models::TeamMember::get_for_project_permissions(
+                &project_item.inner,
+                user.id.into(),
+                pool,
+            )
+            .await?;
+
+        // Hide the project
+        if team_member.is_none() && organization_team_member.is_none() {
+            return Err(ApiError::CustomAuthentication(
+                "The specified project does not exist!".to_string(),
+            ));
+        }
+
+        let permissions = ProjectPermissions::get_permissions_by_role(
+            &user.role,
+            &team_member,
+            &organization_team_member,
+        )
+        .unwrap_or_default();
+
+        if !permissions.contains(permission) {
+            return Err(ApiError::CustomAuthentication(permission_error.to_string()));
+        }
+    }
+
+    Ok((user, project_item))
+}
+
+pub async fn project_custom_license_upload(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    mut payload: web::Payload,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (user, project_item) = get_project_for_edit(
+        &req,
+        &info.into_inner().0,
+        &pool,
+        &redis,
+        &session_queue,
+        ProjectPermissions::EDIT_DETAILS,
+        "You do not have the permissions to edit the license of this project!",
+    )
+    .await?;
+
+    // A character is at most four bytes in UTF-8
+    let bytes = read_from_payload(
+        &mut payload,
+        MAX_CUSTOM_LICENSE_LENGTH * 4,
+        "License texts must be at most 20000 characters long",
+    )
+    .await?;
+    let text = String::from_utf8(bytes.to_vec())
+        .map_err(|_| ApiError::InvalidInput("License texts must be valid UTF-8".to_string()))?;
+    if text.trim().is_empty() || text.chars().count() > MAX_CUSTOM_LICENSE_LENGTH {
+        return Err(ApiError::InvalidInput(
+            "License texts must be between 1 and 20000 characters long".to_string(),
+        ));
+    }
+
+    let id = project_item.inner.id;
+    let mut transaction = pool.begin().await?;
+    if let Some(conflict) = bump_project_version(id, &edit_query, &mut transaction).await? {
+        return Ok(conflict);
+    }
+
+    sqlx::query!(
+        "
+        INSERT INTO project_custom_licenses (project_id, license_text, previous_license)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (project_id) DO UPDATE
+        SET license_text = EXCLUDED.license_text, updated = NOW(),
+        previous_license = CASE WHEN $3 LIKE 'LicenseRef-%'
+            THEN project_custom_licenses.previous_license
+            ELSE EXCLUDED.previous_license END
+        ",
+        id as db_ids::ProjectId,
+        text,
+        project_item.inner.license,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    if !project_item.inner.license.starts_with("LicenseRef-") {
+        sqlx::query!(
+            "
+            UPDATE mods
+            SET license = $1
+            WHERE (id = $2)
+            ",
+            CUSTOM_LICENSE_ID,
+            id as db_ids::ProjectId,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        ProjectAuditLogBuilder {
+            project_id: id,
+            user_id: user.id.into(),
+            changed_field: "license_id",
+            old_value: Some(project_item.inner.license.clone()),
+            new_value: Some(CUSTOM_LICENSE_ID.to_string()),
+        }
+        .insert(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    db_models::project_item::clear_project_caches(id, project_item.inner.slug, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+pub async fn project_custom_license_delete(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (user, project_item) = get_project_for_edit(
+        &req,
+        &info.into_inner().0,
+        &pool,
+        &redis,
+        &session_queue,
+        ProjectPermissions::EDIT_DETAILS,
+        "You do not have the permissions to edit the license of this project!",
+    )
+    .await?;
+
+    let id = project_item.inner.id;
+    let mut transaction = pool.begin().await?;
+    if let Some(conflict) = bump_project_version(id, &edit_query, &mut transaction).await? {
+        return Ok(conflict);
+    }
+
+    let previous_license = sqlx::query!(
+        "
+        DELETE FROM project_custom_licenses
+        WHERE project_id = $1
+        RETURNING previous_license
+        ",
+        id as db_ids::ProjectId,
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(ApiError::NotFound)?
+    .previous_license;
+
+    if previous_license != project_item.inner.license {
+        sqlx::query!(
+            "
+            UPDATE mods
+            SET license = $1
+            WHERE (id = $2)
+            ",
+            previous_license,
+            id as db_ids::ProjectId,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        ProjectAuditLogBuilder {
+            project_id: id,
+            user_id: user.id.into(),
+            changed_field: "license_id",
+            old_value: Some(project_item.inner.license.clone()),
+            new_value: Some(previous_license),
+        }
+        .insert(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    db_models::project_item::clear_project_caches(id, project_item.inner.slug, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// The maximum length of a project body, matching the limit of `PATCH /project/{id}`
+const MAX_BODY_LENGTH: usize = 65535;
+
+/// Computes the revision of a project body that edits must be based on
+pub fn body_revision(body: &str) -> String {
+    blake3::hash(body.as_bytes()).to_hex().to_string()
+}
+
+#[derive(Deserialize)]
+pub struct EditProjectStatus {
+    pub status: String,
+    /// Why the status was set, shown to the project's members. Required for rejections.
+    pub message: Option<String>,
+}
+
+/// Sets the status of a project as a moderator, recording the message that explains it.
+///
+/// Rejected and withheld projects need a message. It is also stored as the project's
+/// moderation message, and the project's members are notified of the change.
+#[allow(clippy::too_many_arguments)]
+pub async fn project_status_edit(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    web::Json(data): web::Json<EditProjectStatus>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    moderation_queue: web::Data<AutomatedModerationQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?;
+
+    let status = ProjectStatus::from_string(&data.status);
+    if status == ProjectStatus::Unknown {
+        return Err(ApiError::InvalidInput(format!(
+            "Unknown project status `{}`",
+            data.status
+        )));
+    }
+
+    let message = data
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(str::to_string);
+    if message.is_none() && matches!(status, ProjectStatus::Rejected | ProjectStatus::Withheld) {
+        return Ok(HttpResponse::UnprocessableEntity().json(json!({
+            "error": "invalid_input",
+            "description": "A message explaining the decision is required when rejecting or withholding a project.",
+        })));
+    }
+    if let Some(message) = &message {
+        validate_string_length("message", message, 1, 2000)?;
+    }
+
+    let project = db_models::Project::get(&info.0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    project_edit_with_status_message(
+        req,
+        info,
+        web::Query(edit_query),
+        pool,
+        search_config,
+        web::Json(EditProject {
+            status: Some(status),
+            moderation_message: Some(message.clone()),
+            ..Default::default()
+        }),
+        redis,
+        session_queue,
+        moderation_queue,
+        Some(ProjectStatusMessageBuilder {
+            project_id: project.inner.id,
+            moderator_id: user.id.into(),
+            status,
+            message,
+        }),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditProjectVisibility {
+    pub public: bool,
+}
+
+/// Switches an approved project between public (`approved`) and `private`.
+///
+/// The status change goes through [`project_edit`], so it is permission checked and audit logged
+/// the same way.
+#[allow(clippy::too_many_arguments)]
+pub async fn project_visibility_edit(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    web::Json(visibility): web::Json<EditProjectVisibility>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    moderation_queue: web::Data<AutomatedModerationQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?
+    .1;
+
+    let project = db_models::Project::get(&info.0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&project.inner, &Some(user), &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    if !project.inner.status.is_approved() {
+        return Err(ApiError::InvalidInput(
+            "Only projects approved by moderators can change their visibility".to_string(),
+        ));
+    }
+
+    if visibility.public {
+        let versions = db_models::Version::get_many(&project.versions, &**pool, &redis).await?;
+        if !versions
+            .iter()
+            .any(|x| x.inner.status == VersionStatus::Listed)
+        {
+            return Ok(HttpResponse::UnprocessableEntity().json(json!({
+                "error": "invalid_input",
+                "description": "A project needs at least one listed version to be made public. Publish a version first, then try again.",
+            })));
+        }
+    }
+
+    let status = if visibility.public {
+        ProjectStatus::Approved
+    } else {
+        ProjectStatus::Private
+    };
+    if project.inner.status == status {
+        return Ok(HttpResponse::NoContent().body(""));
+    }
+
+    let response = project_edit(
+        req,
+        info,
+        web::Query(edit_query),
+        pool.clone(),
+        search_config.clone(),
+        web::Json(EditProject {
+            status: Some(status),
+            ..Default::default()
+        }),
+        redis.clone(),
+        session_queue,
+        moderation_queue,
+    )
+    .await?;
+    // Conflicts with the expected version are sent back as they are
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    // Projects leaving the public index are removed from it by the edit
+    if status.is_searchable() {
+        crate::search::indexing::retry::index_projects_by_id_or_retry(
+            &pool,
+            &redis,
+            &search_config,
+            &[project.inner.id],
+        )
+        .await?;
+    }
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// The most additional categories a project can have
+const MAX_ADDITIONAL_CATEGORIES: usize = 3;
+
+/// Adds and removes additional categories of a project, leaving its primary categories untouched.
+///
+/// The change goes through [`project_edit`], so it is permission checked the same way.
+#[allow(clippy::too_many_arguments)]
+pub async fn project_additional_categories_edit(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    web::Json(edit): web::Json<super::versions::EditVersionSet>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    moderation_queue: web::Data<AutomatedModerationQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (_, project) = get_project_for_edit(
+        &req,
+        &info.0,
+        &pool,
+        &redis,
+        &session_queue,
+        ProjectPermissions::EDIT_DETAILS,
+        "You do not have the permissions to edit the additional categories of this project!",
+    )
+    .await?;
+
+    let all_categories = db_models::categories::Category::list(&**pool, &redis).await?;
+    if let Some(category) = edit
+        .add
+        .iter()
+        .find(|x| !all_categories.iter().any(|c| &c.category == *x))
+    {
+        return Err(ApiError::InvalidInput(format!(
+            "Category {category} does not exist."
+        )));
+    }
+
+    if let Some(category) = edit.add.iter().find(|x| project.categories.contains(x)) {
+        return Ok(HttpResponse::UnprocessableEntity().json(json!({
+            "error": "invalid_input",
+            "description": format!("Category {category} is already a primary category of this project"),
+        })));
+    }
+
+    let additional_categories = edit.apply(project.additional_categories.clone());
+    if additional_categories.len() > MAX_ADDITIONAL_CATEGORIES {
+        return Ok(HttpResponse::UnprocessableEntity().json(json!({
+            "error": "invalid_input",
+            "description": format!("A project can have at most {MAX_ADDITIONAL_CATEGORIES} additional categories"),
+        })));
+    }
+
+    if additional_categories == project.additional_categories {
+        return Ok(HttpResponse::NoContent().body(""));
+    }
+
+    let response = project_edit(
+        req,
+        info,
+        web::Query(edit_query),
+        pool.clone(),
+        search_config.clone(),
+        web::Json(EditProject {
+            additional_categories: Some(additional_categories),
+            ..Default::default()
+        }),
+        redis.clone(),
+        session_queue,
+        moderation_queue,
+    )
+    .await?;
+    // Conflicts with the expected version are sent back as they are
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &search_config,
+        &[project.inner.id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// A partial update of the links to a project's resources outside of the site. A link set to
+/// `null` is removed, and links that are absent are left untouched.
+#[derive(Serialize, Deserialize, Validate, Default)]
+pub struct EditExternalLinks {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_url"),
+        length(max = 512)
+    )]
+    pub source_url: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_url"),
+        length(max = 512)
+    )]
+    pub wiki_url: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_issue_tracker_url"),
+        length(max = 512)
+    )]
+    pub issues_url: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_url"),
+        length(max = 512)
+    )]
+    pub discord_url: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_url"),
+        length(max = 512)
+    )]
+    pub forum_url: Option<Option<String>>,
+}
+
+/// Sets or removes the source, wiki, issue tracker, Discord and forum links of a project. They
+/// are stored as the link URLs of the matching platforms, so they are returned in `link_urls`.
+///
+/// The change goes through [`project_edit`], so it is permission checked the same way.
+#[allow(clippy::too_many_arguments)]
+pub async fn project_external_links_edit(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    web::Json(edit): web::Json<EditExternalLinks>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    moderation_queue: web::Data<AutomatedModerationQueue>,
+) -> Result<HttpResponse, ApiError> {
+    edit.validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let (_, project) = get_project_for_edit(
+        &req,
+        &info.0,
+        &pool,
+        &redis,
+        &session_queue,
+        ProjectPermissions::EDIT_DETAILS,
+        "You do not have the permissions to edit the links of this project!",
+    )
+    .await?;
+
+    let link_urls = vec![
+        ("source", edit.source_url),
+        ("wiki", edit.wiki_url),
+        ("issues", edit.issues_url),
+        ("discord", edit.discord_url),
+        ("forum", edit.forum_url),
+    ]
+    .into_iter()
+    .filter_map(|(platform, url)| Some((platform.to_string(), url?)))
+    .collect::<HashMap<_, _>>();
+
+    if link_urls.is_empty() {
+        return Ok(HttpResponse::NoContent().body(""));
+    }
+
+    let response = project_edit(
+        req,
+        info,
+        web::Query(edit_query),
+        pool.clone(),
+        search_config.clone(),
+        web::Json(EditProject {
+            link_urls: Some(link_urls),
+            ..Default::default()
+        }),
+        redis.clone(),
+        session_queue,
+        moderation_queue,
+    )
+    .await?;
+    // Conflicts with the expected version are sent back as they are
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    // Whether the project has a wiki or an issue tracker can be filtered on in search
+    if project.inner.status.is_searchable() {
+        crate::search::indexing::retry::index_projects_by_id_or_retry(
+            &pool,
+            &redis,
+            &search_config,
+            &[project.inner.id],
+        )
+        .await?;
+    }
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditProjectBody {
+    /// The new body, replacing the current one
+    pub body: Option<String>,
+    /// A unified diff to apply to the current body
+    pub patch: Option<String>,
+    /// The revision of the body the edit was made against
+    pub base_rev: Option<String>,
+}
+
+pub async fn project_body_edit(
+    req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
+    info: web::Path<(String,)>,
+    edit: web::Json<EditProjectBody>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (_, project_item) = get_project_for_edit(
+        &req,
+        &info.into_inner().0,
+        &pool,
+        &redis,
+        &session_queue,
+        ProjectPermissions::EDIT_BODY,
+        "You do not have the permissions to edit the description (body) of this project!",
+    )
+    .await?;
+    let edit = edit.into_inner();
+
+    let id = project_item.inner.id;
+    let mut transaction = pool.begin().await?;
+    if let Some(conflict) = bump_project_version(id, &edit_query, &mut transaction).await? {
+        return Ok(conflict);
+    }
+
+    // Lock the row so concurrent edits are serialized against the revision check
+    let current = sqlx::query!(
+        "
+        SELECT description FROM mods
+        WHERE id = $1
+        FOR UPDATE
+        ",
+        id as db_ids::ProjectId,
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .description;
+    let current_rev = body_revision(&current);
+
+    if edit.base_rev.as_ref().is_some_and(|x| *x != current_rev) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": "conflict",
+            "description": "The body was edited since the given base revision",
+            "base_rev": current_rev,
+        })));
+    }
+
+    let body = match (edit.body, edit.patch) {
+        (Some(body), None) => body,
+        (None, Some(patch)) => {
+            if edit.base_rev.is_none() {
+                return Err(ApiError::InvalidInput(
+                    "A base revision is required to apply a patch!".to_string(),
+                ));
+            }
+
+            let patch = diffy::Patch::from_str(&patch)
+                .map_err(|err| ApiError::InvalidInput(format!("Invalid patch: {err}")))?;
+            diffy::apply(&current, &patch).map_err(|err| {
+                ApiError::InvalidInput(format!("The patch does not apply to the body: {err}"))
+            })?
+        }
+        _ => {
+            return Err(ApiError::InvalidInput(
+                "Exactly one of `body` or `patch` must be specified!".to_string(),
+            ))
+        }
+    };
+
+    validate_string_length("body", &body, 0, MAX_BODY_LENGTH)?;
+
+    sqlx::query!(
+        "
+        UPDATE mods
+        SET description = $1
+        WHERE (id = $2)
+        ",
+        body,
+        id as db_ids::ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    let context = ImageContext::Project {
+        project_id: Some(id.into()),
+    };
+    img::delete_unused_images(context, vec![&body], &mut transaction, &redis).await?;
+
+    transaction.commit().await?;
+    db_models::project_item::clear_project_caches(id, project_item.inner.slug, &redis).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "base_rev": body_revision(&body),
+    })))
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ProjectEditQuery {
+    /// The version of the project the edit was based on.
+    /// If the project has been edited since, the edit is rejected with a 409.
+    pub expected_version: Option<i32>,
+}
+
+/// Bumps the version of a project that is being changed, which also locks its row for the rest of
+/// the transaction. Returns the response to send instead if the project is not at the expected
+/// version.
+async fn bump_project_version(
+    id: db_ids::ProjectId,
+    edit_query: &ProjectEditQuery,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<Option<HttpResponse>, ApiError> {
+    let rows = sqlx::query!(
+        "
+        UPDATE mods
+        SET version = version + 1
+        WHERE id = $1 AND ($2::integer IS NULL OR version = $2)
+        ",
+        id as db_ids::ProjectId,
+        edit_query.expected_version,
+    )
+    .execute(&mut **transaction)
+    .await?;
+    if rows.rows_affected() > 0 {
+        return Ok(None);
+    }
+
+    let current = sqlx::query!(
+        "SELECT version FROM mods WHERE id = $1",
+        id as db_ids::ProjectId,
+    )
+    .fetch_one(&mut **transaction)
+    .await?
+    .version;
+
+    Ok(Some(HttpResponse::Conflict().json(json!({
+        "error": "conflict",
+        "description": "The project was edited since the expected version",
+        "version": current,
+    }))))
+}
+
+#[derive(Serialize, Deserialize, Validate, Default)]
+pub struct EditProject {
+    #[validate(custom(function = "crate::util::validate::validate_name"))]
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    #[validate(length(max = 3))]
+    pub categories: Option<Vec<String>>,
+    #[validate(length(max = 256))]
+    pub additional_categories: Option<Vec<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(
+        custom(function = "crate::util::validate::validate_url"),
+        length(max = 2048)
+    )]
+    pub license_url: Option<Option<String>>,
+    #[validate(custom(function = "crate::util::validate::validate_link_urls"))]
+    // <name, url> (leave url empty to delete)
+    pub link_urls: Option<HashMap<String, Option<String>>>,
+    pub license_id: Option<String>,
+    #[validate(
+        length(min = 3, max = 64),
+        regex = "crate::util::validate::RE_URL_SAFE"
+    )]
+    pub slug: Option<String>,
+    pub status: Option<ProjectStatus>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub requested_status: Option<Option<ProjectStatus>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(length(max = 2000))]
+    pub moderation_message: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    #[validate(length(max = 65536))]
+    pub moderation_message_body: Option<Option<String>>,
+    pub monetization_status: Option<MonetizationStatus>,
+    /// The fields to apply. Fields that are not listed are ignored, even if they are set, so
+    /// an edit based on a stale read only overwrites what it meant to change.
+    pub update_mask: Option<Vec<String>>,
+}
+
+impl EditProject {
+    /// Clears the fields that are not in the update mask, if there is one
+    fn apply_update_mask(&mut self) -> Result<(), ApiError> {
+        let Some(update_mask) = self.update_mask.take() else {
+            return Ok(());
+        };
+
+        let mut masked = EditProject::default();
+        for field in update_mask {
+            match &*field {
+                "name" => masked.name = self.name.take(),
+                "summary" => masked.summary = self.summary.take(),
+                "description" => masked.description = self.description.take(),
+                "categories" => masked.categories = self.categories.take(),
+                "additional_categories" => {
+                    masked.additional_categories = self.additional_categories.take()
+                }
+                "license_url" => masked.license_url = self.license_url.take(),
+                "link_urls" => masked.link_urls = self.link_urls.take(),
+                "license_id" => masked.license_id = self.license_id.take(),
+                "slug" => masked.slug = self.slug.take(),
+                "status" => masked.status = self.status.take(),
+                "requested_status" => masked.requested_status = self.requested_status.take(),
+                "moderation_message" => masked.moderation_message = self.moderation_message.take(),
+                "moderation_message_body" => {
+                    masked.moderation_message_body = self.moderation_message_body.take()
+                }
+                "monetization_status" => {
+                    masked.monetization_status = self.monetization_status.take()
+                }
+                _ => {
+                    return Err(ApiError::InvalidInput(format!(
+                        "Unknown field in update mask: {field}"
+                    )))
+                }
+            }
+        }
+
+        *self = masked;
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn project_edit(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    edit_query: web::Query<ProjectEditQuery>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    new_project: web::Json<EditProject>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    moderation_queue: web::Data<AutomatedModerationQueue>,
+) -> Result<HttpResponse, ApiError> {
+    project_edit_with_status_message(
+        req,
+        info,
+        edit_query,
+        pool,
+        search_config,
+        new_project,
+        redis,
+        session_queue,
+        moderation_queue,
+        None,
+    )
+    .await
 }
 
+/// Edits a project like [`project_edit`], inserting the moderator's status message in the same
+/// transaction if there is one
 #[allow(clippy::too_many_arguments)]
-pub async fn project_edit(
+async fn project_edit_with_status_message(
     req: HttpRequest,
     info: web::Path<(String,)>,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     pool: web::Data<PgPool>,
     search_config: web::Data<SearchConfig>,
-    new_project: web::Json<EditProject>,
+    mut new_project: web::Json<EditProject>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
     moderation_queue: web::Data<AutomatedModerationQueue>,
+    status_message: Option<ProjectStatusMessageBuilder>,
 ) -> Result<HttpResponse, ApiError> {
     let user = get_user_from_headers(
         &req,
@@ -251,12 +2027,24 @@ pub async fn project_edit(
     .await?
     .1;
 
+    new_project.apply_update_mask()?;
+    if let Some(name) = &new_project.name {
+        validate_string_length("name", name, 2, 64)?;
+    }
+    if let Some(summary) = &new_project.summary {
+        validate_string_length("summary", summary, 2, 256)?;
+    }
+    if let Some(description) = &new_project.description {
+        validate_string_length("description", description, 0, MAX_BODY_LENGTH)?;
+    }
     new_project
         .validate()
         .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
 
     let string = info.into_inner().0;
-    let result = db_models::Project::get(&string, &**pool, &redis).await?;
+    let result = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .filter(|x| x.inner.deleted_at.is_none());
     if let Some(project_item) = result {
         let id = project_item.inner.id;
 
@@ -276,6 +2064,11 @@ pub async fn project_edit(
 
         if let Some(perms) = permissions {
             let mut transaction = pool.begin().await?;
+            let mut audit_log = Vec::new();
+
+            if let Some(conflict) = bump_project_version(id, &edit_query, &mut transaction).await? {
+                return Ok(conflict);
+            }
 
             if let Some(name) = &new_project.name {
                 if !perms.contains(ProjectPermissions::EDIT_DETAILS) {
@@ -296,6 +2089,16 @@ pub async fn project_edit(
                 )
                 .execute(&mut *transaction)
                 .await?;
+
+                if project_item.inner.name != name.trim() {
+                    audit_log.push(ProjectAuditLogBuilder {
+                        project_id: id,
+                        user_id: user.id.into(),
+                        changed_field: "name",
+                        old_value: Some(project_item.inner.name.clone()),
+                        new_value: Some(name.trim().to_string()),
+                    });
+                }
             }
 
             if let Some(summary) = &new_project.summary {
@@ -470,16 +2273,48 @@ pub async fn project_edit(
                 .execute(&mut *transaction)
                 .await?;
 
+                if project_item.inner.status != *status {
+                    audit_log.push(ProjectAuditLogBuilder {
+                        project_id: id,
+                        user_id: user.id.into(),
+                        changed_field: "status",
+                        old_value: Some(project_item.inner.status.to_string()),
+                        new_value: Some(status.to_string()),
+                    });
+                }
+
+                // Moderation decisions change the trust score of the project's owner
+                if project_item.inner.status != *status
+                    && (status.is_approved() || status == &ProjectStatus::Rejected)
+                {
+                    let owners = sqlx::query!(
+                        "
+                        SELECT tm.user_id id
+                        FROM team_members tm
+                        WHERE tm.team_id = $1 AND tm.is_owner
+                        ",
+                        project_item.inner.team_id as db_ids::TeamId
+                    )
+                    .fetch_many(&mut *transaction)
+                    .try_filter_map(|e| async { Ok(e.right().map(|c| db_models::UserId(c.id))) })
+                    .try_collect::<Vec<_>>()
+                    .await?;
+
+                    db_models::User::update_trust_scores(Some(&owners), &mut *transaction).await?;
+                }
+
                 if project_item.inner.status.is_searchable() && !status.is_searchable() {
-                    remove_documents(
+                    remove_documents_or_retry(
+                        &pool,
+                        &search_config,
                         &project_item
                             .versions
                             .into_iter()
                             .map(|x| x.into())
                             .collect::<Vec<_>>(),
-                        &search_config,
                     )
                     .await?;
+                    crate::search::clear_search_cache(&redis).await?;
                 }
             }
 
@@ -638,6 +2473,17 @@ pub async fn project_edit(
                 )
                 .execute(&mut *transaction)
                 .await?;
+
+                let new_slug = slug.to_lowercase();
+                if project_item.inner.slug.as_deref() != Some(new_slug.as_str()) {
+                    audit_log.push(ProjectAuditLogBuilder {
+                        project_id: id,
+                        user_id: user.id.into(),
+                        changed_field: "slug",
+                        old_value: project_item.inner.slug.clone(),
+                        new_value: Some(new_slug),
+                    });
+                }
             }
 
             if let Some(license) = &new_project.license_id {
@@ -669,6 +2515,16 @@ pub async fn project_edit(
                 )
                 .execute(&mut *transaction)
                 .await?;
+
+                if project_item.inner.license != license {
+                    audit_log.push(ProjectAuditLogBuilder {
+                        project_id: id,
+                        user_id: user.id.into(),
+                        changed_field: "license_id",
+                        old_value: Some(project_item.inner.license.clone()),
+                        new_value: Some(license),
+                    });
+                }
             }
             if let Some(links) = &new_project.link_urls {
                 if !links.is_empty() {
@@ -838,11 +2694,17 @@ pub async fn project_edit(
 
             img::delete_unused_images(context, checkable_strings, &mut transaction, &redis).await?;
 
+            for entry in audit_log {
+                entry.insert(&mut transaction).await?;
+            }
+            if let Some(status_message) = status_message {
+                status_message.insert(&mut transaction).await?;
+            }
+
             transaction.commit().await?;
-            db_models::Project::clear_cache(
+            db_models::project_item::clear_project_caches(
                 project_item.inner.id,
                 project_item.inner.slug,
-                None,
                 &redis,
             )
             .await?;
@@ -852,91 +2714,655 @@ pub async fn project_edit(
             Err(ApiError::CustomAuthentication(
                 "You do not have permission to edit this project!".to_string(),
             ))
-        }
-    } else {
-        Err(ApiError::NotFound)
+        }
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+pub async fn edit_project_categories(
+    categories: &Vec<String>,
+    perms: &ProjectPermissions,
+    project_id: db_ids::ProjectId,
+    additional: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<(), ApiError> {
+    if !perms.contains(ProjectPermissions::EDIT_DETAILS) {
+        let additional_str = if additional { "additional " } else { "" };
+        return Err(ApiError::CustomAuthentication(format!(
+            "You do not have the permissions to edit the {additional_str}categories of this project!"
+        )));
+    }
+
+    let mut mod_categories = Vec::new();
+    for category in categories {
+        let category_ids =
+            db_models::categories::Category::get_ids(category, &mut **transaction).await?;
+        // TODO: We should filter out categories that don't match the project type of any of the versions
+        // ie: if mod and modpack both share a name this should only have modpack if it only has a modpack as a version
+
+        let mcategories = category_ids
+            .values()
+            .map(|x| ModCategory::new(project_id, *x, additional))
+            .collect::<Vec<_>>();
+        mod_categories.extend(mcategories);
+    }
+    ModCategory::insert_many(mod_categories, &mut *transaction).await?;
+
+    Ok(())
+}
+
+// TODO: Re-add this if we want to match v3 Projects structure to v3 Search Result structure, otherwise, delete
+// #[derive(Serialize, Deserialize)]
+// pub struct ReturnSearchResults {
+//     pub hits: Vec<Project>,
+//     pub page: usize,
+//     pub hits_per_page: usize,
+//     pub total_hits: usize,
+// }
+
+#[utoipa::path(
+    get,
+    path = "/v3/search",
+    tag = "projects",
+    params(
+        SearchRequest,
+        ("fields[]" = Option<Vec<String>>, Query, description = "Only return these fields of each hit"),
+        ("group_by_type" = Option<bool>, Query, description = "Search each project type separately, paginated with `{type}_offset` and `{type}_limit`"),
+    ),
+    responses(
+        (status = 200, description = "The search results", body = crate::search::SearchResults),
+        (status = 400, description = "The search request was invalid", body = crate::models::error::ApiError),
+    ),
+)]
+pub async fn project_search(
+    req: HttpRequest,
+    web::Query(info): web::Query<SearchRequest>,
+    config: web::Data<SearchConfig>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    analytics_queue: web::Data<Arc<AnalyticsQueue>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut info = info;
+    expand_search_game_versions(&mut info, &pool, &redis).await?;
+
+    if prefers_csv(&req) {
+        // Exports are authenticated so that they are rate limited per user
+        get_user_from_headers(
+            &req,
+            &**pool,
+            &redis,
+            &session_queue,
+            Some(&[Scopes::PROJECT_READ]),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+        return Ok(project_search_csv(info, &config, &redis).await?);
+    }
+
+    let start = std::time::Instant::now();
+    let params = url::form_urlencoded::parse(req.query_string().as_bytes())
+        .into_owned()
+        .collect_vec();
+
+    if params
+        .iter()
+        .any(|(key, value)| key == "group_by_type" && value == "true")
+    {
+        let results = search_grouped_by_type(&info, &params, &config, &pool, &redis).await?;
+        let total_hits = results.values().map(|x| x.total_hits).sum();
+        if let Some(log) = search_log(&info, total_hits, start.elapsed(), config.logging) {
+            analytics_queue.add_search_log(log);
+        }
+
+        return Ok(HttpResponse::Ok().json(results));
+    }
+
+    // Sparse field sets are given as repeated `fields[]` parameters, which `web::Query` can't parse
+    let fields = params
+        .iter()
+        .filter(|(key, _)| key == "fields[]")
+        .map(|(_, value)| value.clone())
+        .collect_vec();
+    if !fields.is_empty() {
+        let results = search_for_project_fields(&info, &fields, &config).await?;
+        if let Some(log) = search_log(&info, results.total_hits, start.elapsed(), config.logging) {
+            analytics_queue.add_search_log(log);
+        }
+
+        return Ok(HttpResponse::Ok().json(results));
+    }
+
+    let results = search_for_project_cached(&info, &config, &redis).await?;
+    if let Some(log) = search_log(&info, results.total_hits, start.elapsed(), config.logging) {
+        analytics_queue.add_search_log(log);
+    }
+
+    // TODO: add this back
+    // let results = ReturnSearchResults {
+    //     hits: results
+    //         .hits
+    //         .into_iter()
+    //         .filter_map(Project::from_search)
+    //         .collect::<Vec<_>>(),
+    //     page: results.page,
+    //     hits_per_page: results.hits_per_page,
+    //     total_hits: results.total_hits,
+    // };
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
+/// Searches each project type separately, with the same query and filters. The window of each
+/// type is given by its `{type}_offset` and `{type}_limit` parameters.
+async fn search_grouped_by_type(
+    info: &SearchRequest,
+    params: &[(String, String)],
+    config: &SearchConfig,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<HashMap<String, crate::search::SearchResults>, actix_web::Error> {
+    let param = |name: String| {
+        params
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let project_types = db_models::categories::ProjectType::list(pool, redis)
+        .await
+        .map_err(ApiError::from)?;
+    let requests = project_types
+        .iter()
+        .map(|project_type| {
+            search_request_for_project_type(
+                info,
+                project_type,
+                param(format!("{project_type}_offset")),
+                param(format!("{project_type}_limit")),
+            )
+        })
+        .collect_vec();
+
+    let results = futures::future::try_join_all(
+        requests
+            .iter()
+            .map(|request| search_for_project_cached(request, config, redis)),
+    )
+    .await?;
+
+    Ok(project_types.into_iter().zip(results).collect())
+}
+
+/// Expands game version ranges (ex: `game_versions:1.20.x`) in the facets of a search request
+pub async fn expand_search_game_versions(
+    info: &mut SearchRequest,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), ApiError> {
+    if let Some(facets) = &info.facets {
+        if facets.contains(MinecraftGameVersion::FIELD_NAME) {
+            let game_versions = MinecraftGameVersion::list(None, None, pool, redis).await?;
+            info.facets = Some(expand_game_version_facets(facets, &game_versions)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// The most results a CSV export of a search returns, regardless of the requested limit
+const MAX_SEARCH_CSV_RESULTS: usize = 1000;
+
+fn prefers_csv(req: &HttpRequest) -> bool {
+    let Ok(accept) = header::Accept::parse(req) else {
+        return false;
+    };
+
+    accept
+        .ranked()
+        .into_iter()
+        .find(|x| {
+            x.essence_str() == "text/csv"
+                || x.essence_str() == "application/json"
+                || x.essence_str() == "*/*"
+        })
+        .is_some_and(|x| x.essence_str() == "text/csv")
+}
+
+const SEARCH_CSV_HEADER: [&str; 10] = [
+    "project_id",
+    "slug",
+    "title",
+    "author",
+    "downloads",
+    "follows",
+    "created",
+    "updated",
+    "categories",
+    "license",
+];
+
+// Writes search hits as CSV rows, under the header if `header` is set
+fn search_csv_rows(
+    hits: &[crate::search::ResultSearchProject],
+    header: bool,
+) -> Result<web::Bytes, SearchError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if header {
+        writer.write_record(SEARCH_CSV_HEADER)?;
+    }
+    for hit in hits {
+        writer.write_record([
+            hit.project_id.as_str(),
+            hit.slug.as_deref().unwrap_or_default(),
+            &hit.name,
+            &hit.author,
+            &hit.downloads.to_string(),
+            &hit.follows.to_string(),
+            &hit.date_created,
+            &hit.date_modified,
+            &hit.categories.join(","),
+            &hit.license,
+        ])?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))?;
+    Ok(bytes.into())
+}
+
+// The search request of one page of a CSV export
+fn search_csv_page(info: &SearchRequest, offset: usize, limit: usize) -> SearchRequest {
+    SearchRequest {
+        query: info.query.clone(),
+        offset: Some(offset.to_string()),
+        index: info.index.clone(),
+        limit: Some(limit.to_string()),
+        new_filters: info.new_filters.clone(),
+        facets: info.facets.clone(),
+        filters: info.filters.clone(),
+        version: info.version.clone(),
+    }
+}
+
+// Exports search results as CSV, paging through the results from the requested offset. The first
+// page is searched before responding so that its errors get a status, and the following pages are
+// streamed as they are found.
+async fn project_search_csv(
+    info: SearchRequest,
+    config: &SearchConfig,
+    redis: &RedisPool,
+) -> Result<HttpResponse, SearchError> {
+    let offset: usize = info.offset.as_deref().unwrap_or("0").parse()?;
+    if offset > MAX_SEARCH_OFFSET {
+        return Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .body(search_csv_rows(&[], true)?));
+    }
+
+    let limit = MAX_SEARCH_CSV_RESULTS.min(MAX_SEARCH_LIMIT);
+    let first =
+        search_for_project_cached(&search_csv_page(&info, offset, limit), config, redis).await?;
+    let first_rows = search_csv_rows(&first.hits, true)?;
+
+    // The offset and count of exported results the next page starts at, or none on the last page
+    let next = (first.hits.len() == limit).then_some((offset + first.hits.len(), first.hits.len()));
+    let config = config.clone();
+    let redis = redis.clone();
+    let rest = futures::stream::unfold(next, move |next| {
+        let page = next
+            .filter(|(offset, exported)| {
+                *exported < MAX_SEARCH_CSV_RESULTS && *offset <= MAX_SEARCH_OFFSET
+            })
+            .map(|(offset, exported)| {
+                let limit = (MAX_SEARCH_CSV_RESULTS - exported).min(MAX_SEARCH_LIMIT);
+                (
+                    search_csv_page(&info, offset, limit),
+                    offset,
+                    exported,
+                    limit,
+                )
+            });
+        let config = config.clone();
+        let redis = redis.clone();
+        async move {
+            let (request, offset, exported, limit) = page?;
+            let results = match search_for_project_cached(&request, &config, &redis).await {
+                Ok(results) => results,
+                Err(err) => return Some((Err(err), None)),
+            };
+
+            let count = results.hits.len();
+            let next = (count == limit).then_some((offset + count, exported + count));
+            Some((search_csv_rows(&results.hits, false), next))
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .streaming(futures::stream::once(async move { Ok(first_rows) }).chain(rest)))
+}
+
+//checks the validity of a project id or slug
+pub async fn project_get_check(
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let slug = info.into_inner().0;
+
+    let project_data = db_models::Project::get(&slug, &**pool, &redis).await?;
+
+    if let Some(project) = project_data {
+        Ok(HttpResponse::Ok().json(json! ({
+            "id": models::ids::ProjectId::from(project.inner.id)
+        })))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+pub async fn project_audit_log_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await?
+    .1;
+
+    let string = info.into_inner().0;
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let (team_member, organization_team_member) =
+        TeamMember::get_for_project_permissions(&project.inner, user.id.into(), &**pool).await?;
+
+    if ProjectPermissions::get_permissions_by_role(
+        &user.role,
+        &team_member,
+        &organization_team_member,
+    )
+    .is_none()
+    {
+        return if is_visible_project(&project.inner, &Some(user), &pool, false).await? {
+            Err(ApiError::CustomAuthentication(
+                "You do not have permission to view the audit log of this project!".to_string(),
+            ))
+        } else {
+            Err(ApiError::NotFound)
+        };
+    }
+
+    let entries = ProjectAuditLogEntry::get_for_project(project.inner.id, 100, &**pool).await?;
+
+    Ok(HttpResponse::Ok().json(
+        entries
+            .into_iter()
+            .map(models::projects::ProjectAuditLogEntry::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// How often a comment is sent on an idle traffic stream, so proxies do not close the connection
+const TRAFFIC_KEEPALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Streams the downloads of a project as they are counted, as server-sent events. Only the
+/// project's owners can follow its traffic.
+pub async fn project_traffic_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::ANALYTICS]),
+    )
+    .await?
+    .1;
+
+    let string = info.into_inner().0;
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let (team_member, organization_team_member) =
+        TeamMember::get_for_project_permissions(&project.inner, user.id.into(), &**pool).await?;
+
+    let is_owner = team_member.map(|x| x.is_owner).unwrap_or(false)
+        || organization_team_member
+            .map(|x| x.is_owner)
+            .unwrap_or(false);
+    if !is_owner && !user.role.is_admin() {
+        return if is_visible_project(&project.inner, &Some(user), &pool, false).await? {
+            Err(ApiError::CustomAuthentication(
+                "Only the owner of this project can view its traffic!".to_string(),
+            ))
+        } else {
+            Err(ApiError::NotFound)
+        };
     }
+
+    let Some(events) = redis
+        .subscribe(&traffic_channel(project.inner.id.into()))
+        .await?
+    else {
+        return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": "too_many_subscriptions",
+            "description": "Too many traffic streams are open, please try again later",
+        })));
+    };
+
+    // The keepalive stops once the subscription ends, so the response is closed with it
+    let (events_ended, on_events_ended) = futures::channel::oneshot::channel::<()>();
+    let events = events
+        .map(|event| {
+            Ok::<_, std::convert::Infallible>(web::Bytes::from(format!(
+                "event: download\ndata: {event}\n\n"
+            )))
+        })
+        .chain(
+            futures::stream::once(async move {
+                let _ = events_ended.send(());
+            })
+            .filter_map(|_| async { None }),
+        );
+
+    let keepalive = tokio_stream::wrappers::IntervalStream::new(actix_rt::time::interval_at(
+        actix_rt::time::Instant::now() + TRAFFIC_KEEPALIVE_INTERVAL,
+        TRAFFIC_KEEPALIVE_INTERVAL,
+    ))
+    .map(|_| Ok(web::Bytes::from_static(b": keepalive\n\n")))
+    .take_until(on_events_ended);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(futures::stream::select(events, keepalive)))
 }
 
-pub async fn edit_project_categories(
-    categories: &Vec<String>,
-    perms: &ProjectPermissions,
-    project_id: db_ids::ProjectId,
-    additional: bool,
-    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-) -> Result<(), ApiError> {
-    if !perms.contains(ProjectPermissions::EDIT_DETAILS) {
-        let additional_str = if additional { "additional " } else { "" };
-        return Err(ApiError::CustomAuthentication(format!(
-            "You do not have the permissions to edit the {additional_str}categories of this project!"
-        )));
-    }
+#[derive(Serialize)]
+pub struct ProjectTimelineEntry {
+    pub date: chrono::NaiveDate,
+    /// Absent if the metric was not requested, and null if there was no activity of that kind
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<Option<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<Option<u64>>,
+}
 
-    let mut mod_categories = Vec::new();
-    for category in categories {
-        let category_ids =
-            db_models::categories::Category::get_ids(category, &mut **transaction).await?;
-        // TODO: We should filter out categories that don't match the project type of any of the versions
-        // ie: if mod and modpack both share a name this should only have modpack if it only has a modpack as a version
+/// Returns the downloads and views of a project per day, week or month. The metrics are chosen
+/// with repeated `metrics[]` parameters (`downloads`, `views`) and default to both. Only available
+/// to members of the project.
+pub async fn project_stats_timeline_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    query: web::Query<crate::routes::v3::versions::DownloadStatsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::ANALYTICS]),
+    )
+    .await?
+    .1;
 
-        let mcategories = category_ids
-            .values()
-            .map(|x| ModCategory::new(project_id, *x, additional))
-            .collect::<Vec<_>>();
-        mod_categories.extend(mcategories);
+    // Repeated `metrics[]` parameters can't be parsed by `web::Query`
+    let metrics = url::form_urlencoded::parse(req.query_string().as_bytes())
+        .filter(|(key, _)| key == "metrics[]")
+        .map(|(_, value)| value.into_owned())
+        .collect_vec();
+    if let Some(metric) = metrics
+        .iter()
+        .find(|x| !matches!(x.as_str(), "downloads" | "views"))
+    {
+        return Err(ApiError::InvalidInput(format!(
+            "Unknown metric {metric}, expected downloads or views"
+        )));
     }
-    ModCategory::insert_many(mod_categories, &mut *transaction).await?;
+    let include_downloads = metrics.is_empty() || metrics.iter().any(|x| x == "downloads");
+    let include_views = metrics.is_empty() || metrics.iter().any(|x| x == "views");
 
-    Ok(())
-}
+    let string = info.into_inner().0;
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
 
-// TODO: Re-add this if we want to match v3 Projects structure to v3 Search Result structure, otherwise, delete
-// #[derive(Serialize, Deserialize)]
-// pub struct ReturnSearchResults {
-//     pub hits: Vec<Project>,
-//     pub page: usize,
-//     pub hits_per_page: usize,
-//     pub total_hits: usize,
-// }
+    let (team_member, organization_team_member) =
+        TeamMember::get_for_project_permissions(&project.inner, user.id.into(), &**pool).await?;
 
-pub async fn project_search(
-    web::Query(info): web::Query<SearchRequest>,
-    config: web::Data<SearchConfig>,
-) -> Result<HttpResponse, SearchError> {
-    let results = search_for_project(&info, &config).await?;
+    if ProjectPermissions::get_permissions_by_role(
+        &user.role,
+        &team_member,
+        &organization_team_member,
+    )
+    .is_none()
+    {
+        return if is_visible_project(&project.inner, &Some(user), &pool, false).await? {
+            Err(ApiError::CustomAuthentication(
+                "You do not have permission to view the stats of this project!".to_string(),
+            ))
+        } else {
+            Err(ApiError::NotFound)
+        };
+    }
 
-    // TODO: add this back
-    // let results = ReturnSearchResults {
-    //     hits: results
-    //         .hits
-    //         .into_iter()
-    //         .filter_map(Project::from_search)
-    //         .collect::<Vec<_>>(),
-    //     page: results.page,
-    //     hits_per_page: results.hits_per_page,
-    //     total_hits: results.total_hits,
-    // };
+    let entries = DownloadHistory::get_project_timeline(
+        project.inner.id,
+        query.resolution.as_str(),
+        query.start,
+        query.end,
+        &**pool,
+    )
+    .await?
+    .into_iter()
+    .filter(|x| {
+        (include_downloads && x.downloads.is_some()) || (include_views && x.views.is_some())
+    })
+    .map(|x| ProjectTimelineEntry {
+        date: x.date,
+        downloads: include_downloads.then(|| x.downloads.map(|x| x as u64)),
+        views: include_views.then(|| x.views.map(|x| x as u64)),
+    })
+    .collect_vec();
+
+    Ok(HttpResponse::Ok().json(entries))
+}
 
-    Ok(HttpResponse::Ok().json(results))
+#[derive(Deserialize)]
+pub struct ProjectEventsQuery {
+    /// Only return events after the event with this ID
+    pub after: Option<i64>,
+    pub limit: Option<i64>,
 }
 
-//checks the validity of a project id or slug
-pub async fn project_get_check(
+/// Returns the timeline of a project, oldest first. Users who are not on the project's team only
+/// see the publishing of versions that are listed.
+pub async fn project_events_get(
+    req: HttpRequest,
     info: web::Path<(String,)>,
+    query: web::Query<ProjectEventsQuery>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
-    let slug = info.into_inner().0;
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
 
-    let project_data = db_models::Project::get(&slug, &**pool, &redis).await?;
+    let string = info.into_inner().0;
+    let project = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
 
-    if let Some(project) = project_data {
-        Ok(HttpResponse::Ok().json(json! ({
-            "id": models::ids::ProjectId::from(project.inner.id)
-        })))
-    } else {
-        Err(ApiError::NotFound)
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
     }
+
+    let is_member = if let Some(user) = &user_option {
+        let (team_member, organization_team_member) =
+            TeamMember::get_for_project_permissions(&project.inner, user.id.into(), &**pool)
+                .await?;
+
+        ProjectPermissions::get_permissions_by_role(
+            &user.role,
+            &team_member,
+            &organization_team_member,
+        )
+        .is_some()
+    } else {
+        false
+    };
+
+    let listed_statuses = VersionStatus::iterator()
+        .filter(|x| x.is_listed())
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+
+    let events = ProjectEvent::get_for_project(
+        project.inner.id,
+        query.after,
+        query.limit.unwrap_or(50).clamp(1, 100),
+        (!is_member).then_some(&*listed_statuses),
+        &**pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(
+        events
+            .into_iter()
+            .map(models::projects::ProjectEvent::from)
+            .collect::<Vec<_>>(),
+    ))
 }
 
 #[derive(Serialize, Deserialize)]
@@ -1039,7 +3465,7 @@ pub struct BulkEditProject {
     pub add_additional_categories: Option<Vec<String>>,
     pub remove_additional_categories: Option<Vec<String>>,
 
-    #[validate(custom(function = " crate::util::validate::validate_url_hashmap_optional_values"))]
+    #[validate(custom(function = "crate::util::validate::validate_link_urls"))]
     pub link_urls: Option<HashMap<String, Option<String>>>,
 }
 
@@ -1231,7 +3657,8 @@ pub async fn projects_edit(
             }
         }
 
-        db_models::Project::clear_cache(project.inner.id, project.inner.slug, None, &redis).await?;
+        db_models::project_item::clear_project_caches(project.inner.id, project.inner.slug, &redis)
+            .await?;
     }
 
     transaction.commit().await?;
@@ -1310,6 +3737,7 @@ pub struct Extension {
 pub async fn project_icon_edit(
     web::Query(ext): web::Query<Extension>,
     req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     info: web::Path<(String,)>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
@@ -1332,6 +3760,7 @@ pub async fn project_icon_edit(
 
         let project_item = db_models::Project::get(&string, &**pool, &redis)
             .await?
+            .filter(|x| x.inner.deleted_at.is_none())
             .ok_or_else(|| {
                 ApiError::InvalidInput("The specified project does not exist!".to_string())
             })?;
@@ -1390,6 +3819,11 @@ pub async fn project_icon_edit(
             .await?;
 
         let mut transaction = pool.begin().await?;
+        if let Some(conflict) =
+            bump_project_version(project_item.inner.id, &edit_query, &mut transaction).await?
+        {
+            return Ok(conflict);
+        }
 
         sqlx::query!(
             "
@@ -1405,10 +3839,9 @@ pub async fn project_icon_edit(
         .await?;
 
         transaction.commit().await?;
-        db_models::Project::clear_cache(
+        db_models::project_item::clear_project_caches(
             project_item.inner.id,
             project_item.inner.slug,
-            None,
             &redis,
         )
         .await?;
@@ -1424,6 +3857,7 @@ pub async fn project_icon_edit(
 
 pub async fn delete_project_icon(
     req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     info: web::Path<(String,)>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
@@ -1443,6 +3877,7 @@ pub async fn delete_project_icon(
 
     let project_item = db_models::Project::get(&string, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
@@ -1486,6 +3921,11 @@ pub async fn delete_project_icon(
     }
 
     let mut transaction = pool.begin().await?;
+    if let Some(conflict) =
+        bump_project_version(project_item.inner.id, &edit_query, &mut transaction).await?
+    {
+        return Ok(conflict);
+    }
 
     sqlx::query!(
         "
@@ -1499,8 +3939,12 @@ pub async fn delete_project_icon(
     .await?;
 
     transaction.commit().await?;
-    db_models::Project::clear_cache(project_item.inner.id, project_item.inner.slug, None, &redis)
-        .await?;
+    db_models::project_item::clear_project_caches(
+        project_item.inner.id,
+        project_item.inner.slug,
+        &redis,
+    )
+    .await?;
 
     Ok(HttpResponse::NoContent().body(""))
 }
@@ -1519,6 +3963,7 @@ pub struct GalleryCreateQuery {
 pub async fn add_gallery_item(
     web::Query(ext): web::Query<Extension>,
     req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     web::Query(item): web::Query<GalleryCreateQuery>,
     info: web::Path<(String,)>,
     pool: web::Data<PgPool>,
@@ -1545,6 +3990,7 @@ pub async fn add_gallery_item(
 
         let project_item = db_models::Project::get(&string, &**pool, &redis)
             .await?
+            .filter(|x| x.inner.deleted_at.is_none())
             .ok_or_else(|| {
                 ApiError::InvalidInput("The specified project does not exist!".to_string())
             })?;
@@ -1612,6 +4058,11 @@ pub async fn add_gallery_item(
             .await?;
 
         let mut transaction = pool.begin().await?;
+        if let Some(conflict) =
+            bump_project_version(project_item.inner.id, &edit_query, &mut transaction).await?
+        {
+            return Ok(conflict);
+        }
 
         if item.featured {
             sqlx::query!(
@@ -1638,10 +4089,9 @@ pub async fn add_gallery_item(
         GalleryItem::insert_many(gallery_item, project_item.inner.id, &mut transaction).await?;
 
         transaction.commit().await?;
-        db_models::Project::clear_cache(
+        db_models::project_item::clear_project_caches(
             project_item.inner.id,
             project_item.inner.slug,
-            None,
             &redis,
         )
         .await?;
@@ -1679,6 +4129,7 @@ pub struct GalleryEditQuery {
 
 pub async fn edit_gallery_item(
     req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     web::Query(item): web::Query<GalleryEditQuery>,
     info: web::Path<(String,)>,
     pool: web::Data<PgPool>,
@@ -1701,6 +4152,7 @@ pub async fn edit_gallery_item(
 
     let project_item = db_models::Project::get(&string, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
@@ -1738,9 +4190,10 @@ pub async fn edit_gallery_item(
     let id = sqlx::query!(
         "
         SELECT id FROM mods_gallery
-        WHERE image_url = $1
+        WHERE image_url = $1 AND mod_id = $2
         ",
-        item.url
+        item.url,
+        project_item.inner.id as db_ids::ProjectId,
     )
     .fetch_optional(&mut *transaction)
     .await?
@@ -1753,6 +4206,11 @@ pub async fn edit_gallery_item(
     .id;
 
     let mut transaction = pool.begin().await?;
+    if let Some(conflict) =
+        bump_project_version(project_item.inner.id, &edit_query, &mut transaction).await?
+    {
+        return Ok(conflict);
+    }
 
     if let Some(featured) = item.featured {
         if featured {
@@ -1823,8 +4281,12 @@ pub async fn edit_gallery_item(
 
     transaction.commit().await?;
 
-    db_models::Project::clear_cache(project_item.inner.id, project_item.inner.slug, None, &redis)
-        .await?;
+    db_models::project_item::clear_project_caches(
+        project_item.inner.id,
+        project_item.inner.slug,
+        &redis,
+    )
+    .await?;
 
     Ok(HttpResponse::NoContent().body(""))
 }
@@ -1834,8 +4296,10 @@ pub struct GalleryDeleteQuery {
     pub url: String,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn delete_gallery_item(
     req: HttpRequest,
+    web::Query(edit_query): web::Query<ProjectEditQuery>,
     web::Query(item): web::Query<GalleryDeleteQuery>,
     info: web::Path<(String,)>,
     pool: web::Data<PgPool>,
@@ -1856,6 +4320,7 @@ pub async fn delete_gallery_item(
 
     let project_item = db_models::Project::get(&string, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
@@ -1894,9 +4359,10 @@ pub async fn delete_gallery_item(
     let id = sqlx::query!(
         "
         SELECT id FROM mods_gallery
-        WHERE image_url = $1
+        WHERE image_url = $1 AND mod_id = $2
         ",
-        item.url
+        item.url,
+        project_item.inner.id as db_ids::ProjectId,
     )
     .fetch_optional(&mut *transaction)
     .await?
@@ -1908,14 +4374,30 @@ pub async fn delete_gallery_item(
     })?
     .id;
 
+    // Duplicated projects share their gallery images with the source
+    let shared = sqlx::query!(
+        "SELECT EXISTS(SELECT 1 FROM mods_gallery WHERE image_url = $1 AND id != $2)",
+        item.url,
+        id,
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .exists
+    .unwrap_or(false);
+
     let cdn_url = dotenvy::var("CDN_URL")?;
     let name = item.url.split(&format!("{cdn_url}/")).nth(1);
 
-    if let Some(icon_path) = name {
+    if let (Some(icon_path), false) = (name, shared) {
         file_host.delete_file_version("", icon_path).await?;
     }
 
     let mut transaction = pool.begin().await?;
+    if let Some(conflict) =
+        bump_project_version(project_item.inner.id, &edit_query, &mut transaction).await?
+    {
+        return Ok(conflict);
+    }
 
     sqlx::query!(
         "
@@ -1929,12 +4411,181 @@ pub async fn delete_gallery_item(
 
     transaction.commit().await?;
 
-    db_models::Project::clear_cache(project_item.inner.id, project_item.inner.slug, None, &redis)
-        .await?;
+    db_models::project_item::clear_project_caches(
+        project_item.inner.id,
+        project_item.inner.slug,
+        &redis,
+    )
+    .await?;
 
     Ok(HttpResponse::NoContent().body(""))
 }
 
+/// Picks a free slug for a duplicated project, based on the slug or name of the source
+async fn duplicate_slug(
+    source: &db_models::project_item::Project,
+    project_id: ProjectId,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<String, ApiError> {
+    let base = source
+        .slug
+        .clone()
+        .unwrap_or_else(|| ProjectId::from(source.id).to_string())
+        .chars()
+        .take(50)
+        .collect::<String>();
+
+    let candidate = format!("{base}-copy");
+    let taken = sqlx::query!(
+        "SELECT EXISTS(SELECT 1 FROM mods WHERE slug = LOWER($1))",
+        candidate
+    )
+    .fetch_one(&mut **transaction)
+    .await?
+    .exists
+    .unwrap_or(true);
+
+    // The new project's ID cannot collide with an existing slug, as slugs are checked against IDs
+    Ok(if taken {
+        format!("{base}-{project_id}")
+    } else {
+        candidate
+    })
+}
+
+pub async fn project_duplicate(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_CREATE]),
+    )
+    .await?
+    .1;
+
+    let string = info.into_inner().0;
+    let source = db_models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&source.inner, &Some(user.clone()), &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+    if !source.inner.status.is_searchable() {
+        return Err(ApiError::InvalidInput(
+            "Only public projects can be duplicated!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    let project_id: ProjectId = db_models::generate_project_id(&mut transaction)
+        .await?
+        .into();
+    let slug = duplicate_slug(&source.inner, project_id, &mut transaction).await?;
+
+    let team_id = db_models::team_item::TeamBuilder {
+        members: vec![db_models::team_item::TeamMemberBuilder {
+            user_id: user.id.into(),
+            role: models::teams::DEFAULT_ROLE.to_owned(),
+            is_owner: true,
+            permissions: ProjectPermissions::all(),
+            organization_permissions: None,
+            accepted: true,
+            payouts_split: rust_decimal::Decimal::ONE_HUNDRED,
+            ordering: 0,
+        }],
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    let id = db_models::project_item::ProjectBuilder {
+        project_id: project_id.into(),
+        team_id,
+        organization_id: None,
+        name: format!("Copy of {}", source.inner.name)
+            .chars()
+            .take(64)
+            .collect(),
+        summary: source.inner.summary.clone(),
+        description: source.inner.description.clone(),
+        icon_url: None,
+        license_url: source.inner.license_url.clone(),
+        // Categories are copied below, as the source only has their names
+        categories: vec![],
+        additional_categories: vec![],
+        initial_versions: vec![],
+        status: ProjectStatus::Draft,
+        requested_status: Some(ProjectStatus::Approved),
+        license: source.inner.license.clone(),
+        slug: Some(slug),
+        link_urls: source.urls.iter().filter(|x| x.donation).cloned().collect(),
+        gallery_items: source.gallery_items.clone(),
+        color: None,
+        monetization_status: MonetizationStatus::Monetized,
+        forked_from: Some(source.inner.id),
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        INSERT INTO mods_categories (joining_mod_id, joining_category_id, is_additional)
+        SELECT $1, joining_category_id, is_additional
+        FROM mods_categories
+        WHERE joining_mod_id = $2
+        ",
+        id as db_ids::ProjectId,
+        source.inner.id as db_ids::ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        INSERT INTO project_custom_licenses (project_id, license_text, previous_license)
+        SELECT $1, license_text, previous_license
+        FROM project_custom_licenses
+        WHERE project_id = $2
+        ",
+        id as db_ids::ProjectId,
+        source.inner.id as db_ids::ProjectId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    db_models::thread_item::ThreadBuilder {
+        type_: models::threads::ThreadType::Project,
+        members: vec![],
+        project_id: Some(id),
+        report_id: None,
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+    db_models::User::clear_project_cache(&[user.id.into()], &redis).await?;
+
+    let project = db_models::Project::get_id(id, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(Project::from(project)))
+}
+
+#[derive(Deserialize)]
+pub struct ProjectDeletion {
+    pub confirm: bool,
+}
+
+/// Deletes a project. It is hidden and removed from search right away, and permanently deleted
+/// with its files once the deletion delay has passed.
 pub async fn project_delete(
     req: HttpRequest,
     info: web::Path<(String,)>,
@@ -1942,6 +4593,7 @@ pub async fn project_delete(
     redis: web::Data<RedisPool>,
     search_config: web::Data<SearchConfig>,
     session_queue: web::Data<AuthQueue>,
+    web::Json(deletion): web::Json<ProjectDeletion>,
 ) -> Result<HttpResponse, ApiError> {
     let user = get_user_from_headers(
         &req,
@@ -1956,6 +4608,7 @@ pub async fn project_delete(
 
     let project = db_models::Project::get(&string, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
@@ -1990,44 +4643,62 @@ pub async fn project_delete(
         }
     }
 
+    if !deletion.confirm {
+        return Err(ApiError::InvalidInput(
+            "Deleting a project must be confirmed!".to_string(),
+        ));
+    }
+
+    if db_models::Project::has_required_dependents(project.inner.id, &**pool).await? {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": "conflict",
+            "description": "Other projects require this project, so it cannot be deleted.",
+        })));
+    }
+
     let mut transaction = pool.begin().await?;
-    let context = ImageContext::Project {
-        project_id: Some(project.inner.id.into()),
+
+    let Some(scheduled_for) =
+        db_models::Project::soft_delete(project.inner.id, &mut transaction, &redis).await?
+    else {
+        return Err(ApiError::NotFound);
     };
-    let uploaded_images = db_models::Image::get_many_contexted(context, &mut transaction).await?;
-    for image in uploaded_images {
-        image_item::Image::remove(image.id, &mut transaction, &redis).await?;
-    }
 
-    sqlx::query!(
-        "
-        DELETE FROM collections_mods
-        WHERE mod_id = $1
-        ",
-        project.inner.id as db_ids::ProjectId,
+    let members = TeamMember::get_from_team_full(project.inner.team_id, &**pool, &redis).await?;
+    NotificationBuilder {
+        body: NotificationBody::ProjectDeleted {
+            project_id: project.inner.id.into(),
+            project_title: project.inner.name.clone(),
+            deleted_by: user.id,
+            scheduled_for,
+        },
+    }
+    .insert_many(
+        members
+            .into_iter()
+            .filter(|x| x.accepted && x.user_id != user.id.into())
+            .map(|x| x.user_id)
+            .collect(),
+        &mut transaction,
+        &redis,
     )
-    .execute(&mut *transaction)
     .await?;
 
-    let result = db_models::Project::remove(project.inner.id, &mut transaction, &redis).await?;
-
     transaction.commit().await?;
 
-    remove_documents(
+    remove_documents_or_retry(
+        &pool,
+        &search_config,
         &project
             .versions
             .into_iter()
             .map(|x| x.into())
             .collect::<Vec<_>>(),
-        &search_config,
     )
     .await?;
+    crate::search::clear_search_cache(&redis).await?;
 
-    if result.is_some() {
-        Ok(HttpResponse::NoContent().body(""))
-    } else {
-        Err(ApiError::NotFound)
-    }
+    Ok(HttpResponse::NoContent().body(""))
 }
 
 pub async fn project_follow(
@@ -2050,6 +4721,7 @@ pub async fn project_follow(
 
     let result = db_models::Project::get(&string, &**pool, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .ok_or_else(|| {
             ApiError::InvalidInput("The specified project does not exist!".to_string())
         })?;
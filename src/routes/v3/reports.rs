@@ -113,7 +113,7 @@ pub async fn report_create(
             let version_id = VersionId(parse_base62(new_report.item_id.as_str())?);
 
             let result = sqlx::query!(
-                "SELECT EXISTS(SELECT 1 FROM versions WHERE id = $1)",
+                "SELECT EXISTS(SELECT 1 FROM versions WHERE id = $1 AND deleted_at IS NULL)",
                 version_id.0 as i64
             )
             .fetch_one(&mut *transaction)
@@ -19,7 +19,7 @@ pub async fn get_stats(pool: web::Data<PgPool>) -> Result<HttpResponse, ApiError
         "
         SELECT COUNT(id)
         FROM mods
-        WHERE status = ANY($1)
+        WHERE status = ANY($1) AND deleted_at IS NULL
         ",
         &*crate::models::projects::ProjectStatus::iterator()
             .filter(|x| x.is_searchable())
@@ -34,7 +34,7 @@ pub async fn get_stats(pool: web::Data<PgPool>) -> Result<HttpResponse, ApiError
         SELECT COUNT(v.id)
         FROM versions v
         INNER JOIN mods m on v.mod_id = m.id AND m.status = ANY($1)
-        WHERE v.status = ANY($2)
+        WHERE v.status = ANY($2) AND v.deleted_at IS NULL
         ",
         &*crate::models::projects::ProjectStatus::iterator()
             .filter(|x| x.is_searchable())
@@ -66,7 +66,7 @@ pub async fn get_stats(pool: web::Data<PgPool>) -> Result<HttpResponse, ApiError
     let files = sqlx::query!(
         "
         SELECT COUNT(f.id) FROM files f
-        INNER JOIN versions v on f.version_id = v.id AND v.status = ANY($2)
+        INNER JOIN versions v on f.version_id = v.id AND v.status = ANY($2) AND v.deleted_at IS NULL
         INNER JOIN mods m on v.mod_id = m.id AND m.status = ANY($1)
         ",
         &*crate::models::projects::ProjectStatus::iterator()
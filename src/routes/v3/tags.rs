@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
 use super::ApiError;
-use crate::database::models::categories::{Category, LinkPlatform, ProjectType, ReportType};
+use crate::database::models::categories::{
+    Category, CategoryProject, CategoryStats, LinkPlatform, ProjectType, ReportType,
+};
+use crate::database::models::legacy_loader_fields::{GameVersionSemver, MinecraftGameVersion};
 use crate::database::models::loader_fields::{
     Game, Loader, LoaderField, LoaderFieldEnumValue, LoaderFieldType,
 };
+use crate::database::models::loader_version_map_item::LoaderVersionMapping;
 use crate::database::redis::RedisPool;
 use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
 
 use itertools::Itertools;
 use serde_json::Value;
@@ -16,7 +21,14 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("tag")
             .route("category", web::get().to(category_list))
-            .route("loader", web::get().to(loader_list)),
+            .route("category/{name}", web::get().to(category_get))
+            .route("loader", web::get().to(loader_list))
+            .route("loader/{name}", web::get().to(loader_get))
+            .route("game_version", web::get().to(game_version_list))
+            .route(
+                "loader/{name}/version-map",
+                web::get().to(loader_version_map_get),
+            ),
     )
     .route("games", web::get().to(games_list))
     .route("loader_field", web::get().to(loader_fields_list))
@@ -27,6 +39,48 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     .route("project_type", web::get().to(project_type_list));
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct GameVersionData {
+    pub version: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub date: DateTime<Utc>,
+    pub major: bool,
+    pub semver: Option<GameVersionSemver>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GameVersionQuery {
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub major: Option<bool>,
+}
+
+/// Lists game versions, newest first. Versions with numeric components are sorted by them and come
+/// before the others, which are sorted by release date.
+pub async fn game_version_list(
+    query: web::Query<GameVersionQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut game_versions =
+        MinecraftGameVersion::list(query.type_.as_deref(), query.major, &**pool, &redis).await?;
+    game_versions.sort_by(|a, b| (b.semver, b.created).cmp(&(a.semver, a.created)));
+
+    let results = game_versions
+        .into_iter()
+        .map(|x| GameVersionData {
+            version: x.version,
+            type_: x.type_,
+            date: x.created,
+            major: x.major,
+            semver: x.semver,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct GameData {
     pub slug: String,
@@ -53,7 +107,7 @@ pub async fn games_list(
     Ok(HttpResponse::Ok().json(results))
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct CategoryData {
     pub icon: String,
     pub name: String,
@@ -61,6 +115,12 @@ pub struct CategoryData {
     pub header: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/tag/category",
+    tag = "tags",
+    responses((status = 200, description = "All project categories", body = Vec<CategoryData>)),
+)]
 pub async fn category_list(
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
@@ -80,6 +140,70 @@ pub async fn category_list(
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
+pub struct CategoryProjectData {
+    pub id: crate::models::ids::ProjectId,
+    pub slug: Option<String>,
+    pub name: String,
+    pub icon_url: Option<String>,
+    pub downloads: i32,
+}
+
+impl From<CategoryProject> for CategoryProjectData {
+    fn from(project: CategoryProject) -> Self {
+        CategoryProjectData {
+            id: project.id.into(),
+            slug: project.slug,
+            name: project.name,
+            icon_url: project.icon_url,
+            downloads: project.downloads,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CategoryDetails {
+    pub name: String,
+    pub header: String,
+    pub icon: String,
+    /// The project types the category exists for
+    pub project_types: Vec<String>,
+    pub project_count: i64,
+    pub most_popular: Vec<CategoryProjectData>,
+}
+
+/// Gets a category with the number of projects in it and its most downloaded projects
+pub async fn category_get(
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let name = info.into_inner().0;
+    let categories = Category::list(&**pool, &redis)
+        .await?
+        .into_iter()
+        .filter(|x| x.category == name)
+        .collect_vec();
+    let Some(category) = categories.first() else {
+        return Err(ApiError::NotFound);
+    };
+
+    let stats = CategoryStats::get(&name, &pool, &redis).await?;
+
+    Ok(HttpResponse::Ok().json(CategoryDetails {
+        name: category.category.clone(),
+        header: category.header.clone(),
+        icon: category.icon.clone(),
+        project_types: categories
+            .iter()
+            .map(|x| x.project_type.clone())
+            .unique()
+            .collect(),
+        project_count: stats.project_count,
+        most_popular: stats.most_popular.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct LoaderData {
     pub icon: String,
     pub name: String,
@@ -87,22 +211,24 @@ pub struct LoaderData {
     pub supported_games: Vec<String>,
     pub supported_fields: Vec<String>, // Available loader fields for this loader
     pub metadata: Value,
+    pub deprecated: bool,
+    /// The loader to use instead, if the loader is deprecated in favour of another
+    pub superseded_by: Option<String>,
 }
 
-pub async fn loader_list(
-    pool: web::Data<PgPool>,
-    redis: web::Data<RedisPool>,
-) -> Result<HttpResponse, ApiError> {
-    let loaders = Loader::list(&**pool, &redis).await?;
-
+async fn loaders_data(
+    loaders: Vec<Loader>,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<Vec<LoaderData>, ApiError> {
     let loader_fields = LoaderField::get_fields_per_loader(
         &loaders.iter().map(|x| x.id).collect_vec(),
-        &**pool,
-        &redis,
+        pool,
+        redis,
     )
     .await?;
 
-    let mut results = loaders
+    Ok(loaders
         .into_iter()
         .map(|x| LoaderData {
             icon: x.icon,
@@ -114,14 +240,91 @@ pub async fn loader_list(
                 .map(|x| x.iter().map(|x| x.field.clone()).collect_vec())
                 .unwrap_or_default(),
             metadata: x.metadata,
+            deprecated: x.deprecated,
+            superseded_by: x.superseded_by,
         })
-        .collect::<Vec<_>>();
+        .collect())
+}
 
+#[utoipa::path(
+    get,
+    path = "/v3/tag/loader",
+    tag = "tags",
+    responses((status = 200, description = "All loaders", body = Vec<LoaderData>)),
+)]
+pub async fn loader_list(
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let loaders = Loader::list(&**pool, &redis).await?;
+
+    let mut results = loaders_data(loaders, &pool, &redis).await?;
     results.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
 
     Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/tag/loader/{name}",
+    tag = "tags",
+    params(("name" = String, Path, description = "The name of the loader")),
+    responses(
+        (status = 200, description = "The loader, including whether it is deprecated", body = LoaderData),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
+pub async fn loader_get(
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let name = info.into_inner().0;
+    let loader = Loader::list(&**pool, &redis)
+        .await?
+        .into_iter()
+        .find(|x| x.loader == name)
+        .ok_or(ApiError::NotFound)?;
+
+    let result = loaders_data(vec![loader], &pool, &redis)
+        .await?
+        .pop()
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LoaderVersionMapData {
+    pub id: i32,
+    /// An exact loader version, or a prefix followed by `*`
+    pub loader_version_pattern: String,
+    pub game_version: String,
+}
+
+/// Lists the game versions that versions of a loader are mapped to
+pub async fn loader_version_map_get(
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let loader_id = Loader::get_id(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let results = LoaderVersionMapping::list_for_loaders(&[loader_id], &**pool)
+        .await?
+        .into_iter()
+        .map(|x| LoaderVersionMapData {
+            id: x.id,
+            loader_version_pattern: x.loader_version_pattern,
+            game_version: x.game_version,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct LoaderFieldsEnumQuery {
     pub loader_field: String,
@@ -166,12 +369,18 @@ pub async fn loader_fields_list(
     Ok(HttpResponse::Ok().json(results))
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct License {
     pub short: String,
     pub name: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/license",
+    tag = "tags",
+    responses((status = 200, description = "All SPDX licenses", body = Vec<License>)),
+)]
 pub async fn license_list() -> HttpResponse {
     let licenses = spdx::identifiers::LICENSES;
     let mut results: Vec<License> = Vec::with_capacity(licenses.len());
@@ -186,12 +395,22 @@ pub async fn license_list() -> HttpResponse {
     HttpResponse::Ok().json(results)
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct LicenseText {
     pub title: String,
     pub body: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/license/{id}",
+    tag = "tags",
+    params(("id" = String, Path, description = "The SPDX identifier of the license")),
+    responses(
+        (status = 200, description = "The text of the license", body = LicenseText),
+        (status = 400, description = "The license identifier was invalid", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn license_text(params: web::Path<(String,)>) -> Result<HttpResponse, ApiError> {
     let license_id = params.into_inner().0;
 
@@ -1,20 +1,25 @@
 use crate::auth::checks::is_visible_project;
 use crate::auth::get_user_from_headers;
 use crate::database::models::notification_item::NotificationBuilder;
+use crate::database::models::project_audit_log_item::ProjectAuditLogBuilder;
 use crate::database::models::team_item::TeamAssociationId;
 use crate::database::models::{Organization, Team, TeamMember, User};
 use crate::database::redis::RedisPool;
 use crate::database::Project;
 use crate::models::notifications::NotificationBody;
 use crate::models::pats::Scopes;
-use crate::models::teams::{OrganizationPermissions, ProjectPermissions, TeamId};
+use crate::models::teams::{
+    OrganizationPermissions, ProjectPermissions, ProjectTeamMember, TeamId,
+};
 use crate::models::users::UserId;
 use crate::queue::session::AuthQueue;
 use crate::routes::ApiError;
+use crate::util::validate::validation_errors_to_string;
 use actix_web::{web, HttpRequest, HttpResponse};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use validator::Validate;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.route("teams", web::get().to(teams_get));
@@ -23,6 +28,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::scope("team")
             .route("{id}/members", web::get().to(team_members_get))
             .route("{id}/members/{user_id}", web::patch().to(edit_team_member))
+            .route(
+                "{id}/members/{user_id}/role",
+                web::patch().to(edit_team_member_role),
+            )
             .route(
                 "{id}/members/{user_id}",
                 web::delete().to(remove_team_member),
@@ -104,6 +113,102 @@ pub async fn team_members_get_project(
     }
 }
 
+#[derive(Deserialize, Validate)]
+pub struct ProjectTeamsQuery {
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// The most members listed in one page of `GET /project/{id}/teams`
+const PROJECT_TEAMS_PAGE_SIZE: usize = 50;
+
+// Lists the members of a project's team with their permissions.
+// Non-members only see accepted members and no permissions, while the project's members and
+// moderators see every member including pending invites.
+pub async fn project_teams_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    query: web::Query<ProjectTeamsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let string = info.into_inner().0;
+    let project = crate::database::models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let current_user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if !is_visible_project(&project.inner, &current_user, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let show_all = if let Some(user) = &current_user {
+        if user.role.is_mod() {
+            true
+        } else {
+            let (team_member, organization_team_member) =
+                TeamMember::get_for_project_permissions(&project.inner, user.id.into(), &**pool)
+                    .await?;
+
+            team_member.is_some() || organization_team_member.is_some()
+        }
+    } else {
+        false
+    };
+
+    let members_data: Vec<_> =
+        TeamMember::get_from_team_full(project.inner.team_id, &**pool, &redis)
+            .await?
+            .into_iter()
+            .filter(|x| show_all || x.accepted)
+            .skip(query.offset.unwrap_or(0))
+            .take(query.limit.unwrap_or(PROJECT_TEAMS_PAGE_SIZE))
+            .collect();
+    let users = User::get_many_ids(
+        &members_data.iter().map(|x| x.user_id).collect::<Vec<_>>(),
+        &**pool,
+        &redis,
+    )
+    .await?;
+    let joined_at = TeamMember::get_joined_at(project.inner.team_id, &**pool).await?;
+
+    let team_members: Vec<_> = members_data
+        .into_iter()
+        .flat_map(|data| {
+            users
+                .iter()
+                .find(|x| x.id == data.user_id)
+                .map(|user| ProjectTeamMember {
+                    user_id: user.id.into(),
+                    username: user.username.clone(),
+                    avatar_url: user.avatar_url.clone(),
+                    role: data.role,
+                    permissions: show_all.then_some(data.permissions),
+                    accepted: data.accepted,
+                    joined_at: joined_at.get(&data.user_id).copied(),
+                })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(team_members))
+}
+
 pub async fn team_members_get_organization(
     req: HttpRequest,
     info: web::Path<(String,)>,
@@ -740,6 +845,147 @@ pub async fn edit_team_member(
     Ok(HttpResponse::NoContent().body(""))
 }
 
+/// The role shown for the owner of a team
+const OWNER_ROLE: &str = "Owner";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EditTeamMemberRole {
+    pub role: String,
+    pub permissions: Option<ProjectPermissions>,
+}
+
+// Changes the role and permissions of a member of a project team. Only the team's owner can do
+// this. A team always keeps its owner, so ownership has to be transferred before the owner's role
+// can be changed.
+pub async fn edit_team_member_role(
+    req: HttpRequest,
+    info: web::Path<(TeamId, UserId)>,
+    pool: web::Data<PgPool>,
+    edit_role: web::Json<EditTeamMemberRole>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let ids = info.into_inner();
+    let id = ids.0.into();
+    let user_id = ids.1.into();
+
+    let current_user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_WRITE]),
+    )
+    .await?
+    .1;
+
+    let TeamAssociationId::Project(project_id) = Team::get_association(id, &**pool)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("The team specified does not exist".to_string()))?
+    else {
+        return Err(ApiError::InvalidInput(
+            "Roles can only be changed in project teams".to_string(),
+        ));
+    };
+
+    let member = TeamMember::get_from_user_id(id, current_user.id.into(), &**pool).await?;
+    if !current_user.role.is_admin() && !member.map(|x| x.is_owner).unwrap_or(false) {
+        return Err(ApiError::CustomAuthentication(
+            "Only the owner of this team can change the roles of its members".to_string(),
+        ));
+    }
+
+    let role = edit_role.role.trim();
+    if role.is_empty() || role.len() > 64 {
+        return Err(ApiError::InvalidInput(
+            "The role must be between 1 and 64 characters".to_string(),
+        ));
+    }
+
+    let members = TeamMember::get_from_team_full(id, &**pool, &redis).await?;
+    let edit_member_db = members
+        .iter()
+        .find(|x| x.user_id == user_id)
+        .ok_or(ApiError::NotFound)?;
+
+    if edit_member_db.is_owner {
+        if edit_role.permissions.is_some() {
+            return Err(ApiError::InvalidInput(
+                "The owner's permission's in a team cannot be edited".to_string(),
+            ));
+        }
+
+        if !role.eq_ignore_ascii_case(OWNER_ROLE)
+            && !members.iter().any(|x| x.is_owner && x.user_id != user_id)
+        {
+            return Err(ApiError::InvalidInput(
+                "A team must always have an owner. Transfer the ownership of the team before \
+                 changing the owner's role"
+                    .to_string(),
+            ));
+        }
+    } else if role.eq_ignore_ascii_case(OWNER_ROLE) {
+        return Err(ApiError::InvalidInput(
+            "Transfer the ownership of the team to make a member its owner".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    TeamMember::edit_team_member(
+        id,
+        user_id,
+        edit_role.permissions,
+        None,
+        Some(role.to_string()),
+        None,
+        None,
+        None,
+        None,
+        &mut transaction,
+    )
+    .await?;
+
+    // The member is part of the values, as the audit log has no field for it
+    let member_id = UserId::from(user_id);
+    let mut audit_log = Vec::new();
+    if edit_member_db.role != role {
+        audit_log.push(ProjectAuditLogBuilder {
+            project_id,
+            user_id: current_user.id.into(),
+            changed_field: "member_role",
+            old_value: Some(format!("{member_id}: {}", edit_member_db.role)),
+            new_value: Some(format!("{member_id}: {role}")),
+        });
+    }
+    if let Some(permissions) = edit_role
+        .permissions
+        .filter(|x| *x != edit_member_db.permissions)
+    {
+        audit_log.push(ProjectAuditLogBuilder {
+            project_id,
+            user_id: current_user.id.into(),
+            changed_field: "member_permissions",
+            old_value: Some(format!(
+                "{member_id}: {}",
+                edit_member_db.permissions.bits()
+            )),
+            new_value: Some(format!("{member_id}: {}", permissions.bits())),
+        });
+    }
+    for entry in audit_log {
+        entry.insert(&mut transaction).await?;
+    }
+
+    transaction.commit().await?;
+
+    // Permissions are read from the database on each request, so clearing the cached team is
+    // enough for them to take effect
+    TeamMember::clear_cache(id, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
 #[derive(Deserialize)]
 pub struct TransferOwnership {
     pub user_id: UserId,
@@ -1,6 +1,9 @@
 use std::{collections::HashMap, sync::Arc};
 
 use actix_web::{web, HttpRequest, HttpResponse};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -8,19 +11,35 @@ use sqlx::PgPool;
 use validator::Validate;
 
 use crate::{
-    auth::{filter_visible_projects, get_user_from_headers},
-    database::{models::User, redis::RedisPool},
+    auth::email::send_email,
+    auth::{
+        checks::filter_visible_versions,
+        filter_visible_projects, get_user_from_headers,
+        password::{upgrade_password_hash, verify_password},
+    },
+    database::{
+        models::{
+            collection_item::CollectionSortValue, flow_item::Flow,
+            moderation_log_item::ModerationLogBuilder, push_token_item::PushToken, User,
+        },
+        redis::RedisPool,
+    },
     file_hosting::FileHost,
     models::{
-        collections::{Collection, CollectionStatus},
-        ids::UserId,
-        notifications::Notification,
+        collections::{CollectionId, CollectionSort, CollectionStatus, CollectionSummary},
+        ids::{ProjectId, UserId, VersionId},
+        notifications::{Notification, PushPlatform},
         pats::Scopes,
-        projects::Project,
+        projects::{Project, ProjectStatus},
         users::{Badges, Role},
     },
-    queue::session::AuthQueue,
-    util::{routes::read_from_payload, validate::validation_errors_to_string},
+    queue::{bans::reindex_user_projects, session::AuthQueue},
+    routes::internal::flows::send_email_verify,
+    search::SearchConfig,
+    util::{
+        routes::read_from_payload,
+        validate::{validate_string_length, validation_errors_to_string},
+    },
 };
 
 use super::{oauth_clients::get_user_clients, ApiError};
@@ -32,21 +51,44 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("user")
             .route("{user_id}/projects", web::get().to(projects_list))
+            .route(
+                "{id}/version-contributions",
+                web::get().to(version_contributions_list),
+            )
             .route("{id}", web::get().to(user_get))
+            .route(
+                "{id}/download-stats",
+                web::get().to(user_download_stats_get),
+            )
             .route("{user_id}/collections", web::get().to(collections_list))
             .route("{user_id}/organizations", web::get().to(orgs_list))
             .route("{id}", web::patch().to(user_edit))
+            .route("{id}/username", web::patch().to(user_username_edit))
             .route("{id}/icon", web::patch().to(user_icon_edit))
+            .route("{id}/roles", web::patch().to(user_role_edit))
             .route("{id}", web::delete().to(user_delete))
             .route("{id}/follows", web::get().to(user_follows))
             .route("{id}/notifications", web::get().to(user_notifications))
+            .route("verify-email", web::post().to(user_email_verify))
+            .route("{id}/email", web::patch().to(user_email_edit))
+            .route("{id}/push-token", web::post().to(user_push_token_add))
+            .route(
+                "{id}/push-token/{token}",
+                web::delete().to(user_push_token_delete),
+            )
             .route("{id}/oauth_apps", web::get().to(get_user_clients)),
     );
 }
 
+#[derive(Serialize, Deserialize, Default)]
+pub struct UserProjectsQuery {
+    pub status: Option<String>,
+}
+
 pub async fn projects_list(
     req: HttpRequest,
     info: web::Path<(String,)>,
+    web::Query(query): web::Query<UserProjectsQuery>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
@@ -65,7 +107,22 @@ pub async fn projects_list(
     let id_option = User::get(&info.into_inner().0, &**pool, &redis).await?;
 
     if let Some(id) = id_option.map(|x| x.id) {
-        let project_data = User::get_projects(id, &**pool, &redis).await?;
+        let project_data = if let Some(status) = query.status {
+            // Only moderators and the user themselves may filter by a non-public status
+            let privileged = user
+                .as_ref()
+                .map(|x| x.role.is_mod() || crate::database::models::UserId::from(x.id) == id)
+                .unwrap_or(false);
+            let status = if privileged {
+                ProjectStatus::from_string(&status)
+            } else {
+                ProjectStatus::Approved
+            };
+
+            User::get_projects_with_status(id, status.as_str(), &**pool).await?
+        } else {
+            User::get_projects(id, &**pool, &redis).await?
+        };
 
         let projects: Vec<_> =
             crate::database::Project::get_many_ids(&project_data, &**pool, &redis).await?;
@@ -76,6 +133,153 @@ pub async fn projects_list(
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct UserProjectDownloadStats {
+    pub project_id: ProjectId,
+    pub project_title: String,
+    pub downloads: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDownloadStats {
+    pub total_downloads: i64,
+    pub projects: Vec<UserProjectDownloadStats>,
+}
+
+/// The downloads of each of a user's projects, most downloaded first. Only public projects are
+/// included, unless the user is requesting their own stats or is a moderator.
+pub async fn user_download_stats_get(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let id = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?
+        .id;
+    let privileged = user
+        .map(|x| x.role.is_mod() || crate::database::models::UserId::from(x.id) == id)
+        .unwrap_or(false);
+
+    let projects = User::get_download_stats(id, &**pool, &redis)
+        .await?
+        .into_iter()
+        .filter(|x| privileged || ProjectStatus::from_string(&x.status).is_searchable())
+        .map(|x| UserProjectDownloadStats {
+            project_id: x.project_id.into(),
+            project_title: x.name,
+            downloads: x.downloads,
+        })
+        .collect_vec();
+
+    Ok(HttpResponse::Ok().json(UserDownloadStats {
+        total_downloads: projects.iter().map(|x| x.downloads).sum(),
+        projects,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VersionContributionsQuery {
+    /// Only list versions of this project (ID or slug)
+    pub project_id: Option<String>,
+    pub limit: Option<usize>,
+    /// The ID of the last version of the previous page
+    pub after: Option<VersionId>,
+}
+
+/// The most versions returned in one page of contributions
+const MAX_CONTRIBUTIONS_LIMIT: usize = 100;
+
+pub async fn version_contributions_list(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Query(query): web::Query<VersionContributionsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let current_user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let user = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let project_id = if let Some(project_id) = &query.project_id {
+        Some(
+            crate::database::Project::get(project_id, &**pool, &redis)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::InvalidInput("The specified project does not exist!".to_string())
+                })?
+                .inner
+                .id,
+        )
+    } else {
+        None
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(MAX_CONTRIBUTIONS_LIMIT)
+        .min(MAX_CONTRIBUTIONS_LIMIT);
+
+    // Versions of projects the requester cannot see are filtered out, so pages are fetched until
+    // enough visible versions are found or the user has no older versions
+    let mut versions = Vec::new();
+    let mut after = query.after.map(|x| x.into());
+    while versions.len() < limit {
+        let remaining = limit - versions.len();
+        let version_ids =
+            User::get_version_contributions(user.id, project_id, after, remaining as i64, &**pool)
+                .await?;
+        let Some(last) = version_ids.last().copied() else {
+            break;
+        };
+        after = Some(last);
+
+        let page =
+            crate::database::models::Version::get_many(&version_ids, &**pool, &redis).await?;
+        versions.extend(
+            filter_visible_versions(page, &current_user, &pool, &redis)
+                .await?
+                .into_iter()
+                .sorted_by(|a, b| {
+                    b.date_published
+                        .cmp(&a.date_published)
+                        .then(b.id.0.cmp(&a.id.0))
+                }),
+        );
+
+        if version_ids.len() < remaining {
+            break;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(versions))
+}
+
 pub async fn user_auth_get(
     req: HttpRequest,
     pool: web::Data<PgPool>,
@@ -136,13 +340,102 @@ pub async fn user_get(
     }
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UserCollectionsQuery {
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub sort: CollectionSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// The `next_cursor` of the previous page, to get the collections after it
+    pub cursor: Option<String>,
+}
+
+/// Where a page of collections starts, as the sort value and ID of the last collection of the
+/// previous page. Sent to clients as base64 encoded JSON.
+#[derive(Serialize, Deserialize)]
+pub struct UserCollectionsCursor {
+    pub sort_val: String,
+    pub id: CollectionId,
+}
+
+impl UserCollectionsCursor {
+    fn from_summary(sort: CollectionSort, summary: &CollectionSummary) -> Self {
+        let sort_val = match sort {
+            CollectionSort::Created => summary.created.to_rfc3339(),
+            CollectionSort::Updated => summary.updated.to_rfc3339(),
+            CollectionSort::Title => summary.name.clone(),
+        };
+
+        UserCollectionsCursor {
+            sort_val,
+            id: summary.id,
+        }
+    }
+
+    fn encode(&self) -> Result<String, ApiError> {
+        Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(
+        cursor: &str,
+        sort: CollectionSort,
+    ) -> Result<(CollectionSortValue, CollectionId), ApiError> {
+        let invalid = || ApiError::InvalidInput("Invalid collections cursor".to_string());
+
+        let cursor: UserCollectionsCursor = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|x| serde_json::from_slice(&x).ok())
+            .ok_or_else(invalid)?;
+        let sort_val = match sort {
+            CollectionSort::Created | CollectionSort::Updated => CollectionSortValue::Date(
+                DateTime::parse_from_rfc3339(&cursor.sort_val)
+                    .map_err(|_| invalid())?
+                    .with_timezone(&Utc),
+            ),
+            CollectionSort::Title => CollectionSortValue::Name(cursor.sort_val),
+        };
+
+        Ok((sort_val, cursor.id))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserCollectionsPage {
+    pub collections: Vec<CollectionSummary>,
+    /// The cursor of the next page, absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Lists summaries of a user's collections. Anonymous users only see listed collections, signed
+/// in users also see unlisted ones, and the owner and moderators see all of them.
 pub async fn collections_list(
     req: HttpRequest,
     info: web::Path<(String,)>,
+    web::Query(query): web::Query<UserCollectionsQuery>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+    let after = query
+        .cursor
+        .as_deref()
+        .map(|x| UserCollectionsCursor::decode(x, query.sort))
+        .transpose()?;
+
     let user = get_user_from_headers(
         &req,
         &**pool,
@@ -159,21 +452,51 @@ pub async fn collections_list(
     if let Some(id) = id_option.map(|x| x.id) {
         let user_id: UserId = id.into();
 
-        let can_view_private = user
-            .map(|y| y.role.is_mod() || y.id == user_id)
-            .unwrap_or(false);
-
-        let project_data = User::get_collections(id, &**pool).await?;
-
-        let response: Vec<_> =
-            crate::database::models::Collection::get_many(&project_data, &**pool, &redis)
-                .await?
-                .into_iter()
-                .filter(|x| can_view_private || matches!(x.status, CollectionStatus::Listed))
-                .map(Collection::from)
-                .collect();
+        let statuses = match &user {
+            Some(user) if user.role.is_mod() || user.id == user_id => vec![
+                CollectionStatus::Listed,
+                CollectionStatus::Unlisted,
+                CollectionStatus::Private,
+                CollectionStatus::Rejected,
+                CollectionStatus::Unknown,
+            ],
+            Some(_) => vec![CollectionStatus::Listed, CollectionStatus::Unlisted],
+            None => vec![CollectionStatus::Listed],
+        };
+
+        let limit = query.limit.unwrap_or(100);
+        // One more collection than requested is fetched to know whether there is a next page
+        let mut collections = crate::database::models::Collection::get_summaries_for_user(
+            id,
+            &statuses
+                .iter()
+                .map(|x| x.as_str().to_string())
+                .collect::<Vec<_>>(),
+            query.sort,
+            query.order == SortOrder::Desc,
+            after.map(|(sort_val, id)| (sort_val, id.into())),
+            limit + 1,
+            &**pool,
+        )
+        .await?
+        .into_iter()
+        .map(CollectionSummary::from)
+        .collect::<Vec<_>>();
+
+        let next_cursor = if collections.len() as i64 > limit {
+            collections.truncate(limit as usize);
+            collections
+                .last()
+                .map(|x| UserCollectionsCursor::from_summary(query.sort, x).encode())
+                .transpose()?
+        } else {
+            None
+        };
 
-        Ok(HttpResponse::Ok().json(response))
+        Ok(HttpResponse::Ok().json(UserCollectionsPage {
+            collections,
+            next_cursor,
+        }))
     } else {
         Err(ApiError::NotFound)
     }
@@ -281,7 +604,6 @@ pub struct EditUser {
         skip_serializing_if = "Option::is_none",
         with = "::serde_with::rust::double_option"
     )]
-    #[validate(length(max = 160))]
     pub bio: Option<Option<String>>,
     pub role: Option<Role>,
     pub badges: Option<Badges>,
@@ -296,6 +618,7 @@ pub async fn user_edit(
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
     session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
 ) -> Result<HttpResponse, ApiError> {
     let (scopes, user) = get_user_from_headers(
         &req,
@@ -306,6 +629,9 @@ pub async fn user_edit(
     )
     .await?;
 
+    if let Some(Some(bio)) = &new_user.bio {
+        validate_string_length("bio", bio, 0, 4000)?;
+    }
     new_user
         .validate()
         .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
@@ -320,28 +646,17 @@ pub async fn user_edit(
             let mut transaction = pool.begin().await?;
 
             if let Some(username) = &new_user.username {
-                let existing_user_id_option = User::get(username, &**pool, &redis).await?;
-
-                if existing_user_id_option
-                    .map(|x| UserId::from(x.id))
-                    .map(|id| id == user.id)
-                    .unwrap_or(true)
+                if let Some(retry_after) = change_username(
+                    &user,
+                    &actual_user,
+                    username,
+                    &pool,
+                    &mut transaction,
+                    &redis,
+                )
+                .await?
                 {
-                    sqlx::query!(
-                        "
-                        UPDATE users
-                        SET username = $1
-                        WHERE (id = $2)
-                        ",
-                        username,
-                        id as crate::database::models::ids::UserId,
-                    )
-                    .execute(&mut *transaction)
-                    .await?;
-                } else {
-                    return Err(ApiError::InvalidInput(format!(
-                        "Username {username} is taken!"
-                    )));
+                    return Ok(username_cooldown_response(retry_after));
                 }
             }
 
@@ -439,7 +754,14 @@ pub async fn user_edit(
             }
 
             transaction.commit().await?;
+            let username_changed = new_user
+                .username
+                .as_ref()
+                .is_some_and(|x| *x != actual_user.username);
             User::clear_caches(&[(id, Some(actual_user.username))], &redis).await?;
+            if username_changed {
+                reindex_user_projects(id, &pool, &redis, &search_config).await?;
+            }
             Ok(HttpResponse::NoContent().body(""))
         } else {
             Err(ApiError::CustomAuthentication(
@@ -451,11 +773,236 @@ pub async fn user_edit(
     }
 }
 
+/// How long a user has to wait between two username changes
+const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+
+/// Changes the username of a user, recording the old one in their username history.
+///
+/// Returns how long is left until the username can be changed instead, if it was changed in the
+/// last 30 days. Moderators are not held to the cooldown.
+async fn change_username(
+    user: &crate::models::users::User,
+    actual_user: &User,
+    username: &str,
+    pool: &PgPool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    redis: &RedisPool,
+) -> Result<Option<Duration>, ApiError> {
+    if username == actual_user.username {
+        return Ok(None);
+    }
+
+    if User::get(username, pool, redis)
+        .await?
+        .map(|x| x.id != actual_user.id)
+        .unwrap_or(false)
+    {
+        return Err(ApiError::InvalidInput(format!(
+            "Username {username} is taken!"
+        )));
+    }
+
+    let last_change = sqlx::query!(
+        "
+        SELECT last_username_change FROM users
+        WHERE id = $1
+        FOR UPDATE
+        ",
+        actual_user.id as crate::database::models::ids::UserId,
+    )
+    .fetch_one(&mut **transaction)
+    .await?
+    .last_username_change;
+    if let Some(last_change) = last_change {
+        let retry_after = last_change + Duration::days(USERNAME_CHANGE_COOLDOWN_DAYS) - Utc::now();
+        if retry_after > Duration::zero() && !user.role.is_mod() {
+            return Ok(Some(retry_after));
+        }
+    }
+
+    sqlx::query!(
+        "
+        INSERT INTO username_history (user_id, username)
+        VALUES ($1, $2)
+        ",
+        actual_user.id as crate::database::models::ids::UserId,
+        actual_user.username,
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET username = $1, last_username_change = NOW()
+        WHERE (id = $2)
+        ",
+        username,
+        actual_user.id as crate::database::models::ids::UserId,
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(None)
+}
+
+fn username_cooldown_response(retry_after: Duration) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header(("Retry-After", retry_after.num_seconds().max(1).to_string()))
+        .json(serde_json::json!({
+            "error": "ratelimit_error",
+            "description": "Usernames can only be changed once every 30 days.",
+        }))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditUsername {
+    #[validate(length(min = 1, max = 39), regex = "RE_URL_SAFE")]
+    pub username: String,
+}
+
+/// Changes the username of a user. Usernames can be changed once every 30 days, and the old
+/// ones are kept in the user's username history.
+pub async fn user_username_edit(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Json(edit): web::Json<EditUsername>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_WRITE]),
+    )
+    .await?
+    .1;
+
+    edit.validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let actual_user = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if user.id != actual_user.id.into() && !user.role.is_mod() {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have permission to edit this user!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    if let Some(retry_after) = change_username(
+        &user,
+        &actual_user,
+        &edit.username,
+        &pool,
+        &mut transaction,
+        &redis,
+    )
+    .await?
+    {
+        return Ok(username_cooldown_response(retry_after));
+    }
+    transaction.commit().await?;
+
+    let username_changed = edit.username != actual_user.username;
+    User::clear_caches(&[(actual_user.id, Some(actual_user.username))], &redis).await?;
+    if username_changed {
+        reindex_user_projects(actual_user.id, &pool, &redis, &search_config).await?;
+    }
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Extension {
     pub ext: String,
 }
 
+#[derive(Deserialize)]
+pub struct EditUserRole {
+    pub role: String,
+}
+
+pub async fn user_role_edit(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    body: web::Json<EditUserRole>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_WRITE]),
+    )
+    .await?
+    .1;
+
+    if !user.role.is_admin() {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have the permissions to edit the role of this user!".to_string(),
+        ));
+    }
+
+    let role = match body.role.as_str() {
+        "user" | "developer" => Role::Developer,
+        "moderator" => Role::Moderator,
+        "admin" => Role::Admin,
+        _ => {
+            return Err(ApiError::InvalidInput(
+                "Role must be one of `user`, `moderator` or `admin`!".to_string(),
+            ))
+        }
+    };
+
+    let actual_user = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if actual_user.role == role.as_str() {
+        return Ok(HttpResponse::NoContent().body(""));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET role = $1
+        WHERE (id = $2)
+        ",
+        role.as_str(),
+        actual_user.id as crate::database::models::ids::UserId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    ModerationLogBuilder {
+        moderator_id: user.id.into(),
+        action: "user_role_change",
+        target_type: "user",
+        target_user_id: Some(actual_user.id),
+        target_version_id: None,
+        old_value: Some(actual_user.role.clone()),
+        new_value: Some(role.to_string()),
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+    User::clear_caches(&[(actual_user.id, Some(actual_user.username))], &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn user_icon_edit(
     web::Query(ext): web::Query<Extension>,
@@ -671,3 +1218,277 @@ pub async fn user_notifications(
         Err(ApiError::NotFound)
     }
 }
+
+#[derive(Deserialize, Validate)]
+pub struct PushTokenData {
+    pub platform: PushPlatform,
+    #[validate(length(min = 1, max = 4096))]
+    pub token: String,
+}
+
+/// Registers a device to receive push notifications for the user
+pub async fn user_push_token_add(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    data: web::Json<PushTokenData>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::NOTIFICATION_WRITE]),
+    )
+    .await?
+    .1;
+
+    data.validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let id = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?
+        .id;
+
+    if user.id != id.into() {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have permission to register push tokens for this user!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    PushToken {
+        token: data.token.clone(),
+        user_id: id,
+        platform: data.platform,
+        created: chrono::Utc::now(),
+    }
+    .insert(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+pub async fn user_push_token_delete(
+    req: HttpRequest,
+    info: web::Path<(String, String)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::NOTIFICATION_WRITE]),
+    )
+    .await?
+    .1;
+
+    let (id, token) = info.into_inner();
+    let id = User::get(&id, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?
+        .id;
+
+    if !user.role.is_admin() && user.id != id.into() {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have permission to remove push tokens of this user!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let result = PushToken::remove(&token, id, &mut transaction).await?;
+    transaction.commit().await?;
+
+    if result.is_some() {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct EditEmail {
+    #[validate(email, length(max = 2048))]
+    pub email: String,
+    pub password: String,
+}
+
+/// Requests a change of a user's email. The change is only applied once the new address is
+/// verified, until then the old email stays on the account.
+pub async fn user_email_edit(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    data: web::Json<EditEmail>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::USER_AUTH_WRITE]),
+    )
+    .await?
+    .1;
+
+    data.validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let db_user = User::get(&info.into_inner().0, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if user.id != db_user.id.into() {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have permission to change the email of this user!".to_string(),
+        ));
+    }
+
+    let password = db_user.password.as_deref().ok_or_else(|| {
+        ApiError::InvalidInput(
+            "You must set a password before changing your email this way!".to_string(),
+        )
+    })?;
+    verify_password(password, &data.password)
+        .map_err(|_| ApiError::CustomAuthentication("Incorrect password!".to_string()))?;
+    if let Err(err) =
+        upgrade_password_hash(db_user.id, password, &data.password, &pool, &redis).await
+    {
+        log::warn!(
+            "Failed to upgrade the password hash of user {}: {err}",
+            db_user.id.0
+        );
+    }
+
+    request_email_change(db_user.id, db_user.email, data.email.clone(), &pool, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// Starts a change of a user's email. The new address is sent a verification link and the old
+/// one is told about the change, which is only applied once the link is followed.
+pub(crate) async fn request_email_change(
+    user_id: crate::database::models::ids::UserId,
+    old_email: Option<String>,
+    new_email: String,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), ApiError> {
+    if User::get_email(&new_email, pool).await?.is_some() {
+        return Err(ApiError::InvalidInput(
+            "This email is already in use by another account!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET email_verified = FALSE
+        WHERE id = $1
+        ",
+        user_id as crate::database::models::ids::UserId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    if let Some(old_email) = old_email {
+        send_email(
+            old_email,
+            "Email change requested",
+            &format!("A change of the email on your account to {} was requested.", new_email),
+            "If you did not make this change, please contact us immediately through our support channels on Discord or via email (support@modrinth.com).",
+            None,
+        )?;
+    }
+
+    let flow = Flow::ChangeEmail {
+        user_id,
+        new_email: new_email.clone(),
+    }
+    .insert(Duration::hours(24), redis)
+    .await?;
+
+    send_email_verify(
+        new_email,
+        flow,
+        "You requested to change the email of your account to this address.",
+    )?;
+
+    transaction.commit().await?;
+    User::clear_caches(&[(user_id, None)], redis).await?;
+
+    Ok(())
+}
+
+/// Applies a change of email started by [`request_email_change`], once its `flow` was followed
+pub(crate) async fn complete_email_change(
+    flow: &str,
+    user_id: crate::database::models::ids::UserId,
+    new_email: String,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), ApiError> {
+    // The address may have been taken by another account since the change was requested
+    if User::get_email(&new_email, pool)
+        .await?
+        .is_some_and(|x| x != user_id)
+    {
+        return Err(ApiError::InvalidInput(
+            "This email is already in use by another account!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE users
+        SET email = $1, email_verified = TRUE
+        WHERE id = $2
+        ",
+        new_email,
+        user_id as crate::database::models::ids::UserId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    Flow::remove(flow, redis).await?;
+    transaction.commit().await?;
+    User::clear_caches(&[(user_id, None)], redis).await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+/// Completes an email change requested through [`user_email_edit`]
+pub async fn user_email_verify(
+    web::Query(query): web::Query<VerifyEmailQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(Flow::ChangeEmail { user_id, new_email }) = Flow::get(&query.token, &redis).await?
+    else {
+        return Err(ApiError::InvalidInput(
+            "Invalid or expired token. Try requesting the email change again.".to_string(),
+        ));
+    };
+
+    complete_email_change(&query.token, user_id, new_email, &pool, &redis).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
@@ -1,6 +1,7 @@
 use super::project_creation::{CreateError, UploadedFile};
 use crate::auth::get_user_from_headers;
 use crate::database::models::loader_fields::{LoaderField, LoaderFieldEnumValue, VersionField};
+use crate::database::models::loader_version_map_item::LoaderVersionMapping;
 use crate::database::models::notification_item::NotificationBuilder;
 use crate::database::models::version_item::{
     DependencyBuilder, VersionBuilder, VersionFileBuilder,
@@ -19,10 +20,14 @@ use crate::models::projects::{
 };
 use crate::models::teams::ProjectPermissions;
 use crate::queue::session::AuthQueue;
-use crate::util::routes::read_from_field;
-use crate::util::validate::validation_errors_to_string;
+use crate::util::routes::read_from_field_decoded;
+use crate::util::validate::{
+    validate_string_length, validation_errors_to_string, StringLengthError,
+};
+use crate::validate::loader_version::extract_loader_versions;
 use crate::validate::{validate_file, ValidationResult};
 use actix_multipart::{Field, Multipart};
+use actix_web::http::header::WARNING;
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use chrono::Utc;
@@ -44,10 +49,7 @@ pub struct InitialVersionData {
     pub project_id: Option<ProjectId>,
     #[validate(length(min = 1, max = 256))]
     pub file_parts: Vec<String>,
-    #[validate(
-        length(min = 1, max = 32),
-        regex = "crate::util::validate::RE_URL_SAFE"
-    )]
+    #[validate(regex = "crate::util::validate::RE_URL_SAFE")]
     pub version_number: String,
     #[validate(
         length(min = 1, max = 64),
@@ -55,7 +57,6 @@ pub struct InitialVersionData {
     )]
     #[serde(alias = "name")]
     pub version_title: String,
-    #[validate(length(max = 65536))]
     #[serde(alias = "changelog")]
     pub version_body: Option<String>,
     #[validate(
@@ -88,12 +89,68 @@ pub struct InitialVersionData {
     pub fields: HashMap<String, serde_json::Value>,
 }
 
+impl InitialVersionData {
+    /// Checks the lengths of the free-text fields, reporting the first one out of range
+    pub fn validate_lengths(&self) -> Result<(), StringLengthError> {
+        validate_string_length("version_number", &self.version_number, 1, 32)?;
+        if let Some(body) = &self.version_body {
+            validate_string_length("changelog", body, 0, 65535)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct InitialFileData {
     #[serde(default = "HashMap::new")]
     pub file_types: HashMap<String, Option<FileType>>,
 }
 
+/// The `game_versions` field of a version created without it, which is inferred from the loader
+/// versions its files declare
+pub struct GameVersionInference {
+    field: LoaderField,
+    enum_values: Vec<LoaderFieldEnumValue>,
+    /// The mappings of each loader, by loader name
+    mappings: Vec<(String, LoaderVersionMapping)>,
+}
+
+impl GameVersionInference {
+    /// Infers the game versions of a file, returning `None` if none of its loader versions are
+    /// mapped to a game version
+    fn infer(
+        &self,
+        version_id: VersionId,
+        data: &[u8],
+        file_extension: &str,
+    ) -> Result<Option<VersionField>, CreateError> {
+        let game_versions = extract_loader_versions(data, file_extension)
+            .into_iter()
+            .flat_map(|(loader, loader_version)| {
+                self.mappings
+                    .iter()
+                    .filter(move |(x, mapping)| *x == loader && mapping.matches(&loader_version))
+                    .map(|(_, mapping)| mapping.game_version.clone())
+            })
+            .unique()
+            .collect_vec();
+
+        if game_versions.is_empty() {
+            return Ok(None);
+        }
+
+        VersionField::check_parse(
+            version_id.into(),
+            self.field.clone(),
+            serde_json::json!(game_versions),
+            self.enum_values.clone(),
+        )
+        .map(Some)
+        .map_err(CreateError::InvalidInput)
+    }
+}
+
 // under `/api/v1/version`
 pub async fn version_create(
     req: HttpRequest,
@@ -150,6 +207,7 @@ async fn version_create_inner(
     let mut initial_version_data = None;
     let mut version_builder = None;
     let mut selected_loaders = None;
+    let mut game_version_inference = None;
 
     let user = get_user_from_headers(
         &req,
@@ -190,6 +248,7 @@ async fn version_create_inner(
                     ));
                 }
 
+                version_create_data.validate_lengths()?;
                 version_create_data.validate().map_err(|err| {
                     CreateError::ValidationError(validation_errors_to_string(err, None))
                 })?;
@@ -202,15 +261,14 @@ async fn version_create_inner(
 
                 let project_id: models::ProjectId = version_create_data.project_id.unwrap().into();
 
-                // Ensure that the project this version is being added to exists
-                if models::Project::get_id(project_id, &mut **transaction, redis)
+                // Ensure that the project this version is being added to exists, and was not
+                // deleted
+                let project = models::Project::get_id(project_id, &mut **transaction, redis)
                     .await?
-                    .is_none()
-                {
-                    return Err(CreateError::InvalidInput(
-                        "An invalid project id was supplied".to_string(),
-                    ));
-                }
+                    .filter(|x| x.inner.deleted_at.is_none())
+                    .ok_or_else(|| {
+                        CreateError::InvalidInput("An invalid project id was supplied".to_string())
+                    })?;
 
                 // Check that the user creating this version is a team member
                 // of the project the version is being added to.
@@ -268,6 +326,7 @@ async fn version_create_inner(
                             .ok_or_else(|| CreateError::InvalidLoader(x.0.clone()))
                     })
                     .collect::<Result<Vec<_>, _>>()?;
+                check_loader_project_types(&loaders, &project.project_types)?;
                 selected_loaders = Some(loaders.clone());
                 let loader_ids: Vec<models::LoaderId> = loaders.iter().map(|y| y.id).collect_vec();
 
@@ -279,6 +338,37 @@ async fn version_create_inner(
                     redis,
                 )
                 .await?;
+                // Versions without game versions have them inferred from their files
+                let loader_fields = if !version_create_data.fields.contains_key("game_versions") {
+                    if let Some(field) = loader_fields.iter().find(|x| x.field == "game_versions") {
+                        game_version_inference = Some(GameVersionInference {
+                            field: field.clone(),
+                            enum_values: loader_field_enum_values
+                                .get(&field.id)
+                                .cloned()
+                                .unwrap_or_default(),
+                            mappings: LoaderVersionMapping::list_for_loaders(
+                                &all_loaders.iter().map(|x| x.id).collect_vec(),
+                                &mut **transaction,
+                            )
+                            .await?
+                            .into_iter()
+                            .filter_map(|x| {
+                                let loader = all_loaders.iter().find(|y| y.id == x.loader_id)?;
+                                Some((loader.loader.clone(), x))
+                            })
+                            .collect(),
+                        });
+                    }
+
+                    loader_fields
+                        .into_iter()
+                        .filter(|x| x.field != "game_versions")
+                        .collect_vec()
+                } else {
+                    loader_fields
+                };
+
                 let version_fields = try_create_version_fields(
                     version_id,
                     &version_create_data.fields,
@@ -333,7 +423,7 @@ async fn version_create_inner(
                 .clone()
                 .ok_or_else(|| CreateError::InvalidInput("`data` field is required".to_string()))?;
 
-            upload_file(
+            let inferred_game_versions = upload_file(
                 &mut field,
                 file_host,
                 version_data.file_parts.len(),
@@ -349,11 +439,19 @@ async fn version_create_inner(
                 version_data.primary_file.is_some(),
                 version_data.primary_file.as_deref() == Some(name),
                 version_data.file_types.get(name).copied().flatten(),
+                false,
+                game_version_inference.as_ref(),
                 transaction,
                 redis,
             )
             .await?;
 
+            // The first file with known loader versions decides the game versions
+            if let Some(game_versions) = inferred_game_versions {
+                version.version_fields.push(game_versions);
+                game_version_inference = None;
+            }
+
             Ok(())
         }
         .await;
@@ -378,6 +476,12 @@ async fn version_create_inner(
         ));
     }
 
+    if game_version_inference.is_some() {
+        return Err(CreateError::InvalidInput(
+            "Missing mandatory loader fields: game_versions (they could not be inferred from the loader versions of the files)".to_string(),
+        ));
+    }
+
     use futures::stream::TryStreamExt;
 
     let users = sqlx::query!(
@@ -432,6 +536,7 @@ async fn version_create_inner(
             .files
             .iter()
             .map(|file| VersionFile {
+                id: file.id.into(),
                 hashes: file
                     .hashes
                     .iter()
@@ -451,6 +556,7 @@ async fn version_create_inner(
                 primary: file.primary,
                 size: file.size,
                 file_type: file.file_type,
+                parts: Vec::new(),
             })
             .collect::<Vec<_>>(),
         dependencies: version_data.dependencies,
@@ -496,14 +602,51 @@ async fn version_create_inner(
         }
     }
 
-    models::Project::clear_cache(project_id, None, Some(true), redis).await?;
+    models::project_item::clear_project_caches(project_id, None, redis).await?;
 
-    Ok(HttpResponse::Ok().json(response))
+    let mut http_response = HttpResponse::Ok();
+    if let Some(warning) = deprecated_loaders_warning(&loader_structs) {
+        http_response.insert_header((WARNING, warning));
+    }
+    Ok(http_response.json(response))
 }
 
+/// The value of the `Warning` header (RFC 7234) for a version uploaded with deprecated loaders,
+/// if any of its loaders are deprecated
+pub fn deprecated_loaders_warning<'a>(
+    loaders: impl IntoIterator<Item = &'a models::loader_fields::Loader>,
+) -> Option<String> {
+    let warnings = loaders
+        .into_iter()
+        .filter(|x| x.deprecated)
+        .map(|x| match &x.superseded_by {
+            Some(superseded_by) => format!(
+                "299 - \"Loader '{}' is deprecated; consider using '{}'\"",
+                x.loader, superseded_by
+            ),
+            None => format!("299 - \"Loader '{}' is deprecated\"", x.loader),
+        })
+        .collect_vec();
+
+    if warnings.is_empty() {
+        None
+    } else {
+        Some(warnings.join(", "))
+    }
+}
+
+/// Identifies the file an upload is a part of, for split archives
+#[derive(Serialize, Deserialize, Default)]
+pub struct FilePartQuery {
+    pub part_of: Option<crate::models::ids::FileId>,
+    pub part_index: Option<u32>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_file_to_version(
     req: HttpRequest,
     url_data: web::Path<(VersionId,)>,
+    part_query: web::Query<FilePartQuery>,
     mut payload: Multipart,
     client: Data<PgPool>,
     redis: Data<RedisPool>,
@@ -517,6 +660,7 @@ pub async fn upload_file_to_version(
 
     let result = upload_file_to_version_inner(
         req,
+        part_query.into_inner(),
         &mut payload,
         client,
         &mut transaction,
@@ -547,6 +691,7 @@ pub async fn upload_file_to_version(
 #[allow(clippy::too_many_arguments)]
 async fn upload_file_to_version_inner(
     req: HttpRequest,
+    part_query: FilePartQuery,
     payload: &mut Multipart,
     client: Data<PgPool>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -582,6 +727,57 @@ async fn upload_file_to_version_inner(
         }
     };
 
+    let part = match (part_query.part_of, part_query.part_index) {
+        (Some(part_of), Some(part_index)) => {
+            let group = version
+                .files
+                .iter()
+                .find(|x| x.part_group_id.is_none() && x.id == part_of.into())
+                .ok_or_else(|| {
+                    CreateError::InvalidInput(
+                        "The file this is a part of does not exist in this version".to_string(),
+                    )
+                })?;
+
+            if part_index == 0 {
+                return Err(CreateError::InvalidInput(
+                    "Part indices start at 1".to_string(),
+                ));
+            }
+            if version.files.iter().any(|x| {
+                x.part_group_id == Some(group.id) && x.part_index == Some(part_index as i32)
+            }) {
+                return Err(CreateError::InvalidInput(format!(
+                    "Part {part_index} has already been uploaded for this file"
+                )));
+            }
+
+            // Versions are only published with every part of their split archives, so parts
+            // added afterwards must come in order
+            if version.inner.status != VersionStatus::Draft {
+                let next_index = version
+                    .files
+                    .iter()
+                    .filter(|x| x.part_group_id == Some(group.id))
+                    .count()
+                    + 1;
+                if part_index as usize != next_index {
+                    return Err(CreateError::InvalidInput(format!(
+                        "Part {next_index} must be uploaded next, as the version is not a draft"
+                    )));
+                }
+            }
+
+            Some((group.id, part_index as i32))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(CreateError::InvalidInput(
+                "`part_of` and `part_index` must be specified together".to_string(),
+            ));
+        }
+    };
+
     let all_loaders = models::loader_fields::Loader::list(&mut **transaction, &redis).await?;
     let selected_loaders = version
         .loaders
@@ -597,6 +793,7 @@ async fn upload_file_to_version_inner(
 
     if models::Project::get_id(version.inner.project_id, &mut **transaction, &redis)
         .await?
+        .filter(|x| x.inner.deleted_at.is_none())
         .is_none()
     {
         return Err(CreateError::InvalidInput(
@@ -706,6 +903,8 @@ async fn upload_file_to_version_inner(
                 true,
                 false,
                 file_data.file_types.get(name).copied().flatten(),
+                part.is_some(),
+                None,
                 transaction,
                 &redis,
             )
@@ -728,6 +927,18 @@ async fn upload_file_to_version_inner(
         return Err(CreateError::InvalidInput(
             "At least one file must be specified".to_string(),
         ));
+    } else if let Some((part_group_id, part_index)) = part {
+        if file_builders.len() > 1 {
+            return Err(CreateError::InvalidInput(
+                "Only one archive part can be uploaded at a time".to_string(),
+            ));
+        }
+
+        for mut file in file_builders {
+            file.part_group_id = Some(part_group_id);
+            file.part_index = Some(part_index);
+            file.insert(version_id, &mut *transaction).await?;
+        }
     } else {
         for file in file_builders {
             file.insert(version_id, &mut *transaction).await?;
@@ -740,6 +951,184 @@ async fn upload_file_to_version_inner(
     Ok(HttpResponse::NoContent().body(""))
 }
 
+#[derive(Serialize, Deserialize, Validate)]
+pub struct VersionCopyData {
+    /// The project the copy is created on, the source version's project if absent
+    pub target_project_id: Option<String>,
+    #[validate(length(min = 1))]
+    pub new_loaders: Vec<Loader>,
+    #[validate(length(min = 1))]
+    pub new_game_versions: Vec<String>,
+}
+
+/// Whether the user can upload versions to a project, either as a member of its team or of
+/// the organization that owns it
+async fn can_upload_version(
+    user: &crate::models::users::User,
+    project_id: models::ProjectId,
+    exec: &PgPool,
+) -> Result<bool, CreateError> {
+    let team_member =
+        models::TeamMember::get_from_user_id_project(project_id, user.id.into(), false, exec)
+            .await?;
+
+    let organization =
+        models::Organization::get_associated_organization_project_id(project_id, exec).await?;
+    let organization_team_member = if let Some(organization) = &organization {
+        models::TeamMember::get_from_user_id(organization.team_id, user.id.into(), exec).await?
+    } else {
+        None
+    };
+
+    let permissions = ProjectPermissions::get_permissions_by_role(
+        &user.role,
+        &team_member,
+        &organization_team_member,
+    )
+    .unwrap_or_default();
+
+    Ok(permissions.contains(ProjectPermissions::UPLOAD_VERSION))
+}
+
+/// Creates a draft version with the metadata of another version, for other loaders and game
+/// versions. The copy has no files, they are uploaded to it afterwards.
+pub async fn version_copy(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    client: Data<PgPool>,
+    redis: Data<RedisPool>,
+    session_queue: Data<AuthQueue>,
+    copy_data: web::Json<VersionCopyData>,
+) -> Result<HttpResponse, CreateError> {
+    let user = get_user_from_headers(
+        &req,
+        &**client,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_CREATE]),
+    )
+    .await?
+    .1;
+
+    copy_data
+        .validate()
+        .map_err(|err| CreateError::ValidationError(validation_errors_to_string(err, None)))?;
+
+    let source = models::Version::get(info.into_inner().0.into(), &**client, &redis)
+        .await?
+        .ok_or_else(|| {
+            CreateError::InvalidInput("An invalid version id was supplied".to_string())
+        })?;
+
+    let project = match &copy_data.target_project_id {
+        Some(target_project_id) => models::Project::get(target_project_id, &**client, &redis)
+            .await?
+            .filter(|x| x.inner.deleted_at.is_none())
+            .ok_or_else(|| {
+                CreateError::InvalidInput("An invalid project id was supplied".to_string())
+            })?,
+        None => models::Project::get_id(source.inner.project_id, &**client, &redis)
+            .await?
+            .ok_or_else(|| {
+                CreateError::InvalidInput("An invalid project id was supplied".to_string())
+            })?,
+    };
+
+    if !can_upload_version(&user, source.inner.project_id, &client).await?
+        || !can_upload_version(&user, project.inner.id, &client).await?
+    {
+        return Err(CreateError::CustomAuthenticationError(
+            "You don't have permission to copy this version to this project!".to_string(),
+        ));
+    }
+
+    let mut transaction = client.begin().await?;
+
+    let all_loaders = models::loader_fields::Loader::list(&mut *transaction, &redis).await?;
+    let loaders = copy_data
+        .new_loaders
+        .iter()
+        .map(|x| {
+            all_loaders
+                .iter()
+                .find(|y| y.loader == x.0)
+                .cloned()
+                .ok_or_else(|| CreateError::InvalidLoader(x.0.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    check_loader_project_types(&loaders, &project.project_types)?;
+    let loader_ids = loaders.iter().map(|x| x.id).collect_vec();
+
+    let loader_fields = LoaderField::get_fields(&loader_ids, &mut *transaction, &redis).await?;
+    let mut loader_field_enum_values =
+        LoaderFieldEnumValue::list_many_loader_fields(&loader_fields, &mut *transaction, &redis)
+            .await?;
+
+    // Fields the new loaders do not have are dropped, the game versions are replaced
+    let mut fields = source
+        .version_fields
+        .iter()
+        .filter(|x| {
+            x.field_name != "game_versions" && loader_fields.iter().any(|y| y.field == x.field_name)
+        })
+        .map(|x| (x.field_name.clone(), x.value.serialize_internal()))
+        .collect::<HashMap<_, _>>();
+    fields.insert(
+        "game_versions".to_string(),
+        serde_json::json!(copy_data.new_game_versions),
+    );
+
+    let version_id = models::generate_version_id(&mut transaction).await?;
+    let version_fields = try_create_version_fields(
+        version_id.into(),
+        &fields,
+        &loader_fields,
+        &mut loader_field_enum_values,
+    )?;
+
+    let dependencies = source
+        .dependencies
+        .iter()
+        .map(|x| DependencyBuilder {
+            project_id: x.project_id,
+            version_id: x.version_id,
+            file_name: x.file_name.clone(),
+            dependency_type: x.dependency_type.clone(),
+        })
+        .collect_vec();
+
+    VersionBuilder {
+        version_id,
+        project_id: project.inner.id,
+        author_id: user.id.into(),
+        name: source.inner.name.clone(),
+        version_number: source.inner.version_number.clone(),
+        changelog: source.inner.changelog.clone(),
+        files: Vec::new(),
+        dependencies,
+        loaders: loader_ids,
+        version_fields,
+        version_type: source.inner.version_type.clone(),
+        featured: false,
+        status: VersionStatus::Draft,
+        requested_status: None,
+        ordering: None,
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    models::project_item::clear_project_caches(project.inner.id, project.inner.slug, &redis)
+        .await?;
+
+    let version = models::Version::get(version_id, &**client, &redis)
+        .await?
+        .ok_or_else(|| CreateError::InvalidInput("The copied version was not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(Version::from(version)))
+}
+
 // This function is used for adding a file to a version, uploading the initial
 // files for a version, and for uploading the initial version files for a project
 #[allow(clippy::too_many_arguments)]
@@ -759,9 +1148,11 @@ pub async fn upload_file(
     ignore_primary: bool,
     force_primary: bool,
     file_type: Option<FileType>,
+    is_archive_part: bool,
+    game_version_inference: Option<&GameVersionInference>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     redis: &RedisPool,
-) -> Result<(), CreateError> {
+) -> Result<Option<VersionField>, CreateError> {
     let (file_name, file_extension) = get_name_ext(content_disposition)?;
 
     if file_name.contains('/') {
@@ -771,20 +1162,36 @@ pub async fn upload_file(
     }
 
     let content_type = crate::util::ext::project_file_type(file_extension)
+        .or_else(|| {
+            (is_archive_part && crate::util::ext::is_archive_part_ext(file_extension))
+                .then_some("application/zip")
+        })
         .ok_or_else(|| CreateError::InvalidFileType(file_extension.to_string()))?;
 
-    let data = read_from_field(
+    let data = read_from_field_decoded(
         field, 500 * (1 << 20),
         "Project file exceeds the maximum of 500MiB. Contact a moderator or admin to request permission to upload larger files."
     ).await?;
 
+    let inferred_game_versions = match game_version_inference {
+        Some(inference) if !is_archive_part => {
+            inference.infer(version_id, &data, file_extension)?
+        }
+        _ => None,
+    };
+    let version_fields = version_fields
+        .iter()
+        .cloned()
+        .chain(inferred_game_versions.clone())
+        .collect::<Vec<_>>();
+
     let hash = sha1::Sha1::from(&data).hexdigest();
     let exists = sqlx::query!(
         "
         SELECT EXISTS(SELECT 1 FROM hashes h
         INNER JOIN files f ON f.id = h.file_id
         INNER JOIN versions v ON v.id = f.version_id
-        WHERE h.algorithm = $2 AND h.hash = $1 AND v.mod_id != $3)
+        WHERE h.algorithm = $2 AND h.hash = $1 AND v.mod_id != $3 AND v.deleted_at IS NULL)
         ",
         hash.as_bytes(),
         "sha1",
@@ -801,16 +1208,31 @@ pub async fn upload_file(
         ));
     }
 
-    let validation_result = validate_file(
-        data.clone().into(),
-        file_extension.to_string(),
-        loaders.clone(),
-        file_type,
-        version_fields.to_vec(),
-        &mut *transaction,
-        redis,
-    )
-    .await?;
+    // Modpacks must have a well-formed manifest whichever loaders the version is uploaded for. A
+    // missing one is only warned about, like the modpack validator does.
+    if file_extension == "mrpack" && !is_archive_part {
+        match crate::validate::modpack::validate_mrpack_manifest(&data) {
+            Ok(_) | Err(crate::validate::modpack::MrpackError::MissingManifest) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    // Parts of a split archive are not readable on their own, so they are never validated
+    // (and therefore never primary)
+    let validation_result = if is_archive_part {
+        ValidationResult::Warning("Parts of split archives cannot be validated")
+    } else {
+        validate_file(
+            data.clone().into(),
+            file_extension.to_string(),
+            loaders.clone(),
+            file_type,
+            version_fields.to_vec(),
+            &mut *transaction,
+            redis,
+        )
+        .await?
+    };
 
     if let ValidationResult::PassWithPackDataAndFiles {
         ref format,
@@ -830,7 +1252,7 @@ pub async fn upload_file(
                     SELECT v.id version_id, v.mod_id project_id, h.hash hash FROM hashes h
                     INNER JOIN files f on h.file_id = f.id
                     INNER JOIN versions v on f.version_id = v.id
-                    WHERE h.algorithm = 'sha1' AND h.hash = ANY($1)
+                    WHERE h.algorithm = 'sha1' AND h.hash = ANY($1) AND v.deleted_at IS NULL
                     ",
                 &*hashes
             )
@@ -920,6 +1342,7 @@ pub async fn upload_file(
     }
 
     version_files.push(VersionFileBuilder {
+        id: models::generate_file_id(&mut *transaction).await?,
         filename: file_name.to_string(),
         url: format!("{cdn_url}/{file_path_encode}"),
         hashes: vec![
@@ -939,9 +1362,11 @@ pub async fn upload_file(
         primary,
         size: upload_data.content_length,
         file_type,
+        part_group_id: None,
+        part_index: None,
     });
 
-    Ok(())
+    Ok(inferred_game_versions)
 }
 
 pub fn get_name_ext(
@@ -1006,3 +1431,82 @@ pub fn try_create_version_fields(
     }
     Ok(version_fields)
 }
+
+/// Project types of loaders which can be mixed within one project (eg: a mod that is also
+/// published as a plugin and a datapack)
+const MIXABLE_PROJECT_TYPES: &[&str] = &["mod", "plugin", "datapack"];
+
+// Ensures every loader of a version supports one of the project types the project already has,
+// or a type that can be mixed with it (eg: `mrpack` versions can only be added to modpacks).
+// Projects without any listed versions have no project type yet, so anything is accepted.
+pub fn check_loader_project_types(
+    loaders: &[models::loader_fields::Loader],
+    project_types: &[String],
+) -> Result<(), CreateError> {
+    if project_types.is_empty() {
+        return Ok(());
+    }
+
+    let is_compatible = |loader_type: &String| {
+        project_types.iter().any(|project_type| {
+            project_type == loader_type
+                || (MIXABLE_PROJECT_TYPES.contains(&&**project_type)
+                    && MIXABLE_PROJECT_TYPES.contains(&&**loader_type))
+        })
+    };
+    if loaders
+        .iter()
+        .all(|loader| loader.supported_project_types.iter().any(is_compatible))
+    {
+        return Ok(());
+    }
+
+    Err(CreateError::IncompatibleLoader(format!(
+        "Loaders {} cannot be used for a project of type {}",
+        loaders.iter().map(|x| format!("'{}'", x.loader)).join(", "),
+        project_types.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loader(name: &str, project_types: &[&str]) -> models::loader_fields::Loader {
+        models::loader_fields::Loader {
+            id: models::LoaderId(0),
+            loader: name.to_string(),
+            icon: String::new(),
+            supported_project_types: project_types.iter().map(|x| x.to_string()).collect(),
+            supported_games: vec!["minecraft".to_string()],
+            metadata: serde_json::Value::Null,
+            deprecated: false,
+            superseded_by: None,
+        }
+    }
+
+    #[test]
+    fn loaders_must_support_a_compatible_project_type() {
+        let project_types = ["mod".to_string(), "plugin".to_string()];
+        let fabric = loader("fabric", &["mod"]);
+        let paper = loader("paper", &["plugin"]);
+        let datapack = loader("datapack", &["datapack"]);
+        let mrpack = loader("mrpack", &["modpack"]);
+
+        assert!(check_loader_project_types(std::slice::from_ref(&fabric), &project_types).is_ok());
+        assert!(check_loader_project_types(&[paper], &project_types).is_ok());
+        assert!(
+            check_loader_project_types(std::slice::from_ref(&datapack), &project_types).is_ok()
+        );
+        assert!(check_loader_project_types(std::slice::from_ref(&mrpack), &project_types).is_err());
+        assert!(check_loader_project_types(
+            std::slice::from_ref(&fabric),
+            &["modpack".to_string()]
+        )
+        .is_err());
+        assert!(
+            check_loader_project_types(&[mrpack.clone(), fabric.clone()], &project_types).is_err()
+        );
+        assert!(check_loader_project_types(&[mrpack, datapack], &[]).is_ok());
+    }
+}
@@ -1,25 +1,42 @@
 use super::ApiError;
 use crate::auth::checks::{filter_visible_versions, is_visible_version};
 use crate::auth::{filter_visible_projects, get_user_from_headers};
+use crate::database::models::version_item::QueryVersion;
 use crate::database::redis::RedisPool;
 use crate::models::ids::VersionId;
 use crate::models::pats::Scopes;
 use crate::models::projects::VersionType;
 use crate::models::teams::ProjectPermissions;
 use crate::queue::session::AuthQueue;
+use crate::util::head::is_head_request;
+use crate::util::headers::{parse_byte_range, ByteRange};
+use crate::util::validate::validation_errors_to_string;
 use crate::{database, models};
+use actix_web::http::header::{
+    ContentDisposition, DispositionParam, DispositionType, ACCEPT_RANGES, CONTENT_RANGE,
+    CONTENT_TYPE, RANGE,
+};
 use actix_web::{web, HttpRequest, HttpResponse};
+use bytes::Bytes;
 use dashmap::DashMap;
 use futures::TryStreamExt;
 use itertools::Itertools;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("version_file")
             .route("{version_id}", web::get().to(get_version_from_hash))
+            .route(
+                "{version_id}/update",
+                web::get().to(get_update_from_hash_query),
+            )
             .route("{version_id}/update", web::post().to(get_update_from_hash))
             .route("project", web::post().to(get_projects_from_hashes))
             .route("{version_id}", web::delete().to(delete_file))
@@ -33,6 +50,17 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     );
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/version_file/{hash}",
+    tag = "version files",
+    params(("hash" = String, Path, description = "The hash of the file"), HashQuery),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The version the file belongs to", body = models::projects::Version),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn get_version_from_hash(
     req: HttpRequest,
     info: web::Path<(String,)>,
@@ -80,9 +108,12 @@ pub async fn get_version_from_hash(
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct HashQuery {
+    /// The algorithm of the hash, `sha1` or `sha512`. Defaults to a guess based on the hash length
     pub algorithm: Option<String>, // Defaults to calculation based on size of hash
+    /// The version to look the file up in, if the hash is shared by several versions
     pub version_id: Option<VersionId>,
 }
 
@@ -134,65 +165,167 @@ pub async fn get_update_from_hash(
     .map(|x| x.1)
     .ok();
     let hash = info.into_inner().0.to_lowercase();
-    if let Some(file) = database::models::Version::get_file_from_hash(
+
+    if let Some((_, latest)) =
+        find_update_from_hash(hash, &hash_query, &update_data, &pool, &redis).await?
+    {
+        if !is_visible_version(&latest.inner, &user_option, &pool, &redis).await? {
+            return Err(ApiError::NotFound);
+        }
+
+        return Ok(HttpResponse::Ok().json(models::projects::Version::from(latest)));
+    }
+    Err(ApiError::NotFound)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateQuery {
+    pub algorithm: Option<String>, // Defaults to calculation based on size of hash
+    pub version_id: Option<VersionId>,
+    /// JSON arrays of the constraints the update must match
+    pub loaders: Option<String>,
+    pub game_versions: Option<String>,
+    pub version_types: Option<String>,
+}
+
+// Same as `get_update_from_hash`, but with the constraints in the query, and returning
+// no content when the version the file belongs to is already the latest one
+pub async fn get_update_from_hash_query(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    web::Query(update_query): web::Query<UpdateQuery>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+    let hash = info.into_inner().0.to_lowercase();
+
+    let hash_query = HashQuery {
+        algorithm: update_query.algorithm,
+        version_id: update_query.version_id,
+    };
+    let update_data = UpdateData {
+        loaders: update_query
+            .loaders
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?,
+        version_types: update_query
+            .version_types
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?,
+        loader_fields: update_query
+            .game_versions
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?
+            .map(|game_versions| HashMap::from([("game_versions".to_string(), game_versions)])),
+    };
+
+    if let Some((current, latest)) =
+        find_update_from_hash(hash, &hash_query, &update_data, &pool, &redis).await?
+    {
+        // Checked before the no content response, so it doesn't reveal hidden versions
+        if !is_visible_version(&current.inner, &user_option, &pool, &redis).await?
+            || !is_visible_version(&latest.inner, &user_option, &pool, &redis).await?
+        {
+            return Err(ApiError::NotFound);
+        }
+
+        if latest <= current {
+            return Ok(HttpResponse::NoContent().body(""));
+        }
+
+        return Ok(HttpResponse::Ok().json(models::projects::Version::from(latest)));
+    }
+    Err(ApiError::NotFound)
+}
+
+// Finds the version the file with the hash belongs to, and the latest version of its project
+// matching the update constraints
+async fn find_update_from_hash(
+    hash: String,
+    hash_query: &HashQuery,
+    update_data: &UpdateData,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<Option<(QueryVersion, QueryVersion)>, ApiError> {
+    let Some(file) = database::models::Version::get_file_from_hash(
         hash_query
             .algorithm
             .clone()
             .unwrap_or_else(|| default_algorithm_from_hashes(&[hash.clone()])),
         hash,
         hash_query.version_id.map(|x| x.into()),
-        &**pool,
-        &redis,
+        pool,
+        redis,
     )
     .await?
-    {
-        if let Some(project) =
-            database::models::Project::get_id(file.project_id, &**pool, &redis).await?
-        {
-            let versions = database::models::Version::get_many(&project.versions, &**pool, &redis)
-                .await?
-                .into_iter()
-                .filter(|x| {
-                    let mut bool = true;
-                    if let Some(version_types) = &update_data.version_types {
-                        bool &= version_types
-                            .iter()
-                            .any(|y| y.as_str() == x.inner.version_type);
-                    }
-                    if let Some(loaders) = &update_data.loaders {
-                        bool &= x.loaders.iter().any(|y| loaders.contains(y));
-                    }
-                    if let Some(loader_fields) = &update_data.loader_fields {
-                        for (key, values) in loader_fields {
-                            bool &= if let Some(x_vf) =
-                                x.version_fields.iter().find(|y| y.field_name == *key)
-                            {
-                                values.iter().any(|v| x_vf.value.contains_json_value(v))
-                            } else {
-                                true
-                            };
-                        }
-                    }
-                    bool
-                })
-                .sorted();
-
-            if let Some(first) = versions.last() {
-                if !is_visible_version(&first.inner, &user_option, &pool, &redis).await? {
-                    return Err(ApiError::NotFound);
-                }
-
-                return Ok(HttpResponse::Ok().json(models::projects::Version::from(first)));
+    else {
+        return Ok(None);
+    };
+
+    let Some(project) = database::models::Project::get_id(file.project_id, pool, redis).await?
+    else {
+        return Ok(None);
+    };
+
+    let mut versions = database::models::Version::get_many(&project.versions, pool, redis).await?;
+    let current = versions
+        .iter()
+        .find(|x| x.inner.id == file.version_id)
+        .cloned();
+
+    versions.retain(|x| {
+        let mut bool = true;
+        if let Some(version_types) = &update_data.version_types {
+            bool &= version_types
+                .iter()
+                .any(|y| y.as_str() == x.inner.version_type);
+        }
+        if let Some(loaders) = &update_data.loaders {
+            bool &= x.loaders.iter().any(|y| loaders.contains(y));
+        }
+        if let Some(loader_fields) = &update_data.loader_fields {
+            for (key, values) in loader_fields {
+                bool &= if let Some(x_vf) = x.version_fields.iter().find(|y| y.field_name == *key) {
+                    values.iter().any(|v| x_vf.value.contains_json_value(v))
+                } else {
+                    true
+                };
             }
         }
-    }
-    Err(ApiError::NotFound)
+        bool
+    });
+
+    let current = match current {
+        Some(current) => current,
+        // The file's version is not listed, so it is not part of the project's versions
+        None => match database::models::Version::get(file.version_id, pool, redis).await? {
+            Some(current) => current,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(versions.into_iter().max().map(|latest| (current, latest)))
 }
 
 // Requests above with multiple versions below
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct FileHashes {
     pub algorithm: Option<String>, // Defaults to calculation based on size of hash
+    #[validate(length(max = 1000))]
     pub hashes: Vec<String>,
 }
 
@@ -214,6 +347,10 @@ pub async fn get_versions_from_hashes(
     .map(|x| x.1)
     .ok();
 
+    file_data
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
     let algorithm = file_data
         .algorithm
         .clone()
@@ -267,6 +404,10 @@ pub async fn get_projects_from_hashes(
     .map(|x| x.1)
     .ok();
 
+    file_data
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
     let algorithm = file_data
         .algorithm
         .clone()
@@ -336,7 +477,7 @@ pub async fn update_files(
         INNER JOIN loader_field_enum_values lfev ON vf.enum_value = lfev.id AND (cardinality($2::varchar[]) = 0 OR lfev.value = ANY($2::varchar[]))
         INNER JOIN loaders_versions lv ON lv.version_id = v.id
         INNER JOIN loaders l on lv.loader_id = l.id AND (cardinality($3::varchar[]) = 0 OR l.loader = ANY($3::varchar[]))
-        WHERE v.mod_id = ANY($1) AND (cardinality($4::varchar[]) = 0 OR v.version_type = ANY($4))
+        WHERE v.mod_id = ANY($1) AND v.deleted_at IS NULL AND (cardinality($4::varchar[]) = 0 OR v.version_type = ANY($4))
         ORDER BY v.date_published ASC
         ",
         &files.iter().map(|x| x.project_id.0).collect::<Vec<_>>(),
@@ -597,7 +738,7 @@ pub async fn delete_file(
         sqlx::query!(
             "
             DELETE FROM hashes
-            WHERE file_id = $1
+            WHERE file_id IN (SELECT id FROM files WHERE id = $1 OR file_part_group_id = $1)
             ",
             row.id.0
         )
@@ -622,12 +763,25 @@ pub async fn delete_file(
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DownloadRedirect {
     pub url: String,
 }
 
 // under /api/v1/version_file/{hash}/download
+#[utoipa::path(
+    get,
+    path = "/v3/version_file/{hash}/download",
+    tag = "version files",
+    params(("hash" = String, Path, description = "The hash of the file"), HashQuery),
+    security((), ("pat" = [])),
+    responses(
+        (status = 206, description = "The range of the file requested with a `Range` header, streamed from the CDN"),
+        (status = 307, description = "A redirect to the file on the CDN", body = DownloadRedirect),
+        (status = 416, description = "The requested range is outside of the file"),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn download_version(
     req: HttpRequest,
     info: web::Path<(String,)>,
@@ -636,11 +790,70 @@ pub async fn download_version(
     hash_query: web::Query<HashQuery>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
+    let file = get_download_file(&req, info, &pool, &redis, &hash_query, &session_queue).await?;
+
+    if is_head_request(&req) {
+        return Ok(download_headers(file));
+    }
+
+    // Ranges are proxied from the CDN, as redirects can't be partial
+    if let Some(range) = req
+        .headers()
+        .get(RANGE)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| parse_byte_range(x, file.size as u64))
+    {
+        let Ok(range) = range else {
+            return Ok(range_not_satisfiable(&file));
+        };
+
+        if let Some(response) = proxy_file_range(&file, range).await {
+            return Ok(response);
+        }
+    }
+
+    // The `Content-Length` of a redirect is that of its own body, so the size of the file is only
+    // sent in the `Content-Length` of `HEAD` requests and the `Content-Range` of ranges
+    Ok(HttpResponse::TemporaryRedirect()
+        .append_header(("Location", &*file.url))
+        .insert_header((ACCEPT_RANGES, "bytes"))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(file.filename)],
+        })
+        .json(DownloadRedirect { url: file.url }))
+}
+
+/// Answers `HEAD` requests for a download with the headers of the file, so clients can check its
+/// size without being redirected
+fn download_headers(file: database::models::version_item::SingleFile) -> HttpResponse {
+    // A streamed body without chunking keeps this `Content-Length`, while actix replaces it with
+    // the size of any other body. The body itself is never sent for `HEAD` requests.
+    HttpResponse::Ok()
+        .insert_header((ACCEPT_RANGES, "bytes"))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(file.filename)],
+        })
+        .insert_header((CONTENT_TYPE, "application/octet-stream"))
+        .no_chunking(file.size as u64)
+        .streaming(futures::stream::empty::<Result<Bytes, ApiError>>())
+}
+
+/// Finds a file to download by its hash, checking its version is visible to the user
+async fn get_download_file(
+    req: &HttpRequest,
+    info: web::Path<(String,)>,
+    pool: &PgPool,
+    redis: &RedisPool,
+    hash_query: &HashQuery,
+    session_queue: &AuthQueue,
+) -> Result<database::models::version_item::SingleFile, ApiError> {
     let user_option = get_user_from_headers(
-        &req,
-        &**pool,
-        &redis,
-        &session_queue,
+        req,
+        pool,
+        redis,
+        session_queue,
         Some(&[Scopes::VERSION_READ]),
     )
     .await
@@ -656,26 +869,93 @@ pub async fn download_version(
         algorithm.clone(),
         hash,
         hash_query.version_id.map(|x| x.into()),
-        &**pool,
-        &redis,
+        pool,
+        redis,
     )
-    .await?;
+    .await?
+    .ok_or(ApiError::NotFound)?;
 
-    if let Some(file) = file {
-        let version = database::models::Version::get(file.version_id, &**pool, &redis).await?;
+    let version = database::models::Version::get(file.version_id, pool, redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_version(&version.inner, &user_option, pool, redis).await? {
+        return Err(ApiError::NotFound);
+    }
 
-        if let Some(version) = version {
-            if !is_visible_version(&version.inner, &user_option, &pool, &redis).await? {
-                return Err(ApiError::NotFound);
-            }
+    Ok(file)
+}
 
-            Ok(HttpResponse::TemporaryRedirect()
-                .append_header(("Location", &*file.url))
-                .json(DownloadRedirect { url: file.url }))
-        } else {
-            Err(ApiError::NotFound)
+/// Answers a download whose range is outside of the file
+fn range_not_satisfiable(file: &database::models::version_item::SingleFile) -> HttpResponse {
+    HttpResponse::RangeNotSatisfiable()
+        .insert_header((CONTENT_RANGE, format!("bytes */{}", file.size)))
+        .finish()
+}
+
+/// How long connecting to the CDN and streaming a range of a file from it may take, so a stalled
+/// CDN cannot hold a download open forever
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const PROXY_TIMEOUT: Duration = Duration::from_secs(300);
+
+lazy_static! {
+    static ref PROXY_CLIENT: reqwest::Client = reqwest::Client::builder()
+        .connect_timeout(PROXY_CONNECT_TIMEOUT)
+        .timeout(PROXY_TIMEOUT)
+        .build()
+        .unwrap();
+}
+
+/// Streams a range of a file from the CDN. A range the CDN can't satisfy is answered with a `416`,
+/// and `None` is returned if the CDN fails or does not support ranges, so the whole file can be
+/// redirected to instead.
+async fn proxy_file_range(
+    file: &database::models::version_item::SingleFile,
+    range: ByteRange,
+) -> Option<HttpResponse> {
+    let response = match PROXY_CLIENT
+        .get(&file.url)
+        .header(RANGE, format!("bytes={}-{}", range.start, range.end))
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(err) => {
+            log::warn!(
+                "Failed to fetch a range of {} from the CDN: {}",
+                file.url,
+                err
+            );
+            return None;
         }
-    } else {
-        Err(ApiError::NotFound)
+    };
+
+    match response.status() {
+        reqwest::StatusCode::PARTIAL_CONTENT => {}
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE => return Some(range_not_satisfiable(file)),
+        _ => return None,
     }
+
+    let body = futures::stream::unfold(Some(response), |response| async move {
+        let mut response = response?;
+        match response.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+            Ok(None) => None,
+            Err(err) => Some((Err(ApiError::from(err)), None)),
+        }
+    });
+
+    Some(
+        HttpResponse::PartialContent()
+            .insert_header((ACCEPT_RANGES, "bytes"))
+            .insert_header((
+                CONTENT_RANGE,
+                format!("bytes {}-{}/{}", range.start, range.end, file.size),
+            ))
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(file.filename.clone())],
+            })
+            .no_chunking(range.end - range.start + 1)
+            .streaming(body),
+    )
 }
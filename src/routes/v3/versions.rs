@@ -1,32 +1,42 @@
 use std::collections::HashMap;
 
 use super::ApiError;
-use crate::auth::checks::{filter_visible_versions, is_visible_project, is_visible_version};
-use crate::auth::get_user_from_headers;
+use crate::auth::checks::{
+    filter_visible_version_ids, filter_visible_versions, is_visible_project, is_visible_version,
+};
+use crate::auth::{check_is_moderator_from_headers, get_user_from_headers};
 use crate::database;
+use crate::database::models::download_history_item::DownloadHistory;
+use crate::database::models::legacy_loader_fields::MinecraftGameVersion;
 use crate::database::models::loader_fields::{
     self, LoaderField, LoaderFieldEnumValue, VersionField,
 };
+use crate::database::models::moderation_log_item::ModerationLogBuilder;
+use crate::database::models::notification_item::NotificationBuilder;
+use crate::database::models::project_audit_log_item::ProjectAuditLogBuilder;
+use crate::database::models::project_item::clear_project_caches;
 use crate::database::models::version_item::{DependencyBuilder, LoaderVersion};
-use crate::database::models::{image_item, Organization};
+use crate::database::models::Organization;
 use crate::database::redis::RedisPool;
 use crate::models;
 use crate::models::ids::base62_impl::parse_base62;
 use crate::models::ids::VersionId;
 use crate::models::images::ImageContext;
+use crate::models::notifications::NotificationBody;
 use crate::models::pats::Scopes;
 use crate::models::projects::{skip_nulls, Loader};
 use crate::models::projects::{Dependency, FileType, VersionStatus, VersionType};
 use crate::models::teams::ProjectPermissions;
 use crate::queue::session::AuthQueue;
-use crate::search::indexing::remove_documents;
+use crate::search::indexing::retry::remove_documents_or_retry;
 use crate::search::SearchConfig;
 use crate::util::img;
-use crate::util::validate::validation_errors_to_string;
+use crate::util::validate::{validate_string_length, validation_errors_to_string};
 use actix_web::{web, HttpRequest, HttpResponse};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use utoipa::IntoParams;
 use validator::Validate;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -41,6 +51,33 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("{id}", web::get().to(version_get))
             .route("{id}", web::patch().to(version_edit))
             .route("{id}", web::delete().to(version_delete))
+            .route("{id}/restore", web::post().to(version_restore))
+            .route("{id}/promote", web::post().to(version_promote))
+            .route(
+                "{id}/copy",
+                web::post().to(super::version_creation::version_copy),
+            )
+            .route(
+                "{id}/review-status",
+                web::patch().to(version_review_status_edit),
+            )
+            .route("{id}/loaders", web::patch().to(version_loaders_edit))
+            .route(
+                "{id}/primary_file/{file_id}",
+                web::patch().to(version_primary_file_edit),
+            )
+            .route(
+                "{id}/download-stats",
+                web::get().to(version_download_stats_get),
+            )
+            .route(
+                "{id}/game_versions",
+                web::patch().to(version_game_versions_edit),
+            )
+            .route(
+                "{id}/dependencies",
+                web::patch().to(version_dependencies_edit),
+            )
             .route(
                 "{version_id}/file",
                 web::post().to(super::version_creation::upload_file_to_version),
@@ -102,11 +139,21 @@ pub async fn version_project_get_helper(
     Err(ApiError::NotFound)
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct VersionIds {
+    /// A JSON array of version IDs
     pub ids: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/versions",
+    tag = "versions",
+    params(VersionIds),
+    security((), ("pat" = [])),
+    responses((status = 200, description = "The requested versions", body = Vec<models::projects::Version>)),
+)]
 pub async fn versions_get(
     req: HttpRequest,
     web::Query(ids): web::Query<VersionIds>,
@@ -136,6 +183,17 @@ pub async fn versions_get(
     Ok(HttpResponse::Ok().json(versions))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v3/version/{id}",
+    tag = "versions",
+    params(("id" = String, Path, description = "The ID of the version")),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The requested version", body = models::projects::Version),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
 pub async fn version_get(
     req: HttpRequest,
     info: web::Path<(models::ids::VersionId,)>,
@@ -183,12 +241,8 @@ pub struct EditVersion {
         custom(function = "crate::util::validate::validate_name")
     )]
     pub name: Option<String>,
-    #[validate(
-        length(min = 1, max = 32),
-        regex = "crate::util::validate::RE_URL_SAFE"
-    )]
+    #[validate(regex = "crate::util::validate::RE_URL_SAFE")]
     pub version_number: Option<String>,
-    #[validate(length(max = 65536))]
     pub changelog: Option<String>,
     pub version_type: Option<models::projects::VersionType>,
     #[validate(
@@ -217,6 +271,34 @@ pub struct EditVersion {
     pub fields: HashMap<String, serde_json::Value>,
 }
 
+// Ensures every split archive in a version has contiguous parts (1, 2, ..., n), so that none
+// are missing when it is published
+fn check_file_parts_complete(
+    files: &[database::models::version_item::QueryFile],
+) -> Result<(), ApiError> {
+    for group in files.iter().filter(|x| x.part_group_id.is_none()) {
+        let indices = files
+            .iter()
+            .filter(|x| x.part_group_id == Some(group.id))
+            .filter_map(|x| x.part_index)
+            .sorted()
+            .collect::<Vec<_>>();
+
+        if indices
+            .iter()
+            .zip(1..)
+            .any(|(index, expected)| *index != expected)
+        {
+            return Err(ApiError::InvalidInput(format!(
+                "The split archive {} is missing some of its parts",
+                group.filename
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EditVersionFileType {
     pub algorithm: String,
@@ -261,6 +343,12 @@ pub async fn version_edit_helper(
     .await?
     .1;
 
+    if let Some(version_number) = &new_version.version_number {
+        validate_string_length("version_number", version_number, 1, 32)?;
+    }
+    if let Some(changelog) = &new_version.changelog {
+        validate_string_length("changelog", changelog, 0, 65535)?;
+    }
     new_version
         .validate()
         .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
@@ -270,6 +358,16 @@ pub async fn version_edit_helper(
 
     let result = database::models::Version::get(id, &**pool, &redis).await?;
 
+    // Versions of deleted projects can no longer be changed
+    if let Some(version_item) = &result {
+        if database::models::Project::get_id(version_item.inner.project_id, &**pool, &redis)
+            .await?
+            .is_some_and(|x| x.inner.deleted_at.is_some())
+        {
+            return Err(ApiError::NotFound);
+        }
+    }
+
     if let Some(version_item) = result {
         let team_member = database::models::TeamMember::get_from_user_id_project(
             version_item.inner.project_id,
@@ -443,6 +541,39 @@ pub async fn version_edit_helper(
             }
 
             if let Some(loaders) = &new_version.loaders {
+                let all_loaders = loader_fields::Loader::list(&mut *transaction, &redis).await?;
+                let loaders = loaders
+                    .iter()
+                    .map(|loader| {
+                        all_loaders
+                            .iter()
+                            .find(|x| x.loader == loader.0)
+                            .cloned()
+                            .ok_or_else(|| {
+                                ApiError::InvalidInput(
+                                    "No database entry for loader provided.".to_string(),
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // The project types come from the listed versions, this one included, so a
+                // project without other versions can switch to any type
+                let project = database::models::Project::get_id(
+                    version_item.inner.project_id,
+                    &mut *transaction,
+                    &redis,
+                )
+                .await?
+                .ok_or(ApiError::NotFound)?;
+                let project_types = if project.versions.iter().all(|x| *x == id) {
+                    &[][..]
+                } else {
+                    &project.project_types[..]
+                };
+                super::version_creation::check_loader_project_types(&loaders, project_types)
+                    .map_err(|err| ApiError::InvalidInput(err.to_string()))?;
+
                 sqlx::query!(
                     "
                     DELETE FROM loaders_versions WHERE version_id = $1
@@ -452,25 +583,15 @@ pub async fn version_edit_helper(
                 .execute(&mut *transaction)
                 .await?;
 
-                let mut loader_versions = Vec::new();
-                for loader in loaders {
-                    let loader_id = database::models::loader_fields::Loader::get_id(
-                        &loader.0,
-                        &mut *transaction,
-                        &redis,
-                    )
-                    .await?
-                    .ok_or_else(|| {
-                        ApiError::InvalidInput("No database entry for loader provided.".to_string())
-                    })?;
-                    loader_versions.push(LoaderVersion::new(loader_id, id));
-                }
+                let loader_versions = loaders
+                    .iter()
+                    .map(|loader| LoaderVersion::new(loader.id, id))
+                    .collect();
                 LoaderVersion::insert_many(loader_versions, &mut transaction).await?;
 
-                crate::database::models::Project::clear_cache(
+                crate::database::models::project_item::clear_project_caches(
                     version_item.inner.project_id,
                     None,
-                    None,
                     &redis,
                 )
                 .await?;
@@ -495,10 +616,11 @@ pub async fn version_edit_helper(
                     "
                     SELECT f.id id FROM hashes h
                     INNER JOIN files f ON h.file_id = f.id
-                    WHERE h.algorithm = $2 AND h.hash = $1
+                    WHERE h.algorithm = $2 AND h.hash = $1 AND f.version_id = $3
                     ",
                     primary_file.1.as_bytes(),
-                    primary_file.0
+                    primary_file.0,
+                    id as database::models::ids::VersionId,
                 )
                 .fetch_optional(&**pool)
                 .await?
@@ -587,6 +709,16 @@ pub async fn version_edit_helper(
                     ));
                 }
 
+                if version_item.inner.status == VersionStatus::Rejected && !user.role.is_mod() {
+                    return Err(ApiError::CustomAuthentication(
+                        "Only moderators can change the status of a rejected version!".to_string(),
+                    ));
+                }
+
+                if *status != VersionStatus::Draft {
+                    check_file_parts_complete(&version_item.files)?;
+                }
+
                 sqlx::query!(
                     "
                     UPDATE versions
@@ -661,10 +793,9 @@ pub async fn version_edit_helper(
 
             transaction.commit().await?;
             database::models::Version::clear_cache(&version_item, &redis).await?;
-            database::models::Project::clear_cache(
+            database::models::project_item::clear_project_caches(
                 version_item.inner.project_id,
                 None,
-                Some(true),
                 &redis,
             )
             .await?;
@@ -679,34 +810,32 @@ pub async fn version_edit_helper(
     }
 }
 
+const LATEST_FILE_CACHE_EXPIRY: i64 = 5 * 60;
+/// How long the versions of a project for a game version are cached, in seconds
+const GAME_VERSION_VERSIONS_CACHE_EXPIRY: i64 = 2 * 60;
+
 #[derive(Serialize, Deserialize)]
-pub struct VersionListFilters {
-    pub loaders: Option<String>,
-    pub featured: Option<bool>,
+pub struct LatestFileQuery {
+    pub loader: Option<String>,
+    pub game_version: Option<String>,
+    /// Defaults to `release`
     pub version_type: Option<VersionType>,
-    pub limit: Option<usize>,
-    pub offset: Option<usize>,
-    /*
-        Loader fields to filter with:
-        "game_versions": ["1.16.5", "1.17"]
-
-        Returns if it matches any of the values
-    */
-    pub loader_fields: Option<String>,
+    /// Version type to try when no version of `version_type` matches
+    pub fallback: Option<VersionType>,
 }
 
-pub async fn version_list(
+// Redirects to the primary file of the latest version of a project matching the query.
+// Only resolutions made for anonymous users are cached, as they can't see hidden versions.
+pub async fn project_latest_file_get(
     req: HttpRequest,
     info: web::Path<(String,)>,
-    web::Query(filters): web::Query<VersionListFilters>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
+    web::Query(query): web::Query<LatestFileQuery>,
     session_queue: web::Data<AuthQueue>,
 ) -> Result<HttpResponse, ApiError> {
     let string = info.into_inner().0;
 
-    let result = database::models::Project::get(&string, &**pool, &redis).await?;
-
     let user_option = get_user_from_headers(
         &req,
         &**pool,
@@ -718,202 +847,1233 @@ pub async fn version_list(
     .map(|x| x.1)
     .ok();
 
-    if let Some(project) = result {
-        if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
-            return Err(ApiError::NotFound);
-        }
+    let project = database::models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
 
-        let loader_field_filters = filters.loader_fields.as_ref().map(|x| {
-            serde_json::from_str::<HashMap<String, Vec<serde_json::Value>>>(x).unwrap_or_default()
-        });
-        let loader_filters = filters
-            .loaders
-            .as_ref()
-            .map(|x| serde_json::from_str::<Vec<String>>(x).unwrap_or_default());
-        let mut versions = database::models::Version::get_many(&project.versions, &**pool, &redis)
-            .await?
-            .into_iter()
-            .skip(filters.offset.unwrap_or(0))
-            .take(filters.limit.unwrap_or(usize::MAX))
-            .filter(|x| {
-                let mut bool = true;
+    let version_type = query.version_type.unwrap_or(VersionType::Release);
+    let cache_key = format!(
+        "{}:{}:{}:{}:{}",
+        project.inner.id.0,
+        query.loader.as_deref().unwrap_or_default(),
+        query.game_version.as_deref().unwrap_or_default(),
+        version_type.as_str(),
+        query.fallback.map(|x| x.as_str()).unwrap_or_default(),
+    );
 
-                if let Some(version_type) = filters.version_type {
-                    bool &= &*x.inner.version_type == version_type.as_str();
-                }
-                if let Some(loaders) = &loader_filters {
-                    bool &= x.loaders.iter().any(|y| loaders.contains(y));
-                }
-                if let Some(loader_fields) = &loader_field_filters {
-                    for (key, values) in loader_fields {
-                        bool &= if let Some(x_vf) =
-                            x.version_fields.iter().find(|y| y.field_name == *key)
-                        {
-                            values.iter().any(|v| x_vf.value.contains_json_value(v))
-                        } else {
-                            true
-                        };
-                    }
-                }
-                bool
-            })
-            .collect::<Vec<_>>();
+    let mut redis_conn = redis.connect().await?;
+    if user_option.is_none() {
+        if let Some(url) = redis_conn
+            .get(
+                database::models::project_item::PROJECTS_LATEST_FILES_NAMESPACE,
+                &cache_key,
+            )
+            .await?
+        {
+            return Ok(HttpResponse::Found()
+                .append_header(("Location", url))
+                .body(""));
+        }
+    }
 
-        let mut response = versions
+    let versions = database::models::Version::get_many(&project.versions, &**pool, &redis).await?;
+    let visible_version_ids = filter_visible_version_ids(
+        versions.iter().map(|x| &x.inner).collect_vec(),
+        &user_option,
+        &pool,
+        &redis,
+    )
+    .await?;
+
+    let game_version = query
+        .game_version
+        .as_ref()
+        .map(|x| serde_json::Value::String(x.clone()));
+    let latest_of_type = |version_type: VersionType| {
+        versions
             .iter()
-            .filter(|version| {
-                filters
-                    .featured
-                    .map(|featured| featured == version.inner.featured)
-                    .unwrap_or(true)
+            .filter(|x| {
+                visible_version_ids.contains(&x.inner.id)
+                    && x.inner.version_type == version_type.as_str()
+                    && query
+                        .loader
+                        .as_ref()
+                        .map(|loader| x.loaders.contains(loader))
+                        .unwrap_or(true)
+                    && game_version
+                        .as_ref()
+                        .map(|game_version| {
+                            x.version_fields.iter().any(|f| {
+                                f.field_name == "game_versions"
+                                    && f.value.contains_json_value(game_version)
+                            })
+                        })
+                        .unwrap_or(true)
             })
-            .cloned()
-            .collect::<Vec<_>>();
+            .max()
+    };
 
-        versions.sort_by(|a, b| b.inner.date_published.cmp(&a.inner.date_published));
+    let Some(file) = latest_of_type(version_type)
+        .or_else(|| query.fallback.and_then(latest_of_type))
+        .and_then(|x| {
+            x.files
+                .iter()
+                .find(|f| f.primary && f.part_group_id.is_none())
+                .or_else(|| x.files.iter().find(|f| f.part_group_id.is_none()))
+        })
+    else {
+        return Err(ApiError::NotFound);
+    };
 
-        // Attempt to populate versions with "auto featured" versions
-        if response.is_empty() && !versions.is_empty() && filters.featured.unwrap_or(false) {
-            // TODO: This is a bandaid fix for detecting auto-featured versions.
-            // In the future, not all versions will have 'game_versions' fields, so this will need to be changed.
-            let (loaders, game_versions) = futures::future::try_join(
-                database::models::loader_fields::Loader::list(&**pool, &redis),
-                database::models::legacy_loader_fields::MinecraftGameVersion::list(
-                    None,
-                    Some(true),
-                    &**pool,
-                    &redis,
-                ),
+    if user_option.is_none() {
+        redis_conn
+            .set(
+                database::models::project_item::PROJECTS_LATEST_FILES_NAMESPACE,
+                &cache_key,
+                &file.url,
+                Some(LATEST_FILE_CACHE_EXPIRY),
             )
             .await?;
+    }
 
-            let mut joined_filters = Vec::new();
-            for game_version in &game_versions {
-                for loader in &loaders {
-                    joined_filters.push((game_version, loader))
-                }
-            }
-
-            joined_filters.into_iter().for_each(|filter| {
-                versions
-                    .iter()
-                    .find(|version| {
-                        // TODO: This is the bandaid fix for detecting auto-featured versions.
-                        let game_versions = version
-                            .version_fields
-                            .iter()
-                            .find(|vf| vf.field_name == "game_versions")
-                            .map(|vf| vf.value.clone())
-                            .map(|v| v.as_strings())
-                            .unwrap_or_default();
-                        game_versions.contains(&filter.0.version)
-                            && version.loaders.contains(&filter.1.loader)
-                    })
-                    .map(|version| response.push(version.clone()))
-                    .unwrap_or(());
-            });
+    Ok(HttpResponse::Found()
+        .append_header(("Location", &*file.url))
+        .body(""))
+}
 
-            if response.is_empty() {
-                versions
-                    .into_iter()
-                    .for_each(|version| response.push(version));
-            }
-        }
+#[derive(Serialize, Deserialize, Default)]
+pub struct EditVersionSet {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
 
-        response.sort_by(|a, b| b.inner.date_published.cmp(&a.inner.date_published));
-        response.dedup_by(|a, b| a.inner.id == b.inner.id);
+impl EditVersionSet {
+    /// Applies the additions and removals to the current values, keeping their order
+    pub(crate) fn apply(&self, current: Vec<String>) -> Vec<String> {
+        current
+            .into_iter()
+            .chain(self.add.iter().cloned())
+            .filter(|x| !self.remove.contains(x))
+            .unique()
+            .collect()
+    }
+}
 
-        let response = filter_visible_versions(response, &user_option, &pool, &redis).await?;
+pub async fn version_loaders_edit(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    config: web::Data<SearchConfig>,
+    edit: web::Json<EditVersionSet>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(version) =
+        get_version_for_set_edit(&req, info.0, &pool, &redis, &session_queue).await?
+    else {
+        return Err(ApiError::NotFound);
+    };
 
-        Ok(HttpResponse::Ok().json(response))
-    } else {
-        Err(ApiError::NotFound)
+    let loaders = edit.apply(version.loaders.clone());
+    if loaders.is_empty() {
+        return Ok(empty_version_set_response("loaders"));
     }
+
+    let new_version = EditVersion {
+        loaders: Some(loaders.into_iter().map(Loader).collect()),
+        ..Default::default()
+    };
+    version_edit_helper(
+        req,
+        info.into_inner(),
+        pool.clone(),
+        redis.clone(),
+        new_version,
+        session_queue,
+    )
+    .await?;
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &config,
+        &[version.inner.project_id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
 }
 
-pub async fn version_delete(
+pub async fn version_game_versions_edit(
     req: HttpRequest,
     info: web::Path<(VersionId,)>,
     pool: web::Data<PgPool>,
     redis: web::Data<RedisPool>,
+    config: web::Data<SearchConfig>,
+    edit: web::Json<EditVersionSet>,
     session_queue: web::Data<AuthQueue>,
-    search_config: web::Data<SearchConfig>,
 ) -> Result<HttpResponse, ApiError> {
-    let user = get_user_from_headers(
-        &req,
-        &**pool,
+    let Some(version) =
+        get_version_for_set_edit(&req, info.0, &pool, &redis, &session_queue).await?
+    else {
+        return Err(ApiError::NotFound);
+    };
+
+    let current = version
+        .version_fields
+        .iter()
+        .find(|vf| vf.field_name == "game_versions")
+        .map(|vf| vf.value.as_strings())
+        .unwrap_or_default();
+    let game_versions = edit.apply(current);
+    if game_versions.is_empty() {
+        return Ok(empty_version_set_response("game_versions"));
+    }
+
+    // The values are checked against the game version tags when the field is parsed
+    let mut fields = HashMap::new();
+    fields.insert(
+        "game_versions".to_string(),
+        serde_json::json!(game_versions),
+    );
+    let new_version = EditVersion {
+        fields,
+        ..Default::default()
+    };
+    version_edit_helper(
+        req,
+        info.into_inner(),
+        pool.clone(),
+        redis.clone(),
+        new_version,
+        session_queue,
+    )
+    .await?;
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
         &redis,
-        &session_queue,
-        Some(&[Scopes::VERSION_DELETE]),
+        &config,
+        &[version.inner.project_id],
     )
-    .await?
-    .1;
-    let id = info.into_inner().0;
+    .await?;
 
-    let version = database::models::Version::get(id.into(), &**pool, &redis)
-        .await?
-        .ok_or_else(|| {
-            ApiError::InvalidInput("The specified version does not exist!".to_string())
-        })?;
+    Ok(HttpResponse::NoContent().body(""))
+}
 
-    if !user.role.is_admin() {
-        let team_member = database::models::TeamMember::get_from_user_id_project(
-            version.inner.project_id,
-            user.id.into(),
-            false,
-            &**pool,
-        )
-        .await
-        .map_err(ApiError::Database)?;
+#[derive(Serialize, Deserialize)]
+pub struct VersionDependencies {
+    /// Validated like the dependencies of a version edit
+    pub dependencies: Vec<Dependency>,
+}
 
-        let organization =
-            Organization::get_associated_organization_project_id(version.inner.project_id, &**pool)
-                .await?;
+/// Replaces all of the dependencies of a version at once
+pub async fn version_dependencies_edit(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    config: web::Data<SearchConfig>,
+    edit: web::Json<VersionDependencies>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let Some(version) =
+        get_version_for_set_edit(&req, info.0, &pool, &redis, &session_queue).await?
+    else {
+        return Err(ApiError::NotFound);
+    };
+    let dependencies = edit.into_inner().dependencies;
 
-        let organization_team_member = if let Some(organization) = &organization {
-            database::models::TeamMember::get_from_user_id(
-                organization.team_id,
-                user.id.into(),
-                &**pool,
-            )
-            .await?
-        } else {
-            None
-        };
-        let permissions = ProjectPermissions::get_permissions_by_role(
-            &user.role,
-            &team_member,
-            &organization_team_member,
-        )
-        .unwrap_or_default();
+    let invalid = |description: String| {
+        HttpResponse::UnprocessableEntity().json(serde_json::json!({
+            "error": "invalid_input",
+            "description": description,
+        }))
+    };
 
-        if !permissions.contains(ProjectPermissions::DELETE_VERSION) {
-            return Err(ApiError::CustomAuthentication(
-                "You do not have permission to delete versions in this team".to_string(),
-            ));
-        }
+    let project_ids = dependencies
+        .iter()
+        .filter_map(|x| x.project_id)
+        .map(database::models::ProjectId::from)
+        .unique()
+        .collect_vec();
+    let projects = database::models::Project::get_many_ids(&project_ids, &**pool, &redis).await?;
+    if let Some(missing) = project_ids
+        .iter()
+        .find(|id| !projects.iter().any(|x| x.inner.id == **id))
+    {
+        return Ok(invalid(format!(
+            "Project {} does not exist",
+            models::ids::ProjectId::from(*missing)
+        )));
     }
 
-    let mut transaction = pool.begin().await?;
-    let context = ImageContext::Version {
-        version_id: Some(version.inner.id.into()),
-    };
-    let uploaded_images =
-        database::models::Image::get_many_contexted(context, &mut transaction).await?;
-    for image in uploaded_images {
-        image_item::Image::remove(image.id, &mut transaction, &redis).await?;
+    let version_ids = dependencies
+        .iter()
+        .filter_map(|x| x.version_id)
+        .map(database::models::VersionId::from)
+        .unique()
+        .collect_vec();
+    let versions = database::models::Version::get_many(&version_ids, &**pool, &redis).await?;
+    if let Some(missing) = version_ids
+        .iter()
+        .find(|id| !versions.iter().any(|x| x.inner.id == **id))
+    {
+        return Ok(invalid(format!(
+            "Version {} does not exist",
+            VersionId::from(*missing)
+        )));
     }
 
-    let result =
-        database::models::Version::remove_full(version.inner.id, &redis, &mut transaction).await?;
-    transaction.commit().await?;
-    remove_documents(&[version.inner.id.into()], &search_config).await?;
-    database::models::Project::clear_cache(version.inner.project_id, None, Some(true), &redis)
-        .await?;
-
-    if result.is_some() {
-        Ok(HttpResponse::NoContent().body(""))
-    } else {
-        Err(ApiError::NotFound)
+    // A version can't depend on its own project, or on a version depending back on it
+    let project_id = version.inner.project_id;
+    let circular = project_ids.contains(&project_id)
+        || versions.iter().any(|x| {
+            x.inner.project_id == project_id
+                || x.dependencies.iter().any(|dependency| {
+                    dependency.version_id == Some(version.inner.id)
+                        || dependency.project_id == Some(project_id)
+                })
+        });
+    if circular {
+        return Ok(invalid(
+            "A version can't depend on itself, its project or a version depending on it"
+                .to_string(),
+        ));
     }
+
+    let new_version = EditVersion {
+        dependencies: Some(dependencies),
+        ..Default::default()
+    };
+    version_edit_helper(
+        req,
+        info.into_inner(),
+        pool.clone(),
+        redis.clone(),
+        new_version,
+        session_queue,
+    )
+    .await?;
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &config,
+        &[project_id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+/// Makes a file of the version its only primary file
+pub async fn version_primary_file_edit(
+    req: HttpRequest,
+    info: web::Path<(VersionId, models::ids::FileId)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (version_id, file_id) = info.into_inner();
+    let Some(version) =
+        get_version_for_set_edit(&req, version_id, &pool, &redis, &session_queue).await?
+    else {
+        return Err(ApiError::NotFound);
+    };
+
+    // Parts of split archives cannot be primary, only the file they belong to
+    let Some(file) = version
+        .files
+        .iter()
+        .find(|x| x.id == file_id.into() && x.part_group_id.is_none())
+    else {
+        return Ok(HttpResponse::UnprocessableEntity().json(serde_json::json!({
+            "error": "invalid_input",
+            "description": format!("File {file_id} does not belong to version {version_id}"),
+        })));
+    };
+    let Some(sha1) = file.hashes.get("sha1") else {
+        return Err(ApiError::InvalidInput(
+            "The file does not have a sha1 hash".to_string(),
+        ));
+    };
+
+    let new_version = EditVersion {
+        primary_file: Some(("sha1".to_string(), sha1.clone())),
+        ..Default::default()
+    };
+    version_edit_helper(req, (version_id,), pool, redis, new_version, session_queue).await
+}
+
+// Gets a version for one of the set edit routes, hiding versions the user cannot see.
+// The edit permissions are checked when the change is applied.
+async fn get_version_for_set_edit(
+    req: &HttpRequest,
+    version_id: VersionId,
+    pool: &PgPool,
+    redis: &RedisPool,
+    session_queue: &AuthQueue,
+) -> Result<Option<database::models::version_item::QueryVersion>, ApiError> {
+    let user = get_user_from_headers(
+        req,
+        pool,
+        redis,
+        session_queue,
+        Some(&[Scopes::VERSION_WRITE]),
+    )
+    .await?
+    .1;
+
+    let version = database::models::Version::get(version_id.into(), pool, redis).await?;
+    match version {
+        Some(version) if is_visible_version(&version.inner, &Some(user), pool, redis).await? => {
+            Ok(Some(version))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn empty_version_set_response(field: &str) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(serde_json::json!({
+        "error": "invalid_input",
+        "description": format!("A version must have at least one value in `{field}`"),
+    }))
+}
+
+// Replaces game version ranges (ex: `1.20.x` or `>= 1.19`) in a filter with the versions they match
+async fn expand_game_version_ranges(
+    game_versions: &mut Vec<serde_json::Value>,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<(), ApiError> {
+    if !game_versions
+        .iter()
+        .any(|x| x.as_str().is_some_and(MinecraftGameVersion::is_range))
+    {
+        return Ok(());
+    }
+
+    let all_game_versions = MinecraftGameVersion::list(None, None, pool, redis).await?;
+    *game_versions = game_versions
+        .drain(..)
+        .flat_map(|x| match x.as_str() {
+            Some(value) => MinecraftGameVersion::expand_range(value, &all_game_versions)
+                .into_iter()
+                .map(serde_json::Value::String)
+                .collect(),
+            None => vec![x],
+        })
+        .collect();
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VersionListFilters {
+    pub loaders: Option<String>,
+    pub featured: Option<bool>,
+    pub version_type: Option<VersionType>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /*
+        Loader fields to filter with:
+        "game_versions": ["1.16.5", "1.17"]
+
+        Returns if it matches any of the values
+    */
+    pub loader_fields: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v3/project/{id}/version",
+    tag = "versions",
+    params(
+        ("id" = String, Path, description = "The ID or slug of the project"),
+        VersionListFilters,
+    ),
+    security((), ("pat" = [])),
+    responses(
+        (status = 200, description = "The versions of the project", body = Vec<models::projects::Version>),
+        (status = 404, description = "The requested item(s) were not found or no authorization to access the requested item(s)", body = crate::models::error::ApiError),
+    ),
+)]
+pub async fn version_list(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    web::Query(filters): web::Query<VersionListFilters>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let result = database::models::Project::get(&string, &**pool, &redis).await?;
+
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    if let Some(project) = result {
+        if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+            return Err(ApiError::NotFound);
+        }
+
+        let response =
+            list_project_versions(&project, filters, &user_option, &pool, &redis).await?;
+
+        Ok(HttpResponse::Ok().json(response))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+/// The versions of a project matching the filters and visible to the user, newest first
+async fn list_project_versions(
+    project: &database::models::project_item::QueryProject,
+    filters: VersionListFilters,
+    user_option: &Option<models::users::User>,
+    pool: &PgPool,
+    redis: &RedisPool,
+) -> Result<Vec<models::projects::Version>, ApiError> {
+    let mut loader_field_filters = filters.loader_fields.as_ref().map(|x| {
+        serde_json::from_str::<HashMap<String, Vec<serde_json::Value>>>(x).unwrap_or_default()
+    });
+    if let Some(game_versions) = loader_field_filters
+        .as_mut()
+        .and_then(|x| x.get_mut(MinecraftGameVersion::FIELD_NAME))
+    {
+        expand_game_version_ranges(game_versions, pool, redis).await?;
+    }
+    let loader_filters = filters
+        .loaders
+        .as_ref()
+        .map(|x| serde_json::from_str::<Vec<String>>(x).unwrap_or_default());
+    let mut versions = database::models::Version::get_many(&project.versions, pool, redis)
+        .await?
+        .into_iter()
+        .skip(filters.offset.unwrap_or(0))
+        .take(filters.limit.unwrap_or(usize::MAX))
+        .filter(|x| {
+            let mut bool = true;
+
+            if let Some(version_type) = filters.version_type {
+                bool &= &*x.inner.version_type == version_type.as_str();
+            }
+            if let Some(loaders) = &loader_filters {
+                bool &= x.loaders.iter().any(|y| loaders.contains(y));
+            }
+            if let Some(loader_fields) = &loader_field_filters {
+                for (key, values) in loader_fields {
+                    bool &= if let Some(x_vf) =
+                        x.version_fields.iter().find(|y| y.field_name == *key)
+                    {
+                        values.iter().any(|v| x_vf.value.contains_json_value(v))
+                    } else {
+                        true
+                    };
+                }
+            }
+            bool
+        })
+        .collect::<Vec<_>>();
+
+    let mut response = versions
+        .iter()
+        .filter(|version| {
+            filters
+                .featured
+                .map(|featured| featured == version.inner.featured)
+                .unwrap_or(true)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    versions.sort_by(|a, b| b.inner.date_published.cmp(&a.inner.date_published));
+
+    // Attempt to populate versions with "auto featured" versions
+    if response.is_empty() && !versions.is_empty() && filters.featured.unwrap_or(false) {
+        // TODO: This is a bandaid fix for detecting auto-featured versions.
+        // In the future, not all versions will have 'game_versions' fields, so this will need to be changed.
+        let (loaders, game_versions) = futures::future::try_join(
+            database::models::loader_fields::Loader::list(pool, redis),
+            database::models::legacy_loader_fields::MinecraftGameVersion::list(
+                None,
+                Some(true),
+                pool,
+                redis,
+            ),
+        )
+        .await?;
+
+        let mut joined_filters = Vec::new();
+        for game_version in &game_versions {
+            for loader in &loaders {
+                joined_filters.push((game_version, loader))
+            }
+        }
+
+        joined_filters.into_iter().for_each(|filter| {
+            versions
+                .iter()
+                .find(|version| {
+                    // TODO: This is the bandaid fix for detecting auto-featured versions.
+                    let game_versions = version
+                        .version_fields
+                        .iter()
+                        .find(|vf| vf.field_name == "game_versions")
+                        .map(|vf| vf.value.clone())
+                        .map(|v| v.as_strings())
+                        .unwrap_or_default();
+                    game_versions.contains(&filter.0.version)
+                        && version.loaders.contains(&filter.1.loader)
+                })
+                .map(|version| response.push(version.clone()))
+                .unwrap_or(());
+        });
+
+        if response.is_empty() {
+            versions
+                .into_iter()
+                .for_each(|version| response.push(version));
+        }
+    }
+
+    response.sort_by(|a, b| b.inner.date_published.cmp(&a.inner.date_published));
+    response.dedup_by(|a, b| a.inner.id == b.inner.id);
+
+    filter_visible_versions(response, user_option, pool, redis).await
+}
+
+/// The versions of a project for a game version, the same as listing them with a
+/// `game_versions` loader field filter
+pub async fn version_list_game_version(
+    req: HttpRequest,
+    info: web::Path<(String, String)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let (string, game_version) = info.into_inner();
+
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let project = database::models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    // Only the versions everyone can see are cached. They are kept per project, keyed by game
+    // version, so clearing the project's caches clears them too.
+    let mut redis_conn = redis.connect().await?;
+    let mut cached = if user_option.is_none() {
+        redis_conn
+            .get_deserialized_from_json::<HashMap<String, Vec<models::projects::Version>>>(
+                database::models::project_item::PROJECTS_GAME_VERSION_VERSIONS_NAMESPACE,
+                &project.inner.id.0.to_string(),
+            )
+            .await?
+            .unwrap_or_default()
+    } else {
+        HashMap::new()
+    };
+    if let Some(versions) = cached.get(&game_version) {
+        return Ok(HttpResponse::Ok().json(versions));
+    }
+
+    let filters = VersionListFilters {
+        loaders: None,
+        featured: None,
+        version_type: None,
+        limit: None,
+        offset: None,
+        loader_fields: Some(
+            serde_json::json!({ MinecraftGameVersion::FIELD_NAME: [game_version] }).to_string(),
+        ),
+    };
+    let versions = list_project_versions(&project, filters, &user_option, &pool, &redis).await?;
+
+    // Unknown game versions are not cached, so they cannot grow the project's entry
+    if user_option.is_none() && !versions.is_empty() {
+        cached.insert(game_version, versions.clone());
+        redis_conn
+            .set_serialized_to_json(
+                database::models::project_item::PROJECTS_GAME_VERSION_VERSIONS_NAMESPACE,
+                project.inner.id.0,
+                &cached,
+                Some(GAME_VERSION_VERSIONS_CACHE_EXPIRY),
+            )
+            .await?;
+    }
+
+    Ok(HttpResponse::Ok().json(versions))
+}
+
+/// An RSS feed of the releases of a project, newest first
+pub async fn version_list_rss(
+    req: HttpRequest,
+    info: web::Path<(String,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let string = info.into_inner().0;
+
+    let user_option = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::PROJECT_READ, Scopes::VERSION_READ]),
+    )
+    .await
+    .map(|x| x.1)
+    .ok();
+
+    let project = database::models::Project::get(&string, &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_project(&project.inner, &user_option, &pool, false).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let versions = database::models::Version::get_many(&project.versions, &**pool, &redis).await?;
+    let mut versions = filter_visible_versions(versions, &user_option, &pool, &redis).await?;
+    versions.sort_by(|a, b| b.date_published.cmp(&a.date_published));
+
+    let items = versions
+        .into_iter()
+        .map(|version| {
+            let mut item = rss::Item::default();
+            item.set_title(format!("{} ({})", version.name, version.version_number));
+            item.set_description(version.changelog);
+            item.set_pub_date(version.date_published.to_rfc2822());
+            item.set_link(
+                version
+                    .files
+                    .iter()
+                    .find(|x| x.primary)
+                    .or(version.files.first())
+                    .map(|x| x.url.clone()),
+            );
+            item.set_guid(rss::Guid {
+                value: version.id.to_string(),
+                permalink: false,
+            });
+            item
+        })
+        .collect::<Vec<_>>();
+
+    let project_id = models::ids::ProjectId::from(project.inner.id);
+    let mut channel = rss::Channel::default();
+    channel.set_title(project.inner.name);
+    channel.set_link(format!(
+        "{}/project/{}",
+        dotenvy::var("SITE_URL").unwrap_or_default(),
+        project.inner.slug.unwrap_or_else(|| project_id.to_string())
+    ));
+    channel.set_description(project.inner.summary);
+    channel.set_items(items);
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/rss+xml")
+        .body(channel.to_string()))
+}
+
+pub async fn version_delete(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_DELETE]),
+    )
+    .await?
+    .1;
+    let id = info.into_inner().0;
+
+    let version = database::models::Version::get(id.into(), &**pool, &redis)
+        .await?
+        .ok_or_else(|| {
+            ApiError::InvalidInput("The specified version does not exist!".to_string())
+        })?;
+
+    if !user.role.is_admin() {
+        let team_member = database::models::TeamMember::get_from_user_id_project(
+            version.inner.project_id,
+            user.id.into(),
+            false,
+            &**pool,
+        )
+        .await
+        .map_err(ApiError::Database)?;
+
+        let organization =
+            Organization::get_associated_organization_project_id(version.inner.project_id, &**pool)
+                .await?;
+
+        let organization_team_member = if let Some(organization) = &organization {
+            database::models::TeamMember::get_from_user_id(
+                organization.team_id,
+                user.id.into(),
+                &**pool,
+            )
+            .await?
+        } else {
+            None
+        };
+        let permissions = ProjectPermissions::get_permissions_by_role(
+            &user.role,
+            &team_member,
+            &organization_team_member,
+        )
+        .unwrap_or_default();
+
+        if !permissions.contains(ProjectPermissions::DELETE_VERSION) {
+            return Err(ApiError::CustomAuthentication(
+                "You do not have permission to delete versions in this team".to_string(),
+            ));
+        }
+    }
+
+    // The version is only hidden, so it can be restored for a while before it is purged
+    let mut transaction = pool.begin().await?;
+    let result =
+        database::models::Version::soft_delete(version.inner.id, &redis, &mut transaction).await?;
+    transaction.commit().await?;
+    remove_documents_or_retry(&pool, &search_config, &[version.inner.id.into()]).await?;
+    crate::search::clear_search_cache(&redis).await?;
+    database::models::project_item::clear_project_caches(version.inner.project_id, None, &redis)
+        .await?;
+
+    if result.is_some() {
+        Ok(HttpResponse::NoContent().body(""))
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+/// Restores a version deleted in the last 30 days. Versions deleted longer ago are purged and
+/// cannot be restored.
+pub async fn version_restore(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_DELETE]),
+    )
+    .await?
+    .1;
+    let id: database::models::VersionId = info.into_inner().0.into();
+
+    let project_id = database::models::Version::get_restorable_project(id, &**pool)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if !user.role.is_mod() {
+        let team_member = database::models::TeamMember::get_from_user_id_project(
+            project_id,
+            user.id.into(),
+            false,
+            &**pool,
+        )
+        .await?;
+
+        let organization =
+            Organization::get_associated_organization_project_id(project_id, &**pool).await?;
+
+        let organization_team_member = if let Some(organization) = &organization {
+            database::models::TeamMember::get_from_user_id(
+                organization.team_id,
+                user.id.into(),
+                &**pool,
+            )
+            .await?
+        } else {
+            None
+        };
+
+        // Deleted versions are hidden from anyone outside of the project
+        if team_member.is_none() && organization_team_member.is_none() {
+            return Err(ApiError::NotFound);
+        }
+
+        let permissions = ProjectPermissions::get_permissions_by_role(
+            &user.role,
+            &team_member,
+            &organization_team_member,
+        )
+        .unwrap_or_default();
+
+        if !permissions.contains(ProjectPermissions::DELETE_VERSION) {
+            return Err(ApiError::CustomAuthentication(
+                "You do not have permission to restore versions in this team".to_string(),
+            ));
+        }
+    }
+
+    let mut transaction = pool.begin().await?;
+    let result = database::models::Version::restore(id, &redis, &mut transaction).await?;
+    transaction.commit().await?;
+
+    if result.is_none() {
+        return Err(ApiError::NotFound);
+    }
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &search_config,
+        &[project_id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionReviewStatus {
+    Approved,
+    Rejected,
+    Unlisted,
+}
+
+impl VersionReviewStatus {
+    pub fn as_version_status(&self) -> VersionStatus {
+        match self {
+            VersionReviewStatus::Approved => VersionStatus::Listed,
+            VersionReviewStatus::Rejected => VersionStatus::Rejected,
+            VersionReviewStatus::Unlisted => VersionStatus::Unlisted,
+        }
+    }
+}
+
+/// Makes a version the featured one among the versions of its project sharing a loader and game
+/// version with it, without changing any publication date
+pub async fn version_promote(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_WRITE]),
+    )
+    .await?
+    .1;
+
+    let version = database::models::Version::get(info.into_inner().0.into(), &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    if !is_visible_version(&version.inner, &Some(user.clone()), &pool, &redis).await? {
+        return Err(ApiError::NotFound);
+    }
+    let project = database::models::Project::get_id(version.inner.project_id, &**pool, &redis)
+        .await?
+        .filter(|x| x.inner.deleted_at.is_none())
+        .ok_or(ApiError::NotFound)?;
+
+    let (team_member, organization_team_member) =
+        database::models::TeamMember::get_for_project_permissions(
+            &project.inner,
+            user.id.into(),
+            &**pool,
+        )
+        .await?;
+    let permissions = ProjectPermissions::get_permissions_by_role(
+        &user.role,
+        &team_member,
+        &organization_team_member,
+    )
+    .unwrap_or_default();
+    if !permissions.contains(ProjectPermissions::UPLOAD_VERSION) {
+        return Err(ApiError::CustomAuthentication(
+            "You do not have the permissions to edit this version!".to_string(),
+        ));
+    }
+
+    if !version.inner.status.is_listed() {
+        return Err(ApiError::InvalidInput(
+            "Only listed versions can be promoted!".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let unfeatured =
+        database::models::Version::promote(version.inner.id, project.inner.id, &mut transaction)
+            .await?;
+    ProjectAuditLogBuilder {
+        project_id: project.inner.id,
+        user_id: user.id.into(),
+        changed_field: "promoted_version",
+        old_value: None,
+        new_value: Some(VersionId::from(version.inner.id).to_string()),
+    }
+    .insert(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+
+    database::models::Version::clear_promoted_cache(
+        version.inner.id,
+        &unfeatured,
+        project.inner.id,
+        &redis,
+    )
+    .await?;
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &search_config,
+        &[project.inner.id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+pub struct VersionReview {
+    pub status: VersionReviewStatus,
+    #[validate(length(max = 2000))]
+    pub message: Option<String>,
+}
+
+/// Sets the status of a version as a moderator. Rejected versions are hidden from everyone
+/// outside of the project and can only be changed back by a moderator.
+pub async fn version_review_status_edit(
+    req: HttpRequest,
+    info: web::Path<(VersionId,)>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+    search_config: web::Data<SearchConfig>,
+    review: web::Json<VersionReview>,
+) -> Result<HttpResponse, ApiError> {
+    let user = check_is_moderator_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::VERSION_WRITE]),
+    )
+    .await?;
+
+    review
+        .validate()
+        .map_err(|err| ApiError::Validation(validation_errors_to_string(err, None)))?;
+
+    let version = database::models::Version::get(info.into_inner().0.into(), &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let project = database::models::Project::get_id(version.inner.project_id, &**pool, &redis)
+        .await?
+        .filter(|x| x.inner.deleted_at.is_none())
+        .ok_or(ApiError::NotFound)?;
+
+    let status = review.status.as_version_status();
+    if status != VersionStatus::Rejected {
+        check_file_parts_complete(&version.files)?;
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!(
+        "
+        UPDATE versions
+        SET status = $1
+        WHERE (id = $2)
+        ",
+        status.as_str(),
+        version.inner.id as database::models::ids::VersionId,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    ModerationLogBuilder {
+        moderator_id: user.id.into(),
+        action: "version_review",
+        target_type: "version",
+        target_user_id: None,
+        target_version_id: Some(version.inner.id),
+        old_value: Some(version.inner.status.to_string()),
+        new_value: Some(status.to_string()),
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    let members =
+        database::models::TeamMember::get_from_team_full(project.inner.team_id, &**pool, &redis)
+            .await?
+            .into_iter()
+            .filter(|x| x.accepted)
+            .map(|x| x.user_id)
+            .collect_vec();
+    NotificationBuilder {
+        body: NotificationBody::VersionReview {
+            project_id: project.inner.id.into(),
+            version_id: version.inner.id.into(),
+            status,
+            message: review.message.clone(),
+        },
+    }
+    .insert_many(members, &mut transaction, &redis)
+    .await?;
+
+    transaction.commit().await?;
+
+    database::models::Version::clear_cache(&version, &redis).await?;
+    clear_project_caches(project.inner.id, project.inner.slug.clone(), &redis).await?;
+
+    crate::search::indexing::retry::index_projects_by_id_or_retry(
+        &pool,
+        &redis,
+        &search_config,
+        &[project.inner.id],
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadStatsResolution {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl DownloadStatsResolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DownloadStatsResolution::Day => "day",
+            DownloadStatsResolution::Week => "week",
+            DownloadStatsResolution::Month => "month",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DownloadStatsQuery {
+    #[serde(default)]
+    pub resolution: DownloadStatsResolution,
+    /// The first day to include (inclusive)
+    pub start: Option<chrono::NaiveDate>,
+    /// The last day to include (inclusive)
+    pub end: Option<chrono::NaiveDate>,
+}
+
+/// Returns the downloads of a version per day, week or month. Only available to members of the
+/// version's project.
+pub async fn version_download_stats_get(
+    req: HttpRequest,
+    info: web::Path<(models::ids::VersionId,)>,
+    query: web::Query<DownloadStatsQuery>,
+    pool: web::Data<PgPool>,
+    redis: web::Data<RedisPool>,
+    session_queue: web::Data<AuthQueue>,
+) -> Result<HttpResponse, ApiError> {
+    let user = get_user_from_headers(
+        &req,
+        &**pool,
+        &redis,
+        &session_queue,
+        Some(&[Scopes::ANALYTICS]),
+    )
+    .await?
+    .1;
+
+    let id = info.into_inner().0;
+    let version = database::models::Version::get(id.into(), &**pool, &redis)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    let project = database::models::Project::get_id(version.inner.project_id, &**pool, &redis)
+        .await?
+        .filter(|x| x.inner.deleted_at.is_none())
+        .ok_or(ApiError::NotFound)?;
+
+    let (team_member, organization_team_member) =
+        database::models::TeamMember::get_for_project_permissions(
+            &project.inner,
+            user.id.into(),
+            &**pool,
+        )
+        .await?;
+
+    if ProjectPermissions::get_permissions_by_role(
+        &user.role,
+        &team_member,
+        &organization_team_member,
+    )
+    .is_none()
+    {
+        return if is_visible_version(&version.inner, &Some(user), &pool, &redis).await? {
+            Err(ApiError::CustomAuthentication(
+                "You do not have permission to view the download stats of this version!"
+                    .to_string(),
+            ))
+        } else {
+            Err(ApiError::NotFound)
+        };
+    }
+
+    let entries = DownloadHistory::get_for_version(
+        version.inner.id,
+        query.resolution.as_str(),
+        query.start,
+        query.end,
+        &**pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(entries))
 }
@@ -12,13 +12,17 @@ use crate::database::models::loader_fields::{
 use crate::database::models::{
     LoaderFieldEnumId, LoaderFieldEnumValueId, LoaderFieldId, ProjectId, VersionId,
 };
-use crate::models::projects::from_duplicate_version_fields;
+use crate::models::projects::{from_duplicate_version_fields, VersionStatus};
 use crate::models::v2::projects::LegacyProject;
 use crate::routes::v2_reroute;
 use crate::search::UploadSearchProject;
 use sqlx::postgres::PgPool;
 
-pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, IndexingError> {
+/// Builds the search documents for all searchable projects, or only for `project_ids` if given
+pub async fn index_local(
+    pool: &PgPool,
+    project_ids: Option<&[ProjectId]>,
+) -> Result<Vec<UploadSearchProject>, IndexingError> {
     info!("Indexing local projects!");
 
     // todo: loaders, project type, game versions
@@ -36,18 +40,20 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
         license: String,
     }
 
+    let filter_ids = project_ids.map(|ids| ids.iter().map(|x| x.0).collect::<Vec<i64>>());
     let db_projects = sqlx::query!(
         "
         SELECT m.id id, m.name name, m.summary summary, m.downloads downloads, m.follows follows,
         m.icon_url icon_url, m.updated updated, m.approved approved, m.published, m.license license, m.slug slug, m.color
         FROM mods m
-        WHERE m.status = ANY($1)
+        WHERE m.status = ANY($1) AND m.deleted_at IS NULL AND ($2::bigint[] IS NULL OR m.id = ANY($2))
         GROUP BY m.id;
         ",
         &*crate::models::projects::ProjectStatus::iterator()
         .filter(|x| x.is_searchable())
         .map(|x| x.to_string())
         .collect::<Vec<String>>(),
+        filter_ids.as_deref(),
     )
         .fetch_many(pool)
         .try_filter_map(|e| async {
@@ -127,14 +133,32 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
     )
     .await?;
 
+    info!("Indexing local links!");
+
+    let link_platforms: DashMap<ProjectId, Vec<String>> = sqlx::query!(
+        "
+        SELECT ml.joining_mod_id mod_id, lp.name platform
+        FROM mods_links ml
+        INNER JOIN link_platforms lp ON ml.joining_platform_id = lp.id
+        WHERE ml.joining_mod_id = ANY($1)
+        ",
+        &*project_ids,
+    )
+    .fetch(pool)
+    .try_fold(DashMap::new(), |acc: DashMap<ProjectId, Vec<String>>, m| {
+        acc.entry(ProjectId(m.mod_id)).or_default().push(m.platform);
+        async move { Ok(acc) }
+    })
+    .await?;
+
     info!("Indexing local versions!");
     let mut versions = index_versions(pool, project_ids.clone()).await?;
 
     info!("Indexing local org owners!");
 
-    let mods_org_owners: DashMap<ProjectId, String> = sqlx::query!(
+    let mods_org_owners: DashMap<ProjectId, (String, bool)> = sqlx::query!(
         "
-        SELECT m.id mod_id, u.username
+        SELECT m.id mod_id, u.username, u.banned
         FROM mods m
         INNER JOIN organizations o ON o.id = m.organization_id
         INNER JOIN team_members tm ON tm.is_owner = TRUE and tm.team_id = o.team_id
@@ -144,17 +168,20 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
         &*project_ids,
     )
     .fetch(pool)
-    .try_fold(DashMap::new(), |acc: DashMap<ProjectId, String>, m| {
-        acc.insert(ProjectId(m.mod_id), m.username);
-        async move { Ok(acc) }
-    })
+    .try_fold(
+        DashMap::new(),
+        |acc: DashMap<ProjectId, (String, bool)>, m| {
+            acc.insert(ProjectId(m.mod_id), (m.username, m.banned));
+            async move { Ok(acc) }
+        },
+    )
     .await?;
 
     info!("Indexing local team owners!");
 
-    let mods_team_owners: DashMap<ProjectId, String> = sqlx::query!(
+    let mods_team_owners: DashMap<ProjectId, (String, bool)> = sqlx::query!(
         "
-        SELECT m.id mod_id, u.username
+        SELECT m.id mod_id, u.username, u.banned
         FROM mods m
         INNER JOIN team_members tm ON tm.is_owner = TRUE and tm.team_id = m.team_id
         INNER JOIN users u ON u.id = tm.user_id
@@ -163,10 +190,13 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
         &project_ids,
     )
     .fetch(pool)
-    .try_fold(DashMap::new(), |acc: DashMap<ProjectId, String>, m| {
-        acc.insert(ProjectId(m.mod_id), m.username);
-        async move { Ok(acc) }
-    })
+    .try_fold(
+        DashMap::new(),
+        |acc: DashMap<ProjectId, (String, bool)>, m| {
+            acc.insert(ProjectId(m.mod_id), (m.username, m.banned));
+            async move { Ok(acc) }
+        },
+    )
     .await?;
 
     info!("Getting all loader fields!");
@@ -220,7 +250,7 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
         count += 1;
         info!("projects index prog: {count}/{total_len}");
 
-        let owner = if let Some((_, org_owner)) = mods_org_owners.remove(&project.id) {
+        let (owner, banned) = if let Some((_, org_owner)) = mods_org_owners.remove(&project.id) {
             org_owner
         } else if let Some((_, team_owner)) = mods_team_owners.remove(&project.id) {
             team_owner
@@ -242,6 +272,13 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
             _ => false,
         };
 
+        let links = link_platforms
+            .remove(&project.id)
+            .map(|(_, links)| links)
+            .unwrap_or_default();
+        let has_wiki = links.iter().any(|x| x == "wiki");
+        let has_issues = links.iter().any(|x| x == "issues");
+
         let (featured_gallery, gallery) =
             if let Some((_, gallery)) = mods_gallery.remove(&project.id) {
                 let mut vals = Vec::new();
@@ -373,6 +410,7 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
                     downloads: project.downloads,
                     icon_url: project.icon_url.clone(),
                     author: owner.clone(),
+                    author_normalized: crate::search::normalize_author(&owner),
                     date_created: project.approved,
                     created_timestamp: project.approved.timestamp(),
                     date_modified: project.updated,
@@ -385,6 +423,9 @@ pub async fn index_local(pool: &PgPool) -> Result<Vec<UploadSearchProject>, Inde
                     featured_gallery: featured_gallery.clone(),
                     open_source,
                     color: project.color.map(|x| x as u32),
+                    banned,
+                    has_wiki,
+                    has_issues,
                     loader_fields,
                     project_loader_fields: project_loader_fields.clone(),
                     // 'loaders' is aggregate of all versions' loaders
@@ -414,9 +455,10 @@ async fn index_versions(
         "
         SELECT v.id, v.mod_id
         FROM versions v
-        WHERE mod_id = ANY($1)
+        WHERE mod_id = ANY($1) AND deleted_at IS NULL AND status <> $2
         ",
         &project_ids,
+        VersionStatus::Rejected.as_str(),
     )
     .fetch(pool)
     .try_fold(
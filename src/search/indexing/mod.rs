@@ -1,5 +1,7 @@
 /// This module is used for the indexing from any source.
 pub mod local_import;
+pub mod reindex;
+pub mod retry;
 
 use crate::database::redis::RedisPool;
 use crate::models::ids::base62_impl::to_base62;
@@ -76,7 +78,7 @@ pub async fn index_projects(
             .map(|x| x.field)
             .collect::<Vec<_>>();
 
-    let uploads = index_local(&pool).await?;
+    let uploads = index_local(&pool, None).await?;
     add_projects(&indices, uploads, all_loader_fields.clone(), config).await?;
 
     // Swap the index
@@ -88,10 +90,42 @@ pub async fn index_projects(
         index.delete().await?;
     }
 
+    crate::search::clear_search_cache(&redis).await?;
+
     info!("Done adding projects.");
     Ok(())
 }
 
+/// Re-indexes only the given projects in the current indexes, replacing their existing documents
+pub async fn index_projects_by_id(
+    pool: &PgPool,
+    redis: &RedisPool,
+    config: &SearchConfig,
+    project_ids: &[crate::database::models::ProjectId],
+) -> Result<(), IndexingError> {
+    if project_ids.is_empty() {
+        return Ok(());
+    }
+
+    let indices = get_indexes_for_indexing(config, false).await?;
+
+    let all_loader_fields =
+        crate::database::models::loader_fields::LoaderField::get_fields_all(pool, redis)
+            .await?
+            .into_iter()
+            .map(|x| x.field)
+            .collect::<Vec<_>>();
+
+    let uploads = index_local(pool, Some(project_ids)).await?;
+    if uploads.is_empty() {
+        return Ok(());
+    }
+    add_projects(&indices, uploads, all_loader_fields, config).await?;
+    crate::search::clear_search_cache(redis).await?;
+
+    Ok(())
+}
+
 pub async fn swap_index(config: &SearchConfig, index_name: &str) -> Result<(), IndexingError> {
     let client = config.make_client();
     let index_name_next = config.get_index_name(index_name, true);
@@ -108,6 +142,16 @@ pub async fn swap_index(config: &SearchConfig, index_name: &str) -> Result<(), I
     Ok(())
 }
 
+/// Applies the current settings, such as newly filterable attributes, to the indexes that are
+/// searched. Filters like the one hiding banned users fail until this has happened.
+pub async fn sync_index_settings(
+    config: &SearchConfig,
+) -> Result<(), meilisearch_sdk::errors::Error> {
+    get_indexes_for_indexing(config, false).await?;
+
+    Ok(())
+}
+
 pub async fn get_indexes_for_indexing(
     config: &SearchConfig,
     next: bool, // Get the 'next' one
@@ -351,6 +395,7 @@ const DEFAULT_ATTRIBUTES_FOR_FACETING: &[&str] = &[
     "downloads",
     "follows",
     "author",
+    "author_normalized",
     "name",
     "date_created",
     "created_timestamp",
@@ -359,6 +404,9 @@ const DEFAULT_ATTRIBUTES_FOR_FACETING: &[&str] = &[
     "project_id",
     "open_source",
     "color",
+    "banned",
+    "has_wiki",
+    "has_issues",
     // Note: loader fields are not here, but are added on as they are needed (so they can be dynamically added depending on which exist).
     // TODO: remove these- as they should be automatically populated. This is a band-aid fix.
     "server_only",
@@ -0,0 +1,134 @@
+//! Re-indexing of a selection of projects, run in the background. The state of each task is kept
+//! in Redis so it can be polled from any instance.
+use super::index_projects_by_id;
+use crate::database::models::{DatabaseError, ProjectId};
+use crate::database::redis::RedisPool;
+use crate::search::SearchConfig;
+use log::warn;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+const REINDEX_TASKS_NAMESPACE: &str = "reindex_tasks";
+/// How long the state of a task is kept after its last update
+const REINDEX_TASK_EXPIRY: i64 = 60 * 60 * 24;
+/// The most projects re-indexed in one request to Meilisearch
+pub const REINDEX_BATCH_SIZE: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReindexStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct ReindexProgress {
+    /// Projects whose batch has been processed, whether it succeeded or not
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReindexTask {
+    pub status: ReindexStatus,
+    pub progress: ReindexProgress,
+    pub errors: Vec<String>,
+}
+
+impl ReindexTask {
+    /// Stores a new queued task, returning its ID
+    pub async fn create(
+        total: usize,
+        errors: Vec<String>,
+        redis: &RedisPool,
+    ) -> Result<String, DatabaseError> {
+        let task_id = ChaCha20Rng::from_entropy()
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect::<String>();
+
+        ReindexTask {
+            status: ReindexStatus::Queued,
+            progress: ReindexProgress {
+                processed: 0,
+                total,
+            },
+            errors,
+        }
+        .save(&task_id, redis)
+        .await?;
+
+        Ok(task_id)
+    }
+
+    pub async fn get(task_id: &str, redis: &RedisPool) -> Result<Option<Self>, DatabaseError> {
+        let mut redis = redis.connect().await?;
+        redis
+            .get_deserialized_from_json(REINDEX_TASKS_NAMESPACE, task_id)
+            .await
+    }
+
+    async fn save(&self, task_id: &str, redis: &RedisPool) -> Result<(), DatabaseError> {
+        let mut redis = redis.connect().await?;
+        redis
+            .set_serialized_to_json(
+                REINDEX_TASKS_NAMESPACE,
+                task_id,
+                self,
+                Some(REINDEX_TASK_EXPIRY),
+            )
+            .await
+    }
+}
+
+/// Re-indexes projects in batches, recording the progress and errors of each batch on the task.
+/// A failed batch does not stop the task; it is only marked as failed if no batch succeeded.
+pub async fn run_reindex_task(
+    task_id: String,
+    project_ids: Vec<ProjectId>,
+    pool: PgPool,
+    redis: RedisPool,
+    config: SearchConfig,
+) -> Result<(), DatabaseError> {
+    let Some(mut task) = ReindexTask::get(&task_id, &redis).await? else {
+        return Ok(());
+    };
+
+    task.status = ReindexStatus::Running;
+    task.save(&task_id, &redis).await?;
+
+    let mut failed_batches = 0;
+    let batches = project_ids.chunks(REINDEX_BATCH_SIZE);
+    let batch_count = batches.len();
+    for batch in batches {
+        if let Err(err) = index_projects_by_id(&pool, &redis, &config, batch).await {
+            warn!("Re-indexing batch of task {task_id} failed: {err}");
+            failed_batches += 1;
+            task.errors.push(format!(
+                "Failed to re-index projects {}: {err}",
+                batch
+                    .iter()
+                    .map(|x| crate::models::ids::ProjectId::from(*x).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        task.progress.processed += batch.len();
+        task.save(&task_id, &redis).await?;
+    }
+
+    task.status = if batch_count > 0 && failed_batches == batch_count {
+        ReindexStatus::Failed
+    } else {
+        ReindexStatus::Completed
+    };
+    task.save(&task_id, &redis).await
+}
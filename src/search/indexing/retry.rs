@@ -0,0 +1,203 @@
+//! Search index operations that failed because Meilisearch was unavailable are stored in the
+//! `meilisearch_retry_queue` table, and retried in the background with an exponential backoff.
+use super::{index_projects_by_id, remove_documents, IndexingError};
+use crate::database::models::ProjectId;
+use crate::database::redis::RedisPool;
+use crate::models::ids::VersionId;
+use crate::search::SearchConfig;
+use chrono::{Duration, Utc};
+use log::{error, warn};
+use meilisearch_sdk::errors::{Error as MeilisearchError, ErrorType};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Attempts after which an operation is given up on
+const MAX_ATTEMPTS: i32 = 5;
+/// The delay before the first retry, doubled after each further attempt
+const BASE_BACKOFF_SECONDS: i64 = 30;
+/// The most operations retried in one run of the queue
+const RETRY_BATCH_SIZE: i64 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RetryOperation {
+    /// Re-index a project (`document_type` "project")
+    Index,
+    /// Remove a version's document (`document_type` "version")
+    Remove,
+}
+
+impl RetryOperation {
+    fn document_type(&self) -> &'static str {
+        match self {
+            RetryOperation::Index => "project",
+            RetryOperation::Remove => "version",
+        }
+    }
+}
+
+/// Whether an error is likely caused by Meilisearch being temporarily unavailable
+pub fn is_transient(err: &MeilisearchError) -> bool {
+    match err {
+        MeilisearchError::UnreachableServer
+        | MeilisearchError::Timeout
+        | MeilisearchError::HttpError(..) => true,
+        MeilisearchError::MeilisearchCommunication(err) => err.status_code >= 500,
+        MeilisearchError::Meilisearch(err) => err.error_type == ErrorType::Internal,
+        _ => false,
+    }
+}
+
+/// Re-indexes the given projects, queueing them for a retry if Meilisearch is unavailable
+pub async fn index_projects_by_id_or_retry(
+    pool: &PgPool,
+    redis: &RedisPool,
+    config: &SearchConfig,
+    project_ids: &[ProjectId],
+) -> Result<(), IndexingError> {
+    match index_projects_by_id(pool, redis, config, project_ids).await {
+        Err(IndexingError::Indexing(err)) if is_transient(&err) => {
+            warn!("Queueing projects to be re-indexed later: {err}");
+            enqueue(
+                RetryOperation::Index,
+                &project_ids.iter().map(|x| x.0).collect::<Vec<_>>(),
+                pool,
+            )
+            .await
+        }
+        result => result,
+    }
+}
+
+/// Removes the documents of the given versions, queueing them for a retry if Meilisearch is
+/// unavailable
+pub async fn remove_documents_or_retry(
+    pool: &PgPool,
+    config: &SearchConfig,
+    ids: &[VersionId],
+) -> Result<(), IndexingError> {
+    match remove_documents(ids, config).await {
+        Err(err) if is_transient(&err) => {
+            warn!("Queueing search documents to be removed later: {err}");
+            enqueue(
+                RetryOperation::Remove,
+                &ids.iter().map(|x| x.0 as i64).collect::<Vec<_>>(),
+                pool,
+            )
+            .await
+        }
+        result => Ok(result?),
+    }
+}
+
+async fn enqueue(
+    operation: RetryOperation,
+    document_ids: &[i64],
+    pool: &PgPool,
+) -> Result<(), IndexingError> {
+    if document_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        "
+        INSERT INTO meilisearch_retry_queue (document_type, document_id, payload)
+        SELECT $1, id, $3 FROM UNNEST($2::bigint[]) id
+        ",
+        operation.document_type(),
+        document_ids,
+        serde_json::to_value(operation)?,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Retries the queued operations that are due, backing off after each failure
+pub async fn process_retry_queue(
+    pool: &PgPool,
+    redis: &RedisPool,
+    config: &SearchConfig,
+) -> Result<(), IndexingError> {
+    let rows = sqlx::query!(
+        "
+        SELECT id, document_type, document_id, payload, attempts
+        FROM meilisearch_retry_queue
+        WHERE next_attempt_at <= NOW() AND attempts < $1
+        ORDER BY next_attempt_at
+        LIMIT $2
+        ",
+        MAX_ATTEMPTS,
+        RETRY_BATCH_SIZE,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for row in rows {
+        let result = match serde_json::from_value::<RetryOperation>(row.payload) {
+            Ok(RetryOperation::Index) => {
+                index_projects_by_id(pool, redis, config, &[ProjectId(row.document_id)]).await
+            }
+            Ok(RetryOperation::Remove) => {
+                match remove_documents(&[VersionId(row.document_id as u64)], config).await {
+                    Ok(()) => Ok(crate::search::clear_search_cache(redis).await?),
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Err(err) => Err(err.into()),
+        };
+
+        let attempts = row.attempts + 1;
+        match result {
+            Ok(()) => {
+                sqlx::query!("DELETE FROM meilisearch_retry_queue WHERE id = $1", row.id)
+                    .execute(pool)
+                    .await?;
+            }
+            Err(err) if attempts >= MAX_ATTEMPTS => {
+                error!(
+                    "Giving up on search index operation: document_type={} document_id={} attempts={} error={}",
+                    row.document_type, row.document_id, attempts, err
+                );
+                sqlx::query!("DELETE FROM meilisearch_retry_queue WHERE id = $1", row.id)
+                    .execute(pool)
+                    .await?;
+            }
+            Err(err) => {
+                warn!(
+                    "Retrying search index operation failed: document_type={} document_id={} attempts={} error={}",
+                    row.document_type, row.document_id, attempts, err
+                );
+                let next_attempt_at =
+                    Utc::now() + Duration::seconds(BASE_BACKOFF_SECONDS << attempts);
+                sqlx::query!(
+                    "
+                    UPDATE meilisearch_retry_queue
+                    SET attempts = $2, last_attempt = NOW(), next_attempt_at = $3
+                    WHERE id = $1
+                    ",
+                    row.id,
+                    attempts,
+                    next_attempt_at,
+                )
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_server_is_transient() {
+        assert!(is_transient(&MeilisearchError::UnreachableServer));
+        assert!(is_transient(&MeilisearchError::Timeout));
+        assert!(!is_transient(&MeilisearchError::InvalidRequest));
+    }
+}
@@ -1,10 +1,17 @@
+use crate::database::models::legacy_loader_fields::MinecraftGameVersion;
+use crate::database::models::DatabaseError;
+use crate::database::redis::RedisPool;
 use crate::models::error::ApiError;
+use crate::models::ids::random_base62;
 use crate::models::projects::SearchRequest;
 use actix_web::http::StatusCode;
 use actix_web::HttpResponse;
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
+use log::warn;
 use meilisearch_sdk::client::Client;
+use meilisearch_sdk::search::Selectors;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::Cow;
@@ -28,6 +35,10 @@ pub enum SearchError {
     Env(#[from] dotenvy::Error),
     #[error("Invalid index to sort by: {0}")]
     InvalidIndex(String),
+    #[error("Invalid Input: {0}")]
+    InvalidInput(String),
+    #[error("Error while writing CSV: {0}")]
+    Csv(#[from] csv::Error),
 }
 
 impl actix_web::ResponseError for SearchError {
@@ -38,7 +49,9 @@ impl actix_web::ResponseError for SearchError {
             SearchError::Serde(..) => StatusCode::BAD_REQUEST,
             SearchError::IntParsing(..) => StatusCode::BAD_REQUEST,
             SearchError::InvalidIndex(..) => StatusCode::BAD_REQUEST,
+            SearchError::InvalidInput(..) => StatusCode::BAD_REQUEST,
             SearchError::FormatError(..) => StatusCode::BAD_REQUEST,
+            SearchError::Csv(..) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
@@ -50,18 +63,41 @@ impl actix_web::ResponseError for SearchError {
                 SearchError::Serde(..) => "invalid_input",
                 SearchError::IntParsing(..) => "invalid_input",
                 SearchError::InvalidIndex(..) => "invalid_input",
+                SearchError::InvalidInput(..) => "invalid_input",
                 SearchError::FormatError(..) => "invalid_input",
+                SearchError::Csv(..) => "csv_error",
             },
             description: self.to_string(),
         })
     }
 }
 
+/// How much of each search request is logged for analytics, set with `SEARCH_LOGGING`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchLogging {
+    Off,
+    /// Logs hashes and timings, plus the query text of searches without results
+    Enabled,
+    /// Also logs the query text of searches with results
+    Verbose,
+}
+
+impl SearchLogging {
+    pub fn from_string(string: &str) -> SearchLogging {
+        match string {
+            "off" => SearchLogging::Off,
+            "verbose" => SearchLogging::Verbose,
+            _ => SearchLogging::Enabled,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchConfig {
     pub address: String,
     pub key: String,
     pub meta_namespace: String,
+    pub logging: SearchLogging,
 }
 
 impl SearchConfig {
@@ -75,6 +111,9 @@ impl SearchConfig {
             address,
             key,
             meta_namespace: meta_namespace.unwrap_or_default(),
+            logging: SearchLogging::from_string(
+                &dotenvy::var("SEARCH_LOGGING").unwrap_or_default(),
+            ),
         }
     }
 
@@ -99,6 +138,8 @@ pub struct UploadSearchProject {
     pub project_types: Vec<String>,
     pub slug: Option<String>,
     pub author: String,
+    /// The author's username as normalized by [`normalize_author`], used by `author:` facets
+    pub author_normalized: String,
     pub name: String,
     pub summary: String,
     pub categories: Vec<String>,
@@ -119,6 +160,12 @@ pub struct UploadSearchProject {
     pub modified_timestamp: i64,
     pub open_source: bool,
     pub color: Option<u32>,
+    /// Whether the owner of the project is banned, which hides it from search
+    pub banned: bool,
+    /// Whether the project links to a wiki
+    pub has_wiki: bool,
+    /// Whether the project links to an issue tracker
+    pub has_issues: bool,
 
     // Hidden fields to get the Project model out of the search results.
     pub loaders: Vec<String>, // Search uses loaders as categories- this is purely for the Project model.
@@ -128,15 +175,29 @@ pub struct UploadSearchProject {
     pub loader_fields: HashMap<String, Vec<serde_json::Value>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, utoipa::ToSchema)]
 pub struct SearchResults {
     pub hits: Vec<ResultSearchProject>,
+    pub offset: usize,
+    pub limit: usize,
     pub page: usize,
     pub hits_per_page: usize,
+    /// The estimated total amount of hits, for pagination
     pub total_hits: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Search results with only some of the fields of each hit
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SparseSearchResults {
+    pub hits: Vec<serde_json::Map<String, Value>>,
+    pub offset: usize,
+    pub limit: usize,
+    pub page: usize,
+    pub hits_per_page: usize,
+    pub total_hits: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, utoipa::ToSchema)]
 pub struct ResultSearchProject {
     pub version_id: String,
     pub project_id: String,
@@ -167,6 +228,68 @@ pub struct ResultSearchProject {
     pub loader_fields: HashMap<String, Vec<serde_json::Value>>,
 }
 
+/// The most results a single search request may return
+pub const MAX_SEARCH_LIMIT: usize = 100;
+/// Meilisearch does not return results past this offset by default
+pub const MAX_SEARCH_OFFSET: usize = 10000;
+
+/// Normalizes a username for `author:` facets, so that it matches regardless of case and
+/// punctuation (ex: `Test_User` and `testuser` are the same)
+pub fn normalize_author(author: &str) -> String {
+    author
+        .chars()
+        .filter(|x| x.is_alphanumeric())
+        .flat_map(|x| x.to_lowercase())
+        .collect()
+}
+
+/// Expands game version ranges in `game_versions:` facets into the versions they match, see
+/// [`MinecraftGameVersion::expand_range`]
+pub fn expand_game_version_facets(
+    facets: &str,
+    game_versions: &[MinecraftGameVersion],
+) -> Result<String, serde_json::Error> {
+    let facets = serde_json::from_str::<Vec<Vec<Value>>>(facets)?
+        .into_iter()
+        .map(|facets| {
+            facets
+                .into_iter()
+                .flat_map(
+                    |facet| match facet.as_str().and_then(|x| x.split_once(':')) {
+                        Some((key, value))
+                            if key.trim() == MinecraftGameVersion::FIELD_NAME
+                                && MinecraftGameVersion::is_range(value) =>
+                        {
+                            MinecraftGameVersion::expand_range(value, game_versions)
+                                .into_iter()
+                                .map(|x| {
+                                    Value::String(format!(
+                                        "{}:{x}",
+                                        MinecraftGameVersion::FIELD_NAME
+                                    ))
+                                })
+                                .collect_vec()
+                        }
+                        _ => vec![facet],
+                    },
+                )
+                .collect_vec()
+        })
+        .collect_vec();
+
+    serde_json::to_string(&facets)
+}
+
+// Converts a `key:value` facet into a Meilisearch filter
+fn facet_to_filter(facet: &str) -> String {
+    match facet.split_once(':') {
+        Some((key, value)) if key.trim() == "author" => {
+            format!("author_normalized = \"{}\"", normalize_author(value))
+        }
+        _ => facet.replace(':', " = "),
+    }
+}
+
 pub fn get_sort_index(
     config: &SearchConfig,
     index: &str,
@@ -183,40 +306,317 @@ pub fn get_sort_index(
     })
 }
 
+/// Hides the projects of banned users from every search
+const HIDE_BANNED_FILTER: &str = "banned != true";
+
+const SEARCH_RESULTS_NAMESPACE: &str = "search_results";
+pub const SEARCH_RESULTS_GENERATION_NAMESPACE: &str = "search_results_generation";
+/// The key the current generation of cached search results is stored at
+pub const SEARCH_RESULTS_GENERATION_KEY: &str = "current";
+const SEARCH_RESULTS_CACHE_EXPIRY: i64 = 60;
+
+/// Invalidates every cached search result.
+///
+/// Cached results are keyed by a generation, which is replaced here, so the old results are never
+/// read again and expire on their own.
+pub async fn clear_search_cache(redis: &RedisPool) -> Result<(), DatabaseError> {
+    redis
+        .connect()
+        .await?
+        .set(
+            SEARCH_RESULTS_GENERATION_NAMESPACE,
+            SEARCH_RESULTS_GENERATION_KEY,
+            &random_base62(8).to_string(),
+            None,
+        )
+        .await
+}
+
+/// Same as [`search_for_project`], but with the results cached in Redis for a minute.
+///
+/// The search index only contains public projects, so all users share the same cached results.
+/// Failing to reach Redis falls back to an uncached search.
+pub async fn search_for_project_cached(
+    info: &SearchRequest,
+    config: &SearchConfig,
+    redis: &RedisPool,
+) -> Result<SearchResults, SearchError> {
+    let mut redis_conn = match redis.connect().await {
+        Ok(conn) => Some(conn),
+        Err(err) => {
+            warn!("Unable to connect to redis for the search cache: {err}");
+            None
+        }
+    };
+
+    let mut key = search_cache_key(info);
+    if let Some(redis_conn) = &mut redis_conn {
+        if let Ok(generation) = redis_conn
+            .get(
+                SEARCH_RESULTS_GENERATION_NAMESPACE,
+                SEARCH_RESULTS_GENERATION_KEY,
+            )
+            .await
+        {
+            key = format!("{}:{key}", generation.unwrap_or_default());
+        }
+
+        if let Ok(Some(results)) = redis_conn
+            .get_deserialized_from_json(SEARCH_RESULTS_NAMESPACE, &key)
+            .await
+        {
+            return Ok(results);
+        }
+    }
+
+    let results = search_for_project(info, config).await?;
+
+    if let Some(redis_conn) = &mut redis_conn {
+        if let Err(err) = redis_conn
+            .set_serialized_to_json(
+                SEARCH_RESULTS_NAMESPACE,
+                &key,
+                &results,
+                Some(SEARCH_RESULTS_CACHE_EXPIRY),
+            )
+            .await
+        {
+            warn!("Unable to cache search results: {err}");
+        }
+    }
+
+    Ok(results)
+}
+
+/// Builds the analytics log entry of a search, or `None` if search logging is off
+pub fn search_log(
+    info: &SearchRequest,
+    total_hits: usize,
+    response_time: std::time::Duration,
+    logging: SearchLogging,
+) -> Option<crate::models::analytics::SearchLog> {
+    if logging == SearchLogging::Off {
+        return None;
+    }
+
+    let query = info
+        .query
+        .as_deref()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let store_query = total_hits == 0 || logging == SearchLogging::Verbose;
+
+    Some(crate::models::analytics::SearchLog {
+        recorded: crate::util::date::get_current_tenths_of_ms(),
+        query_hash: blake3::hash(query.as_bytes()).to_hex().to_string(),
+        query_text: if store_query { query } else { String::new() },
+        facets_hash: blake3::hash(info.facets.as_deref().unwrap_or_default().as_bytes())
+            .to_hex()
+            .to_string(),
+        result_count: total_hits as u64,
+        response_time_ms: response_time.as_millis() as u64,
+    })
+}
+
+// Hashes the parts of a search request that affect its results, normalized so that equivalent
+// requests share the same key
+fn search_cache_key(info: &SearchRequest) -> String {
+    // The order of facets within the AND and OR lists doesn't matter
+    let facets = info.facets.as_deref().map(|facets| {
+        match serde_json::from_str::<Vec<Vec<Value>>>(facets) {
+            Ok(facets) => facets
+                .into_iter()
+                .map(|x| x.into_iter().map(|y| y.to_string()).sorted().collect_vec())
+                .sorted()
+                .collect_vec(),
+            Err(_) => vec![vec![facets.to_string()]],
+        }
+    });
+
+    let normalized = serde_json::json!([
+        info.query.as_deref().map(|x| x.trim().to_lowercase()),
+        facets,
+        info.new_filters,
+        info.filters,
+        info.version,
+        info.index.as_deref().unwrap_or("relevance"),
+        info.limit.as_deref().unwrap_or("10"),
+        info.offset.as_deref().unwrap_or("0"),
+    ]);
+
+    blake3::hash(normalized.to_string().as_bytes())
+        .to_hex()
+        .to_string()
+}
+
 pub async fn search_for_project(
     info: &SearchRequest,
     config: &SearchConfig,
 ) -> Result<SearchResults, SearchError> {
-    let client = Client::new(&*config.address, Some(&*config.key));
+    let (offset, limit) = search_window(info)?;
+    let results = execute_search::<ResultSearchProject>(info, config, offset, limit, None).await?;
 
+    Ok(SearchResults {
+        hits: results.hits.into_iter().map(|r| r.result).collect(),
+        offset,
+        limit,
+        // Kept for clients which paginate by page
+        page: offset / limit.max(1) + 1,
+        hits_per_page: limit,
+        total_hits: results.estimated_total_hits.unwrap_or_default(),
+    })
+}
+
+/// The fields of [`ResultSearchProject`] which can be asked for in sparse field sets. Loader
+/// fields are flattened into the hits, so the ones which are displayed are listed as well.
+const RESULT_SEARCH_PROJECT_FIELDS: &[&str] = &[
+    "version_id",
+    "project_id",
+    "project_types",
+    "slug",
+    "author",
+    "name",
+    "summary",
+    "categories",
+    "display_categories",
+    "downloads",
+    "follows",
+    "icon_url",
+    "date_created",
+    "date_modified",
+    "license",
+    "gallery",
+    "featured_gallery",
+    "color",
+    "loaders",
+    "project_loader_fields",
+    "server_only",
+    "client_only",
+    "game_versions",
+    "singleplayer",
+    "client_and_server",
+    "mrpack_loaders",
+    "client_side",
+    "server_side",
+];
+
+/// Same as [`search_for_project`], but only the given fields of each hit are retrieved from
+/// Meilisearch. Fields which are not part of search results are rejected, and loader fields a
+/// project doesn't have are left out of its hit.
+pub async fn search_for_project_fields(
+    info: &SearchRequest,
+    fields: &[String],
+    config: &SearchConfig,
+) -> Result<SparseSearchResults, SearchError> {
+    if let Some(field) = fields
+        .iter()
+        .find(|x| !RESULT_SEARCH_PROJECT_FIELDS.contains(&x.as_str()))
+    {
+        return Err(SearchError::InvalidInput(format!(
+            "Unknown search result field `{field}`"
+        )));
+    }
+
+    let (offset, limit) = search_window(info)?;
+    let fields = fields.iter().map(|x| &**x).collect_vec();
+    let results = execute_search::<serde_json::Map<String, Value>>(
+        info,
+        config,
+        offset,
+        limit,
+        Some(&fields),
+    )
+    .await?;
+
+    Ok(SparseSearchResults {
+        hits: results.hits.into_iter().map(|r| r.result).collect(),
+        offset,
+        limit,
+        page: offset / limit.max(1) + 1,
+        hits_per_page: limit,
+        total_hits: results.estimated_total_hits.unwrap_or_default(),
+    })
+}
+
+/// Restricts a search to the projects of one type, with its own window of results
+pub fn search_request_for_project_type(
+    info: &SearchRequest,
+    project_type: &str,
+    offset: Option<String>,
+    limit: Option<String>,
+) -> SearchRequest {
+    let type_filter = format!("project_types = \"{}\"", project_type.replace('"', ""));
+    let restrict = |filters: Option<&str>| match filters {
+        Some(filters) if !filters.trim().is_empty() => format!("({filters}) AND {type_filter}"),
+        _ => type_filter.clone(),
+    };
+
+    // The legacy filters are ignored by Meilisearch queries using the new filters
+    let (new_filters, filters) = match info.new_filters.as_deref() {
+        Some(new_filters) => (Some(restrict(Some(new_filters))), info.filters.clone()),
+        None => (None, Some(restrict(info.filters.as_deref()))),
+    };
+
+    SearchRequest {
+        query: info.query.clone(),
+        offset: offset.or_else(|| info.offset.clone()),
+        index: info.index.clone(),
+        limit: limit.or_else(|| info.limit.clone()),
+        new_filters,
+        facets: info.facets.clone(),
+        filters,
+        version: info.version.clone(),
+    }
+}
+
+// Parses and bounds the offset and limit of a search request
+fn search_window(info: &SearchRequest) -> Result<(usize, usize), SearchError> {
     let offset: usize = info.offset.as_deref().unwrap_or("0").parse()?;
+    let limit = info.limit.as_deref().unwrap_or("10").parse::<usize>()?;
+
+    if limit > MAX_SEARCH_LIMIT {
+        return Err(SearchError::InvalidInput(format!(
+            "The limit may be at most {MAX_SEARCH_LIMIT}"
+        )));
+    }
+    if offset > MAX_SEARCH_OFFSET {
+        return Err(SearchError::InvalidInput(format!(
+            "The offset may be at most {MAX_SEARCH_OFFSET}"
+        )));
+    }
+
+    Ok((offset, limit))
+}
+
+async fn execute_search<T: 'static + DeserializeOwned>(
+    info: &SearchRequest,
+    config: &SearchConfig,
+    offset: usize,
+    limit: usize,
+    attributes: Option<&[&str]>,
+) -> Result<meilisearch_sdk::search::SearchResults<T>, SearchError> {
+    let client = Client::new(&*config.address, Some(&*config.key));
     let index = info.index.as_deref().unwrap_or("relevance");
-    let limit = info
-        .limit
-        .as_deref()
-        .unwrap_or("10")
-        .parse::<usize>()?
-        .min(100);
 
     let sort = get_sort_index(config, index)?;
     let meilisearch_index = client.get_index(sort.0).await?;
 
     let mut filter_string = String::new();
 
-    // Convert offset and limit to page and hits_per_page
-    let hits_per_page = limit;
-    let page = offset / limit + 1;
-
     let results = {
         let mut query = meilisearch_index.search();
         query
-            .with_page(page)
-            .with_hits_per_page(hits_per_page)
+            .with_offset(offset)
+            .with_limit(limit)
             .with_query(info.query.as_deref().unwrap_or_default())
             .with_sort(&sort.1);
+        if let Some(attributes) = attributes {
+            query.with_attributes_to_retrieve(Selectors::Some(attributes));
+        }
 
         if let Some(new_filters) = info.new_filters.as_deref() {
-            query.with_filter(new_filters);
+            filter_string.push_str(new_filters);
         } else {
             let facets = if let Some(facets) = &info.facets {
                 Some(serde_json::from_str::<Vec<Vec<Value>>>(facets)?)
@@ -260,7 +660,7 @@ pub async fn search_for_project(
                     {
                         filter_string.push('(');
                         for (facet_inner_index, facet) in facet_inner_list.iter().enumerate() {
-                            filter_string.push_str(&facet.replace(':', " = "));
+                            filter_string.push_str(&facet_to_filter(facet));
                             if facet_inner_index != (facet_inner_list.len() - 1) {
                                 filter_string.push_str(" AND ")
                             }
@@ -286,19 +686,112 @@ pub async fn search_for_project(
             } else {
                 filter_string.push_str(&filters);
             }
-
-            if !filter_string.is_empty() {
-                query.with_filter(&filter_string);
-            }
         }
 
-        query.execute::<ResultSearchProject>().await?
+        // Documents without the field were indexed before bans existed, so they are not hidden
+        filter_string = if filter_string.is_empty() {
+            HIDE_BANNED_FILTER.to_string()
+        } else {
+            format!("({filter_string}) AND {HIDE_BANNED_FILTER}")
+        };
+        query.with_filter(&filter_string);
+
+        query.execute::<T>().await?
     };
 
-    Ok(SearchResults {
-        hits: results.hits.into_iter().map(|r| r.result).collect(),
-        page: results.page.unwrap_or_default(),
-        hits_per_page: results.hits_per_page.unwrap_or_default(),
-        total_hits: results.total_hits.unwrap_or_default(),
-    })
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(query: &str, facets: &str) -> SearchRequest {
+        SearchRequest {
+            query: Some(query.to_string()),
+            offset: None,
+            index: None,
+            limit: Some("10".to_string()),
+            new_filters: None,
+            facets: Some(facets.to_string()),
+            filters: None,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn search_log_only_keeps_zero_result_queries() {
+        let info = request(" Sodium ", "[]");
+        let elapsed = std::time::Duration::from_millis(12);
+
+        let log = search_log(&info, 0, elapsed, SearchLogging::Enabled).unwrap();
+        assert_eq!(log.query_text, "sodium");
+        assert_eq!(log.response_time_ms, 12);
+
+        let log = search_log(&info, 5, elapsed, SearchLogging::Enabled).unwrap();
+        assert_eq!(log.query_text, "");
+        assert_eq!(log.result_count, 5);
+
+        let log = search_log(&info, 5, elapsed, SearchLogging::Verbose).unwrap();
+        assert_eq!(log.query_text, "sodium");
+
+        assert!(search_log(&info, 0, elapsed, SearchLogging::Off).is_none());
+    }
+
+    #[test]
+    fn author_facets_are_normalized() {
+        assert_eq!(
+            facet_to_filter("author:Test_User"),
+            "author_normalized = \"testuser\""
+        );
+        assert_eq!(facet_to_filter("categories:fabric"), "categories = fabric");
+    }
+
+    #[test]
+    fn project_type_requests_keep_filters() {
+        let info = request("sodium", r#"[["categories:optimization"]]"#);
+        let grouped =
+            search_request_for_project_type(&info, "modpack", Some("20".to_string()), None);
+        assert_eq!(grouped.query.as_deref(), Some("sodium"));
+        assert_eq!(grouped.facets, info.facets);
+        assert_eq!(grouped.offset.as_deref(), Some("20"));
+        assert_eq!(grouped.limit.as_deref(), Some("10"));
+        assert_eq!(
+            grouped.filters.as_deref(),
+            Some(r#"project_types = "modpack""#)
+        );
+
+        let info = SearchRequest {
+            new_filters: Some("downloads > 10".to_string()),
+            ..request("sodium", "[]")
+        };
+        let grouped = search_request_for_project_type(&info, "mod", None, None);
+        assert_eq!(
+            grouped.new_filters.as_deref(),
+            Some(r#"(downloads > 10) AND project_types = "mod""#)
+        );
+    }
+
+    #[test]
+    fn search_cache_key_normalizes_equivalent_requests() {
+        let key = search_cache_key(&request(
+            "Sodium",
+            r#"[["categories:fabric","categories:quilt"],["project_types:mod"]]"#,
+        ));
+
+        assert_eq!(
+            key,
+            search_cache_key(&request(
+                " sodium ",
+                r#"[["project_types:mod"],["categories:quilt","categories:fabric"]]"#,
+            ))
+        );
+        assert_ne!(
+            key,
+            search_cache_key(&request(
+                "sodium",
+                r#"[["categories:fabric"],["project_types:mod"]]"#,
+            ))
+        );
+    }
 }
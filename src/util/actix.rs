@@ -9,6 +9,7 @@ pub struct MultipartSegment {
     pub name: String,
     pub filename: Option<String>,
     pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
     pub data: MultipartSegmentData,
 }
 
@@ -66,6 +67,10 @@ pub fn generate_multipart(data: impl IntoIterator<Item = MultipartSegment>) -> (
                 .as_bytes(),
             );
         }
+        if let Some(content_encoding) = &segment.content_encoding {
+            payload
+                .extend_from_slice(format!("\r\nContent-Encoding: {content_encoding}").as_bytes());
+        }
         payload.extend_from_slice(b"\r\n\r\n");
 
         match &segment.data {
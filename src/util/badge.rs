@@ -0,0 +1,115 @@
+//! Shields-style SVG badges, rendered from a template so no image library is needed
+
+#[derive(serde::Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum BadgeStyle {
+    #[default]
+    Flat,
+    ForTheBadge,
+}
+
+impl BadgeStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeStyle::Flat => "flat",
+            BadgeStyle::ForTheBadge => "for-the-badge",
+        }
+    }
+}
+
+/// Maps a named shields color, or a hex color without the `#`, to a CSS color
+pub fn parse_color(color: &str) -> Option<String> {
+    let named = match color {
+        "brightgreen" => Some("#4c1"),
+        "green" => Some("#97ca00"),
+        "yellowgreen" => Some("#a4a61d"),
+        "yellow" => Some("#dfb317"),
+        "orange" => Some("#fe7d37"),
+        "red" => Some("#e05d44"),
+        "blue" => Some("#007ec6"),
+        "lightgrey" => Some("#9f9f9f"),
+        _ => None,
+    };
+    if let Some(named) = named {
+        return Some(named.to_string());
+    }
+
+    (matches!(color.len(), 3 | 6) && color.chars().all(|x| x.is_ascii_hexdigit()))
+        .then(|| format!("#{color}"))
+}
+
+/// Formats a count the way badges show it (ex: `950`, `12.5k`, `1.2M`)
+pub fn format_count(count: u64) -> String {
+    let (value, suffix) = match count {
+        0..=999 => return count.to_string(),
+        1_000..=999_999 => (count as f64 / 1_000.0, "k"),
+        1_000_000..=999_999_999 => (count as f64 / 1_000_000.0, "M"),
+        _ => (count as f64 / 1_000_000_000.0, "B"),
+    };
+
+    // Truncated rather than rounded, so a badge never shows more downloads than there are
+    let value = (value * 10.0).floor() / 10.0;
+    if value.fract() == 0.0 {
+        format!("{value:.0}{suffix}")
+    } else {
+        format!("{value:.1}{suffix}")
+    }
+}
+
+fn escape(x: &str) -> String {
+    x.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn render_badge(style: BadgeStyle, label: &str, value: &str, color: &str) -> String {
+    let (label, value) = match style {
+        BadgeStyle::Flat => (label.to_string(), value.to_string()),
+        BadgeStyle::ForTheBadge => (label.to_uppercase(), value.to_uppercase()),
+    };
+    // Text widths are estimated from the character count, which is close enough for the
+    // fonts badges are rendered with
+    let (height, char_width, padding, font) = match style {
+        BadgeStyle::Flat => (20, 7, 10, r#"font-size="11""#),
+        BadgeStyle::ForTheBadge => (28, 8, 24, r#"font-size="10" font-weight="bold""#),
+    };
+    let label_width = label.chars().count() * char_width + padding;
+    let value_width = value.chars().count() * char_width + padding;
+    let width = label_width + value_width;
+    let text_y = height * 7 / 10;
+    let radius = match style {
+        BadgeStyle::Flat => 3,
+        BadgeStyle::ForTheBadge => 0,
+    };
+
+    let (label, value, color) = (escape(&label), escape(&value), escape(color));
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" role="img" aria-label="{label}: {value}"><title>{label}: {value}</title><clipPath id="r"><rect width="{width}" height="{height}" rx="{radius}" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="{height}" fill="#555"/><rect x="{label_width}" width="{value_width}" height="{height}" fill="{color}"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" {font}><text x="{label_x}" y="{text_y}">{label}</text><text x="{value_x}" y="{text_y}">{value}</text></g></svg>"##,
+        label_x = label_width / 2,
+        value_x = label_width + value_width / 2,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_are_shortened() {
+        assert_eq!(format_count(950), "950");
+        assert_eq!(format_count(1_000), "1k");
+        assert_eq!(format_count(12_549), "12.5k");
+        assert_eq!(format_count(1_299_999), "1.2M");
+        assert_eq!(format_count(3_000_000_000), "3B");
+    }
+
+    #[test]
+    fn colors_are_named_or_hex() {
+        assert_eq!(parse_color("brightgreen").as_deref(), Some("#4c1"));
+        assert_eq!(parse_color("1bd96a").as_deref(), Some("#1bd96a"));
+        assert_eq!(parse_color("fff").as_deref(), Some("#fff"));
+        assert_eq!(parse_color("url(x)"), None);
+        assert_eq!(parse_color("12345"), None);
+    }
+}
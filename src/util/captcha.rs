@@ -1,20 +1,11 @@
 use crate::routes::ApiError;
-use crate::util::env::parse_var;
+use crate::util::ip::ClientIp;
 use actix_web::HttpRequest;
 use serde::Deserialize;
 use serde_json::json;
 
 pub async fn check_turnstile_captcha(req: &HttpRequest, challenge: &str) -> Result<bool, ApiError> {
-    let conn_info = req.connection_info().clone();
-    let ip_addr = if parse_var("CLOUDFLARE_INTEGRATION").unwrap_or(false) {
-        if let Some(header) = req.headers().get("CF-Connecting-IP") {
-            header.to_str().ok()
-        } else {
-            conn_info.peer_addr()
-        }
-    } else {
-        conn_info.peer_addr()
-    };
+    let ip_addr = ClientIp::get(req).map(|x| x.to_string());
 
     let client = reqwest::Client::new();
 
@@ -28,3 +28,8 @@ pub fn project_file_type(ext: &str) -> Option<&str> {
         _ => None,
     }
 }
+
+/// Whether the extension is one of a split zip archive's parts (`z01`, `z02`, ...)
+pub fn is_archive_part_ext(ext: &str) -> bool {
+    ext.len() == 3 && ext.starts_with('z') && ext[1..].chars().all(|c| c.is_ascii_digit())
+}
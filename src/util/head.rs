@@ -0,0 +1,211 @@
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use actix_web::body::{BodySize, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderValue, CONTENT_LENGTH, ETAG};
+use actix_web::http::Method;
+use actix_web::web::Bytes;
+use actix_web::{Error, HttpMessage, HttpRequest};
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+
+/// Answers `HEAD` requests with the status and headers of the matching `GET` route, and adds an
+/// `ETag` to successful `GET` responses.
+///
+/// `HEAD` requests are routed as `GET`, so they go through the same authentication. Streamed
+/// responses (ex: event streams) have no length, so they are passed through untouched instead of
+/// being buffered. Handlers that answer `HEAD` differently (ex: downloads, which redirect on
+/// `GET`) can check [`is_head_request`] and set their own `Content-Length`, which is kept.
+pub struct HeadSupport;
+
+/// Marks requests that were sent as `HEAD` and are routed as `GET`
+#[derive(Clone, Copy)]
+struct HeadRequest;
+
+/// Whether a request routed as `GET` was sent as `HEAD`
+pub fn is_head_request(req: &HttpRequest) -> bool {
+    req.extensions().get::<HeadRequest>().is_some()
+}
+
+impl<S, B> Transform<S, ServiceRequest> for HeadSupport
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = HeadSupportService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HeadSupportService { service }))
+    }
+}
+
+#[doc(hidden)]
+pub struct HeadSupportService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for HeadSupportService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let is_head = req.method() == Method::HEAD;
+        if is_head {
+            req.head_mut().method = Method::GET;
+            req.extensions_mut().insert(HeadRequest);
+        }
+        let is_get = req.method() == Method::GET;
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?.map_into_boxed_body();
+            if !is_get || !matches!(res.response().body().size(), BodySize::Sized(_)) {
+                return Ok(res);
+            }
+            // The handler answered the `HEAD` request itself
+            if is_head && res.headers().contains_key(CONTENT_LENGTH) {
+                return Ok(res);
+            }
+
+            let (req, res) = res.into_parts();
+            let (mut res, body) = res.into_parts();
+            let bytes = actix_web::body::to_bytes(body)
+                .await
+                .map_err(actix_web::error::ErrorInternalServerError)?;
+
+            if res.status().is_success() && !res.headers().contains_key(ETAG) {
+                let etag = format!("\"{}\"", sha1::Sha1::from(&bytes).hexdigest());
+                if let Ok(etag) = HeaderValue::from_str(&etag) {
+                    res.headers_mut().insert(ETAG, etag);
+                }
+            }
+
+            let res = if is_head {
+                // The server writes the length of the body itself, this is only set so the header
+                // can be read before the response is sent
+                res.headers_mut()
+                    .insert(CONTENT_LENGTH, HeaderValue::from(bytes.len()));
+                res.set_body(BoxBody::new(HeadBody(bytes.len() as u64)))
+            } else {
+                res.set_body(BoxBody::new(bytes))
+            };
+
+            Ok(ServiceResponse::new(req, res))
+        })
+    }
+}
+
+/// The body of a response to a `HEAD` request, with the length of the `GET` response but no
+/// content
+struct HeadBody(u64);
+
+impl MessageBody for HeadBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Sized(self.0)
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        Poll::Ready(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use futures_util::stream;
+
+    #[actix_rt::test]
+    async fn head_has_get_headers_without_body() {
+        let app = test::init_service(
+            App::new().service(
+                web::scope("")
+                    .wrap(HeadSupport)
+                    .route(
+                        "/",
+                        web::get().to(|| async { HttpResponse::Ok().body("hello") }),
+                    )
+                    .route(
+                        "/missing",
+                        web::get().to(|| async { HttpResponse::NotFound().body("missing") }),
+                    ),
+            ),
+        )
+        .await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        let etag = resp.headers().get(ETAG).unwrap().clone();
+        assert_eq!(test::read_body(resp).await, "hello");
+
+        let req = test::TestRequest::default()
+            .method(Method::HEAD)
+            .uri("/")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get(ETAG), Some(&etag));
+        assert_eq!(resp.headers().get(CONTENT_LENGTH).unwrap(), "5");
+        assert_eq!(resp.response().body().size(), BodySize::Sized(5));
+        assert!(test::read_body(resp).await.is_empty());
+
+        let req = test::TestRequest::default()
+            .method(Method::HEAD)
+            .uri("/missing")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+        assert!(resp.headers().get(ETAG).is_none());
+        assert!(test::read_body(resp).await.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn handlers_can_answer_head_themselves() {
+        let app = test::init_service(App::new().service(web::scope("").wrap(HeadSupport).route(
+            "/download",
+            web::get().to(|req: HttpRequest| async move {
+                if is_head_request(&req) {
+                    HttpResponse::Ok()
+                        .no_chunking(1024)
+                        .streaming(stream::empty::<Result<Bytes, Infallible>>())
+                } else {
+                    HttpResponse::TemporaryRedirect()
+                        .insert_header(("Location", "https://cdn.example.com/file"))
+                        .body("redirect")
+                }
+            }),
+        )))
+        .await;
+
+        let resp =
+            test::call_service(&app, test::TestRequest::get().uri("/download").to_request()).await;
+        assert_eq!(resp.status(), 307);
+
+        let req = test::TestRequest::default()
+            .method(Method::HEAD)
+            .uri("/download")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get(CONTENT_LENGTH).unwrap(), "1024");
+        assert!(resp.headers().get(ETAG).is_none());
+    }
+}
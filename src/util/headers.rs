@@ -0,0 +1,105 @@
+use actix_web::middleware::DefaultHeaders;
+
+use crate::util::env::parse_var;
+
+/// The content security policy of routes that serve rendered HTML (ex: project embeds)
+pub const HTML_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; img-src *";
+
+/// Security headers added to every response that doesn't set them itself.
+///
+/// `Strict-Transport-Security` can be turned off with `SECURITY_HEADERS_HSTS=false`, for
+/// environments that are not served over HTTPS.
+pub fn security_headers() -> DefaultHeaders {
+    let headers = DefaultHeaders::new()
+        .add(("Content-Security-Policy", "default-src 'none'"))
+        .add(("X-Content-Type-Options", "nosniff"))
+        .add(("X-Frame-Options", "DENY"))
+        .add(("Referrer-Policy", "strict-origin"));
+
+    if parse_var("SECURITY_HEADERS_HSTS").unwrap_or(true) {
+        headers.add((
+            "Strict-Transport-Security",
+            "max-age=31536000; includeSubDomains",
+        ))
+    } else {
+        headers
+    }
+}
+
+/// Headers identifying the build that served a response, to help debugging requests that went
+/// through a load balancer.
+pub fn build_info_headers() -> DefaultHeaders {
+    DefaultHeaders::new()
+        .add(("X-Modrinth-API-Version", env!("CARGO_PKG_VERSION")))
+        .add(("X-Modrinth-Git-Commit", env!("GIT_COMMIT_HASH")))
+}
+
+/// An inclusive range of bytes requested with a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Parses a `Range` header against a file of `size` bytes. Only single ranges of bytes are
+/// supported (ex: `bytes=0-499`, `bytes=500-` or `bytes=-500`), and the end is clamped to the file.
+///
+/// Returns `None` if the header is not a supported range, and `Some(Err(()))` if the range falls
+/// outside of the file.
+pub fn parse_byte_range(header: &str, size: u64) -> Option<Result<ByteRange, ()>> {
+    let range = header.trim().strip_prefix("bytes=")?.trim();
+    if range.contains(',') {
+        return None;
+    }
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // A suffix, the last `end` bytes of the file
+        let suffix = end.parse::<u64>().ok()?;
+        if suffix == 0 || size == 0 {
+            return Some(Err(()));
+        }
+        ByteRange {
+            start: size.saturating_sub(suffix),
+            end: size - 1,
+        }
+    } else {
+        let start = start.parse::<u64>().ok()?;
+        let end = if end.is_empty() {
+            u64::MAX
+        } else {
+            end.parse::<u64>().ok()?
+        };
+        if end < start {
+            return None;
+        }
+        if start >= size {
+            return Some(Err(()));
+        }
+        ByteRange {
+            start,
+            end: end.min(size - 1),
+        }
+    };
+
+    Some(Ok(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_ranges_are_clamped_to_the_file() {
+        let range = |start, end| Some(Ok(ByteRange { start, end }));
+
+        assert_eq!(parse_byte_range("bytes=0-499", 1000), range(0, 499));
+        assert_eq!(parse_byte_range("bytes=500-", 1000), range(500, 999));
+        assert_eq!(parse_byte_range("bytes=-200", 1000), range(800, 999));
+        assert_eq!(parse_byte_range("bytes=900-2000", 1000), range(900, 999));
+        assert_eq!(parse_byte_range("bytes=1000-", 1000), Some(Err(())));
+        assert_eq!(parse_byte_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_byte_range("items=0-1", 1000), None);
+    }
+}
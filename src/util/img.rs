@@ -19,6 +19,20 @@ pub fn get_color_from_img(data: &[u8]) -> Result<Option<u32>, ImageError> {
     Ok(color)
 }
 
+/// Resizes an icon to fill a `size` by `size` square, cropping what overflows, and encodes it as
+/// a PNG
+pub fn resize_icon(data: &[u8], size: u32) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = Vec::new();
+    image::load_from_memory(data)?
+        .resize_to_fill(size, size, FilterType::Lanczos3)
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageOutputFormat::Png,
+        )?;
+
+    Ok(bytes)
+}
+
 // check changes to associated images
 // if they no longer exist in the String list, delete them
 // Eg: if description is modified and no longer contains a link to an iamge
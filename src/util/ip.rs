@@ -0,0 +1,190 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use crate::routes::ApiError;
+use crate::util::env::{parse_strings_from_var, parse_var};
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::HeaderMap,
+    Error, FromRequest, HttpMessage, HttpRequest,
+};
+use futures_util::future::LocalBoxFuture;
+use futures_util::future::{ready, Ready};
+use ipnetwork::IpNetwork;
+use log::warn;
+
+/// The resolved address of the client that made a request.
+///
+/// Set on the request extensions by [`ClientIpResolver`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// Gets the client IP of a request, falling back to the TCP peer if the resolver did not run
+    pub fn get(req: &HttpRequest) -> Option<IpAddr> {
+        req.extensions()
+            .get::<ClientIp>()
+            .map(|x| x.0)
+            .or_else(|| resolve_client_ip(req.peer_addr().map(|x| x.ip()), req.headers(), &[]))
+    }
+}
+
+impl FromRequest for ClientIp {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(ClientIp::get(req).map(ClientIp).ok_or_else(|| {
+            ApiError::InvalidInput("Unable to determine the client IP address".to_string())
+        }))
+    }
+}
+
+/// Parses the `TRUSTED_PROXIES` variable, a JSON array of CIDR ranges (ex: `["10.0.0.0/8"]`)
+pub fn trusted_proxies_from_env() -> Vec<IpNetwork> {
+    parse_strings_from_var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|x| match x.parse() {
+            Ok(network) => Some(network),
+            Err(err) => {
+                warn!("Ignoring invalid trusted proxy range `{x}`: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolves the client IP of a request.
+///
+/// `X-Forwarded-For` is only read if the TCP peer is a trusted proxy. The chain is then walked from
+/// the closest hop outward, and the first address that is not a trusted proxy is the client.
+pub fn resolve_client_ip(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> Option<IpAddr> {
+    if parse_var("CLOUDFLARE_INTEGRATION").unwrap_or(false) {
+        if let Some(ip) = headers
+            .get("CF-Connecting-IP")
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.trim().parse().ok())
+        {
+            return Some(ip);
+        }
+    }
+
+    let peer = peer?;
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|x| x.contains(*ip));
+    if !is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded = headers
+        .get_all("X-Forwarded-For")
+        .filter_map(|x| x.to_str().ok())
+        .flat_map(|x| x.split(','))
+        .filter_map(|x| x.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+
+    Some(
+        forwarded
+            .iter()
+            .rev()
+            .find(|x| !is_trusted(x))
+            .or(forwarded.first())
+            .copied()
+            .unwrap_or(peer),
+    )
+}
+
+pub struct ClientIpResolver(pub Arc<Vec<IpNetwork>>);
+
+impl<S, B> Transform<S, ServiceRequest> for ClientIpResolver
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ClientIpResolverService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ClientIpResolverService {
+            service,
+            trusted_proxies: Arc::clone(&self.0),
+        }))
+    }
+}
+
+#[doc(hidden)]
+pub struct ClientIpResolverService<S> {
+    service: S,
+    trusted_proxies: Arc<Vec<IpNetwork>>,
+}
+
+impl<S, B> Service<ServiceRequest> for ClientIpResolverService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(ip) = resolve_client_ip(
+            req.peer_addr().map(|x| x.ip()),
+            req.headers(),
+            &self.trusted_proxies,
+        ) {
+            req.extensions_mut().insert(ClientIp(ip));
+        }
+
+        Box::pin(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::HeaderValue;
+
+    fn forwarded_headers(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Forwarded-For".parse().unwrap(),
+            HeaderValue::from_static(value),
+        );
+        headers
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_forwarded_for() {
+        let headers = forwarded_headers("1.2.3.4");
+        let trusted = vec!["10.0.0.0/8".parse().unwrap()];
+
+        let ip = resolve_client_ip(Some("192.168.1.5".parse().unwrap()), &headers, &trusted);
+        assert_eq!(ip, Some("192.168.1.5".parse().unwrap()));
+
+        // Without any trusted proxies, the header is never read
+        let ip = resolve_client_ip(Some("10.0.0.1".parse().unwrap()), &headers, &[]);
+        assert_eq!(ip, Some("10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn trusted_peer_uses_closest_untrusted_hop() {
+        // The left-most entry is client supplied and can be spoofed
+        let headers = forwarded_headers("6.6.6.6, 1.2.3.4, 10.0.0.2");
+        let trusted = vec!["10.0.0.0/8".parse().unwrap()];
+
+        let ip = resolve_client_ip(Some("10.0.0.1".parse().unwrap()), &headers, &trusted);
+        assert_eq!(ip, Some("1.2.3.4".parse().unwrap()));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod actix;
+pub mod badge;
 pub mod bitflag;
 pub mod captcha;
 pub mod cors;
@@ -6,7 +7,10 @@ pub mod date;
 pub mod env;
 pub mod ext;
 pub mod guards;
+pub mod head;
+pub mod headers;
 pub mod img;
+pub mod ip;
 pub mod ratelimit;
 pub mod redis;
 pub mod routes;
@@ -4,11 +4,11 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::routes::ApiError;
-use crate::util::env::parse_var;
+use crate::util::ip::{resolve_client_ip, ClientIp};
 use actix_web::{
     body::EitherBody,
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, ResponseError,
+    Error, HttpMessage, ResponseError,
 };
 use futures_util::future::LocalBoxFuture;
 use futures_util::future::{ready, Ready};
@@ -68,16 +68,11 @@ where
             }
         }
 
-        let conn_info = req.connection_info().clone();
-        let ip = if parse_var("CLOUDFLARE_INTEGRATION").unwrap_or(false) {
-            if let Some(header) = req.headers().get("CF-Connecting-IP") {
-                header.to_str().ok()
-            } else {
-                conn_info.peer_addr()
-            }
-        } else {
-            conn_info.peer_addr()
-        };
+        let ip = req
+            .extensions()
+            .get::<ClientIp>()
+            .map(|x| x.0)
+            .or_else(|| resolve_client_ip(req.peer_addr().map(|x| x.ip()), req.headers(), &[]));
 
         if let Some(ip) = ip {
             let ip = ip.to_string();
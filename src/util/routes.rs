@@ -1,9 +1,13 @@
 use crate::routes::v3::project_creation::CreateError;
 use crate::routes::ApiError;
 use actix_multipart::Field;
+use actix_web::http::header::CONTENT_ENCODING;
 use actix_web::web::Payload;
-use bytes::BytesMut;
+use bytes::{BufMut, BytesMut};
+use flate2::read::GzDecoder;
 use futures::StreamExt;
+use std::convert::TryFrom;
+use std::io::Read;
 
 pub async fn read_from_payload(
     payload: &mut Payload,
@@ -23,6 +27,68 @@ pub async fn read_from_payload(
     Ok(bytes)
 }
 
+/// Reads a multipart field, decompressing it if its part declares `Content-Encoding: gzip`.
+///
+/// The cap applies to both the uploaded and the decompressed size.
+pub async fn read_from_field_decoded(
+    field: &mut Field,
+    cap: usize,
+    err_msg: &'static str,
+) -> Result<BytesMut, CreateError> {
+    let encoding = field
+        .headers()
+        .get(CONTENT_ENCODING)
+        .map(|x| {
+            x.to_str()
+                .map(|x| x.trim().to_ascii_lowercase())
+                .map_err(|_| CreateError::InvalidInput("Invalid content encoding".to_string()))
+        })
+        .transpose()?;
+
+    let data = read_from_field(field, cap, err_msg).await?;
+
+    match encoding.as_deref() {
+        None | Some("identity") => Ok(data),
+        Some("gzip") | Some("x-gzip") => {
+            // Decompressing can take a while for large files, so it is kept off the async worker
+            actix_web::web::block(move || decode_gzip(&data, cap))
+                .await
+                .map_err(crate::validate::ValidationError::from)?
+                .map_err(|_| {
+                    CreateError::InvalidInput(
+                        "File is declared as gzip encoded, but is not valid gzip data".to_string(),
+                    )
+                })?
+                .ok_or_else(|| CreateError::InvalidInput(String::from(err_msg)))
+        }
+        Some(encoding) => Err(CreateError::InvalidInput(format!(
+            "Unsupported content encoding `{encoding}`"
+        ))),
+    }
+}
+
+/// How many times its compressed size the buffer for decompressed gzip data starts at, at most
+const GZIP_SIZE_HINT_RATIO: usize = 8;
+
+/// Decompresses gzip data, or returns `None` if it is larger than the cap once decompressed
+fn decode_gzip(data: &[u8], cap: usize) -> std::io::Result<Option<BytesMut>> {
+    // The gzip footer ends with the decompressed size (modulo 2^32). It is sent by the client, so
+    // it is only trusted as a hint up to a few times the compressed size, and the buffer grows
+    // from there
+    let size_hint = data
+        .get(data.len().saturating_sub(4)..)
+        .and_then(|x| <[u8; 4]>::try_from(x).ok())
+        .map(|x| u32::from_le_bytes(x) as usize)
+        .unwrap_or_default()
+        .min(data.len().saturating_mul(GZIP_SIZE_HINT_RATIO));
+
+    let mut decoded = BytesMut::with_capacity(size_hint.min(cap + 1)).writer();
+    std::io::copy(&mut GzDecoder::new(data).take(cap as u64 + 1), &mut decoded)?;
+    let decoded = decoded.into_inner();
+
+    Ok((decoded.len() <= cap).then_some(decoded))
+}
+
 pub async fn read_from_field(
     field: &mut Field,
     cap: usize,
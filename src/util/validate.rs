@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::Serialize;
 use validator::{ValidationErrors, ValidationErrorsKind};
 
 use crate::models::pats::Scopes;
@@ -57,6 +58,41 @@ pub fn validation_errors_to_string(errors: ValidationErrors, adder: Option<Strin
     String::new()
 }
 
+/// Splits validation errors into one message per invalid field, sorted by field. The messages
+/// are formatted like [`validation_errors_to_string`] ones.
+pub fn validation_errors_by_field(errors: ValidationErrors) -> Vec<(String, String)> {
+    errors
+        .into_errors()
+        .into_iter()
+        .map(|(field, error)| {
+            let message = match error {
+                ValidationErrorsKind::Struct(errors) => {
+                    validation_errors_to_string(*errors, Some(format!("of item {field}")))
+                }
+                ValidationErrorsKind::List(list) => list
+                    .into_iter()
+                    .next()
+                    .map(|(index, errors)| {
+                        validation_errors_to_string(
+                            *errors,
+                            Some(format!("of list {field} with index {index}")),
+                        )
+                    })
+                    .unwrap_or_default(),
+                ValidationErrorsKind::Field(errors) => errors
+                    .first()
+                    .map(|error| {
+                        format!("Field {field} failed validation with error: {}", error.code)
+                    })
+                    .unwrap_or_default(),
+            };
+
+            (field.to_string(), message)
+        })
+        .sorted()
+        .collect()
+}
+
 pub fn validate_deps(
     values: &[crate::models::projects::Dependency],
 ) -> Result<(), validator::ValidationError> {
@@ -93,21 +129,45 @@ pub fn validate_url(value: &str) -> Result<(), validator::ValidationError> {
     Ok(())
 }
 
-pub fn validate_url_hashmap_optional_values(
-    values: &std::collections::HashMap<String, Option<String>>,
-) -> Result<(), validator::ValidationError> {
-    for value in values.values().flatten() {
-        validate_url(value)?;
+/// The hosts issue trackers can be linked on: GitHub, GitLab and Jira Cloud
+const ISSUE_TRACKER_HOSTS: &[&str] = &["github.com", "gitlab.com"];
+const ISSUE_TRACKER_HOST_SUFFIXES: &[&str] = &[".atlassian.net"];
+
+pub fn validate_issue_tracker_url(value: &str) -> Result<(), validator::ValidationError> {
+    validate_url(value)?;
+
+    let host = url::Url::parse(value)
+        .ok()
+        .and_then(|x| x.host_str().map(|x| x.to_lowercase()))
+        .unwrap_or_default();
+    if !ISSUE_TRACKER_HOSTS.contains(&&*host)
+        && !ISSUE_TRACKER_HOST_SUFFIXES
+            .iter()
+            .any(|x| host.ends_with(x))
+    {
+        return Err(validator::ValidationError::new(
+            "URL must be a known issue tracker",
+        ));
     }
 
     Ok(())
 }
 
-pub fn validate_url_hashmap_values(
-    values: &std::collections::HashMap<String, String>,
-) -> Result<(), validator::ValidationError> {
-    for value in values.values() {
-        validate_url(value)?;
+/// Validates the `link_urls` of a project creation or edit, where the `issues` link must point to
+/// a known issue tracker like it does when it is set through the external links route. Edits use
+/// `None` values to remove links, which are not validated.
+pub fn validate_link_urls<'a, V>(
+    values: &'a std::collections::HashMap<String, V>,
+) -> Result<(), validator::ValidationError>
+where
+    &'a V: Into<Option<&'a String>>,
+{
+    for (platform, value) in values {
+        match value.into() {
+            Some(value) if platform == "issues" => validate_issue_tracker_url(value)?,
+            Some(value) => validate_url(value)?,
+            None => {}
+        }
     }
 
     Ok(())
@@ -133,10 +193,114 @@ pub fn validate_name(value: &str) -> Result<(), validator::ValidationError> {
     Ok(())
 }
 
+/// A string field whose length, in characters, is outside of its allowed range
+#[derive(thiserror::Error, Serialize, Debug, Clone, PartialEq, Eq)]
+#[error("Field {field} must be between {min} and {max} characters long, but was {actual}")]
+pub struct StringLengthError {
+    pub field: &'static str,
+    pub min: usize,
+    pub max: usize,
+    pub actual: usize,
+}
+
+/// Checks that `value` is between `min` and `max` characters long (inclusive)
+pub fn validate_string_length(
+    field: &'static str,
+    value: &str,
+    min: usize,
+    max: usize,
+) -> Result<(), StringLengthError> {
+    let actual = value.chars().count();
+
+    if actual < min || actual > max {
+        return Err(StringLengthError {
+            field,
+            min,
+            max,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn validate_string_length_counts_characters() {
+        assert!(validate_string_length("name", "ab", 2, 4).is_ok());
+        assert!(validate_string_length("name", "äöüß", 2, 4).is_ok());
+
+        assert_eq!(
+            validate_string_length("name", "abcde", 2, 4),
+            Err(StringLengthError {
+                field: "name",
+                min: 2,
+                max: 4,
+                actual: 5,
+            })
+        );
+        assert!(validate_string_length("name", "", 2, 4).is_err());
+    }
+
+    #[test]
+    fn validation_errors_are_split_by_field() {
+        let mut errors = ValidationErrors::new();
+        errors.add("slug", validator::ValidationError::new("length"));
+        errors.add("name", validator::ValidationError::new("regex"));
+
+        assert_eq!(
+            validation_errors_by_field(errors),
+            vec![
+                (
+                    "name".to_string(),
+                    "Field name failed validation with error: regex".to_string()
+                ),
+                (
+                    "slug".to_string(),
+                    "Field slug failed validation with error: length".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn issue_tracker_urls_must_be_known_hosts() {
+        assert!(validate_issue_tracker_url("https://github.com/modrinth/labrinth/issues").is_ok());
+        assert!(validate_issue_tracker_url("https://gitlab.com/group/project/-/issues").is_ok());
+        assert!(validate_issue_tracker_url("https://example.atlassian.net/jira").is_ok());
+
+        assert!(validate_issue_tracker_url("http://github.com/modrinth/labrinth").is_err());
+        assert!(validate_issue_tracker_url("https://example.com/issues").is_err());
+        assert!(validate_issue_tracker_url("https://notgithub.com/issues").is_err());
+        assert!(validate_issue_tracker_url("https://atlassian.net.example.com").is_err());
+    }
+
+    #[test]
+    fn link_urls_check_the_issue_tracker_host() {
+        let links = |platform: &str, url: Option<&str>| {
+            std::collections::HashMap::from([(platform.to_string(), url.map(str::to_string))])
+        };
+
+        assert!(
+            validate_link_urls(&links("issues", Some("https://github.com/a/b/issues"))).is_ok()
+        );
+        assert!(validate_link_urls(&links("issues", None)).is_ok());
+        assert!(validate_link_urls(&links("wiki", Some("https://example.com/wiki"))).is_ok());
+
+        assert!(validate_link_urls(&links("issues", Some("https://example.com/issues"))).is_err());
+        assert!(validate_link_urls(&links("wiki", Some("not a url"))).is_err());
+
+        // Project creations have no removed links
+        let created = std::collections::HashMap::from([(
+            "issues".to_string(),
+            "https://example.com/issues".to_string(),
+        )]);
+        assert!(validate_link_urls(&created).is_err());
+    }
+
     #[test]
     fn validate_name_with_valid_input() {
         let result = validate_name("My Test mod");
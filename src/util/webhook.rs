@@ -98,7 +98,7 @@ pub async fn send_discord_webhook(
             FROM mods m
             LEFT OUTER JOIN mods_categories mc ON joining_mod_id = m.id AND mc.is_additional = FALSE
             LEFT OUTER JOIN categories c ON mc.joining_category_id = c.id
-            LEFT OUTER JOIN versions v ON v.mod_id = m.id AND v.status != ALL($2)
+            LEFT OUTER JOIN versions v ON v.mod_id = m.id AND v.status != ALL($2) AND v.deleted_at IS NULL
             LEFT OUTER JOIN loaders_versions lv ON lv.version_id = v.id
             LEFT OUTER JOIN loaders lo ON lo.id = lv.loader_id
             LEFT JOIN loaders_project_types lpt ON lpt.joining_loader_id = lo.id
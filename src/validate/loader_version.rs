@@ -0,0 +1,102 @@
+//! Reads the loader versions a file was built for from its metadata, used to infer the game
+//! versions of versions uploaded without them.
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// The loader of each dependency key of a `modrinth.index.json`
+const PACK_LOADER_DEPENDENCIES: &[(&str, &str)] = &[
+    ("forge", "forge"),
+    ("neoforge", "neoforge"),
+    ("fabric-loader", "fabric"),
+    ("quilt-loader", "quilt"),
+];
+
+/// Returns the `(loader, loader version)` pairs declared by a file, in the order they were found.
+/// Files that are not archives or have no readable metadata have none.
+pub fn extract_loader_versions(data: &[u8], file_extension: &str) -> Vec<(String, String)> {
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(data)) else {
+        return Vec::new();
+    };
+
+    let mut read_file = |name: &str| {
+        let mut contents = String::new();
+        archive
+            .by_name(name)
+            .ok()?
+            .read_to_string(&mut contents)
+            .ok()?;
+        Some(contents)
+    };
+
+    let mut loader_versions = Vec::new();
+
+    if file_extension == "mrpack" {
+        if let Some(index) = read_file("modrinth.index.json")
+            .and_then(|x| serde_json::from_str::<serde_json::Value>(&x).ok())
+        {
+            for (key, loader) in PACK_LOADER_DEPENDENCIES {
+                if let Some(version) = index["dependencies"][key].as_str() {
+                    loader_versions.push((loader.to_string(), version.to_string()));
+                }
+            }
+        }
+
+        return loader_versions;
+    }
+
+    if let Some(fabric_mod) = read_file("fabric.mod.json")
+        .and_then(|x| serde_json::from_str::<serde_json::Value>(&x).ok())
+    {
+        let requirement = &fabric_mod["depends"]["fabricloader"];
+        let requirement = requirement
+            .as_str()
+            .or_else(|| requirement.as_array()?.first()?.as_str());
+        if let Some(version) = requirement.and_then(version_from_requirement) {
+            loader_versions.push(("fabric".to_string(), version));
+        }
+    }
+
+    // `loaderVersion` is a maven range of the major forge version (ex: `[41,)`)
+    if let Some(mods_toml) = read_file("META-INF/mods.toml") {
+        if let Some(version) = mods_toml
+            .lines()
+            .map(str::trim)
+            .find_map(|x| x.strip_prefix("loaderVersion"))
+            .and_then(|x| x.trim_start().strip_prefix('='))
+            .and_then(|x| version_from_requirement(x.trim().trim_matches('"')))
+        {
+            loader_versions.push(("forge".to_string(), version));
+        }
+    }
+
+    loader_versions
+}
+
+/// Gets the lowest version allowed by a version requirement (ex: `>=0.14.21` or `[41,)`)
+fn version_from_requirement(requirement: &str) -> Option<String> {
+    let version = requirement
+        .trim_start_matches(|c: char| "<>=~^[(".contains(c) || c.is_whitespace())
+        .split(|c: char| ",)] ".contains(c))
+        .next()?;
+
+    (!version.is_empty() && version != "*").then(|| version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_resolve_to_their_lowest_version() {
+        assert_eq!(
+            version_from_requirement(">=0.14.21"),
+            Some("0.14.21".into())
+        );
+        assert_eq!(version_from_requirement("[41,)"), Some("41".into()));
+        assert_eq!(
+            version_from_requirement("[47.1.0, 48)"),
+            Some("47.1.0".into())
+        );
+        assert_eq!(version_from_requirement("*"), None);
+    }
+}
@@ -22,7 +22,8 @@ mod datapack;
 mod fabric;
 mod forge;
 mod liteloader;
-mod modpack;
+pub mod loader_version;
+pub mod modpack;
 pub mod plugin;
 mod quilt;
 mod resourcepack;
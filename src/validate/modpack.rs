@@ -1,11 +1,206 @@
-use crate::models::pack::{PackFileHash, PackFormat};
-use crate::util::validate::validation_errors_to_string;
+use crate::models::pack::{PackDependency, PackFormat};
+use crate::util::validate::validation_errors_by_field;
 use crate::validate::{SupportedGameVersions, ValidationError, ValidationResult};
-use std::io::{Cursor, Read};
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
 use std::path::Component;
+use thiserror::Error;
 use validator::Validate;
 use zip::ZipArchive;
 
+/// The only version of the mrpack format
+const MRPACK_FORMAT_VERSION: i64 = 1;
+
+#[derive(Error, Debug)]
+pub enum MrpackError {
+    #[error("Unable to read the pack: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Unable to read the pack manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Pack manifest is missing.")]
+    MissingManifest,
+    #[error("The pack manifest is invalid: {}", .0.iter().map(|x| format!("{}: {}", x.field, x.message)).join(", "))]
+    InvalidManifest(Vec<MrpackFieldError>),
+}
+
+/// A problem with one field of a pack manifest
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MrpackFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl MrpackFieldError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        MrpackFieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Reads and checks the `modrinth.index.json` manifest of an mrpack file against version 1 of
+/// the format, reporting every invalid field. A pack without a manifest is reported as
+/// [`MrpackError::MissingManifest`], which uploads only warn about.
+pub fn validate_mrpack_manifest(bytes: &[u8]) -> Result<PackFormat, MrpackError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    validate_manifest(&mut archive)
+}
+
+fn validate_manifest<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<PackFormat, MrpackError> {
+    let contents = {
+        let mut file = match archive.by_name("modrinth.index.json") {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Err(MrpackError::MissingManifest),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        contents
+    };
+
+    let manifest: Value = serde_json::from_str(&contents).map_err(|err| {
+        MrpackError::InvalidManifest(vec![MrpackFieldError::new("", err.to_string())])
+    })?;
+
+    let errors = check_manifest_fields(&manifest);
+    if !errors.is_empty() {
+        return Err(MrpackError::InvalidManifest(errors));
+    }
+
+    // The shape of the fields was checked above, so this only fails on fields not checked there
+    let pack: PackFormat = serde_json::from_value(manifest).map_err(|err| {
+        MrpackError::InvalidManifest(vec![MrpackFieldError::new("", err.to_string())])
+    })?;
+
+    pack.validate().map_err(|err| {
+        MrpackError::InvalidManifest(
+            validation_errors_by_field(err)
+                .into_iter()
+                .map(|(field, message)| MrpackFieldError::new(field, message))
+                .collect(),
+        )
+    })?;
+
+    Ok(pack)
+}
+
+fn check_manifest_fields(manifest: &Value) -> Vec<MrpackFieldError> {
+    let mut errors = Vec::new();
+
+    if manifest["formatVersion"].as_i64() != Some(MRPACK_FORMAT_VERSION) {
+        errors.push(MrpackFieldError::new(
+            "formatVersion",
+            format!("Only format version {MRPACK_FORMAT_VERSION} is supported"),
+        ));
+    }
+
+    match manifest["game"].as_str() {
+        Some("minecraft") => {}
+        Some(game) => errors.push(MrpackFieldError::new(
+            "game",
+            format!("Game {game} does not exist!"),
+        )),
+        None => errors.push(MrpackFieldError::new("game", "Missing game")),
+    }
+
+    match manifest["dependencies"].as_object() {
+        Some(dependencies) => {
+            for key in dependencies.keys() {
+                if serde_json::from_value::<PackDependency>(Value::String(key.clone())).is_err() {
+                    errors.push(MrpackFieldError::new(
+                        format!("dependencies.{key}"),
+                        format!("{key} is not a known loader"),
+                    ));
+                }
+            }
+        }
+        None => errors.push(MrpackFieldError::new(
+            "dependencies",
+            "Missing dependencies",
+        )),
+    }
+
+    let Some(files) = manifest["files"].as_array() else {
+        errors.push(MrpackFieldError::new("files", "Missing files"));
+        return errors;
+    };
+
+    let mut paths = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        let field = format!("files[{index}]");
+
+        match file["path"].as_str() {
+            Some(path) => {
+                if let Some(other) = paths.insert(path, index) {
+                    errors.push(MrpackFieldError::new(
+                        format!("{field}.path"),
+                        format!("The path {path} is already used by files[{other}]"),
+                    ));
+                }
+
+                if !matches!(
+                    std::path::Path::new(path).components().next(),
+                    Some(Component::CurDir | Component::Normal(_))
+                ) {
+                    errors.push(MrpackFieldError::new(
+                        format!("{field}.path"),
+                        "Invalid pack file path!",
+                    ));
+                }
+            }
+            None => errors.push(MrpackFieldError::new(
+                format!("{field}.path"),
+                "Missing path",
+            )),
+        }
+
+        for (algorithm, name) in [("sha1", "SHA1"), ("sha512", "SHA512")] {
+            if !file["hashes"][algorithm].is_string() {
+                errors.push(MrpackFieldError::new(
+                    format!("{field}.hashes"),
+                    format!("All pack files must provide a {name} hash!"),
+                ));
+            }
+        }
+
+        match &file["env"] {
+            Value::Null => {}
+            Value::Object(env) => {
+                for (side, support) in env {
+                    if side != "client" && side != "server" {
+                        errors.push(MrpackFieldError::new(
+                            format!("{field}.env.{side}"),
+                            format!("{side} is not an environment"),
+                        ));
+                    }
+                    if !matches!(
+                        support.as_str(),
+                        Some("required" | "optional" | "unsupported")
+                    ) {
+                        errors.push(MrpackFieldError::new(
+                            format!("{field}.env.{side}"),
+                            "The environment must be required, optional or unsupported",
+                        ));
+                    }
+                }
+            }
+            _ => errors.push(MrpackFieldError::new(
+                format!("{field}.env"),
+                "The environment must be an object",
+            )),
+        }
+    }
+
+    errors
+}
+
 pub struct ModpackValidator;
 
 impl super::Validator for ModpackValidator {
@@ -25,56 +220,13 @@ impl super::Validator for ModpackValidator {
         &self,
         archive: &mut ZipArchive<Cursor<bytes::Bytes>>,
     ) -> Result<ValidationResult, ValidationError> {
-        let pack: PackFormat = {
-            let mut file = if let Ok(file) = archive.by_name("modrinth.index.json") {
-                file
-            } else {
-                return Ok(ValidationResult::Warning("Pack manifest is missing."));
-            };
-
-            let mut contents = String::new();
-            file.read_to_string(&mut contents)?;
-
-            serde_json::from_str(&contents)?
-        };
-
-        pack.validate().map_err(|err| {
-            ValidationError::InvalidInput(validation_errors_to_string(err, None).into())
-        })?;
-
-        if pack.game != "minecraft" {
-            return Err(ValidationError::InvalidInput(
-                format!("Game {0} does not exist!", pack.game).into(),
-            ));
-        }
-
-        for file in &pack.files {
-            if file.hashes.get(&PackFileHash::Sha1).is_none() {
-                return Err(ValidationError::InvalidInput(
-                    "All pack files must provide a SHA1 hash!".into(),
-                ));
-            }
-
-            if file.hashes.get(&PackFileHash::Sha512).is_none() {
-                return Err(ValidationError::InvalidInput(
-                    "All pack files must provide a SHA512 hash!".into(),
-                ));
+        let pack = match validate_manifest(archive) {
+            Ok(pack) => pack,
+            Err(MrpackError::MissingManifest) => {
+                return Ok(ValidationResult::Warning("Pack manifest is missing."))
             }
-
-            let path = std::path::Path::new(&file.path)
-                .components()
-                .next()
-                .ok_or_else(|| ValidationError::InvalidInput("Invalid pack file path!".into()))?;
-
-            match path {
-                Component::CurDir | Component::Normal(_) => {}
-                _ => {
-                    return Err(ValidationError::InvalidInput(
-                        "Invalid pack file path!".into(),
-                    ))
-                }
-            };
-        }
+            Err(err) => return Err(ValidationError::InvalidInput(err.to_string().into())),
+        };
 
         Ok(ValidationResult::PassWithPackDataAndFiles {
             format: pack,
@@ -91,3 +243,161 @@ impl super::Validator for ModpackValidator {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn build_pack(manifest: Option<&str>) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        if let Some(manifest) = manifest {
+            zip.start_file("modrinth.index.json", options).unwrap();
+            zip.write_all(manifest.as_bytes()).unwrap();
+        }
+        zip.start_file("overrides/config/settings.txt", options)
+            .unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn manifest() -> Value {
+        json!({
+            "formatVersion": 1,
+            "game": "minecraft",
+            "versionId": "1.0.0",
+            "name": "Test Pack",
+            "files": [],
+            "dependencies": {
+                "fabric-loader": "0.15.7",
+                "minecraft": "1.20.1"
+            }
+        })
+    }
+
+    fn field_errors(manifest: Value) -> Vec<MrpackFieldError> {
+        match validate_mrpack_manifest(&build_pack(Some(&manifest.to_string()))) {
+            Err(MrpackError::InvalidManifest(errors)) => errors,
+            Err(err) => panic!("Unexpected error: {}", err),
+            Ok(_) => panic!("The manifest should be invalid"),
+        }
+    }
+
+    fn file(path: &str) -> Value {
+        json!({
+            "path": path,
+            "hashes": { "sha1": "a", "sha512": "b" },
+            "env": { "client": "required", "server": "unsupported" },
+            "downloads": ["https://cdn.modrinth.com/data/a/versions/b/mod.jar"],
+            "fileSize": 10
+        })
+    }
+
+    #[test]
+    fn well_formed_manifest() {
+        let pack = validate_mrpack_manifest(&build_pack(Some(&manifest().to_string()))).unwrap();
+
+        assert_eq!(pack.name, "Test Pack");
+        assert_eq!(pack.dependencies[&PackDependency::Minecraft], "1.20.1");
+    }
+
+    #[test]
+    fn missing_manifest() {
+        assert!(matches!(
+            validate_mrpack_manifest(&build_pack(None)),
+            Err(MrpackError::MissingManifest)
+        ));
+        assert!(matches!(
+            validate_mrpack_manifest(b"not a zip"),
+            Err(MrpackError::Zip(..))
+        ));
+    }
+
+    #[test]
+    fn manifest_not_json() {
+        let errors = match validate_mrpack_manifest(&build_pack(Some("{"))) {
+            Err(MrpackError::InvalidManifest(errors)) => errors,
+            _ => panic!("The manifest should be invalid"),
+        };
+
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn unsupported_format_and_game() {
+        let mut manifest = manifest();
+        manifest["formatVersion"] = json!(2);
+        manifest["game"] = json!("terraria");
+
+        let fields = field_errors(manifest)
+            .into_iter()
+            .map(|x| x.field)
+            .collect_vec();
+        assert_eq!(fields, vec!["formatVersion", "game"]);
+    }
+
+    #[test]
+    fn invalid_dependencies() {
+        let mut manifest = manifest();
+        manifest["dependencies"] = json!({ "fabric-loader": "0.15.7", "rift": "1.0" });
+
+        let fields = field_errors(manifest)
+            .into_iter()
+            .map(|x| x.field)
+            .collect_vec();
+        assert_eq!(fields, vec!["dependencies.rift"]);
+    }
+
+    #[test]
+    fn minecraft_dependency_is_optional() {
+        let mut manifest = manifest();
+        manifest["dependencies"] = json!({ "fabric-loader": "0.15.7" });
+
+        let pack = validate_mrpack_manifest(&build_pack(Some(&manifest.to_string()))).unwrap();
+        assert!(!pack.dependencies.contains_key(&PackDependency::Minecraft));
+    }
+
+    #[test]
+    fn invalid_file_environments() {
+        let mut manifest = manifest();
+        let mut invalid = file("mods/b.jar");
+        invalid["env"] = json!({ "client": "sometimes", "proxy": "required" });
+        manifest["files"] = json!([file("mods/a.jar"), invalid]);
+
+        let fields = field_errors(manifest)
+            .into_iter()
+            .map(|x| x.field)
+            .collect_vec();
+        assert_eq!(fields, vec!["files[1].env.client", "files[1].env.proxy"]);
+    }
+
+    #[test]
+    fn duplicate_file_paths() {
+        let mut manifest = manifest();
+        manifest["files"] = json!([file("mods/a.jar"), file("mods/b.jar"), file("mods/a.jar")]);
+
+        let errors = field_errors(manifest);
+        assert_eq!(
+            errors,
+            vec![MrpackFieldError::new(
+                "files[2].path",
+                "The path mods/a.jar is already used by files[0]"
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_file_paths_and_hashes() {
+        let mut manifest = manifest();
+        let mut invalid = file("/etc/passwd");
+        invalid["hashes"] = json!({ "sha1": "a" });
+        manifest["files"] = json!([invalid]);
+
+        let fields = field_errors(manifest)
+            .into_iter()
+            .map(|x| x.field)
+            .collect_vec();
+        assert_eq!(fields, vec!["files[0].path", "files[0].hashes"]);
+    }
+}
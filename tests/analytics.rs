@@ -1,3 +1,5 @@
+use actix_http::StatusCode;
+use actix_web::body::MessageBody;
 use chrono::{DateTime, Duration, Utc};
 use common::permissions::PermissionsTest;
 use common::permissions::PermissionsTestContext;
@@ -7,10 +9,14 @@ use common::{
     environment::{with_test_environment, TestEnvironment},
 };
 use itertools::Itertools;
+use labrinth::models::analytics::TrafficEvent;
 use labrinth::models::ids::base62_impl::parse_base62;
+use labrinth::models::ids::{ProjectId, VersionId};
 use labrinth::models::teams::ProjectPermissions;
+use labrinth::queue::analytics::publish_traffic_event;
 use labrinth::queue::payouts;
 use rust_decimal::{prelude::ToPrimitive, Decimal};
+use std::pin::Pin;
 
 mod common;
 
@@ -221,3 +227,59 @@ pub async fn permissions_analytics_revenue() {
     })
     .await;
 }
+
+#[actix_rt::test]
+pub async fn project_traffic_streams_downloads() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        // Only the owner can follow the traffic of a project
+        let resp = api
+            .get_project_traffic(&alpha.project_id, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .get_project_traffic(&alpha.project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "text/event-stream"
+        );
+        let mut body = resp.into_body();
+
+        let event = TrafficEvent {
+            version_id: VersionId(parse_base62(&alpha.version_id).unwrap()),
+            timestamp: Utc::now(),
+            country_code: "NL".to_string(),
+        };
+        publish_traffic_event(
+            ProjectId(parse_base62(&alpha.project_id).unwrap()),
+            &event,
+            &test_env.db.redis_pool,
+        )
+        .await
+        .unwrap();
+
+        let chunk = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            futures::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx)),
+        )
+        .await
+        .expect("No traffic event received within a second")
+        .unwrap()
+        .unwrap();
+
+        let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+        let data = chunk
+            .strip_prefix("event: download\ndata: ")
+            .and_then(|x| x.strip_suffix("\n\n"))
+            .unwrap();
+        let received: TrafficEvent = serde_json::from_str(data).unwrap();
+        assert_eq!(received.version_id.to_string(), alpha.version_id);
+        assert_eq!(received.country_code, "NL");
+    })
+    .await;
+}
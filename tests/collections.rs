@@ -0,0 +1,730 @@
+use std::io::{Cursor, Read};
+
+use actix_http::StatusCode;
+use actix_web::test;
+use common::api_common::request_data::ImageData;
+use common::api_common::{Api, ApiProject, AppendsOptionalPat};
+use common::api_v3::ApiV3;
+use common::database::{FRIEND_USER_PAT, MOD_USER_PAT, USER_USER_ID, USER_USER_PAT};
+use common::dummy_data::DummyProjectAlpha;
+use common::environment::{with_test_environment, TestEnvironment};
+use labrinth::models::ids::base62_impl::parse_base62;
+use labrinth::routes::v3::users::UserCollectionsPage;
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+pub async fn export_collection_as_mrpack() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let DummyProjectAlpha {
+            project_id: alpha_project_id,
+            ..
+        } = &test_env.dummy.project_alpha;
+
+        let resp = api
+            .create_collection(
+                "Test Collection",
+                "Test Collection Description",
+                &[alpha_project_id.as_str()],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        // A fabric pack cannot be launched without a loader version to install
+        let resp = api
+            .export_collection_mrpack(collection_id, "1.20.1", "fabric", None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Alpha has a fabric version for 1.20.1, so it is included in the index
+        let resp = api
+            .export_collection_mrpack(
+                collection_id,
+                "1.20.1",
+                "fabric",
+                Some("0.15.7"),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert!(resp
+            .headers()
+            .get("Content-Disposition")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .contains("Test Collection.mrpack"));
+        assert!(resp.headers().get("X-Incompatible-Projects").is_none());
+
+        let body = test::read_body(resp).await;
+        let mut zip = zip::ZipArchive::new(Cursor::new(body.to_vec())).unwrap();
+        let mut index = String::new();
+        zip.by_name("modrinth.index.json")
+            .unwrap()
+            .read_to_string(&mut index)
+            .unwrap();
+        let index: serde_json::Value = serde_json::from_str(&index).unwrap();
+        assert_eq!(index["formatVersion"], 1);
+        assert_eq!(index["dependencies"]["minecraft"], "1.20.1");
+        assert_eq!(index["dependencies"]["fabric-loader"], "0.15.7");
+        let files = index["files"].as_array().unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0]["path"].as_str().unwrap().starts_with("mods/"));
+        assert!(files[0]["hashes"]["sha1"].is_string());
+
+        // No version matches, so alpha is reported rather than included
+        let resp = api
+            .export_collection_mrpack(
+                collection_id,
+                "1.8",
+                "fabric",
+                Some("0.15.7"),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get("X-Incompatible-Projects")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            alpha_project_id
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn get_collection_projects_hydrates_visible_projects() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .create_collection(
+                "Test Collection",
+                "Test Collection Description",
+                &[alpha.project_id.as_str(), beta.project_id.as_str()],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        let result = api
+            .get_collection_projects_deserialized(collection_id, true, USER_USER_PAT)
+            .await;
+        assert_eq!(result.total, 2);
+        assert_eq!(result.projects[0].project.id.to_string(), alpha.project_id);
+        assert_eq!(result.projects[1].project.id.to_string(), beta.project_id);
+        let versions = result.versions.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].id.to_string(), alpha.version_id);
+
+        // Beta is private, so it is hidden from other users
+        let result = api
+            .get_collection_projects_deserialized(collection_id, false, FRIEND_USER_PAT)
+            .await;
+        assert_eq!(result.total, 1);
+        assert_eq!(result.projects[0].project.id.to_string(), alpha.project_id);
+        assert!(result.versions.is_none());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn collection_projects_can_be_sorted_by_date_added() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .create_collection(
+                "Test Collection",
+                "Test Description",
+                &[beta.project_id.as_str()],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        // Beta was added a day before alpha
+        sqlx::query(
+            "UPDATE collections_mods SET added_at = NOW() - interval '1 day' WHERE collection_id = $1",
+        )
+        .bind(parse_base62(collection_id).unwrap() as i64)
+        .execute(&test_env.db.pool)
+        .await
+        .unwrap();
+        let resp = api
+            .add_collection_project(collection_id, &alpha.project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let result = api
+            .get_collection_projects_sorted_deserialized(
+                collection_id,
+                "added_at_desc",
+                USER_USER_PAT,
+            )
+            .await;
+        let ids = result
+            .projects
+            .iter()
+            .map(|x| x.project.id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![alpha.project_id.clone(), beta.project_id.clone()]);
+        assert!(result.projects[0].added_to_collection_at > result.projects[1].added_to_collection_at);
+
+        let result = api
+            .get_collection_projects_sorted_deserialized(collection_id, "added_at_asc", USER_USER_PAT)
+            .await;
+        assert_eq!(result.projects[0].project.id.to_string(), beta.project_id);
+
+        // Replacing the projects keeps the date of the ones that stay
+        let resp = api
+            .edit_collection(
+                collection_id,
+                json!({ "new_projects": [beta.project_id] }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let kept = api
+            .get_collection_projects_deserialized(collection_id, false, USER_USER_PAT)
+            .await;
+        assert_eq!(kept.total, 1);
+        assert_eq!(
+            kept.projects[0].added_to_collection_at,
+            result.projects[0].added_to_collection_at
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn add_and_remove_single_collection_project() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .create_collection("Test Collection", "Test Description", &[], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        // Adding is idempotent
+        for _ in 0..2 {
+            let resp = api
+                .add_collection_project(collection_id, &alpha.project_id, USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+        }
+        let collection = api
+            .get_collection_deserialized(collection_id, USER_USER_PAT)
+            .await;
+        assert_eq!(collection.projects.len(), 1);
+        assert_eq!(collection.projects[0].to_string(), alpha.project_id);
+
+        let resp = api
+            .add_collection_project(collection_id, &beta.project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .add_collection_project(collection_id, "nonexistent", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        // Private projects cannot be added by users who cannot see them
+        let resp = api
+            .create_collection(
+                "Friend Collection",
+                "Test Description",
+                &[],
+                FRIEND_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let friend_collection: serde_json::Value = test::read_body_json(resp).await;
+        let resp = api
+            .add_collection_project(
+                friend_collection["id"].as_str().unwrap(),
+                &beta.project_id,
+                FRIEND_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        // Projects that were made private can still be removed by users who cannot see them
+        let friend_collection_id = friend_collection["id"].as_str().unwrap();
+        let resp = api
+            .add_collection_project(friend_collection_id, &alpha.project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .edit_project(
+                &alpha.project_id,
+                json!({ "status": "private" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .remove_collection_project(friend_collection_id, &alpha.project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .add_collection_project(friend_collection_id, &alpha.project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let resp = api
+            .remove_collection_project(collection_id, &alpha.project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .remove_collection_project(collection_id, &alpha.project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let collection = api
+            .get_collection_deserialized(collection_id, USER_USER_PAT)
+            .await;
+        assert!(collection.projects.is_empty());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn featured_collections_are_ordered_and_moderated() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        let mut collection_ids = Vec::new();
+        for (name, projects) in [
+            ("First Collection", vec![alpha.project_id.as_str()]),
+            ("Second Collection", vec![]),
+        ] {
+            let resp = api
+                .create_collection(name, "Test Description", &projects, USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+            let collection: serde_json::Value = test::read_body_json(resp).await;
+            collection_ids.push(collection["id"].as_str().unwrap().to_string());
+        }
+        let (first, second) = (collection_ids[0].as_str(), collection_ids[1].as_str());
+
+        let resp = api.add_featured_collection(first, 1, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        // Added out of order, to check they are sorted by `order`
+        let resp = api.add_featured_collection(second, 2, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.add_featured_collection(first, 1, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let featured_ids = |limit| async move {
+            let resp = api.get_featured_collections(limit, None).await;
+            assert_status!(&resp, StatusCode::OK);
+            let featured: Vec<serde_json::Value> = test::read_body_json(resp).await;
+            featured
+        };
+
+        let featured = featured_ids(None).await;
+        assert_eq!(
+            featured
+                .iter()
+                .map(|x| x["id"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        assert_eq!(featured[0]["project_thumbnails"][0]["slug"], "alpha");
+        assert_eq!(featured_ids(Some(1)).await.len(), 1);
+
+        let resp = api.remove_featured_collection(first, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.remove_featured_collection(first, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let featured = featured_ids(None).await;
+        assert_eq!(featured.len(), 1);
+        assert_eq!(featured[0]["id"], second);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn user_collections_are_filtered_by_viewer() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        let mut created = Vec::new();
+        for status in ["listed", "unlisted", "private"] {
+            let resp = api
+                .create_collection(
+                    &format!("Collection {status}"),
+                    "Test Description",
+                    &[alpha.project_id.as_str()],
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+            let collection: serde_json::Value = test::read_body_json(resp).await;
+            let id = collection["id"].as_str().unwrap().to_string();
+
+            let resp = api
+                .edit_collection(&id, json!({ "status": status }), USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+            created.push(id);
+        }
+
+        for (pat, expected) in [
+            (None, vec!["listed"]),
+            (FRIEND_USER_PAT, vec!["listed", "unlisted"]),
+            (USER_USER_PAT, vec!["listed", "private", "unlisted"]),
+            (MOD_USER_PAT, vec!["listed", "private", "unlisted"]),
+        ] {
+            let mut statuses = api
+                .get_user_collections_deserialized(USER_USER_ID, pat)
+                .await
+                .into_iter()
+                .filter(|x| created.contains(&x.id.to_string()))
+                .map(|x| {
+                    assert_eq!(x.project_count, 1);
+                    x.status.to_string()
+                })
+                .collect::<Vec<_>>();
+            statuses.sort();
+            assert_eq!(statuses, expected);
+        }
+
+        // Pages chain through the cursor
+        let first = api
+            .get_user_collections_page_deserialized(USER_USER_ID, "limit=1", USER_USER_PAT)
+            .await;
+        assert_eq!(first.collections.len(), 1);
+        let rest = api
+            .get_user_collections_page_deserialized(
+                USER_USER_ID,
+                &format!("limit=100&cursor={}", first.next_cursor.as_ref().unwrap()),
+                USER_USER_PAT,
+            )
+            .await;
+        assert!(rest.next_cursor.is_none());
+        assert!(rest
+            .collections
+            .iter()
+            .all(|x| x.id != first.collections[0].id));
+        assert_eq!(
+            rest.collections.len() + 1,
+            api.get_user_collections_deserialized(USER_USER_ID, USER_USER_PAT)
+                .await
+                .len()
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn user_collection_pages_are_stable() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        for name in [
+            "Collection B",
+            "Collection D",
+            "Collection F",
+            "Collection H",
+        ] {
+            let resp = api
+                .create_collection(name, "Test Description", &[], USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+        }
+        let names = |page: &UserCollectionsPage| {
+            page.collections
+                .iter()
+                .map(|x| x.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let first = api
+            .get_user_collections_page_deserialized(
+                USER_USER_ID,
+                "sort=title&order=desc&limit=2",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(names(&first), ["Collection H", "Collection F"]);
+
+        // Collections created before and after the cursor do not shift the next page
+        for name in ["Collection E", "Collection G"] {
+            let resp = api
+                .create_collection(name, "Test Description", &[], USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+        }
+
+        let second = api
+            .get_user_collections_page_deserialized(
+                USER_USER_ID,
+                &format!(
+                    "sort=title&order=desc&limit=2&cursor={}",
+                    first.next_cursor.unwrap()
+                ),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(names(&second), ["Collection E", "Collection D"]);
+
+        // Pages sorted by creation date end with the newest collections
+        let mut cursor = None;
+        let mut created = Vec::new();
+        loop {
+            let query = match &cursor {
+                Some(cursor) => format!("sort=created&limit=3&cursor={cursor}"),
+                None => "sort=created&limit=3".to_string(),
+            };
+            let page = api
+                .get_user_collections_page_deserialized(USER_USER_ID, &query, USER_USER_PAT)
+                .await;
+            created.extend(names(&page));
+            match page.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        assert_eq!(
+            created,
+            [
+                "Collection B",
+                "Collection D",
+                "Collection F",
+                "Collection H",
+                "Collection E",
+                "Collection G"
+            ]
+        );
+
+        let resp = api
+            .get_user_collections_page(USER_USER_ID, "cursor=not-a-cursor", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn collection_icon_sets_color() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let DummyProjectAlpha {
+            project_id: alpha_project_id,
+            ..
+        } = &test_env.dummy.project_alpha;
+
+        let resp = api
+            .create_collection(
+                "Red Collection",
+                "Test Collection Description",
+                &[alpha_project_id.as_str()],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        let mut icon = Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(64, 64, image::Rgb([255, 0, 0]))
+            .write_to(&mut icon, image::ImageOutputFormat::Png)
+            .unwrap();
+        let resp = api
+            .edit_collection_icon(
+                collection_id,
+                Some(ImageData {
+                    filename: "red.png".to_string(),
+                    extension: "png".to_string(),
+                    icon: icon.into_inner(),
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let collection = api
+            .get_collection_deserialized(collection_id, USER_USER_PAT)
+            .await;
+        // The icon is stored resized to 256x256
+        let icon_path = collection
+            .icon_url
+            .as_deref()
+            .unwrap()
+            .split(&format!("{}/", dotenvy::var("CDN_URL").unwrap()))
+            .nth(1)
+            .unwrap()
+            .to_string();
+        let stored = std::fs::read(
+            std::path::Path::new(&dotenvy::var("MOCK_FILE_PATH").unwrap()).join(icon_path),
+        )
+        .unwrap();
+        let stored = image::load_from_memory(&stored).unwrap();
+        assert_eq!((stored.width(), stored.height()), (256, 256));
+        // The dominant color is quantized, so it is only close to pure red
+        let color = collection.color.unwrap();
+        assert!(color >> 16 > 0xF0);
+        assert!(color >> 8 & 0xFF < 0x10);
+        assert!(color & 0xFF < 0x10);
+
+        let resp = api
+            .edit_collection_icon(collection_id, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let collection = api
+            .get_collection_deserialized(collection_id, USER_USER_PAT)
+            .await;
+        assert!(collection.icon_url.is_none());
+        assert!(collection.color.is_none());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn head_collection_matches_get_without_body() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .create_collection(
+                "Head Collection",
+                "Test Collection Description",
+                &[alpha_project_id.as_str()],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap().to_string();
+
+        let resp = api.get_collection(&collection_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let etag = resp.headers().get("ETag").unwrap().clone();
+        let body = test::read_body(resp).await;
+
+        let head = |id: String| async move {
+            let req = test::TestRequest::default()
+                .method(actix_http::Method::HEAD)
+                .uri(&format!("/v3/collection/{id}"))
+                .append_pat(USER_USER_PAT)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = head(collection_id).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+        assert_eq!(
+            resp.headers().get("Content-Length").unwrap(),
+            &body.len().to_string()
+        );
+        assert!(test::read_body(resp).await.is_empty());
+
+        let resp = head("nonexistent".to_string()).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert!(test::read_body(resp).await.is_empty());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn collection_edit_reports_string_length_limits() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let resp = api
+            .create_collection("Test Collection", "Test Description", &[], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: serde_json::Value = test::read_body_json(resp).await;
+        let collection_id = collection["id"].as_str().unwrap();
+
+        for (name, actual) in [("ab".to_string(), 2), ("a".repeat(65), 65)] {
+            let resp = api
+                .edit_collection(collection_id, json!({ "name": name }), USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+            let error: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(error["field"], "name");
+            assert_eq!(error["min"], 3);
+            assert_eq!(error["max"], 64);
+            assert_eq!(error["actual"], actual);
+        }
+
+        let resp = api
+            .edit_collection(
+                collection_id,
+                json!({ "name": "a".repeat(64) }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn collections_can_be_paged_by_cursor() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use labrinth::database::models::Collection;
+
+        let api = &test_env.api;
+        for name in ["Collection A", "Collection B", "Collection C"] {
+            let resp = api
+                .create_collection(name, "Test Description", &[], USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+        }
+
+        let pool = &test_env.db.pool;
+        let redis = &test_env.db.redis_pool;
+        let all = Collection::get_many(None, 1000, pool, redis).await.unwrap();
+        assert!(all.len() >= 3);
+
+        // Pages of two chain into the full, ordered list
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = Collection::get_many(cursor, 2, pool, redis).await.unwrap();
+            assert!(page.len() <= 2);
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(last.id);
+            paged.extend(page);
+        }
+
+        let ids = |x: &[Collection]| x.iter().map(|x| x.id.0).collect::<Vec<_>>();
+        assert_eq!(ids(&paged), ids(&all));
+        assert!(ids(&all).windows(2).all(|x| x[0] < x[1]));
+    })
+    .await;
+}
@@ -106,6 +106,7 @@ pub fn get_public_creation_data_multipart(
         name: "data".to_string(),
         filename: None,
         content_type: Some("application/json".to_string()),
+        content_encoding: None,
         data: MultipartSegmentData::Text(serde_json::to_string(json_data).unwrap()),
     };
 
@@ -115,6 +116,7 @@ pub fn get_public_creation_data_multipart(
             name: jar.filename(),
             filename: Some(jar.filename()),
             content_type: Some("application/java-archive".to_string()),
+            content_encoding: None,
             data: MultipartSegmentData::Binary(jar.bytes()),
         };
 
@@ -4,7 +4,9 @@ use actix_web::{
     test::{self, TestRequest},
 };
 use bytes::Bytes;
-use labrinth::models::{collections::Collection, v3::projects::Project};
+use labrinth::models::collections::{Collection, CollectionSummary};
+use labrinth::routes::v3::collections::CollectionProjects;
+use labrinth::routes::v3::users::UserCollectionsPage;
 use serde_json::json;
 
 use crate::{
@@ -60,9 +62,35 @@ impl ApiV3 {
         self.call(req).await
     }
 
-    pub async fn get_collection_projects(&self, id: &str, pat: Option<&str>) -> ServiceResponse {
+    pub async fn create_collection_from_search(
+        &self,
+        title: &str,
+        query: &str,
+        max_projects: usize,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri("/v3/collection/from-search")
+            .append_pat(pat)
+            .set_json(json!({
+                "title": title,
+                "query": query,
+                "max_projects": max_projects,
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_collection_projects(
+        &self,
+        id: &str,
+        include_versions: bool,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
         let req = test::TestRequest::get()
-            .uri(&format!("/v3/collection/{id}/projects"))
+            .uri(&format!(
+                "/v3/collection/{id}/projects?include_versions={include_versions}"
+            ))
             .append_pat(pat)
             .to_request();
         self.call(req).await
@@ -71,13 +99,54 @@ impl ApiV3 {
     pub async fn get_collection_projects_deserialized(
         &self,
         id: &str,
+        include_versions: bool,
+        pat: Option<&str>,
+    ) -> CollectionProjects {
+        let resp = self
+            .get_collection_projects(id, include_versions, pat)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn get_collection_projects_sorted_deserialized(
+        &self,
+        id: &str,
+        sort: &str,
         pat: Option<&str>,
-    ) -> Vec<Project> {
-        let resp = self.get_collection_projects(id, pat).await;
+    ) -> CollectionProjects {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/collection/{id}/projects?sort={sort}"))
+            .append_pat(pat)
+            .to_request();
+        let resp = self.call(req).await;
         assert_status!(&resp, StatusCode::OK);
         test::read_body_json(resp).await
     }
 
+    pub async fn export_collection_mrpack(
+        &self,
+        id: &str,
+        game_version: &str,
+        loader: &str,
+        loader_version: Option<&str>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let mut uri = format!(
+            "/v3/collection/{id}/export/mrpack?game_version={}&loader={}",
+            urlencoding::encode(game_version),
+            urlencoding::encode(loader)
+        );
+        if let Some(loader_version) = loader_version {
+            uri.push_str(&format!(
+                "&loader_version={}",
+                urlencoding::encode(loader_version)
+            ));
+        }
+        let req = TestRequest::get().uri(&uri).append_pat(pat).to_request();
+        self.call(req).await
+    }
+
     pub async fn edit_collection(
         &self,
         id: &str,
@@ -122,6 +191,34 @@ impl ApiV3 {
         }
     }
 
+    pub async fn add_collection_project(
+        &self,
+        id: &str,
+        project_id: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/collection/{id}/projects/{project_id}"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn remove_collection_project(
+        &self,
+        id: &str,
+        project_id: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/v3/collection/{id}/projects/{project_id}"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
     pub async fn delete_collection(&self, id: &str, pat: Option<&str>) -> ServiceResponse {
         let req = test::TestRequest::delete()
             .uri(&format!("/v3/collection/{id}"))
@@ -131,29 +228,90 @@ impl ApiV3 {
         self.call(req).await
     }
 
+    pub async fn get_featured_collections(
+        &self,
+        limit: Option<usize>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let query = limit.map(|x| format!("?limit={x}")).unwrap_or_default();
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/collection/featured{query}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn add_featured_collection(
+        &self,
+        id: &str,
+        order: i32,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri("/_internal/admin/collections/featured")
+            .append_pat(pat)
+            .set_json(json!({
+                "collection_id": id,
+                "order": order,
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn remove_featured_collection(&self, id: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/_internal/admin/collections/featured/{id}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
     pub async fn get_user_collections(
         &self,
         user_id_or_username: &str,
         pat: Option<&str>,
+    ) -> ServiceResponse {
+        self.get_user_collections_page(user_id_or_username, "", pat)
+            .await
+    }
+
+    pub async fn get_user_collections_page(
+        &self,
+        user_id_or_username: &str,
+        query: &str,
+        pat: Option<&str>,
     ) -> ServiceResponse {
         let req = test::TestRequest::get()
-            .uri(&format!("/v3/user/{}/collections", user_id_or_username))
+            .uri(&format!(
+                "/v3/user/{}/collections?{}",
+                user_id_or_username, query
+            ))
             .append_pat(pat)
             .to_request();
         self.call(req).await
     }
 
-    pub async fn get_user_collections_deserialized_common(
+    pub async fn get_user_collections_deserialized(
         &self,
         user_id_or_username: &str,
         pat: Option<&str>,
-    ) -> Vec<Collection> {
+    ) -> Vec<CollectionSummary> {
         let resp = self.get_user_collections(user_id_or_username, pat).await;
         assert_status!(&resp, StatusCode::OK);
-        // First, deserialize to the non-common format (to test the response is valid for this api version)
-        let projects: Vec<Project> = test::read_body_json(resp).await;
-        // Then, deserialize to the common format
-        let value = serde_json::to_value(projects).unwrap();
-        serde_json::from_value(value).unwrap()
+        let page: UserCollectionsPage = test::read_body_json(resp).await;
+        page.collections
+    }
+
+    pub async fn get_user_collections_page_deserialized(
+        &self,
+        user_id_or_username: &str,
+        query: &str,
+        pat: Option<&str>,
+    ) -> UserCollectionsPage {
+        let resp = self
+            .get_user_collections_page(user_id_or_username, query, pat)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
     }
 }
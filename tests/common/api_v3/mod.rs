@@ -28,7 +28,10 @@ pub struct ApiV3 {
 #[async_trait(?Send)]
 impl ApiBuildable for ApiV3 {
     async fn build(labrinth_config: LabrinthConfig) -> Self {
-        let app = App::new().configure(|cfg| labrinth::app_config(cfg, labrinth_config.clone()));
+        let app = App::new()
+            .wrap(labrinth::util::headers::security_headers())
+            .wrap(labrinth::util::headers::build_info_headers())
+            .configure(|cfg| labrinth::app_config(cfg, labrinth_config.clone()));
         let test_app: Rc<dyn LocalService> = Rc::new(test::init_service(app).await);
 
         Self { test_app }
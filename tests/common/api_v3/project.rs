@@ -102,6 +102,7 @@ impl ApiProject for ApiV3 {
         let req = test::TestRequest::delete()
             .uri(&format!("/v3/project/{project_slug_or_id}"))
             .append_pat(pat)
+            .set_json(json!({ "confirm": true }))
             .to_request();
 
         self.call(req).await
@@ -477,6 +478,104 @@ impl ApiProject for ApiV3 {
 }
 
 impl ApiV3 {
+    pub async fn delete_project_with_confirmation(
+        &self,
+        id_or_slug: &str,
+        confirm: bool,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/v3/project/{id_or_slug}"))
+            .append_pat(pat)
+            .set_json(json!({ "confirm": confirm }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_related_projects(
+        &self,
+        id_or_slug: &str,
+        limit: Option<u32>,
+        pat: Option<&str>,
+    ) -> Vec<Project> {
+        let uri = match limit {
+            Some(limit) => format!("/v3/project/{id_or_slug}/related?limit={limit}"),
+            None => format!("/v3/project/{id_or_slug}/related"),
+        };
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .append_pat(pat)
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn follow_project(&self, id_or_slug: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/project/{id_or_slug}/follow"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_featured_projects(&self, limit: Option<usize>) -> Vec<Project> {
+        let query = limit.map(|x| format!("?limit={x}")).unwrap_or_default();
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/featured{query}"))
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn add_featured_project(
+        &self,
+        id: &str,
+        position: i32,
+        expires_at: Option<DateTime<Utc>>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri("/_internal/admin/projects/featured")
+            .append_pat(pat)
+            .set_json(json!({
+                "project_id": id,
+                "position": position,
+                "expires_at": expires_at,
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn remove_featured_project(&self, id: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/_internal/admin/projects/featured/{id}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_project_badge(&self, id_or_slug: &str, query: &str) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/badge{query}"))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_project_stats_timeline(
+        &self,
+        id_or_slug: &str,
+        query: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/stats/timeline?{query}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
     pub async fn get_project_deserialized(&self, id_or_slug: &str, pat: Option<&str>) -> Project {
         let resp = self.get_project(id_or_slug, pat).await;
         assert_status!(&resp, StatusCode::OK);
@@ -506,6 +605,366 @@ impl ApiV3 {
         test::read_body_json(resp).await
     }
 
+    pub async fn get_project_license(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/license"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_projects_by_slugs(
+        &self,
+        slugs: &[&str],
+        strict: bool,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let slugs = serde_json::to_string(slugs).unwrap();
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/projects?slugs={encoded}&strict={strict}",
+                encoded = urlencoding::encode(&slugs)
+            ))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn upload_project_custom_license(
+        &self,
+        id_or_slug: &str,
+        text: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/project/{id_or_slug}/license/custom"))
+            .append_pat(pat)
+            .insert_header(("Content-Type", "text/plain"))
+            .set_payload(text.to_string())
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn delete_project_custom_license(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/v3/project/{id_or_slug}/license/custom"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_with_accept(
+        &self,
+        id_or_slug: &str,
+        accept: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}"))
+            .append_pat(pat)
+            .insert_header(("Accept", accept))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_versions_rss(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/versions.rss"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_with_expected_version(
+        &self,
+        id_or_slug: &str,
+        patch: serde_json::Value,
+        expected_version: i32,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!(
+                "/v3/project/{id_or_slug}?expected_version={expected_version}"
+            ))
+            .append_pat(pat)
+            .set_json(patch)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_version_counts(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/versions/count"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_version_matrix(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/versions/matrix"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_latest_versions(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/versions/latest"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_supported_game_versions(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/project/{id_or_slug}/versions/game_versions_all"
+            ))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_random_projects(&self, count: u32, seed: Option<&str>) -> ServiceResponse {
+        let seed = seed
+            .map(|x| format!("&seed={}", urlencoding::encode(x)))
+            .unwrap_or_default();
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/projects_random?count={count}{seed}"))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn duplicate_project(&self, id_or_slug: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/project/{id_or_slug}/duplicate"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_embed(&self, id_or_slug: &str) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/embed"))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_latest_file(
+        &self,
+        id_or_slug: &str,
+        query: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/files?{query}"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_visibility(
+        &self,
+        id_or_slug: &str,
+        public: bool,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/project/{id_or_slug}/visibility"))
+            .append_pat(pat)
+            .set_json(json!({ "public": public }))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_additional_categories(
+        &self,
+        id_or_slug: &str,
+        add: &[&str],
+        remove: &[&str],
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/project/{id_or_slug}/additional_categories"))
+            .append_pat(pat)
+            .set_json(json!({ "add": add, "remove": remove }))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_external_links(
+        &self,
+        id_or_slug: &str,
+        links: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/project/{id_or_slug}/external_links"))
+            .append_pat(pat)
+            .set_json(links)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_projects_updated_since(&self, query: &str) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/projects/updated-since?{query}"))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_moderation_queue(
+        &self,
+        sort: Option<&str>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let uri = match sort {
+            Some(sort) => format!("/_internal/moderation/projects?sort={sort}"),
+            None => "/_internal/moderation/projects".to_string(),
+        };
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_body(
+        &self,
+        id_or_slug: &str,
+        body: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/project/{id_or_slug}/body"))
+            .append_pat(pat)
+            .set_json(body)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_audit_log(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/audit"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn validate_project(
+        &self,
+        creation_data: ProjectCreationRequestData,
+        pat: Option<&str>,
+    ) -> serde_json::Value {
+        let req = TestRequest::post()
+            .uri("/v3/project/validate")
+            .append_pat(pat)
+            .set_multipart(creation_data.segment_data)
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn get_project_traffic(
+        &self,
+        id_or_slug: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/traffic"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn edit_project_status(
+        &self,
+        id_or_slug: &str,
+        status: &str,
+        message: Option<&str>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/project/{id_or_slug}/status"))
+            .append_pat(pat)
+            .set_json(json!({ "status": status, "message": message }))
+            .to_request();
+
+        self.call(req).await
+    }
+
+    pub async fn get_project_events(
+        &self,
+        id_or_slug: &str,
+        after: Option<i64>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let query = after.map(|x| format!("?after={x}")).unwrap_or_default();
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{id_or_slug}/events{query}"))
+            .append_pat(pat)
+            .to_request();
+
+        self.call(req).await
+    }
+
     pub async fn search_deserialized(
         &self,
         query: Option<&str>,
@@ -533,6 +992,79 @@ impl ApiV3 {
         test::read_body_json(resp).await
     }
 
+    pub async fn search_csv(
+        &self,
+        facets: Option<serde_json::Value>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let facets_field = if let Some(facets) = facets {
+            format!("facets={}", urlencoding::encode(&facets.to_string()))
+        } else {
+            "".to_string()
+        };
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/search?{}", facets_field))
+            .insert_header(("Accept", "text/csv"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn search_window(
+        &self,
+        query: &str,
+        offset: usize,
+        limit: usize,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/search?query={}&offset={offset}&limit={limit}",
+                urlencoding::encode(query)
+            ))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn search_fields(
+        &self,
+        query: &str,
+        fields: &[&str],
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let fields = fields
+            .iter()
+            .map(|x| format!("&fields[]={}", urlencoding::encode(x)))
+            .collect::<Vec<_>>()
+            .join("");
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/search?query={}&limit=100{fields}",
+                urlencoding::encode(query)
+            ))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn search_grouped_by_type(
+        &self,
+        query: &str,
+        windows: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/search?query={}&group_by_type=true{windows}",
+                urlencoding::encode(query)
+            ))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
     pub async fn get_analytics_revenue(
         &self,
         id_or_slugs: Vec<&str>,
@@ -4,7 +4,7 @@ use actix_web::{
     test::{self, TestRequest},
 };
 use async_trait::async_trait;
-use labrinth::routes::v3::tags::{GameData, LoaderData};
+use labrinth::routes::v3::tags::{GameData, GameVersionData, LoaderData, LoaderVersionMapData};
 use labrinth::{
     database::models::loader_fields::LoaderFieldEnumValue, routes::v3::tags::CategoryData,
 };
@@ -62,6 +62,13 @@ impl ApiTags for ApiV3 {
 }
 
 impl ApiV3 {
+    pub async fn get_category(&self, name: &str) -> ServiceResponse {
+        let req = TestRequest::get()
+            .uri(&format!("/v3/tag/category/{name}"))
+            .to_request();
+        self.call(req).await
+    }
+
     pub async fn get_loaders_deserialized(&self) -> Vec<LoaderData> {
         let resp = self.get_loaders().await;
         assert_status!(&resp, StatusCode::OK);
@@ -98,4 +105,82 @@ impl ApiV3 {
         assert_status!(&resp, StatusCode::OK);
         test::read_body_json(resp).await
     }
+
+    pub async fn add_loader_version_mapping(
+        &self,
+        loader: &str,
+        loader_version_pattern: &str,
+        game_version: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = TestRequest::post()
+            .uri("/_internal/admin/loader-version-map")
+            .append_pat(pat)
+            .set_json(serde_json::json!({
+                "loader": loader,
+                "loader_version_pattern": loader_version_pattern,
+                "game_version": game_version,
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_loader_deprecation(
+        &self,
+        loader: &str,
+        deprecated: bool,
+        superseded_by: Option<&str>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = TestRequest::patch()
+            .uri(&format!("/_internal/admin/tags/loader/{loader}"))
+            .append_pat(pat)
+            .set_json(serde_json::json!({
+                "deprecated": deprecated,
+                "superseded_by": superseded_by,
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_loader_deserialized(&self, loader: &str) -> LoaderData {
+        let req = TestRequest::get()
+            .uri(&format!("/v3/tag/loader/{loader}"))
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn get_loader_version_map_deserialized(
+        &self,
+        loader: &str,
+    ) -> Vec<LoaderVersionMapData> {
+        let req = TestRequest::get()
+            .uri(&format!("/v3/tag/loader/{loader}/version-map"))
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn add_game_version(
+        &self,
+        body: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = TestRequest::post()
+            .uri("/_internal/admin/tags/game_version")
+            .append_pat(pat)
+            .set_json(body)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_game_versions_deserialized(&self) -> Vec<GameVersionData> {
+        let req = TestRequest::get().uri("/v3/tag/game_version").to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
 }
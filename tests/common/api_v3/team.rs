@@ -3,7 +3,7 @@ use actix_web::{dev::ServiceResponse, test};
 use async_trait::async_trait;
 use labrinth::models::{
     notifications::Notification,
-    teams::{OrganizationPermissions, ProjectPermissions, TeamMember},
+    teams::{OrganizationPermissions, ProjectPermissions, ProjectTeamMember, TeamMember},
 };
 use serde_json::json;
 
@@ -47,6 +47,39 @@ impl ApiV3 {
         assert_status!(&resp, StatusCode::OK);
         test::read_body_json(resp).await
     }
+
+    pub async fn get_project_teams(&self, project_id: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/project/{project_id}/teams"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_project_teams_deserialized(
+        &self,
+        project_id: &str,
+        pat: Option<&str>,
+    ) -> Vec<ProjectTeamMember> {
+        let resp = self.get_project_teams(project_id, pat).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn edit_team_member_role(
+        &self,
+        team_id: &str,
+        user_id: &str,
+        patch: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/team/{team_id}/members/{user_id}/role"))
+            .append_pat(pat)
+            .set_json(patch)
+            .to_request();
+        self.call(req).await
+    }
 }
 
 #[async_trait(?Send)]
@@ -1,6 +1,10 @@
+use actix_http::StatusCode;
 use actix_web::{dev::ServiceResponse, test};
 use async_trait::async_trait;
+use labrinth::routes::v3::users::UserDownloadStats;
+use serde_json::json;
 
+use crate::assert_status;
 use crate::common::api_common::{Api, ApiUser, AppendsOptionalPat};
 
 use super::ApiV3;
@@ -46,3 +50,100 @@ impl ApiUser for ApiV3 {
         self.call(req).await
     }
 }
+
+impl ApiV3 {
+    pub async fn get_version_contributions(
+        &self,
+        user_id_or_username: &str,
+        query: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/user/{user_id_or_username}/version-contributions?{query}"
+            ))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_username(
+        &self,
+        user_id_or_username: &str,
+        username: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/user/{user_id_or_username}/username"))
+            .append_pat(pat)
+            .set_json(json!({ "username": username }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_user_download_stats(
+        &self,
+        user_id_or_username: &str,
+        pat: Option<&str>,
+    ) -> UserDownloadStats {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/user/{user_id_or_username}/download-stats"))
+            .append_pat(pat)
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn add_push_token(
+        &self,
+        user_id_or_username: &str,
+        platform: &str,
+        token: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/user/{user_id_or_username}/push-token"))
+            .set_json(json!({ "platform": platform, "token": token }))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn remove_push_token(
+        &self,
+        user_id_or_username: &str,
+        token: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/v3/user/{user_id_or_username}/push-token/{token}"
+            ))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_user_email(
+        &self,
+        user_id_or_username: &str,
+        email: &str,
+        password: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/user/{user_id_or_username}/email"))
+            .set_json(json!({ "email": email, "password": password }))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn verify_user_email(&self, token: &str) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/user/verify-email?token={token}"))
+            .to_request();
+        self.call(req).await
+    }
+}
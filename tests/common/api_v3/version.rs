@@ -23,7 +23,7 @@ use labrinth::{
         v3::projects::Version,
     },
     routes::v3::version_file::FileUpdateData,
-    util::actix::AppendsMultipart,
+    util::actix::{AppendsMultipart, MultipartSegmentData},
 };
 use serde_json::json;
 
@@ -76,6 +76,86 @@ impl ApiV3 {
         test::read_body_json(resp).await
     }
 
+    pub async fn get_update_from_hash_query(
+        &self,
+        hash: &str,
+        algorithm: &str,
+        loaders: Option<Vec<String>>,
+        game_versions: Option<Vec<String>>,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let mut query = format!("algorithm={algorithm}");
+        if let Some(loaders) = loaders {
+            query.push_str(&format!(
+                "&loaders={}",
+                url_encode_json_serialized_vec(&loaders)
+            ));
+        }
+        if let Some(game_versions) = game_versions {
+            query.push_str(&format!(
+                "&game_versions={}",
+                url_encode_json_serialized_vec(&game_versions)
+            ));
+        }
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/version_file/{hash}/update?{query}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn upload_file_part_to_version(
+        &self,
+        version_id: &str,
+        file: &TestFile,
+        part_of: &str,
+        part_index: u32,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let m = request_data::get_public_creation_data_multipart(
+            &json!({
+                "file_parts": [file.filename()]
+            }),
+            Some(file),
+        );
+        let request = test::TestRequest::post()
+            .uri(&format!(
+                "/v3/version/{version_id}/file?part_of={part_of}&part_index={part_index}"
+            ))
+            .append_pat(pat)
+            .set_multipart(m)
+            .to_request();
+        self.call(request).await
+    }
+
+    /// Uploads a file to a version with its part declaring the given `Content-Encoding`, sending
+    /// `data` in place of the file's contents
+    pub async fn upload_encoded_file_to_version(
+        &self,
+        version_id: &str,
+        file: &TestFile,
+        data: Vec<u8>,
+        content_encoding: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let mut m = request_data::get_public_creation_data_multipart(
+            &json!({
+                "file_parts": [file.filename()]
+            }),
+            Some(file),
+        );
+        m[1].content_encoding = Some(content_encoding.to_string());
+        m[1].data = MultipartSegmentData::Binary(data);
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/v3/version/{version_id}/file"))
+            .append_pat(pat)
+            .set_multipart(m)
+            .to_request();
+        self.call(request).await
+    }
+
     pub async fn update_individual_files(
         &self,
         algorithm: &str,
@@ -103,6 +183,166 @@ impl ApiV3 {
         assert_status!(&resp, StatusCode::OK);
         test::read_body_json(resp).await
     }
+
+    pub async fn get_project_versions_with_loader_fields(
+        &self,
+        project_id_slug: &str,
+        loader_fields: serde_json::Value,
+        pat: Option<&str>,
+    ) -> Vec<Version> {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/project/{project_id_slug}/version?loader_fields={}",
+                urlencoding::encode(&loader_fields.to_string())
+            ))
+            .append_pat(pat)
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    pub async fn get_project_versions_for_game_version(
+        &self,
+        project_id_slug: &str,
+        game_version: &str,
+        pat: Option<&str>,
+    ) -> Vec<Version> {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/v3/project/{project_id_slug}/versions/game_version/{game_version}"
+            ))
+            .append_pat(pat)
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
+
+    /// Edits one of the version's value sets (`loaders` or `game_versions`)
+    pub async fn edit_version_set(
+        &self,
+        version_id: &str,
+        set: &str,
+        add: &[&str],
+        remove: &[&str],
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/version/{version_id}/{set}"))
+            .append_pat(pat)
+            .set_json(json!({
+                "add": add,
+                "remove": remove
+            }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_version_dependencies(
+        &self,
+        version_id: &str,
+        dependencies: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/version/{version_id}/dependencies"))
+            .append_pat(pat)
+            .set_json(json!({ "dependencies": dependencies }))
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_version_primary_file(
+        &self,
+        version_id: &str,
+        file_id: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/version/{version_id}/primary_file/{file_id}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn restore_version(&self, version_id: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/version/{version_id}/restore"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn promote_version(&self, version_id: &str, pat: Option<&str>) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/version/{version_id}/promote"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn copy_version(
+        &self,
+        version_id: &str,
+        copy: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::post()
+            .uri(&format!("/v3/version/{version_id}/copy"))
+            .append_pat(pat)
+            .set_json(copy)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn edit_version_review_status(
+        &self,
+        version_id: &str,
+        review: serde_json::Value,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::patch()
+            .uri(&format!("/v3/version/{version_id}/review-status"))
+            .append_pat(pat)
+            .set_json(review)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn get_version_download_stats(
+        &self,
+        version_id: &str,
+        query: &str,
+        pat: Option<&str>,
+    ) -> ServiceResponse {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/version/{version_id}/download-stats?{query}"))
+            .append_pat(pat)
+            .to_request();
+        self.call(req).await
+    }
+
+    pub async fn check_versions(
+        &self,
+        versions: &[&str],
+        loader: &str,
+        game_version: &str,
+        pat: Option<&str>,
+    ) -> serde_json::Value {
+        let req = test::TestRequest::post()
+            .uri("/_internal/version-checker")
+            .append_pat(pat)
+            .set_json(json!({
+                "versions": versions,
+                "loader": loader,
+                "game_version": game_version,
+            }))
+            .to_request();
+        let resp = self.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        test::read_body_json(resp).await
+    }
 }
 
 #[async_trait(?Send)]
@@ -58,6 +58,13 @@ pub enum TestFile {
 
 impl TestFile {
     pub fn build_random_jar() -> Self {
+        Self::build_random_jar_with_depends(serde_json::json!({
+            "minecraft": ">=1.20-"
+        }))
+    }
+
+    // Randomly generates a valid .jar whose fabric.mod.json has the given dependencies
+    pub fn build_random_jar_with_depends(depends: serde_json::Value) -> Self {
         let filename = format!("random-mod-{}.jar", rand::random::<u64>());
 
         let fabric_mod_json = serde_json::json!({
@@ -85,9 +92,7 @@ impl TestFile {
                 "io.github.modrinth.Modrinth"
               ]
             },
-            "depends": {
-              "minecraft": ">=1.20-"
-            }
+            "depends": depends
           }
         )
         .to_string();
@@ -0,0 +1,41 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use common::api_common::Api;
+
+use common::api_v3::ApiV3;
+use common::environment::{with_test_environment, TestEnvironment};
+
+mod common;
+
+#[actix_rt::test]
+pub async fn health_reports_all_services_ok() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test_env.api.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let health: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(health["postgres"], "ok");
+        assert_eq!(health["redis"], "ok");
+        assert_eq!(health["meilisearch"], "ok");
+        assert_eq!(health["status"], "ok");
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn responses_include_build_info_headers() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        for uri in ["/health", "/v3/project/alpha", "/v3/nonexistent"] {
+            let req = test::TestRequest::get().uri(uri).to_request();
+            let resp = test_env.api.call(req).await;
+            let headers = resp.headers();
+            assert_eq!(
+                headers.get("X-Modrinth-API-Version").unwrap(),
+                env!("CARGO_PKG_VERSION")
+            );
+            assert!(!headers.get("X-Modrinth-Git-Commit").unwrap().is_empty());
+        }
+    })
+    .await;
+}
@@ -633,3 +633,69 @@ async fn minecraft_game_version_update() {
     })
     .await
 }
+
+#[actix_rt::test]
+async fn game_versions_are_inferred_from_loader_versions() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id_parsed = &test_env.dummy.project_alpha.project_id_parsed;
+
+        let resp = api
+            .add_loader_version_mapping("fabric", "0.15.*", "1.20.1", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = api
+            .add_loader_version_mapping("fabric", "0.15.*", "0.0.0", MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let resp = api
+            .add_loader_version_mapping("fabric", "0.15.*", "1.20.1", MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let mappings = api.get_loader_version_map_deserialized("fabric").await;
+        assert_eq!(mappings.len(), 1);
+        assert_eq!(mappings[0].loader_version_pattern, "0.15.*");
+        assert_eq!(mappings[0].game_version, "1.20.1");
+
+        let without_game_versions = || {
+            Some(
+                serde_json::from_value(json!([{
+                    "op": "remove",
+                    "path": "/game_versions"
+                }]))
+                .unwrap(),
+            )
+        };
+
+        let version = api
+            .add_public_version_deserialized(
+                *alpha_project_id_parsed,
+                "1.0.0",
+                TestFile::build_random_jar_with_depends(json!({
+                    "fabricloader": ">=0.15.7"
+                })),
+                None,
+                without_game_versions(),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(version.fields["game_versions"], json!(["1.20.1"]));
+
+        // Files of unmapped loader versions still need explicit game versions
+        let resp = api
+            .add_public_version(
+                *alpha_project_id_parsed,
+                "1.0.1",
+                TestFile::build_random_jar_with_depends(json!({
+                    "fabricloader": ">=0.14.21"
+                })),
+                None,
+                without_game_versions(),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
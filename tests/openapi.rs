@@ -0,0 +1,27 @@
+use actix_http::StatusCode;
+use actix_web::test;
+use common::api_common::Api;
+
+use common::api_v3::ApiV3;
+use common::environment::{with_test_environment, TestEnvironment};
+
+mod common;
+
+#[actix_rt::test]
+pub async fn openapi_spec_is_served() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let req = test::TestRequest::get()
+            .uri("/v3/openapi.json")
+            .to_request();
+        let resp = test_env.api.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let spec: serde_json::Value = test::read_body_json(resp).await;
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3.1"));
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(!paths.is_empty());
+        assert!(paths.contains_key("/v3/project/{id}"));
+        assert!(paths.contains_key("/v3/tag/loader/{name}"));
+    })
+    .await;
+}
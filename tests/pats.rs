@@ -164,6 +164,49 @@ pub async fn pat_full_test() {
     .await;
 }
 
+// PATs can also be created with a form-encoded body, as sent by `curl -d`
+#[actix_rt::test]
+pub async fn pat_created_from_form() {
+    with_test_environment_all(None, |test_env| async move {
+        let expires = (Utc::now() + Duration::days(1)).to_rfc3339();
+        let scopes = Scopes::COLLECTION_CREATE.bits().to_string();
+        let req = test::TestRequest::post()
+            .uri("/_internal/pat")
+            .append_pat(USER_USER_PAT)
+            .set_form([
+                ("scopes", scopes.as_str()),
+                ("name", "test_pat_form Test"),
+                ("expires", expires.as_str()),
+            ])
+            .to_request();
+        let resp = test_env.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        let success: serde_json::Value = test::read_body_json(resp).await;
+        let access_token = success["access_token"].as_str().unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/v3/collection")
+            .append_header(("Authorization", access_token))
+            .set_json(json!({
+                "name": "Test Collection 1",
+                "description": "Test Collection Description"
+            }))
+            .to_request();
+        let resp = test_env.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+
+        // Invalid form bodies are rejected like invalid JSON ones
+        let req = test::TestRequest::post()
+            .uri("/_internal/pat")
+            .append_pat(USER_USER_PAT)
+            .set_form([("name", "test_pat_form Test")])
+            .to_request();
+        let resp = test_env.call(req).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
 // Test illegal PAT setting, both in POST and PATCH
 #[actix_rt::test]
 pub async fn bad_pats() {
@@ -7,16 +7,21 @@ use common::dummy_data::DUMMY_CATEGORIES;
 use common::environment::{with_test_environment, with_test_environment_all, TestEnvironment};
 use common::permissions::{PermissionsTest, PermissionsTestContext};
 use futures::StreamExt;
-use labrinth::database::models::project_item::{PROJECTS_NAMESPACE, PROJECTS_SLUGS_NAMESPACE};
+use labrinth::database::models::project_item::{
+    PROJECTS_DEPENDENCIES_NAMESPACE, PROJECTS_EMBEDS_NAMESPACE, PROJECTS_NAMESPACE,
+    PROJECTS_SLUGS_NAMESPACE, PROJECTS_VERSION_COUNTS_NAMESPACE,
+    PROJECTS_VERSION_MATRICES_NAMESPACE,
+};
 use labrinth::models::ids::base62_impl::parse_base62;
-use labrinth::models::projects::ProjectId;
+use labrinth::models::projects::{Project, ProjectId, ProjectStatus};
 use labrinth::models::teams::ProjectPermissions;
+use labrinth::search::{SEARCH_RESULTS_GENERATION_KEY, SEARCH_RESULTS_GENERATION_NAMESPACE};
 use labrinth::util::actix::{MultipartSegment, MultipartSegmentData};
 use serde_json::json;
 
 use crate::common::api_common::models::{CommonItemType, CommonProject};
 use crate::common::api_common::request_data::ProjectCreationRequestData;
-use crate::common::api_common::{ApiProject, ApiTeams, ApiVersion};
+use crate::common::api_common::{Api, ApiProject, ApiTeams, ApiVersion, AppendsOptionalPat};
 use crate::common::dummy_data::{
     DummyImage, DummyOrganizationZeta, DummyProjectAlpha, DummyProjectBeta, TestFile,
 };
@@ -93,6 +98,113 @@ async fn test_get_project() {
     .await;
 }
 
+#[actix_rt::test]
+async fn project_edit_clears_every_project_cache() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let DummyProjectAlpha {
+            project_id: alpha_project_id,
+            project_slug: alpha_project_slug,
+            ..
+        } = &test_env.dummy.project_alpha;
+        let id = parse_base62(alpha_project_id).unwrap().to_string();
+        let keys = [
+            (PROJECTS_NAMESPACE, id.as_str()),
+            (PROJECTS_SLUGS_NAMESPACE, alpha_project_slug.as_str()),
+            (PROJECTS_EMBEDS_NAMESPACE, id.as_str()),
+            (PROJECTS_VERSION_COUNTS_NAMESPACE, id.as_str()),
+            (PROJECTS_VERSION_MATRICES_NAMESPACE, id.as_str()),
+            (PROJECTS_DEPENDENCIES_NAMESPACE, id.as_str()),
+        ];
+
+        let mut redis = test_env.db.redis_pool.connect().await.unwrap();
+        for (namespace, key) in keys {
+            redis.set(namespace, key, "cached", None).await.unwrap();
+        }
+        redis
+            .set(
+                SEARCH_RESULTS_GENERATION_NAMESPACE,
+                SEARCH_RESULTS_GENERATION_KEY,
+                "old",
+                None,
+            )
+            .await
+            .unwrap();
+
+        let resp = test_env
+            .api
+            .edit_project(
+                alpha_project_id,
+                json!({ "summary": "A new summary" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        for (namespace, key) in keys {
+            assert_eq!(
+                redis.get(namespace, key).await.unwrap(),
+                None,
+                "{namespace}"
+            );
+        }
+        // Cached search results are only invalidated once the project is re-indexed
+        assert_eq!(
+            redis
+                .get(
+                    SEARCH_RESULTS_GENERATION_NAMESPACE,
+                    SEARCH_RESULTS_GENERATION_KEY
+                )
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("old")
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn head_project_matches_get_without_body() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        let resp = api.get_project(alpha_project_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let etag = resp.headers().get("ETag").unwrap().clone();
+        let body = test::read_body(resp).await;
+
+        let head = |id: String, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::default()
+                .method(actix_http::Method::HEAD)
+                .uri(&format!("/v3/project/{id}"))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = head(alpha_project_id.clone(), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+        assert_eq!(
+            resp.headers().get("Content-Length").unwrap(),
+            &body.len().to_string()
+        );
+        assert!(test::read_body(resp).await.is_empty());
+
+        // Authentication is the same as for GET, so hidden projects are not found
+        let resp = head(beta_project_id.clone(), ENEMY_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert!(resp.headers().get("ETag").is_none());
+        assert!(test::read_body(resp).await.is_empty());
+
+        let resp = head("nonexistent".to_string(), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
 #[actix_rt::test]
 async fn test_add_remove_project() {
     // Test setup and dummy data
@@ -109,6 +221,7 @@ async fn test_add_remove_project() {
             name: "data".to_string(),
             filename: None,
             content_type: Some("application/json".to_string()),
+            content_encoding: None,
             data: MultipartSegmentData::Text(serde_json::to_string(&json_data).unwrap()),
         };
 
@@ -136,6 +249,7 @@ async fn test_add_remove_project() {
             name: basic_mod_file.filename(),
             filename: Some(basic_mod_file.filename()),
             content_type: basic_mod_file.content_type(),
+            content_encoding: None,
             data: MultipartSegmentData::Binary(basic_mod_file.bytes()),
         };
 
@@ -145,6 +259,7 @@ async fn test_add_remove_project() {
             name: basic_mod_different_file.filename(),
             filename: Some(basic_mod_different_file.filename()),
             content_type: basic_mod_different_file.content_type(),
+            content_encoding: None,
             // 'Basic'
             data: MultipartSegmentData::Binary(basic_mod_file.bytes()),
         };
@@ -155,6 +270,7 @@ async fn test_add_remove_project() {
             name: basic_mod_different_file.filename(),
             filename: Some(basic_mod_different_file.filename()),
             content_type: basic_mod_different_file.content_type(),
+            content_encoding: None,
             data: MultipartSegmentData::Binary(basic_mod_different_file.bytes()),
         };
 
@@ -266,9 +382,9 @@ async fn test_add_remove_project() {
             None
         );
 
-        // Old slug no longer works
+        // Old slug shows the project as deleted until it is permanently deleted
         let resp = api.get_project("demo", USER_USER_PAT).await;
-        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert_status!(&resp, StatusCode::GONE);
     })
     .await;
 }
@@ -537,7 +653,7 @@ pub async fn test_bulk_edit_links() {
 
         // Sets links for issue, source, wiki, and patreon for all projects
         // The first loop, sets issue, the second, clears it for all projects.
-        for issues in [Some("https://www.issues.com"), None] {
+        for issues in [Some("https://github.com/example/issues"), None] {
             let resp = api
                 .edit_project_bulk(
                     &[alpha_project_id, beta_project_id],
@@ -646,14 +762,14 @@ async fn delete_project_with_report() {
             None
         );
 
-        // Report for alpha no longer exists
+        // Report for alpha is kept until the project is permanently deleted
         let resp = api
             .get_report(
                 alpha_report_id,
                 ENEMY_USER_PAT, // Enemy makes a report
             )
             .await;
-        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert_status!(&resp, StatusCode::OK);
 
         // Confirm that report for beta still exists
         let resp = api
@@ -1369,3 +1485,1865 @@ async fn projects_various_visibility() {
 // Permissions:
 // TODO: permissions VIEW_PAYOUTS currently is unused. Add tests when it is used.
 // TODO: permissions VIEW_ANALYTICS currently is unused. Add tests when it is used.
+
+#[actix_rt::test]
+async fn get_project_license() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        // Dummy projects are MIT licensed, so the full SPDX text is available
+        let resp = api
+            .get_project_license(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let license: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(license["id"], "MIT");
+        assert_eq!(license["name"], "MIT License");
+        assert!(license["text"].as_str().unwrap().contains("MIT License"));
+
+        // Custom licenses have no text
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "license_id": "LicenseRef-Custom" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .get_project_license(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let license: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(license["id"], "LicenseRef-Custom");
+        assert!(license.get("text").is_none());
+
+        // Hidden projects are not found
+        let resp = api
+            .get_project_license(&test_env.dummy.project_beta.project_id, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn bulk_recategorize_moves_projects_to_new_category() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({
+                    "categories": [DUMMY_CATEGORIES[0], DUMMY_CATEGORIES[1]],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let bulk_request = |pat: Option<&str>| {
+            test::TestRequest::patch()
+                .uri("/_internal/admin/projects/bulk-category")
+                .append_pat(pat)
+                .set_json(json!({
+                    "from_category": DUMMY_CATEGORIES[0],
+                    "to_category": DUMMY_CATEGORIES[2],
+                    "project_type": "mod",
+                }))
+                .to_request()
+        };
+
+        // Only moderators can re-categorize projects
+        let resp = api.call(bulk_request(USER_USER_PAT)).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api.call(bulk_request(MOD_USER_PAT)).await;
+        assert_status!(&resp, StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["affected_count"], 1);
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        let mut categories = project.categories;
+        categories.sort();
+        assert_eq!(categories, vec![DUMMY_CATEGORIES[1], DUMMY_CATEGORIES[2]]);
+
+        // Misspelled categories are rejected rather than moving no projects
+        for (from_category, to_category) in [
+            ("does-not-exist", DUMMY_CATEGORIES[0]),
+            (DUMMY_CATEGORIES[2], "does-not-exist"),
+        ] {
+            let req = test::TestRequest::patch()
+                .uri("/_internal/admin/projects/bulk-category")
+                .append_pat(MOD_USER_PAT)
+                .set_json(json!({
+                    "from_category": from_category,
+                    "to_category": to_category,
+                }))
+                .to_request();
+            let resp = api.call(req).await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_audit_log_records_license_changes() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let old_license = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await
+            .license
+            .id;
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({
+                    "license_id": "Apache-2.0",
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Only team members can see the audit log
+        let resp = api
+            .get_project_audit_log(alpha_project_id, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .get_project_audit_log(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let entries: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["changed_field"], "license_id");
+        assert_eq!(entries[0]["old_value"], old_license);
+        assert_eq!(entries[0]["new_value"], "Apache-2.0");
+        assert_eq!(entries[0]["user_id"], USER_USER_ID);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn get_projects_preserves_request_order() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        for ids in [
+            [alpha.project_id.as_str(), beta.project_id.as_str()],
+            [beta.project_id.as_str(), alpha.project_id.as_str()],
+        ] {
+            let resp = api.get_projects(&ids, USER_USER_PAT).await;
+            assert_status!(&resp, StatusCode::OK);
+            let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+            let returned = projects
+                .iter()
+                .map(|x| x["id"].as_str().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(returned, ids);
+        }
+
+        let resp = api
+            .get_projects_by_slugs(
+                &[&beta.project_slug, &alpha.project_slug],
+                false,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(projects[0]["id"], beta.project_id.as_str());
+        assert_eq!(projects[1]["id"], alpha.project_id.as_str());
+
+        // Missing projects are omitted, unless the request is strict
+        let resp = api
+            .get_projects_by_slugs(&[&alpha.project_slug, "doesnotexist"], false, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(projects.len(), 1);
+
+        let resp = api
+            .get_projects_by_slugs(&[&alpha.project_slug, "doesnotexist"], true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn custom_license_text_round_trip() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "license_id": "MIT" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Only team members may upload a license
+        let resp = api
+            .upload_project_custom_license(alpha_project_id, "My license", ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .upload_project_custom_license(alpha_project_id, "My license", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api.get_project_license(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let license: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(license["id"], "LicenseRef-Custom");
+        assert_eq!(license["text"], "My license");
+
+        let resp = api
+            .delete_project_custom_license(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // The project falls back to the license it had before
+        let resp = api.get_project_license(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let license: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(license["id"], "MIT");
+        assert!(license["text"].as_str().unwrap().contains("MIT License"));
+
+        let resp = api
+            .delete_project_custom_license(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_body_patch_rejects_stale_revision() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .edit_project_body(
+                alpha_project_id,
+                json!({ "body": "line one\nline two\n" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let result: serde_json::Value = test::read_body_json(resp).await;
+        let base_rev = result["base_rev"].as_str().unwrap().to_string();
+
+        let patch = "--- original\n+++ modified\n@@ -1,2 +1,2 @@\n line one\n-line two\n+line 2\n";
+        let resp = api
+            .edit_project_body(
+                alpha_project_id,
+                json!({ "patch": patch, "base_rev": base_rev }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let result: serde_json::Value = test::read_body_json(resp).await;
+        let new_rev = result["base_rev"].as_str().unwrap().to_string();
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.description, "line one\nline 2\n");
+
+        // Reusing the old revision conflicts, and the current one is returned
+        let resp = api
+            .edit_project_body(
+                alpha_project_id,
+                json!({ "patch": patch, "base_rev": base_rev }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::CONFLICT);
+        let result: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(result["base_rev"], new_rev);
+
+        let resp = api
+            .edit_project_body(
+                alpha_project_id,
+                json!({ "body": "Hacked" }),
+                ENEMY_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_get_negotiates_response_format() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        let resp = api
+            .get_project_with_accept(alpha_project_id, "application/json", None)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "application/json"
+        );
+
+        let resp = api
+            .get_project_with_accept(
+                alpha_project_id,
+                "application/ld+json, application/json;q=0.5",
+                None,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "application/ld+json"
+        );
+        let project: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(project["@type"], "SoftwareApplication");
+        assert_eq!(project["identifier"], alpha_project_id.as_str());
+
+        let resp = api.get_project_versions_rss(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "application/rss+xml"
+        );
+        let feed = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert_eq!(feed.matches("<item>").count(), 1);
+        assert!(feed.contains(alpha_version_id.as_str()));
+
+        // Private projects have no public feed
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+        let resp = api.get_project_versions_rss(beta_project_id, None).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_edit_rejects_stale_expected_version() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        let version = project.version;
+
+        let resp = api
+            .edit_project_with_expected_version(
+                alpha_project_id,
+                json!({ "name": "First edit" }),
+                version,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // A second edit built on the same snapshot is a concurrent modification
+        let resp = api
+            .edit_project_with_expected_version(
+                alpha_project_id,
+                json!({ "name": "Second edit" }),
+                version,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::CONFLICT);
+        let result: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(result["version"], version + 1);
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.name, "First edit");
+        assert_eq!(project.version, version + 1);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_body_edit_bumps_version() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        let version = project.version;
+
+        let resp = api
+            .edit_project_body(
+                alpha_project_id,
+                json!({ "body": "A new body" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.version, version + 1);
+
+        // The body edit moved the version on, so an edit from the old snapshot conflicts
+        let req = test::TestRequest::patch()
+            .uri(&format!(
+                "/v3/project/{alpha_project_id}/body?expected_version={version}"
+            ))
+            .append_pat(USER_USER_PAT)
+            .set_json(json!({ "body": "A stale body" }))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::CONFLICT);
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.description, "A new body");
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_edit_only_applies_masked_fields() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+
+        // The description is from a stale read, so it is left out of the mask
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({
+                    "name": "New",
+                    "description": "A stale description",
+                    "update_mask": ["name"],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let edited = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(edited.name, "New");
+        assert_eq!(edited.description, project.description);
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "name": "Newer", "update_mask": ["name", "version"] }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn seeded_random_projects_are_stable() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let mut picks = Vec::new();
+        for _ in 0..2 {
+            let resp = api.get_random_projects(5, Some("2024-01-15")).await;
+            assert_status!(&resp, StatusCode::OK);
+            let projects: Vec<Project> = test::read_body_json(resp).await;
+            picks.push(projects.into_iter().map(|x| x.id).collect::<Vec<_>>());
+        }
+        assert_eq!(picks[0], picks[1]);
+        // Only public projects are picked
+        assert_eq!(picks[0].len(), 1);
+        assert_eq!(picks[0][0].to_string(), *alpha_project_id);
+
+        let resp = api.get_random_projects(5, Some("")).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_embed_renders_og_tags() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        let resp = api.get_project_embed(alpha_project_id).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "text/html; charset=utf-8"
+        );
+        let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains(r#"<meta property="og:title" content="Test Project alpha">"#));
+
+        // Edits invalidate the cached embed
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "name": "Renamed <Alpha>" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_project_embed(alpha_project_id).await;
+        let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains(r#"content="Renamed &lt;Alpha&gt;""#));
+
+        // Projects that are not public yet still get an embed, without their details
+        let resp = api.get_project_embed(beta_project_id).await;
+        assert_status!(&resp, StatusCode::OK);
+        let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("Pending review"));
+
+        let resp = api.get_project_embed("does-not-exist").await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_version_counts_group_by_loader_and_game_version() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        let resp = api.get_project_version_counts(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let counts: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            counts,
+            json!({ "loaders": { "fabric": { "game_versions": { "1.20.1": 1 } } } })
+        );
+
+        // Drafts are not counted, and editing a version invalidates the cached counts
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "draft" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_project_version_counts(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let counts: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(counts, json!({ "loaders": {} }));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_version_matrix_has_latest_version_per_combination() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_project_id_parsed = test_env.dummy.project_alpha.project_id_parsed;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        // Game versions added by moderators are sorted by release date among the others, which
+        // have no numeric version
+        for (version, date, patch) in [("1.99.10", "2022-01-01", 10), ("1.99.9", "2020-01-01", 9)] {
+            let resp = api
+                .add_game_version(
+                    json!({
+                        "version": version,
+                        "type": "release",
+                        "date": date,
+                        "major": 1,
+                        "minor": 99,
+                        "patch": patch,
+                    }),
+                    MOD_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+        }
+
+        let add_version = |version_number: &'static str, loaders, game_versions| async move {
+            api.add_public_version_deserialized(
+                alpha_project_id_parsed,
+                version_number,
+                TestFile::build_random_jar(),
+                None,
+                Some(
+                    serde_json::from_value(json!([
+                        { "op": "replace", "path": "/loaders", "value": loaders },
+                        { "op": "replace", "path": "/game_versions", "value": game_versions },
+                    ]))
+                    .unwrap(),
+                ),
+                USER_USER_PAT,
+            )
+            .await
+            .id
+            .to_string()
+        };
+        let both = add_version(
+            "2.0.0",
+            json!(["fabric", "forge"]),
+            json!(["1.99.10", "1.99.9"]),
+        )
+        .await;
+
+        let resp = api.get_project_version_matrix(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let matrix: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            matrix,
+            json!({
+                "loaders": ["fabric", "forge"],
+                "game_versions": ["1.99.9", "1.20.1", "1.99.10"],
+                "matrix": [
+                    [both, alpha_version_id, both],
+                    [both, null, both],
+                ],
+            })
+        );
+
+        // New versions replace the cached matrix
+        let newer = add_version("3.0.0", json!(["forge"]), json!(["1.99.9"])).await;
+        let resp = api.get_project_version_matrix(alpha_project_id, None).await;
+        assert_status!(&resp, StatusCode::OK);
+        let matrix: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            matrix["matrix"],
+            json!([[both, alpha_version_id, both], [newer, null, both]])
+        );
+
+        let resp = api.get_project_version_matrix("does-not-exist", None).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_latest_versions_prefer_releases_per_loader() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_project_id_parsed = test_env.dummy.project_alpha.project_id_parsed;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        let add_version = |version_number: &'static str, loader, release_channel| async move {
+            api.add_public_version_deserialized(
+                alpha_project_id_parsed,
+                version_number,
+                TestFile::build_random_jar(),
+                None,
+                Some(
+                    serde_json::from_value(json!([
+                        { "op": "replace", "path": "/loaders", "value": [loader] },
+                        { "op": "replace", "path": "/release_channel", "value": release_channel },
+                    ]))
+                    .unwrap(),
+                ),
+                USER_USER_PAT,
+            )
+            .await
+            .id
+            .to_string()
+        };
+        // A newer alpha does not replace the fabric release, but a beta is the latest forge
+        // version until there is a release
+        add_version("2.0.0-alpha", "fabric", "alpha").await;
+        let forge_beta = add_version("2.0.0-beta", "forge", "beta").await;
+
+        let resp = api
+            .get_project_latest_versions(alpha_project_id, None)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let latest: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(latest.as_object().unwrap().len(), 2);
+        assert_eq!(latest["fabric"]["id"], json!(alpha_version_id));
+        assert_eq!(latest["forge"]["id"], json!(forge_beta));
+
+        // Publishing a version replaces the cached versions
+        let forge_release = add_version("2.0.0", "forge", "release").await;
+        let resp = api
+            .get_project_latest_versions(alpha_project_id, None)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let latest: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(latest["fabric"]["id"], json!(alpha_version_id));
+        assert_eq!(latest["forge"]["id"], json!(forge_release));
+
+        let resp = api
+            .get_project_latest_versions(&test_env.dummy.project_beta.project_id, None)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_supported_game_versions_are_deduplicated_newest_first() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_project_id_parsed = test_env.dummy.project_alpha.project_id_parsed;
+
+        let get_game_versions = || async {
+            let resp = api
+                .get_project_supported_game_versions(alpha_project_id, None)
+                .await;
+            assert_status!(&resp, StatusCode::OK);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            body["game_versions"].clone()
+        };
+        assert_eq!(get_game_versions().await, json!(["1.20.1"]));
+
+        // Old game versions added by moderators are sorted by their release date
+        let resp = api
+            .add_game_version(
+                json!({
+                    "version": "1.8.9",
+                    "type": "release",
+                    "date": "2015-12-09",
+                    "major": 1,
+                    "minor": 8,
+                    "patch": 9,
+                }),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Publishing a version replaces the cached game versions
+        api.add_public_version_deserialized(
+            alpha_project_id_parsed,
+            "2.0.0",
+            TestFile::build_random_jar(),
+            None,
+            Some(
+                serde_json::from_value(json!([
+                    { "op": "replace", "path": "/game_versions", "value": ["1.8.9", "1.20.1", "1.20.5", "1.20.3"] },
+                ]))
+                .unwrap(),
+            ),
+            USER_USER_PAT,
+        )
+        .await;
+        assert_eq!(
+            get_game_versions().await,
+            json!(["1.20.5", "1.20.3", "1.20.1", "1.8.9"])
+        );
+
+        let resp = api
+            .get_project_supported_game_versions(&test_env.dummy.project_beta.project_id, None)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn duplicate_project_creates_draft_copy() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        let alpha = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+
+        let resp = api
+            .duplicate_project(alpha_project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let copy: Project = test::read_body_json(resp).await;
+        assert_eq!(copy.name, format!("Copy of {}", alpha.name));
+        assert_eq!(copy.slug.as_deref(), Some("alpha-copy"));
+        assert_eq!(copy.status, ProjectStatus::Draft);
+        assert_eq!(copy.forked_from, Some(alpha.id));
+        assert_eq!(copy.description, alpha.description);
+        assert_eq!(copy.categories, alpha.categories);
+        assert!(copy.versions.is_empty());
+
+        // The copy is owned by the user who duplicated it
+        let copy = api
+            .get_project_deserialized(&copy.id.to_string(), FRIEND_USER_PAT)
+            .await;
+        assert_eq!(copy.forked_from, Some(alpha.id));
+
+        // Further copies get a different slug
+        let resp = api
+            .duplicate_project(alpha_project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let second: Project = test::read_body_json(resp).await;
+        assert_ne!(second.slug.as_deref(), Some("alpha-copy"));
+
+        // Private projects cannot be duplicated, not even by their own team
+        let resp = api
+            .duplicate_project(beta_project_id, FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        let resp = api.duplicate_project(beta_project_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_files_redirects_to_latest_matching_file() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        let file_url = version.files[0].url.clone();
+
+        for query in [
+            "",
+            "loader=fabric&game_version=1.20.1",
+            "version_type=beta&fallback=release",
+        ] {
+            let resp = api
+                .get_project_latest_file(alpha_project_id, query, None)
+                .await;
+            assert_status!(&resp, StatusCode::FOUND);
+            assert_eq!(resp.headers().get("Location").unwrap(), &*file_url);
+        }
+
+        for query in ["loader=forge", "game_version=1.20.5", "version_type=beta"] {
+            let resp = api
+                .get_project_latest_file(alpha_project_id, query, None)
+                .await;
+            assert_status!(&resp, StatusCode::NOT_FOUND);
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn moderation_queue_includes_author_trust_scores() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        let get_queue = |sort: Option<&'static str>| async move {
+            let resp = api.get_moderation_queue(sort, MOD_USER_PAT).await;
+            assert_status!(&resp, StatusCode::OK);
+            test::read_body_json::<serde_json::Value, _>(resp).await
+        };
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "status": "processing" }),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let queue = get_queue(None).await;
+        assert_eq!(queue[0]["id"], json!(alpha_project_id));
+        assert_eq!(queue[0]["author_trust_score"], json!(0.0));
+
+        // Approving a project recalculates the owner's trust score
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({ "status": "approved" }),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .edit_project(
+                beta_project_id,
+                json!({ "status": "processing" }),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let queue = get_queue(Some("trust_score_asc")).await;
+        assert_eq!(queue.as_array().unwrap().len(), 1);
+        assert_eq!(queue[0]["id"], json!(beta_project_id));
+        assert!(queue[0]["author_trust_score"].as_f64().unwrap() > 0.0);
+
+        let resp = api
+            .get_moderation_queue(Some("unknown"), MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_visibility_requires_listed_version() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+
+        let resp = api
+            .edit_project_visibility(alpha_project_id, false, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_project_visibility(alpha_project_id, false, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.status, ProjectStatus::Private);
+
+        // Without a listed version, the project can't be made public again
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "unlisted" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .edit_project_visibility(alpha_project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "listed" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .edit_project_visibility(alpha_project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.status, ProjectStatus::Approved);
+
+        let resp = api
+            .get_project_audit_log(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let entries: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(entries
+            .iter()
+            .any(|x| x["changed_field"] == "status" && x["new_value"] == "private"));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn projects_updated_since_pages_public_projects() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        // Only public projects are listed, so the private beta project is not
+        let resp = api.get_projects_updated_since("timestamp=0&limit=1").await;
+        assert_status!(&resp, StatusCode::OK);
+        let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0]["id"], json!(alpha_project_id));
+        assert_eq!(projects[0]["slug"], json!("alpha"));
+
+        let resp = api
+            .get_projects_updated_since(&format!("timestamp=0&after={alpha_project_id}"))
+            .await;
+        let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(projects.is_empty());
+
+        let future = chrono::Utc::now().timestamp() + 60;
+        let resp = api
+            .get_projects_updated_since(&format!("timestamp={future}"))
+            .await;
+        let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(projects.is_empty());
+
+        let resp = api.get_projects_updated_since("timestamp=0&limit=0").await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_edit_reports_string_length_limits() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_slug = &test_env.dummy.project_alpha.project_slug;
+
+        for (field, max) in [("name", 64), ("summary", 256), ("description", 65535)] {
+            let resp = api
+                .edit_project(
+                    alpha_project_slug,
+                    json!({ (field): "a".repeat(max) }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+
+            let resp = api
+                .edit_project(
+                    alpha_project_slug,
+                    json!({ (field): "a".repeat(max + 1) }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+            let error: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(error["error"], "invalid_input");
+            assert_eq!(error["field"], field);
+            assert_eq!(error["max"], max);
+            assert_eq!(error["actual"], max + 1);
+        }
+
+        for field in ["name", "summary"] {
+            let resp = api
+                .edit_project(alpha_project_slug, json!({ (field): "" }), USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+            let error: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(error["field"], field);
+            assert_eq!(error["min"], 2);
+            assert_eq!(error["actual"], 0);
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn additional_categories_are_kept_apart_from_primary_categories() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_slug = &test_env.dummy.project_alpha.project_slug;
+
+        let resp = api
+            .edit_project(
+                alpha_project_slug,
+                json!({ "categories": ["combat"] }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_project_additional_categories(alpha_project_slug, &["combat"], &[], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = api
+            .edit_project_additional_categories(
+                alpha_project_slug,
+                &["not-a-category"],
+                &[],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .edit_project_additional_categories(
+                alpha_project_slug,
+                &["decoration", "economy", "food", "magic"],
+                &[],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = api
+            .edit_project_additional_categories(
+                alpha_project_slug,
+                &["decoration", "economy"],
+                &[],
+                FRIEND_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_project_additional_categories(
+                alpha_project_slug,
+                &["decoration", "economy"],
+                &[],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_project_additional_categories(
+                alpha_project_slug,
+                &["food"],
+                &["economy"],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let project = api
+            .get_project_deserialized(alpha_project_slug, USER_USER_PAT)
+            .await;
+        assert_eq!(project.categories, vec!["combat".to_string()]);
+        let mut additional_categories = project.additional_categories;
+        additional_categories.sort();
+        assert_eq!(additional_categories, vec!["decoration", "food"]);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn external_links_are_partially_updated() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_slug = &test_env.dummy.project_alpha.project_slug;
+
+        let resp = api
+            .edit_project_external_links(
+                alpha_project_slug,
+                json!({
+                    "source_url": "https://github.com/example/mod",
+                    "wiki_url": "https://wiki.example.com",
+                    "issues_url": "https://github.com/example/mod/issues",
+                    "forum_url": "https://forum.example.com",
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let project = api
+            .get_project_deserialized(alpha_project_slug, USER_USER_PAT)
+            .await;
+        assert_eq!(
+            project.link_urls["source"].url,
+            "https://github.com/example/mod"
+        );
+        assert_eq!(project.link_urls["wiki"].url, "https://wiki.example.com");
+        assert_eq!(project.link_urls["forum"].url, "https://forum.example.com");
+        assert!(!project.link_urls.contains_key("discord"));
+
+        // Links that are absent are kept, and null ones are removed
+        let resp = api
+            .edit_project_external_links(
+                alpha_project_slug,
+                json!({ "wiki_url": null, "discord_url": "https://discord.gg/example" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let project = api
+            .get_project_deserialized(alpha_project_slug, USER_USER_PAT)
+            .await;
+        assert!(!project.link_urls.contains_key("wiki"));
+        assert_eq!(
+            project.link_urls["discord"].url,
+            "https://discord.gg/example"
+        );
+        assert_eq!(
+            project.link_urls["issues"].url,
+            "https://github.com/example/mod/issues"
+        );
+
+        for links in [
+            json!({ "source_url": "http://github.com/example/mod" }),
+            json!({ "source_url": format!("https://example.com/{}", "a".repeat(512)) }),
+            json!({ "issues_url": "https://example.com/issues" }),
+        ] {
+            let resp = api
+                .edit_project_external_links(alpha_project_slug, links, USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+        }
+
+        // The issue tracker is checked the same way when it is set through the project edit
+        let resp = api
+            .edit_project(
+                alpha_project_slug,
+                json!({ "link_urls": { "issues": "https://example.com/issues" } }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .edit_project_external_links(
+                alpha_project_slug,
+                json!({ "wiki_url": "https://wiki.example.com" }),
+                ENEMY_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn responses_include_security_headers() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api.get_project(alpha_project_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let headers = resp.headers();
+        assert_eq!(
+            headers.get("Content-Security-Policy").unwrap(),
+            "default-src 'none'"
+        );
+        assert_eq!(headers.get("X-Content-Type-Options").unwrap(), "nosniff");
+        assert_eq!(headers.get("X-Frame-Options").unwrap(), "DENY");
+        assert_eq!(headers.get("Referrer-Policy").unwrap(), "strict-origin");
+        assert_eq!(
+            headers.get("Strict-Transport-Security").unwrap(),
+            "max-age=31536000; includeSubDomains"
+        );
+
+        // Rendered HTML needs to load its own styles and remote images
+        let resp = api.get_project_embed(alpha_project_id).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Security-Policy").unwrap(),
+            "default-src 'self'; img-src *"
+        );
+        assert_eq!(resp.headers().get("X-Frame-Options").unwrap(), "DENY");
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_events_timeline() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        let resp = api
+            .edit_project(
+                &alpha.project_id,
+                json!({ "status": "unlisted" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .get_project_events(&alpha.project_id, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let events: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(events.iter().any(|x| x["event_type"] == "version_published"
+            && x["payload"]["version_id"] == alpha.version_id));
+        let last = events.last().unwrap();
+        assert_eq!(last["event_type"], "status_changed");
+        assert_eq!(last["payload"]["old_status"], "approved");
+        assert_eq!(last["payload"]["new_status"], "unlisted");
+
+        // The cursor skips events that were already seen
+        let resp = api
+            .get_project_events(&alpha.project_id, last["id"].as_i64(), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let events: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(events.is_empty());
+
+        // Users outside of the team only see version publishes
+        let resp = api
+            .get_project_events(&alpha.project_id, None, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let events: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(!events.is_empty());
+        assert!(events
+            .iter()
+            .all(|x| x["event_type"] == "version_published"));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_events_record_deletion() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        let resp = api
+            .delete_project_with_confirmation(&alpha.project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .get_project_events(&alpha.project_id, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let events: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        let last = events.last().unwrap();
+        assert_eq!(last["event_type"], "project_deleted");
+        assert!(last["payload"]["deleted_at"].is_string());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_status_edit_requires_rejection_message() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .edit_project_status(alpha_project_id, "rejected", Some("Nice"), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_project_status(alpha_project_id, "not-a-status", None, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        for message in [None, Some(" ")] {
+            let resp = api
+                .edit_project_status(alpha_project_id, "rejected", message, MOD_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        let resp = api
+            .edit_project_status(
+                alpha_project_id,
+                "rejected",
+                Some("Please add a description"),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.status, ProjectStatus::Rejected);
+        assert_eq!(
+            project.moderator_message.unwrap().message,
+            "Please add a description"
+        );
+
+        // Approving clears the message
+        let resp = api
+            .edit_project_status(alpha_project_id, "approved", None, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_eq!(project.status, ProjectStatus::Approved);
+        assert!(project.moderator_message.is_none());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn project_validation_reports_every_invalid_field() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_slug = &test_env.dummy.project_alpha.project_slug;
+
+        let creation_data = common::api_v3::request_data::get_public_project_creation_data(
+            "validated-project",
+            Some(TestFile::build_random_jar()),
+            None,
+        );
+        let result = api.validate_project(creation_data, USER_USER_PAT).await;
+        assert_eq!(result["valid"], true);
+        assert!(result["errors"].as_array().unwrap().is_empty());
+
+        // Nothing was created
+        let resp = api.get_project("validated-project", USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let creation_data = common::api_v3::request_data::get_public_project_creation_data(
+            "validated-project",
+            Some(TestFile::build_random_jar()),
+            Some(
+                serde_json::from_value(json!([
+                    { "op": "replace", "path": "/slug", "value": alpha_slug },
+                    { "op": "replace", "path": "/categories", "value": ["not-a-category"] },
+                    { "op": "replace", "path": "/license_id", "value": "not a license" },
+                ]))
+                .unwrap(),
+            ),
+        );
+        let result = api.validate_project(creation_data, USER_USER_PAT).await;
+        assert_eq!(result["valid"], false);
+        let mut fields = result["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["field"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        fields.sort();
+        assert_eq!(fields, ["categories", "license_id", "slug"]);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn deleted_projects_are_gone_and_scheduled_for_deletion() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .delete_project_with_confirmation(&alpha.project_id, false, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Beta requires alpha, so alpha cannot be deleted
+        let resp = api
+            .edit_version(
+                &beta.version_id,
+                json!({
+                    "dependencies": [{
+                        "project_id": alpha.project_id,
+                        "dependency_type": "required",
+                    }],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .delete_project_with_confirmation(&alpha.project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::CONFLICT);
+
+        let resp = api
+            .delete_project_with_confirmation(&beta.project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_project(&beta.project_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::GONE);
+
+        let scheduled: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM scheduled_deletions WHERE mod_id = $1 AND scheduled_for > NOW() + INTERVAL '29 days'",
+        )
+        .bind(parse_base62(&beta.project_id).unwrap() as i64)
+        .fetch_one(&test_env.db.pool)
+        .await
+        .unwrap();
+        assert_eq!(scheduled, 1);
+
+        // Deleted projects no longer count as dependents
+        let resp = api
+            .delete_project_with_confirmation(&alpha.project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn deleted_projects_can_no_longer_be_changed() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .delete_project_with_confirmation(&beta.project_id, true, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Beta is private, so it is only shown as gone to those who could see it
+        let resp = api.get_project(&beta.project_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::GONE);
+        let resp = api.get_project(&beta.project_id, ENEMY_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let resp = api
+            .edit_project(&beta.project_id, json!({ "name": "Renamed" }), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        let resp = api
+            .edit_version(&beta.version_id, json!({ "name": "Renamed" }), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        let resp = api
+            .add_public_version(
+                beta.project_id_parsed,
+                "2.0.0",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let events: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM project_events WHERE project_id = $1 AND event_type = 'project_deleted'",
+        )
+        .bind(parse_base62(&beta.project_id).unwrap() as i64)
+        .fetch_one(&test_env.db.pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 1);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn related_projects_share_two_categories() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let resp = api
+            .edit_project(
+                alpha_project_id,
+                json!({
+                    "categories": [DUMMY_CATEGORIES[0], DUMMY_CATEGORIES[1], DUMMY_CATEGORIES[2]],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let mut project_ids = Vec::new();
+        for (slug, categories) in [
+            (
+                "related-two",
+                vec![DUMMY_CATEGORIES[0], DUMMY_CATEGORIES[1]],
+            ),
+            ("related-one", vec![DUMMY_CATEGORIES[0]]),
+        ] {
+            let (project, _) = api
+                .add_public_project(
+                    slug,
+                    Some(TestFile::build_random_jar()),
+                    None,
+                    USER_USER_PAT,
+                )
+                .await;
+            let resp = api
+                .edit_project(
+                    &project.id.to_string(),
+                    json!({ "categories": categories }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+            project_ids.push(project.id);
+        }
+
+        // Only the project sharing two categories is related
+        let related = api
+            .get_related_projects(alpha_project_id, None, FRIEND_USER_PAT)
+            .await;
+        assert_eq!(
+            related.iter().map(|x| x.id).collect::<Vec<_>>(),
+            vec![project_ids[0]]
+        );
+
+        // Followed projects are left out
+        let resp = api
+            .follow_project(&project_ids[0].to_string(), FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let related = api
+            .get_related_projects(alpha_project_id, Some(5), FRIEND_USER_PAT)
+            .await;
+        assert!(related.is_empty());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn expired_featured_projects_are_not_shown() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use labrinth::database::models::Project as DBProject;
+
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+
+        let (expired, _) = api
+            .add_public_project(
+                "featured-expired",
+                Some(TestFile::build_random_jar()),
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        sqlx::query(
+            "
+            INSERT INTO featured_projects (project_id, position, expires_at)
+            VALUES ($1, 0, NOW() - INTERVAL '1 second')
+            ",
+        )
+        .bind(expired.id.0 as i64)
+        .execute(&test_env.db.pool)
+        .await
+        .unwrap();
+
+        let resp = api
+            .add_featured_project(alpha_project_id, 1, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = api
+            .add_featured_project(alpha_project_id, 1, None, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let featured = api.get_featured_projects(None).await;
+        assert_eq!(featured.len(), 1);
+        assert_eq!(featured[0].id.to_string(), *alpha_project_id);
+        assert!(featured[0].featured);
+        let project = api
+            .get_project_deserialized(&expired.id.to_string(), USER_USER_PAT)
+            .await;
+        assert!(!project.featured);
+
+        // The expired row is removed by the background task
+        DBProject::remove_expired_featured(&test_env.db.pool, &test_env.db.redis_pool)
+            .await
+            .unwrap();
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM featured_projects")
+            .fetch_one(&test_env.db.pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+
+        let resp = api
+            .remove_featured_project(alpha_project_id, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        assert!(api.get_featured_projects(None).await.is_empty());
+        let project = api
+            .get_project_deserialized(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert!(!project.featured);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn download_badges_are_valid_svg() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use labrinth::database::models::project_item::clear_project_caches;
+
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let alpha_id = parse_base62(&alpha.project_id).unwrap() as i64;
+        sqlx::query("UPDATE mods SET downloads = 1234567 WHERE id = $1")
+            .bind(alpha_id)
+            .execute(&test_env.db.pool)
+            .await
+            .unwrap();
+        clear_project_caches(
+            labrinth::database::models::ProjectId(alpha_id),
+            None,
+            &test_env.db.redis_pool,
+        )
+        .await
+        .unwrap();
+
+        let resp = api
+            .get_project_badge(&alpha.project_id, "?style=for-the-badge&color=blue")
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(resp.headers().get("Content-Type").unwrap(), "image/svg+xml");
+        assert_eq!(resp.headers().get("Cache-Control").unwrap(), "max-age=30");
+        let svg = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        for event in xml::reader::EventReader::from_str(&svg) {
+            event.unwrap();
+        }
+        assert!(svg.contains("1.2M"));
+        assert!(svg.contains("DOWNLOADS"));
+        assert!(svg.contains("#007ec6"));
+
+        let resp = api
+            .get_project_badge(&alpha.project_id, "?color=url(x)")
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Private projects have no badge
+        let resp = api.get_project_badge(&beta.project_id, "").await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn project_stats_timeline_joins_downloads_and_views() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use chrono::NaiveDate;
+        use labrinth::database::models::download_history_item::{DownloadHistory, ViewHistory};
+        use labrinth::database::models::{ProjectId, VersionId};
+
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+
+        let project_id = ProjectId(parse_base62(&alpha.project_id).unwrap() as i64);
+        let version_id = VersionId(parse_base62(&alpha.version_id).unwrap() as i64);
+        let day_1 = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let day_2 = NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+        let day_3 = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+
+        // Downloads on the first two days and views on the last two
+        let mut transaction = test_env.db.pool.begin().await.unwrap();
+        DownloadHistory::record(
+            &[
+                (project_id, version_id, day_1),
+                (project_id, version_id, day_2),
+                (project_id, version_id, day_2),
+            ],
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        ViewHistory::record(
+            &[
+                (project_id, day_2),
+                (project_id, day_3),
+                (project_id, day_3),
+            ],
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let resp = api
+            .get_project_stats_timeline(
+                &alpha.project_id,
+                "metrics[]=downloads&metrics[]=views&resolution=day",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let timeline: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            timeline,
+            json!([
+                { "date": "2024-04-01", "downloads": 1, "views": null },
+                { "date": "2024-04-02", "downloads": 2, "views": 1 },
+                { "date": "2024-04-03", "downloads": null, "views": 2 },
+            ])
+        );
+
+        // Only the requested metrics are included
+        let resp = api
+            .get_project_stats_timeline(
+                &alpha.project_id,
+                "metrics[]=views&start=2024-04-02",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let timeline: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            timeline,
+            json!([
+                { "date": "2024-04-02", "views": 1 },
+                { "date": "2024-04-03", "views": 2 },
+            ])
+        );
+
+        let resp = api
+            .get_project_stats_timeline(&alpha.project_id, "metrics[]=follows", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Only members of the project can see the timeline
+        let resp = api
+            .get_project_stats_timeline(&alpha.project_id, "", ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn project_creation_checks_the_issue_tracker_host() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use crate::common::api_v3::request_data::get_public_project_creation_data;
+
+        let api = &test_env.api;
+        let creation_data = |slug: &str, issues: &str| {
+            get_public_project_creation_data(
+                slug,
+                None,
+                Some(
+                    serde_json::from_value(json!([{
+                        "op": "add",
+                        "path": "/link_urls",
+                        "value": { "issues": issues }
+                    }]))
+                    .unwrap(),
+                ),
+            )
+        };
+
+        let resp = api
+            .create_project(
+                creation_data("issues-elsewhere", "https://example.com/issues"),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .create_project(
+                creation_data("issues-on-github", "https://github.com/example/issues"),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+    })
+    .await;
+}
@@ -985,8 +985,8 @@ pub async fn collections_scopes() {
             .test(req_gen, collection_read)
             .await
             .unwrap();
-        assert_eq!(failure.as_array().unwrap().len(), 0);
-        assert_eq!(success.as_array().unwrap().len(), 1);
+        assert_eq!(failure["collections"].as_array().unwrap().len(), 0);
+        assert_eq!(success["collections"].as_array().unwrap().len(), 1);
 
         let req_gen = |pat: Option<String>| async move {
             api.edit_collection_icon(
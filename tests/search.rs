@@ -8,11 +8,16 @@ use common::environment::with_test_environment;
 use common::environment::TestEnvironment;
 use common::search::setup_search_projects;
 use futures::stream::StreamExt;
+use itertools::Itertools;
+use labrinth::models::collections::{Collection, CollectionStatus};
 use labrinth::models::ids::base62_impl::parse_base62;
+use labrinth::search::SearchResults;
 use serde_json::json;
 
 use crate::common::api_common::Api;
 use crate::common::api_common::ApiProject;
+use crate::common::api_common::ApiUser;
+use crate::common::api_common::AppendsOptionalPat;
 
 mod common;
 
@@ -167,3 +172,379 @@ async fn index_swaps() {
     })
     .await;
 }
+
+#[actix_rt::test]
+async fn search_offset_and_limit_return_window() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        setup_search_projects(&test_env).await;
+
+        let api = &test_env.api;
+        let query = format!("\"&{}\"", test_env.db.database_name);
+
+        let resp = api.search_window(&query, 0, 100, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let all: SearchResults = actix_web::test::read_body_json(resp).await;
+        assert!(all.hits.len() >= 7);
+
+        let resp = api.search_window(&query, 5, 2, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let window: SearchResults = actix_web::test::read_body_json(resp).await;
+        assert_eq!(window.offset, 5);
+        assert_eq!(window.limit, 2);
+        assert_eq!(window.total_hits, all.total_hits);
+        assert_eq!(
+            window
+                .hits
+                .iter()
+                .map(|x| &x.project_id)
+                .collect::<Vec<_>>(),
+            all.hits[5..7]
+                .iter()
+                .map(|x| &x.project_id)
+                .collect::<Vec<_>>()
+        );
+
+        let resp = api.search_window(&query, 0, 101, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let resp = api.search_window(&query, 10001, 10, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn create_collection_from_search_results() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        setup_search_projects(&test_env).await;
+
+        let api = &test_env.api;
+        let query = format!("\"&{}\"", test_env.db.database_name);
+
+        let resp = api.search_window(&query, 0, 3, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let results: SearchResults = actix_web::test::read_body_json(resp).await;
+
+        let resp = api
+            .create_collection_from_search("Saved search", &query, 3, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let collection: Collection = actix_web::test::read_body_json(resp).await;
+        assert_eq!(collection.status, CollectionStatus::Private);
+        assert_eq!(
+            collection
+                .projects
+                .iter()
+                .map(|x| x.to_string())
+                .sorted()
+                .collect::<Vec<_>>(),
+            results
+                .hits
+                .iter()
+                .map(|x| x.project_id.clone())
+                .sorted()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(collection.source_search_query.unwrap()["query"], query);
+
+        // Private collections are hidden from other users
+        let resp = api
+            .get_collection(&collection.id.to_string(), FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_exports_csv() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let resp = api.reset_search_index().await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let facets = json!([["categories:fabric"]]);
+        let projects = api
+            .search_deserialized(None, Some(facets.clone()), USER_USER_PAT)
+            .await;
+        assert_eq!(projects.total_hits, 1);
+
+        // Exports require authentication
+        let resp = api.search_csv(Some(facets.clone()), None).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api.search_csv(Some(facets), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "text/csv; charset=utf-8"
+        );
+        let body = actix_web::test::read_body(resp).await;
+
+        let mut reader = csv::Reader::from_reader(&body[..]);
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec![
+                "project_id",
+                "slug",
+                "title",
+                "author",
+                "downloads",
+                "follows",
+                "created",
+                "updated",
+                "categories",
+                "license",
+            ]
+        );
+        let rows = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            rows.iter().map(|x| x[0].to_string()).collect_vec(),
+            projects
+                .hits
+                .iter()
+                .map(|x| x.project_id.clone())
+                .collect_vec()
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_author_facet_ignores_case_and_punctuation() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let resp = api
+            .edit_user(
+                USER_USER_ID,
+                json!({ "username": "Test_User" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.reset_search_index().await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        for author in ["test_user", "testuser", "Test-User"] {
+            let projects = api
+                .search_deserialized(
+                    None,
+                    Some(json!([[format!("author:{author}")]])),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_eq!(projects.total_hits, 1, "author:{author}");
+            assert_eq!(projects.hits[0].author, "Test_User");
+        }
+
+        let projects = api
+            .search_deserialized(None, Some(json!([["author:friend"]])), USER_USER_PAT)
+            .await;
+        assert_eq!(projects.total_hits, 0);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_game_version_facets_expand_ranges() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let resp = api.reset_search_index().await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        for (range, total_hits) in [("1.20.x", 1), (">= 1.20.1", 1), ("> 1.20.3", 0)] {
+            let projects = api
+                .search_deserialized(
+                    None,
+                    Some(json!([[format!("game_versions:{range}")]])),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_eq!(projects.total_hits, total_hits, "{range}");
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn zero_result_searches_are_moderator_only() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let zero_result_searches = |pat| async move {
+            let req = actix_web::test::TestRequest::get()
+                .uri("/_internal/admin/analytics/searches/zero-result")
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = zero_result_searches(USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = zero_result_searches(MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let searches: Vec<serde_json::Value> = actix_web::test::read_body_json(resp).await;
+        assert!(searches.iter().all(|x| x["total"].as_u64() > Some(0)));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn selective_reindex_reports_progress() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let reindex = |body: serde_json::Value, pat| async move {
+            let req = actix_web::test::TestRequest::post()
+                .uri("/_internal/admin/reindex")
+                .append_pat(pat)
+                .set_json(body)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = reindex(json!({ "project_ids": ["alpha"] }), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = reindex(json!({}), MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = reindex(json!({ "project_ids": ["alpha", "unknown"] }), MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::ACCEPTED);
+        let body: serde_json::Value = actix_web::test::read_body_json(resp).await;
+        let task_id = body["task_id"].as_str().unwrap().to_string();
+
+        let mut task = serde_json::Value::Null;
+        for _ in 0..50 {
+            let req = actix_web::test::TestRequest::get()
+                .uri(&format!("/_internal/admin/reindex/{task_id}"))
+                .append_pat(MOD_USER_PAT)
+                .to_request();
+            let resp = api.call(req).await;
+            assert_status!(&resp, StatusCode::OK);
+            task = actix_web::test::read_body_json(resp).await;
+            if task["status"] == "completed" || task["status"] == "failed" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        assert_eq!(task["status"], "completed");
+        assert_eq!(task["progress"], json!({ "processed": 1, "total": 1 }));
+        assert_eq!(task["errors"], json!(["Unknown project `unknown`"]));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/_internal/admin/reindex/unknown")
+            .append_pat(MOD_USER_PAT)
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_returns_sparse_field_sets() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        setup_search_projects(&test_env).await;
+
+        let api = &test_env.api;
+        let query = format!("\"&{}\"", test_env.db.database_name);
+
+        let resp = api
+            .search_fields(&query, &["name", "downloads"], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let results: serde_json::Value = actix_web::test::read_body_json(resp).await;
+        let hits = results["hits"].as_array().unwrap();
+        assert!(!hits.is_empty());
+        for hit in hits {
+            let hit = hit.as_object().unwrap();
+            assert_eq!(hit.len(), 2);
+            assert!(hit["name"].is_string());
+            assert!(hit["downloads"].is_number());
+        }
+        assert!(results["total_hits"].as_u64().unwrap() >= hits.len() as u64);
+
+        // Fields which are only used for filtering can't be retrieved
+        for field in ["author_normalized", "banned", "does_not_exist"] {
+            let resp = api
+                .search_fields(&query, &["name", field], USER_USER_PAT)
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_groups_results_by_project_type() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        setup_search_projects(&test_env).await;
+
+        let api = &test_env.api;
+        let query = format!("\"&{}\"", test_env.db.database_name);
+
+        let resp = api
+            .search_grouped_by_type(&query, "&modpack_limit=1&mod_limit=100", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let results: serde_json::Value = actix_web::test::read_body_json(resp).await;
+
+        let mods = results["mod"]["hits"].as_array().unwrap();
+        assert!(!mods.is_empty());
+        for hit in mods {
+            assert!(hit["project_types"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("mod")));
+        }
+
+        // Each type is paginated on its own
+        let modpacks = results["modpack"]["hits"].as_array().unwrap();
+        assert_eq!(modpacks.len(), 1);
+        assert!(modpacks[0]["project_types"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("modpack")));
+        assert!(results["modpack"]["total_hits"].as_u64().unwrap() >= 1);
+        assert_eq!(results["mod"]["limit"], json!(100));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn search_stats_are_admin_only_and_compare_with_the_database() {
+    with_test_environment(Some(10), |test_env: TestEnvironment<ApiV3>| async move {
+        setup_search_projects(&test_env).await;
+
+        let api = &test_env.api;
+        let search_stats = |query: &'static str, pat| async move {
+            let req = actix_web::test::TestRequest::get()
+                .uri(&format!("/_internal/admin/stats/search{query}"))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = search_stats("", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = search_stats("", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let stats: serde_json::Value = actix_web::test::read_body_json(resp).await;
+        let total_projects = stats["total_projects_in_db"].as_i64().unwrap();
+        assert!(total_projects > 0);
+        assert!(stats.get("recent_tasks").is_none());
+        // The index was reset and waited for, so nothing is left to index
+        for index in stats["indexes"].as_object().unwrap().values() {
+            assert!(index["number_of_documents"].as_u64().unwrap() > 0);
+            assert_eq!(index["index_lag"].as_u64().unwrap(), 0);
+        }
+
+        let resp = search_stats("?force_refresh=true", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let stats: serde_json::Value = actix_web::test::read_body_json(resp).await;
+        assert!(stats["recent_tasks"].as_array().unwrap().len() <= 5);
+    })
+    .await;
+}
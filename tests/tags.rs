@@ -1,11 +1,16 @@
 use std::collections::{HashMap, HashSet};
 
+use actix_http::StatusCode;
+use actix_web::test;
+use common::database::{MOD_USER_PAT, USER_USER_PAT};
 use common::{
     api_v3::ApiV3,
     environment::{with_test_environment, with_test_environment_all, TestEnvironment},
 };
 
-use crate::common::api_common::ApiTags;
+use crate::common::api_common::{ApiProject, ApiTags, ApiVersion};
+use crate::common::dummy_data::TestFile;
+use serde_json::json;
 
 mod common;
 
@@ -62,3 +67,140 @@ async fn get_tags_v3() {
     })
     .await;
 }
+
+#[actix_rt::test]
+async fn game_versions_are_sorted_numerically() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        for (version, patch) in [("1.99.9", 9), ("1.99.10", 10)] {
+            let body = json!({
+                "version": version,
+                "type": "release",
+                "date": "2020-01-01",
+                "major": 1,
+                "minor": 99,
+                "patch": patch,
+            });
+
+            let resp = api.add_game_version(body.clone(), USER_USER_PAT).await;
+            assert_status!(&resp, StatusCode::UNAUTHORIZED);
+            let resp = api.add_game_version(body, MOD_USER_PAT).await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+        }
+
+        // 1.99.10 is newer than 1.99.9, even though it sorts before it as a string
+        let game_versions = api.get_game_versions_deserialized().await;
+        let versions = game_versions
+            .iter()
+            .map(|x| x.version.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(versions[..2], ["1.99.10", "1.99.9"]);
+
+        // The new versions can be used right away
+        let variants = api
+            .get_loader_field_variants_deserialized("game_versions")
+            .await;
+        assert!(variants.iter().any(|x| x.value == "1.99.10"));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn deprecated_loaders_warn_on_upload() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = test_env.dummy.project_alpha.project_id_parsed;
+
+        let resp = api
+            .edit_loader_deprecation("fabric", true, Some("forge"), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = api
+            .edit_loader_deprecation("fabric", true, Some("unknown"), MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let resp = api
+            .edit_loader_deprecation("fabric", true, Some("forge"), MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let loader = api.get_loader_deserialized("fabric").await;
+        assert!(loader.deprecated);
+        assert_eq!(loader.superseded_by.as_deref(), Some("forge"));
+        let loaders = api.get_loaders_deserialized().await;
+        assert!(loaders.iter().any(|x| x.name == "fabric" && x.deprecated));
+        assert!(loaders.iter().any(|x| x.name == "forge" && !x.deprecated));
+
+        let resp = api
+            .add_public_version(
+                alpha_project_id,
+                "1.2.3-deprecated",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Warning").unwrap(),
+            "299 - \"Loader 'fabric' is deprecated; consider using 'forge'\""
+        );
+
+        // Once it is no longer deprecated, uploads are not warned about
+        let resp = api
+            .edit_loader_deprecation("fabric", false, None, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api
+            .add_public_version(
+                alpha_project_id,
+                "1.2.4",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        assert!(resp.headers().get("Warning").is_none());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn category_details_count_searchable_projects() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        // Beta is private, so it is not counted
+        for project_id in [alpha_project_id, beta_project_id] {
+            let resp = api
+                .edit_project(
+                    project_id,
+                    json!({ "categories": ["combat"] }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+        }
+
+        let resp = api.get_category("combat").await;
+        assert_status!(&resp, StatusCode::OK);
+        let category: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(category["name"], "combat");
+        assert!(category["project_types"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("mod")));
+        assert_eq!(category["project_count"], 1);
+        assert_eq!(category["most_popular"][0]["id"], json!(alpha_project_id));
+
+        let resp = api.get_category("not-a-category").await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
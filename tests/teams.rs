@@ -619,3 +619,109 @@ async fn transfer_ownership_v3() {
 
 //     test_env.cleanup().await;
 // }
+
+#[actix_rt::test]
+async fn project_teams_hide_pending_invites_from_non_members() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let alpha_team_id = &test_env.dummy.project_alpha.team_id;
+
+        let resp = api
+            .add_user_to_team(alpha_team_id, FRIEND_USER_ID, None, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // A non-member only sees the accepted owner, without permissions
+        let members = api
+            .get_project_teams_deserialized(alpha_project_id, ENEMY_USER_PAT)
+            .await;
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].user_id.to_string(), USER_USER_ID);
+        assert!(members[0].accepted);
+        assert!(members[0].permissions.is_none());
+        assert!(members[0].joined_at.is_some());
+
+        // A member also sees the pending invite and everyone's permissions
+        for pat in [USER_USER_PAT, MOD_USER_PAT] {
+            let members = api
+                .get_project_teams_deserialized(alpha_project_id, pat)
+                .await;
+            assert_eq!(members.len(), 2);
+            let invite = members
+                .iter()
+                .find(|x| x.user_id.to_string() == FRIEND_USER_ID)
+                .unwrap();
+            assert!(!invite.accepted);
+            assert!(invite.joined_at.is_none());
+            assert!(members.iter().all(|x| x.permissions.is_some()));
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn team_member_roles_keep_a_single_owner() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_team_id = &test_env.dummy.project_alpha.team_id;
+
+        // The only owner cannot give up their role
+        let resp = api
+            .edit_team_member_role(
+                alpha_team_id,
+                USER_USER_ID,
+                json!({ "role": "Developer" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .add_user_to_team(alpha_team_id, FRIEND_USER_ID, None, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_team_member_role(
+                alpha_team_id,
+                FRIEND_USER_ID,
+                json!({ "role": "Admin" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Ownership is only handed over by transferring it
+        let resp = api
+            .edit_team_member_role(
+                alpha_team_id,
+                FRIEND_USER_ID,
+                json!({ "role": "Owner" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Only the owner can change roles
+        let resp = api
+            .edit_team_member_role(
+                alpha_team_id,
+                FRIEND_USER_ID,
+                json!({ "role": "Lead" }),
+                ENEMY_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let members = api
+            .get_team_members_deserialized(alpha_team_id, USER_USER_PAT)
+            .await;
+        let friend = members
+            .iter()
+            .find(|x| x.user.id.to_string() == FRIEND_USER_ID)
+            .unwrap();
+        assert_eq!(friend.role, "Admin");
+    })
+    .await;
+}
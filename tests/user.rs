@@ -1,9 +1,17 @@
-use crate::common::api_common::{ApiProject, ApiTeams};
+use crate::common::api_common::{Api, ApiProject, ApiTeams, ApiUser, AppendsOptionalPat};
+use actix_http::StatusCode;
+use actix_web::test;
+use common::api_v3::ApiV3;
 use common::dummy_data::TestFile;
 use common::{
-    database::{FRIEND_USER_ID, FRIEND_USER_PAT, USER_USER_ID, USER_USER_PAT},
-    environment::with_test_environment_all,
+    database::{
+        ADMIN_USER_ID, ADMIN_USER_PAT, FRIEND_USER_ID, FRIEND_USER_PAT, MOD_USER_PAT, USER_USER_ID,
+        USER_USER_PAT,
+    },
+    environment::{with_test_environment, with_test_environment_all, TestEnvironment},
 };
+use itertools::Itertools;
+use labrinth::models::ids::base62_impl::parse_base62;
 
 mod common;
 
@@ -103,3 +111,609 @@ pub async fn get_user_projects_after_leaving_team_shows_no_team_projects() {
     })
     .await;
 }
+
+#[actix_rt::test]
+pub async fn get_user_projects_filters_by_status() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_project_id = &test_env.dummy.project_alpha.project_id;
+        let beta_project_id = &test_env.dummy.project_beta.project_id;
+
+        let beta_status = api
+            .get_project_deserialized(beta_project_id, USER_USER_PAT)
+            .await
+            .status;
+        let get_ids = |status: String, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::get()
+                .uri(&format!("/v3/user/{USER_USER_ID}/projects?status={status}"))
+                .append_pat(pat)
+                .to_request();
+            let resp = api.call(req).await;
+            assert_status!(&resp, StatusCode::OK);
+            let projects: Vec<serde_json::Value> = test::read_body_json(resp).await;
+            projects
+                .into_iter()
+                .map(|x| x["id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // The user and moderators can filter by any status
+        assert_eq!(
+            get_ids(beta_status.to_string(), USER_USER_PAT).await,
+            vec![beta_project_id.clone()]
+        );
+        assert_eq!(
+            get_ids(beta_status.to_string(), MOD_USER_PAT).await,
+            vec![beta_project_id.clone()]
+        );
+
+        // Anyone else is restricted to approved projects
+        assert_eq!(
+            get_ids(beta_status.to_string(), FRIEND_USER_PAT).await,
+            vec![alpha_project_id.clone()]
+        );
+        assert_eq!(
+            get_ids(beta_status.to_string(), None).await,
+            vec![alpha_project_id.clone()]
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn admins_can_grant_and_revoke_moderator_role() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let set_role = |role: &'static str, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::patch()
+                .uri(&format!("/v3/user/{FRIEND_USER_ID}/roles"))
+                .append_pat(pat)
+                .set_json(serde_json::json!({ "role": role }))
+                .to_request();
+            api.call(req).await
+        };
+        let get_queue = || async {
+            let req = test::TestRequest::get()
+                .uri("/_internal/moderation/projects")
+                .append_pat(FRIEND_USER_PAT)
+                .to_request();
+            api.call(req).await
+        };
+
+        // Moderators cannot manage roles
+        let resp = set_role("moderator", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = get_queue().await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = set_role("moderator", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = get_queue().await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let resp = set_role("user", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = get_queue().await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = set_role("superuser", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn version_contributions_hide_private_projects() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let version_ids = |versions: Vec<serde_json::Value>| {
+            versions
+                .into_iter()
+                .map(|x| x["id"].as_str().unwrap().to_string())
+                .sorted()
+                .collect::<Vec<_>>()
+        };
+
+        // The uploader sees the versions of their private project too
+        let resp = api
+            .get_version_contributions(USER_USER_ID, "", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let versions: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(
+            version_ids(versions),
+            vec![alpha.version_id.clone(), beta.version_id.clone()]
+                .into_iter()
+                .sorted()
+                .collect::<Vec<_>>()
+        );
+
+        let resp = api
+            .get_version_contributions(USER_USER_ID, "", FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let versions: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(version_ids(versions), vec![alpha.version_id.clone()]);
+
+        // Hidden versions don't take up room in a page
+        let resp = api
+            .get_version_contributions(USER_USER_ID, "limit=1", FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let versions: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(version_ids(versions), vec![alpha.version_id.clone()]);
+
+        let resp = api
+            .get_version_contributions(
+                USER_USER_ID,
+                &format!("project_id={}", beta.project_id),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let versions: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(version_ids(versions), vec![beta.version_id.clone()]);
+
+        let resp = api
+            .get_version_contributions(FRIEND_USER_ID, "", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let versions: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(versions.is_empty());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn user_download_stats_sum_project_downloads() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        for (version_id, downloads) in [(&alpha.version_id, 10), (&beta.version_id, 25)] {
+            sqlx::query("UPDATE versions SET downloads = $1 WHERE id = $2")
+                .bind(downloads)
+                .bind(parse_base62(version_id).unwrap() as i64)
+                .execute(&test_env.db.pool)
+                .await
+                .unwrap();
+        }
+
+        let stats = api
+            .get_user_download_stats(USER_USER_ID, USER_USER_PAT)
+            .await;
+        assert_eq!(stats.total_downloads, 35);
+        assert_eq!(
+            stats
+                .projects
+                .iter()
+                .map(|x| (x.project_id.to_string(), x.downloads))
+                .collect::<Vec<_>>(),
+            vec![
+                (beta.project_id.clone(), 25),
+                (alpha.project_id.clone(), 10)
+            ]
+        );
+
+        // Beta is private, so others only see alpha
+        let stats = api.get_user_download_stats(USER_USER_ID, None).await;
+        assert_eq!(stats.total_downloads, 10);
+        assert_eq!(stats.projects.len(), 1);
+        assert_eq!(stats.projects[0].project_id.to_string(), alpha.project_id);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn usernames_can_only_be_changed_once_every_30_days() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let resp = api
+            .edit_username(USER_USER_ID, "renamed_user", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_user(USER_USER_ID, USER_USER_PAT).await;
+        let user: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(user["username"], "renamed_user");
+
+        let resp = api
+            .edit_username(USER_USER_ID, "renamed_again", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: i64 = resp
+            .headers()
+            .get("Retry-After")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(retry_after > 29 * 24 * 60 * 60);
+
+        // Names cannot be taken from other users
+        let resp = api
+            .edit_username(FRIEND_USER_ID, "renamed_user", FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Moderators are not held to the cooldown
+        let resp = api
+            .edit_username(USER_USER_ID, "renamed_again", MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let history: Vec<(String,)> =
+            sqlx::query_as("SELECT username FROM username_history WHERE user_id = $1 ORDER BY id")
+                .bind(parse_base62(USER_USER_ID).unwrap() as i64)
+                .fetch_all(&test_env.db.pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            history.into_iter().map(|x| x.0).collect::<Vec<_>>(),
+            vec!["User".to_string(), "renamed_user".to_string()]
+        );
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn admin_users_list_filters_and_gates_emails() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let list = |query: &'static str, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::get()
+                .uri(&format!("/_internal/admin/users?{query}"))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = list("", USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = list("role=moderator", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let users: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0]["username"], "Moderator");
+        assert_eq!(users[0]["email"], "moderator@modrinth.com");
+
+        // The project count filter includes the owner of both dummy projects
+        let resp = list("min_projects=2&search=USER%40", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let users: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0]["user_id"], USER_USER_ID);
+        assert_eq!(users[0]["project_count"], 2);
+
+        // Moderators can list users, without emails
+        let resp = list("limit=2", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let users: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(users.len(), 2);
+        assert!(users.iter().all(|x| x.get("email").is_none()));
+
+        // and their searches are not matched against emails
+        let resp = list("search=USER%40", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let users: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert!(users.is_empty());
+
+        // Pages continue after the given user
+        let resp = list("after=2&limit=1", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let users: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        assert_eq!(users[0]["user_id"], USER_USER_ID);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn push_tokens_can_only_be_managed_by_their_user() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let resp = api
+            .add_push_token(USER_USER_ID, "fcm", "device-token", FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .add_push_token(
+                USER_USER_ID,
+                "carrier-pigeon",
+                "device-token",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .add_push_token(USER_USER_ID, "fcm", "device-token", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .remove_push_token(USER_USER_ID, "device-token", FRIEND_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .remove_push_token(USER_USER_ID, "device-token", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .remove_push_token(USER_USER_ID, "device-token", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn user_email_change_is_validated() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
+
+        let api = &test_env.api;
+
+        // Accounts without a password cannot use this route
+        let resp = api
+            .edit_user_email(USER_USER_ID, "new@modrinth.com", "hunter22", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        let hash = Argon2::default()
+            .hash_password(b"hunter22", &salt)
+            .unwrap()
+            .to_string();
+        sqlx::query("UPDATE users SET password = $1 WHERE id = 3")
+            .bind(hash)
+            .execute(&test_env.db.pool)
+            .await
+            .unwrap();
+
+        let resp = api
+            .edit_user_email(USER_USER_ID, "not an email", "hunter22", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .edit_user_email(USER_USER_ID, "new@modrinth.com", "wrong", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_user_email(
+                USER_USER_ID,
+                "new@modrinth.com",
+                "hunter22",
+                FRIEND_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_user_email(
+                USER_USER_ID,
+                "friend@modrinth.com",
+                "hunter22",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api.verify_user_email("not-a-token").await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn email_changes_are_applied_by_either_verify_route() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use chrono::Duration;
+        use labrinth::database::models::{flow_item::Flow, UserId};
+
+        let api = &test_env.api;
+        let redis = &test_env.db.redis_pool;
+        let pool = &test_env.db.pool;
+        let change_email = |new_email: &'static str| async move {
+            Flow::ChangeEmail {
+                user_id: UserId(3),
+                new_email: new_email.to_string(),
+            }
+            .insert(Duration::hours(1), redis)
+            .await
+        };
+        let email = || async move {
+            let (email,): (String,) = sqlx::query_as("SELECT email FROM users WHERE id = 3")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            email
+        };
+
+        let flow = change_email("first@modrinth.com").await.unwrap();
+        let resp = api.verify_user_email(&flow).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        assert_eq!(email().await, "first@modrinth.com");
+
+        // The link sent in the email goes through the site's verify page
+        let flow = change_email("second@modrinth.com").await.unwrap();
+        let req = test::TestRequest::post()
+            .uri("/_internal/auth/email/verify")
+            .set_json(serde_json::json!({ "flow": flow }))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        assert_eq!(email().await, "second@modrinth.com");
+
+        // Flows are single use
+        let resp = api.verify_user_email(&flow).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn outdated_password_hashes_are_upgraded_on_sign_in() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        use argon2::{password_hash::SaltString, Algorithm, Argon2, Params, PasswordHasher};
+
+        let api = &test_env.api;
+
+        let salt = SaltString::generate(&mut rand::thread_rng());
+        let outdated = Argon2::new(
+            Algorithm::Argon2i,
+            argon2::Version::V0x13,
+            Params::new(4096, 1, 1, None).unwrap(),
+        )
+        .hash_password(b"hunter22", &salt)
+        .unwrap()
+        .to_string();
+        sqlx::query("UPDATE users SET password = $1 WHERE id = 3")
+            .bind(&outdated)
+            .execute(&test_env.db.pool)
+            .await
+            .unwrap();
+
+        // Signing in with the wrong password leaves the hash as it is
+        let resp = api
+            .edit_user_email(USER_USER_ID, "new@modrinth.com", "wrong", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let (hash,): (String,) = sqlx::query_as("SELECT password FROM users WHERE id = 3")
+            .fetch_one(&test_env.db.pool)
+            .await
+            .unwrap();
+        assert_eq!(hash, outdated);
+
+        // The email is taken, but the password was checked before that and is upgraded
+        let resp = api
+            .edit_user_email(
+                USER_USER_ID,
+                "friend@modrinth.com",
+                "hunter22",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let (hash,): (String,) = sqlx::query_as("SELECT password FROM users WHERE id = 3")
+            .fetch_one(&test_env.db.pool)
+            .await
+            .unwrap();
+        assert_ne!(hash, outdated);
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(!labrinth::auth::password::needs_upgrade(&hash));
+        assert!(labrinth::auth::password::verify_password(&hash, "hunter22").is_ok());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn banned_users_are_suspended_until_unbanned() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+
+        let ban = |id: &'static str, body: serde_json::Value, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::patch()
+                .uri(&format!("/_internal/admin/user/{id}/ban"))
+                .append_pat(pat)
+                .set_json(body)
+                .to_request();
+            api.call(req).await
+        };
+        let unban = |id: &'static str, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::delete()
+                .uri(&format!("/_internal/admin/user/{id}/ban"))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        // Only moderators can ban, and only admins can ban staff
+        let body = serde_json::json!({ "reason": "Spamming", "duration_days": 7 });
+        let resp = ban(FRIEND_USER_ID, body.clone(), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = ban(ADMIN_USER_ID, body.clone(), MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = ban(FRIEND_USER_ID, body, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api.get_current_user(FRIEND_USER_PAT).await;
+        assert_status!(&resp, StatusCode::FORBIDDEN);
+        let error: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(error["error"], "suspended");
+        assert_eq!(error["reason"], "Spamming");
+        assert!(error["until"].is_string());
+
+        // Other users are unaffected
+        let resp = api.get_current_user(USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+
+        // Permanent bans have no end date
+        let resp = ban(
+            FRIEND_USER_ID,
+            serde_json::json!({ "reason": "Malware" }),
+            MOD_USER_PAT,
+        )
+        .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_current_user(FRIEND_USER_PAT).await;
+        let error: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(error["reason"], "Malware");
+        assert!(error["until"].is_null());
+
+        let resp = unban(FRIEND_USER_ID, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = unban(FRIEND_USER_ID, MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api.get_current_user(FRIEND_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn user_edit_reports_bio_length_limit() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = test_env.api;
+
+        let resp = api
+            .edit_user(
+                USER_USER_ID,
+                serde_json::json!({ "bio": "a".repeat(4000) }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_user(
+                USER_USER_ID,
+                serde_json::json!({ "bio": "a".repeat(4001) }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(error["field"], "bio");
+        assert_eq!(error["max"], 4000);
+        assert_eq!(error["actual"], 4001);
+    })
+    .await;
+}
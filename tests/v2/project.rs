@@ -111,6 +111,7 @@ async fn test_add_remove_project() {
             name: "data".to_string(),
             filename: None,
             content_type: Some("application/json".to_string()),
+            content_encoding: None,
             data: MultipartSegmentData::Text(serde_json::to_string(&json_data).unwrap()),
         };
 
@@ -138,6 +139,7 @@ async fn test_add_remove_project() {
             name: basic_mod_file.filename(),
             filename: Some(basic_mod_file.filename()),
             content_type: basic_mod_file.content_type(),
+            content_encoding: None,
             data: MultipartSegmentData::Binary(basic_mod_file.bytes()),
         };
 
@@ -147,6 +149,7 @@ async fn test_add_remove_project() {
             name: basic_mod_different_file.filename(),
             filename: Some(basic_mod_different_file.filename()),
             content_type: basic_mod_different_file.content_type(),
+            content_encoding: None,
             // 'Basic'
             data: MultipartSegmentData::Binary(basic_mod_file.bytes()),
         };
@@ -157,6 +160,7 @@ async fn test_add_remove_project() {
             name: basic_mod_different_file.filename(),
             filename: Some(basic_mod_different_file.filename()),
             content_type: basic_mod_different_file.content_type(),
+            content_encoding: None,
             data: MultipartSegmentData::Binary(basic_mod_different_file.bytes()),
         };
 
@@ -250,9 +254,9 @@ async fn test_add_remove_project() {
             None
         );
 
-        // Old slug no longer works
+        // Old slug shows the project as deleted until it is permanently deleted
         let resp = api.get_project("demo", USER_USER_PAT).await;
-        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert_status!(&resp, StatusCode::GONE);
     })
     .await;
 }
@@ -401,7 +405,7 @@ async fn permissions_patch_project_v2() {
         let edit_details = ProjectPermissions::EDIT_DETAILS;
         let test_pairs = [
             ("description", json!("description")),
-            ("issues_url", json!("https://issues.com")),
+            ("issues_url", json!("https://github.com/example/issues")),
             ("source_url", json!("https://source.com")),
             ("wiki_url", json!("https://wiki.com")),
             (
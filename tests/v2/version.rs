@@ -9,7 +9,7 @@ use labrinth::{
 use serde_json::json;
 
 use crate::assert_status;
-use crate::common::api_common::{ApiProject, ApiVersion};
+use crate::common::api_common::{Api, ApiProject, ApiVersion};
 use crate::common::api_v2::ApiV2;
 
 use crate::common::api_v2::request_data::get_public_project_creation_data;
@@ -515,3 +515,32 @@ async fn test_incorrect_file_parts() {
     })
     .await;
 }
+
+#[actix_rt::test]
+async fn file_download_head_is_answered_with_the_file_size() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV2>| async move {
+        let api = &test_env.api;
+        let alpha_file_hash = test_env.dummy.project_alpha.file_hash.as_str();
+        let version = api
+            .get_version_deserialized_common(
+                &test_env.dummy.project_alpha.version_id,
+                USER_USER_PAT,
+            )
+            .await;
+        let size = version.files[0].size;
+
+        // HEAD gives the headers of the file instead of those of the redirect to it
+        let req = test::TestRequest::default()
+            .method(actix_http::Method::HEAD)
+            .uri(&format!("/v2/version_file/{alpha_file_hash}/download"))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Length").unwrap(),
+            size.to_string().as_str()
+        );
+        assert!(test::read_body(resp).await.is_empty());
+    })
+    .await;
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::common::api_common::ApiVersion;
+use crate::common::api_common::{Api, ApiProject, ApiTeams, ApiVersion, AppendsOptionalPat};
 use crate::common::database::*;
 use crate::common::dummy_data::{DummyProjectAlpha, DummyProjectBeta, TestFile};
 use crate::common::get_json_val_str;
@@ -8,13 +8,13 @@ use actix_http::StatusCode;
 use actix_web::test;
 use common::api_v3::ApiV3;
 use common::asserts::assert_common_version_ids;
-use common::database::USER_USER_PAT;
-use common::environment::{with_test_environment, with_test_environment_all};
+use common::database::{MOD_USER_PAT, USER_USER_PAT};
+use common::environment::{with_test_environment, with_test_environment_all, TestEnvironment};
 use futures::StreamExt;
 use labrinth::database::models::version_item::VERSIONS_NAMESPACE;
 use labrinth::models::ids::base62_impl::parse_base62;
 use labrinth::models::projects::{
-    Dependency, DependencyType, VersionId, VersionStatus, VersionType,
+    Dependency, DependencyType, Loader, Version, VersionId, VersionStatus, VersionType,
 };
 use labrinth::routes::v3::version_file::FileUpdateData;
 use serde_json::json;
@@ -687,3 +687,1453 @@ async fn version_ordering_when_specified_orders_specified_before_unspecified() {
     })
     .await;
 }
+
+#[actix_rt::test]
+async fn version_loader_must_match_project_type() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id_parsed = env.dummy.project_alpha.project_id_parsed;
+
+        // A modpack loader cannot be added to a regular mod project
+        let resp = api
+            .add_public_version(
+                alpha_project_id_parsed,
+                "1.2.3.4",
+                TestFile::build_random_mrpack(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Every loader of an edited version must fit the project, unless it is the only version
+        let resp = api
+            .add_public_version(
+                alpha_project_id_parsed,
+                "1.2.3.4",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let resp = api
+            .edit_version(
+                &env.dummy.project_alpha.version_id,
+                json!({ "loaders": ["fabric", "mrpack"] }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // A mod loader cannot be added to a modpack project
+        let (modpack, _) = api
+            .add_public_project(
+                "test-modpack",
+                Some(TestFile::build_random_mrpack()),
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        let resp = api
+            .add_public_version(
+                modpack.id,
+                "1.2.3.4",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn version_loader_set_edits_must_match_project_type() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let resp = api
+            .add_public_version(
+                env.dummy.project_alpha.project_id_parsed,
+                "1.2.3.4",
+                TestFile::build_random_jar(),
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+
+        let resp = api
+            .edit_version_set(alpha_version_id, "loaders", &["mrpack"], &[], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.loaders, vec![Loader("fabric".to_string())]);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn split_archive_parts_are_grouped_under_their_file() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = &env.dummy.project_alpha.version_id;
+        let alpha_file_id = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await
+            .files[0]
+            .id
+            .to_string();
+
+        let part = |index: u32| TestFile::BasicModRandom {
+            filename: format!("dummy-project-alpha.z{index:02}"),
+            bytes: rand::random::<[u8; 32]>().to_vec(),
+        };
+
+        // Parts must belong to a file of the version
+        let resp = api
+            .upload_file_part_to_version(alpha_version_id, &part(1), "0000", 1, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // The version is listed, so parts can't be skipped
+        let resp = api
+            .upload_file_part_to_version(
+                alpha_version_id,
+                &part(2),
+                &alpha_file_id,
+                2,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "draft" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .upload_file_part_to_version(
+                alpha_version_id,
+                &part(2),
+                &alpha_file_id,
+                2,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Part 1 is missing, so the version cannot be published
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "listed" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .upload_file_part_to_version(
+                alpha_version_id,
+                &part(1),
+                &alpha_file_id,
+                1,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "listed" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Further parts of a listed version are uploaded in order
+        let resp = api
+            .upload_file_part_to_version(
+                alpha_version_id,
+                &part(3),
+                &alpha_file_id,
+                3,
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.files.len(), 1);
+        let parts = &version.files[0].parts;
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].part_index, 1);
+        assert_eq!(parts[1].part_index, 2);
+        assert_eq!(parts[2].part_index, 3);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn get_update_from_hash_query_returns_newer_versions_only() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id_parsed = env.dummy.project_alpha.project_id_parsed;
+        let alpha_file_hash = &env.dummy.project_alpha.file_hash;
+
+        // The only version is already the latest
+        let resp = api
+            .get_update_from_hash_query(alpha_file_hash, "sha1", None, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let new_version = api
+            .add_public_version_deserialized(
+                alpha_project_id_parsed,
+                "2.0.0",
+                TestFile::build_random_jar(),
+                None,
+                Some(
+                    serde_json::from_value(json!([{
+                        "op": "add",
+                        "path": "/game_versions",
+                        "value": ["1.20.5"]
+                    }]))
+                    .unwrap(),
+                ),
+                USER_USER_PAT,
+            )
+            .await;
+
+        let resp = api
+            .get_update_from_hash_query(
+                alpha_file_hash,
+                "sha1",
+                Some(vec!["fabric".to_string()]),
+                Some(vec!["1.20.5".to_string()]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let version: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(version["id"], json!(new_version.id));
+
+        // The new version does not match these constraints
+        let resp = api
+            .get_update_from_hash_query(
+                alpha_file_hash,
+                "sha1",
+                None,
+                Some(vec!["1.20.1".to_string()]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Beta is private, so whether its version is the latest is only shown to its members
+        let beta_file_hash = &env.dummy.project_beta.file_hash;
+        let resp = api
+            .get_update_from_hash_query(beta_file_hash, "sha1", None, None, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        let resp = api
+            .get_update_from_hash_query(beta_file_hash, "sha1", None, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn get_versions_from_hashes_omits_unknown_and_limits_batch_size() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_file_hash = env.dummy.project_alpha.file_hash.as_str();
+
+        let versions = api
+            .get_versions_from_hashes_deserialized_common(
+                &[alpha_file_hash, "0000000000000000000000000000000000000000"],
+                "sha1",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(versions.len(), 1);
+        assert!(versions.contains_key(alpha_file_hash));
+
+        let hashes = (0..1001).map(|i| format!("{i:040}")).collect::<Vec<_>>();
+        let hashes = hashes.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+        let resp = api
+            .get_versions_from_hashes(&hashes, "sha1", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn version_set_edits_validate_and_reject_empty_sets() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let resp = api
+            .edit_version_set(
+                alpha_version_id,
+                "game_versions",
+                &["1.20.2"],
+                &["1.20.1"],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.fields["game_versions"], json!(["1.20.2"]));
+
+        // Unknown values are rejected
+        let resp = api
+            .edit_version_set(
+                alpha_version_id,
+                "game_versions",
+                &["0.0.0"],
+                &[],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let resp = api
+            .edit_version_set(
+                alpha_version_id,
+                "loaders",
+                &["unknown"],
+                &[],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // A version can't be left without loaders or game versions
+        let resp = api
+            .edit_version_set(alpha_version_id, "loaders", &[], &["fabric"], USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+        let resp = api
+            .edit_version_set(
+                alpha_version_id,
+                "game_versions",
+                &[],
+                &["1.20.2"],
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Only team members with upload permissions can edit the sets
+        let resp = api
+            .edit_version_set(
+                alpha_version_id,
+                "game_versions",
+                &["1.20.1"],
+                &[],
+                ENEMY_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.loaders, vec![Loader("fabric".to_string())]);
+        assert_eq!(version.fields["game_versions"], json!(["1.20.2"]));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn version_list_expands_game_version_ranges() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+
+        // The alpha version supports 1.20.1
+        for (range, count) in [
+            ("1.20.x", 1),
+            ("1.20.*", 1),
+            ("1.19.x", 0),
+            (">= 1.20.1", 1),
+            ("<=1.20.3", 1),
+            ("> 1.20.1", 0),
+        ] {
+            let versions = api
+                .get_project_versions_with_loader_fields(
+                    alpha_project_id,
+                    json!({ "game_versions": [range] }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_eq!(versions.len(), count, "{range}");
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn game_version_shortcut_matches_the_loader_field_filter() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+        let alpha_project_id_parsed = env.dummy.project_alpha.project_id_parsed;
+
+        let check = || async {
+            for game_version in ["1.20.1", "1.20.5"] {
+                let filtered = api
+                    .get_project_versions_with_loader_fields(
+                        alpha_project_id,
+                        json!({ "game_versions": [game_version] }),
+                        None,
+                    )
+                    .await;
+                // Requested twice, as the second one is served from the cache
+                for _ in 0..2 {
+                    let versions = api
+                        .get_project_versions_for_game_version(alpha_project_id, game_version, None)
+                        .await;
+                    assert_eq!(
+                        versions.iter().map(|x| x.id).collect::<Vec<_>>(),
+                        filtered.iter().map(|x| x.id).collect::<Vec<_>>(),
+                        "{game_version}"
+                    );
+                }
+            }
+        };
+        check().await;
+
+        // A new version clears the cached lists of its project
+        api.add_public_version_deserialized(
+            alpha_project_id_parsed,
+            "2.0.0",
+            TestFile::build_random_jar(),
+            None,
+            Some(
+                serde_json::from_value(json!([
+                    { "op": "replace", "path": "/game_versions", "value": ["1.20.1", "1.20.5"] },
+                ]))
+                .unwrap(),
+            ),
+            USER_USER_PAT,
+        )
+        .await;
+        check().await;
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn gzip_encoded_version_file_is_stored_decompressed() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        use sha2::Digest;
+        use std::io::Write;
+
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let jar = TestFile::build_random_jar();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&jar.bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // Bytes that are not gzip data are rejected
+        let resp = api
+            .upload_encoded_file_to_version(
+                alpha_version_id,
+                &jar,
+                jar.bytes(),
+                "gzip",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let resp = api
+            .upload_encoded_file_to_version(
+                alpha_version_id,
+                &jar,
+                compressed,
+                "gzip",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let sha512 = format!("{:x}", sha2::Sha512::digest(&jar.bytes()));
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        let file = version
+            .files
+            .iter()
+            .find(|x| x.filename == jar.filename())
+            .unwrap();
+        assert_eq!(file.hashes["sha512"], sha512);
+        assert_eq!(file.size, jar.bytes().len() as u32);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn version_download_stats_are_grouped_by_date() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        use chrono::NaiveDate;
+        use labrinth::database::models::download_history_item::DownloadHistory;
+        use labrinth::database::models::{ProjectId, VersionId};
+
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let project_id =
+            ProjectId(parse_base62(&env.dummy.project_alpha.project_id).unwrap() as i64);
+        let version_id = VersionId(parse_base62(alpha_version_id).unwrap() as i64);
+        let day_1 = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let day_2 = NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+
+        // 3 downloads on the first day and 2 on the second, flushed in two batches
+        let mut transaction = env.db.pool.begin().await.unwrap();
+        DownloadHistory::record(
+            &[
+                (project_id, version_id, day_1),
+                (project_id, version_id, day_1),
+                (project_id, version_id, day_2),
+            ],
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        DownloadHistory::record(
+            &[
+                (project_id, version_id, day_1),
+                (project_id, version_id, day_2),
+            ],
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let resp = api
+            .get_version_download_stats(alpha_version_id, "resolution=day", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let stats: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            stats,
+            json!([
+                { "date": "2024-04-01", "downloads": 3 },
+                { "date": "2024-04-02", "downloads": 2 },
+            ])
+        );
+
+        let resp = api
+            .get_version_download_stats(alpha_version_id, "start=2024-04-02", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let stats: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(stats, json!([{ "date": "2024-04-02", "downloads": 2 }]));
+
+        let resp = api
+            .get_version_download_stats(alpha_version_id, "resolution=month", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let stats: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(stats, json!([{ "date": "2024-04-01", "downloads": 5 }]));
+
+        // Only members of the project can see the stats
+        let resp = api
+            .get_version_download_stats(alpha_version_id, "", ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn promoted_versions_are_the_only_featured_version_of_their_slot() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+        let alpha_project_id_parsed = env.dummy.project_alpha.project_id_parsed;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let add_version = |version_number: &'static str, loader| async move {
+            api.add_public_version_deserialized(
+                alpha_project_id_parsed,
+                version_number,
+                TestFile::build_random_jar(),
+                None,
+                Some(
+                    serde_json::from_value(json!([
+                        { "op": "replace", "path": "/loaders", "value": [loader] },
+                    ]))
+                    .unwrap(),
+                ),
+                USER_USER_PAT,
+            )
+            .await
+            .id
+            .to_string()
+        };
+        // Both are featured, but only the fabric one shares a slot with the original version
+        let fabric = add_version("2.0.0", "fabric").await;
+        let forge = add_version("2.0.0-forge", "forge").await;
+
+        let resp = api.promote_version(alpha_version_id, ENEMY_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api.promote_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let featured = |id: String| async move {
+            api.get_version_deserialized(&id, USER_USER_PAT)
+                .await
+                .featured
+        };
+        assert!(featured(alpha_version_id.to_string()).await);
+        assert!(!featured(fabric).await);
+        assert!(featured(forge.clone()).await);
+
+        let resp = api
+            .get_project_audit_log(alpha_project_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let entries: serde_json::Value = test::read_body_json(resp).await;
+        assert!(entries.as_array().unwrap().iter().any(|x| {
+            x["changed_field"] == "promoted_version" && x["new_value"] == alpha_version_id
+        }));
+
+        // Versions that are not listed on the project cannot be promoted
+        let resp = api
+            .edit_version(&forge, json!({ "status": "unlisted" }), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.promote_version(&forge, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn dependencies_are_replaced_at_once() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+        let beta_project_id = env.dummy.project_beta.project_id.as_str();
+
+        let (gamma, _) = api
+            .add_public_project(
+                "dependency-gamma",
+                Some(TestFile::build_random_jar()),
+                None,
+                USER_USER_PAT,
+            )
+            .await;
+        let gamma_project_id = gamma.id.to_string();
+
+        let resp = api
+            .edit_version_dependencies(
+                alpha_version_id,
+                json!([{ "project_id": beta_project_id, "dependency_type": "required" }]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .edit_version_dependencies(
+                alpha_version_id,
+                json!([{ "project_id": gamma_project_id, "dependency_type": "optional" }]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.dependencies.len(), 1);
+        assert_eq!(
+            version.dependencies[0].project_id.unwrap().to_string(),
+            gamma_project_id
+        );
+        assert_eq!(
+            version.dependencies[0].dependency_type,
+            DependencyType::Optional
+        );
+
+        // Unknown projects and dependencies on the version's own project are rejected, and the
+        // dependencies are left as they were
+        let resp = api
+            .edit_version_dependencies(
+                alpha_version_id,
+                json!([{ "project_id": "zzzzzzzz", "dependency_type": "required" }]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+        let resp = api
+            .edit_version_dependencies(
+                alpha_version_id,
+                json!([{ "project_id": alpha_project_id, "dependency_type": "required" }]),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.dependencies.len(), 1);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn primary_file_edit_leaves_one_primary_file() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+        let beta_version_id = env.dummy.project_beta.version_id.as_str();
+
+        let jar = TestFile::build_random_jar();
+        let resp = api
+            .upload_file_to_version(alpha_version_id, &jar, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        let file_id = version
+            .files
+            .iter()
+            .find(|x| x.filename == jar.filename())
+            .map(|x| x.id.to_string())
+            .unwrap();
+
+        let resp = api
+            .edit_version_primary_file(alpha_version_id, &file_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        let primary_files = version
+            .files
+            .iter()
+            .filter(|x| x.primary)
+            .map(|x| x.id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(primary_files, vec![file_id.clone()]);
+
+        // A file of another version cannot be made primary
+        let beta_file_id = api
+            .get_version_deserialized(beta_version_id, USER_USER_PAT)
+            .await
+            .files[0]
+            .id
+            .to_string();
+        let resp = api
+            .edit_version_primary_file(alpha_version_id, &beta_file_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = api
+            .edit_version_primary_file(alpha_version_id, &file_id, ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn hash_backfill_job_skips_files_with_the_hash() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let start_backfill = |algorithm: &'static str, pat| async move {
+            let req = test::TestRequest::post()
+                .uri(&format!(
+                    "/_internal/admin/backfill/hashes?algorithm={algorithm}"
+                ))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        // Only admins can start backfills
+        let resp = start_backfill("sha256", USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = start_backfill("sha256", MOD_USER_PAT).await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+        let resp = start_backfill("md5", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        // Every file already has a sha1 hash, so nothing has to be fetched
+        let resp = start_backfill("sha1", ADMIN_USER_PAT).await;
+        assert_status!(&resp, StatusCode::ACCEPTED);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let job_id = body["job_id"].as_i64().unwrap();
+
+        let mut job = serde_json::Value::Null;
+        for _ in 0..100 {
+            let req = test::TestRequest::get()
+                .uri(&format!("/_internal/admin/backfill/hashes/{job_id}"))
+                .append_pat(MOD_USER_PAT)
+                .to_request();
+            let resp = api.call(req).await;
+            assert_status!(&resp, StatusCode::OK);
+            job = test::read_body_json(resp).await;
+            if !job["completed_at"].is_null() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        assert!(!job["completed_at"].is_null());
+        assert_eq!(job["algorithm"], "sha1");
+        assert_eq!(job["total"], 0);
+        assert_eq!(job["processed"], 0);
+        assert_eq!(job["errors"], json!([]));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/_internal/admin/backfill/hashes/{}", job_id + 1))
+            .append_pat(MOD_USER_PAT)
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn hash_backfill_job_hashes_downloaded_files() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        use sha2::Digest;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let api = &env.api;
+        let pool = &env.db.pool;
+        let start_backfill = || async {
+            let req = test::TestRequest::post()
+                .uri("/_internal/admin/backfill/hashes?algorithm=sha256")
+                .append_pat(ADMIN_USER_PAT)
+                .to_request();
+            api.call(req).await
+        };
+
+        // Every file is served by a local origin with the same content
+        let content = b"backfilled file content";
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let origin = format!("http://{}/file.jar", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    content.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(content).await;
+            }
+        });
+        sqlx::query("UPDATE files SET url = $1")
+            .bind(&origin)
+            .execute(pool)
+            .await
+            .unwrap();
+
+        // A job that stopped making progress does not block new ones, unlike a running one
+        sqlx::query(
+            "INSERT INTO backfill_jobs (algorithm, total, updated_at) VALUES ('sha256', 1, NOW() - interval '2 hours')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        let resp = start_backfill().await;
+        assert_status!(&resp, StatusCode::ACCEPTED);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let job_id = body["job_id"].as_i64().unwrap();
+        let resp = start_backfill().await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+
+        let mut job = serde_json::Value::Null;
+        for _ in 0..100 {
+            let req = test::TestRequest::get()
+                .uri(&format!("/_internal/admin/backfill/hashes/{job_id}"))
+                .append_pat(MOD_USER_PAT)
+                .to_request();
+            let resp = api.call(req).await;
+            assert_status!(&resp, StatusCode::OK);
+            job = test::read_body_json(resp).await;
+            if !job["completed_at"].is_null() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        let files: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM files")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert!(files > 0);
+        assert!(!job["completed_at"].is_null());
+        assert_eq!(job["failed"], false);
+        assert_eq!(job["total"], files);
+        assert_eq!(job["processed"], files);
+        assert_eq!(job["errors"], json!([]));
+
+        let expected = format!("{:x}", sha2::Sha256::digest(content));
+        let hashes: Vec<Vec<u8>> =
+            sqlx::query_scalar("SELECT hash FROM hashes WHERE algorithm = 'sha256'")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        assert_eq!(hashes.len() as i64, files);
+        assert!(hashes.iter().all(|x| x == expected.as_bytes()));
+
+        // Every file has the hash now, and the finished job no longer blocks new ones
+        let resp = start_backfill().await;
+        assert_status!(&resp, StatusCode::ACCEPTED);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn deleted_versions_can_be_restored_for_30_days() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let listed_version_ids = || async {
+            api.get_project_versions_deserialized_common(
+                alpha_project_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                USER_USER_PAT,
+            )
+            .await
+            .into_iter()
+            .map(|x| x.id.to_string())
+            .collect::<Vec<_>>()
+        };
+
+        let resp = api.remove_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert!(!listed_version_ids()
+            .await
+            .contains(&alpha_version_id.to_string()));
+
+        // Only the project's members can restore it
+        let resp = api.restore_version(alpha_version_id, ENEMY_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+
+        let resp = api.restore_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.get_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert!(listed_version_ids()
+            .await
+            .contains(&alpha_version_id.to_string()));
+
+        // Both are recorded on the timeline of the project
+        let events: Vec<String> = sqlx::query_scalar(
+            "SELECT event_type FROM project_events WHERE (payload->>'version_id')::bigint = $1 ORDER BY id",
+        )
+        .bind(parse_base62(alpha_version_id).unwrap() as i64)
+        .fetch_all(&env.db.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            events,
+            ["version_published", "version_deleted", "version_restored"]
+        );
+
+        // A version deleted more than 30 days ago is gone for good
+        let resp = api.remove_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        sqlx::query("UPDATE versions SET deleted_at = NOW() - interval '31 days' WHERE id = $1")
+            .bind(parse_base62(alpha_version_id).unwrap() as i64)
+            .execute(&env.db.pool)
+            .await
+            .unwrap();
+        let resp = api.restore_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn moderators_can_reject_versions() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_project_id = env.dummy.project_alpha.project_id.as_str();
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        let listed_version_ids = |pat| async move {
+            api.get_project_versions_deserialized_common(
+                alpha_project_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                pat,
+            )
+            .await
+            .into_iter()
+            .map(|x| x.id.to_string())
+            .collect::<Vec<_>>()
+        };
+
+        let review = json!({ "status": "rejected", "message": "Contains malware" });
+        let resp = api
+            .edit_version_review_status(alpha_version_id, review.clone(), USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .edit_version_review_status(alpha_version_id, review, MOD_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Rejected versions are hidden from the public, but not from the project's members
+        let resp = api.get_version(alpha_version_id, None).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert!(!listed_version_ids(ENEMY_USER_PAT)
+            .await
+            .contains(&alpha_version_id.to_string()));
+        let version = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+        assert_eq!(version.status, VersionStatus::Rejected);
+
+        // Only moderators can bring a rejected version back
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "status": "listed" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let notifications = api
+            .get_user_notifications_deserialized_common(USER_USER_ID, USER_USER_PAT)
+            .await;
+        assert!(notifications
+            .iter()
+            .any(|x| x.link.ends_with(alpha_version_id) && x.text.contains("Contains malware")));
+
+        let logged: (String, String) = sqlx::query_as(
+            "SELECT target_type, new_value FROM moderation_log WHERE target_version_id = $1",
+        )
+        .bind(parse_base62(alpha_version_id).unwrap() as i64)
+        .fetch_one(&env.db.pool)
+        .await
+        .unwrap();
+        assert_eq!(logged, ("version".to_string(), "rejected".to_string()));
+
+        let resp = api
+            .edit_version_review_status(
+                alpha_version_id,
+                json!({ "status": "approved" }),
+                MOD_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        assert!(listed_version_ids(ENEMY_USER_PAT)
+            .await
+            .contains(&alpha_version_id.to_string()));
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn copied_versions_are_drafts_with_the_source_metadata() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+        let beta_project_id = env.dummy.project_beta.project_id.as_str();
+
+        let source = api
+            .get_version_deserialized(alpha_version_id, USER_USER_PAT)
+            .await;
+
+        let copy = json!({
+            "target_project_id": null,
+            "new_loaders": ["forge"],
+            "new_game_versions": ["1.20.5"],
+        });
+        let resp = api
+            .copy_version(alpha_version_id, copy.clone(), ENEMY_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::UNAUTHORIZED);
+
+        let resp = api
+            .copy_version(alpha_version_id, copy, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let copied: Version = test::read_body_json(resp).await;
+        assert_ne!(copied.id, source.id);
+        assert_eq!(copied.project_id, source.project_id);
+        assert_eq!(copied.status, VersionStatus::Draft);
+        assert_eq!(copied.name, source.name);
+        assert_eq!(copied.changelog, source.changelog);
+        assert_eq!(copied.dependencies.len(), source.dependencies.len());
+        assert_eq!(copied.loaders, vec![Loader("forge".to_string())]);
+        assert_eq!(copied.fields["game_versions"], json!(["1.20.5"]));
+        assert!(copied.files.is_empty());
+
+        // Copies to another project need upload permissions on both
+        let resp = api
+            .copy_version(
+                alpha_version_id,
+                json!({
+                    "target_project_id": beta_project_id,
+                    "new_loaders": ["fabric"],
+                    "new_game_versions": ["1.20.1"],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let copied: Version = test::read_body_json(resp).await;
+        assert_eq!(copied.project_id.to_string(), beta_project_id);
+
+        let resp = api
+            .copy_version(
+                alpha_version_id,
+                json!({
+                    "target_project_id": null,
+                    "new_loaders": ["not-a-loader"],
+                    "new_game_versions": ["1.20.1"],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn file_downloads_support_head_and_ranges() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_file_hash = env.dummy.project_alpha.file_hash.as_str();
+        let version = api
+            .get_version_deserialized(&env.dummy.project_alpha.version_id, USER_USER_PAT)
+            .await;
+        let size = version.files[0].size;
+
+        // HEAD gives the size of the file without its body
+        let req = test::TestRequest::default()
+            .method(actix_http::Method::HEAD)
+            .uri(&format!("/v3/version_file/{alpha_file_hash}/download"))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Length").unwrap(),
+            size.to_string().as_str()
+        );
+        assert_eq!(resp.headers().get("Accept-Ranges").unwrap(), "bytes");
+        assert!(test::read_body(resp).await.is_empty());
+
+        // Ranges past the end of the file can't be satisfied
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/version_file/{alpha_file_hash}/download"))
+            .insert_header(("Range", format!("bytes={size}-")))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            resp.headers().get("Content-Range").unwrap(),
+            format!("bytes */{size}").as_str()
+        );
+
+        // The test file is not on the CDN, so its ranges fall back to the redirect
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/version_file/{alpha_file_hash}/download"))
+            .insert_header(("Range", "bytes=0-0"))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::TEMPORARY_REDIRECT);
+
+        // Other requests are still redirected to the CDN
+        let req = test::TestRequest::get()
+            .uri(&format!("/v3/version_file/{alpha_file_hash}/download"))
+            .to_request();
+        let resp = api.call(req).await;
+        assert_status!(&resp, StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(resp.headers().get("Accept-Ranges").unwrap(), "bytes");
+    })
+    .await;
+}
+
+#[actix_rt::test]
+pub async fn version_checker_reports_missing_dependencies_and_conflicts() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha = &test_env.dummy.project_alpha;
+        let beta = &test_env.dummy.project_beta;
+
+        let resp = api
+            .edit_version(
+                &alpha.version_id,
+                json!({
+                    "dependencies": [{
+                        "project_id": beta.project_id,
+                        "dependency_type": "required",
+                    }],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        // Alpha requires beta, which is not in the list
+        let result = api
+            .check_versions(&[&alpha.version_id], "fabric", "1.20.1", USER_USER_PAT)
+            .await;
+        assert_eq!(result["compatible"], false);
+        assert_eq!(result["missing_deps"].as_array().unwrap().len(), 1);
+        assert_eq!(result["missing_deps"][0]["project_id"], beta.project_id);
+        assert_eq!(result["missing_deps"][0]["required_by"], alpha.version_id);
+        assert!(result["conflicts"].as_array().unwrap().is_empty());
+
+        let result = api
+            .check_versions(
+                &[&alpha.version_id, &beta.version_id],
+                "fabric",
+                "1.20.1",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(result["compatible"], true);
+
+        // The versions do not support forge
+        let result = api
+            .check_versions(
+                &[&alpha.version_id, &beta.version_id],
+                "forge",
+                "1.20.1",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(result["compatible"], false);
+        assert_eq!(result["conflicts"].as_array().unwrap().len(), 2);
+        assert!(result["conflicts"][0]["version_b"].is_null());
+
+        let resp = api
+            .edit_version(
+                &beta.version_id,
+                json!({
+                    "dependencies": [{
+                        "project_id": alpha.project_id,
+                        "dependency_type": "incompatible",
+                    }],
+                }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let result = api
+            .check_versions(
+                &[&alpha.version_id, &beta.version_id],
+                "fabric",
+                "1.20.1",
+                USER_USER_PAT,
+            )
+            .await;
+        assert_eq!(result["compatible"], false);
+        assert!(result["missing_deps"].as_array().unwrap().is_empty());
+        assert_eq!(result["conflicts"].as_array().unwrap().len(), 1);
+        assert_eq!(result["conflicts"][0]["version_a"], beta.version_id);
+        assert_eq!(result["conflicts"][0]["version_b"], alpha.version_id);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn head_version_matches_get_without_body() {
+    with_test_environment(None, |test_env: TestEnvironment<ApiV3>| async move {
+        let api = &test_env.api;
+        let alpha_version_id = &test_env.dummy.project_alpha.version_id;
+        let beta_version_id = &test_env.dummy.project_beta.version_id;
+
+        let resp = api.get_version(alpha_version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        let etag = resp.headers().get("ETag").unwrap().clone();
+        let body = test::read_body(resp).await;
+
+        let head = |id: String, pat: Option<&'static str>| async move {
+            let req = test::TestRequest::default()
+                .method(actix_http::Method::HEAD)
+                .uri(&format!("/v3/version/{id}"))
+                .append_pat(pat)
+                .to_request();
+            api.call(req).await
+        };
+
+        let resp = head(alpha_version_id.clone(), USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::OK);
+        assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+        assert_eq!(
+            resp.headers().get("Content-Length").unwrap(),
+            &body.len().to_string()
+        );
+        assert!(test::read_body(resp).await.is_empty());
+
+        let resp = head(beta_version_id.clone(), ENEMY_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NOT_FOUND);
+        assert!(test::read_body(resp).await.is_empty());
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn version_edit_reports_string_length_limits() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha_version_id = env.dummy.project_alpha.version_id.as_str();
+
+        for (field, min, max) in [("version_number", 1, 32), ("changelog", 0, 65535)] {
+            let resp = api
+                .edit_version(
+                    alpha_version_id,
+                    json!({ (field): "a".repeat(max) }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::NO_CONTENT);
+
+            let resp = api
+                .edit_version(
+                    alpha_version_id,
+                    json!({ (field): "a".repeat(max + 1) }),
+                    USER_USER_PAT,
+                )
+                .await;
+            assert_status!(&resp, StatusCode::BAD_REQUEST);
+            let error: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(error["error"], "invalid_input");
+            assert_eq!(error["field"], field);
+            assert_eq!(error["min"], min);
+            assert_eq!(error["max"], max);
+            assert_eq!(error["actual"], max + 1);
+        }
+
+        let resp = api
+            .edit_version(
+                alpha_version_id,
+                json!({ "version_number": "" }),
+                USER_USER_PAT,
+            )
+            .await;
+        assert_status!(&resp, StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(error["field"], "version_number");
+        assert_eq!(error["actual"], 0);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn download_redirect_names_the_file() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+
+        let resp = api
+            .get_version(&env.dummy.project_alpha.version_id, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let version: serde_json::Value = test::read_body_json(resp).await;
+        let file = &version["files"][0];
+
+        let resp = api
+            .download_version_redirect(&env.dummy.project_alpha.file_hash, "sha1", USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            resp.headers().get("Location").unwrap().to_str().unwrap(),
+            file["url"].as_str().unwrap()
+        );
+        assert_eq!(
+            resp.headers()
+                .get("Content-Disposition")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            format!(
+                "attachment; filename=\"{}\"",
+                file["filename"].as_str().unwrap()
+            )
+        );
+
+        let redirect: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(redirect["url"], file["url"]);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn restored_versions_are_recorded_on_the_project_timeline() {
+    with_test_environment(None, |env: TestEnvironment<ApiV3>| async move {
+        let api = &env.api;
+        let alpha = &env.dummy.project_alpha;
+
+        let resp = api.remove_version(&alpha.version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+        let resp = api.restore_version(&alpha.version_id, USER_USER_PAT).await;
+        assert_status!(&resp, StatusCode::NO_CONTENT);
+
+        let resp = api
+            .get_project_events(&alpha.project_id, None, USER_USER_PAT)
+            .await;
+        assert_status!(&resp, StatusCode::OK);
+        let events: Vec<serde_json::Value> = test::read_body_json(resp).await;
+        let last = events.last().unwrap();
+        assert_eq!(last["event_type"], "version_restored");
+        assert_eq!(last["payload"]["version_id"], alpha.version_id);
+    })
+    .await;
+}